@@ -0,0 +1,76 @@
+//! Parser, analyzer and report throughput. Compare against a saved run to catch regressions:
+//! `cargo bench -- --save-baseline main` on the base commit, then `cargo bench -- --baseline main`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use log_analyzer::analyze::{Analyzer, Filters, Granularity};
+use log_analyzer::parse::{DefaultLogParser, LogParser};
+use log_analyzer::report::{build_summary, ReportOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// One line per built-in pattern, plus one that matches none
+const CORPORA: [(&str, &str); 6] = [
+    ("iso", "2025-09-05 14:32:10,123 ERROR Failed to connect to DB host=db-1 attempt=3"),
+    ("iso_bracketed", "2025-09-05T14:32:10Z [WARNING] Disk space at 85% on /var"),
+    ("syslog", "Sep  5 14:32:10 web-1 nginx[1234]: [ERROR] upstream timed out while reading response header"),
+    ("log4j", "2025-09-05 14:32:10,123 [pool-1-thread-7] ERROR com.acme.OrderService - Payment declined for order 42"),
+    ("python", "2025-09-05 14:32:10,123 - app.db - ERROR - connection reset by peer"),
+    ("malformed", "    at com.acme.OrderService.place(OrderService.java:118)"),
+];
+
+/// Lines in the generated file
+const FILE_LINES: usize = 1_000_000;
+
+/// `FILE_LINES` lines cycling through levels, messages and timestamps, written once per run
+fn generated_file() -> &'static PathBuf {
+    static FILE: OnceLock<PathBuf> = OnceLock::new();
+    FILE.get_or_init(|| {
+        let path = std::env::temp_dir().join(format!("log_analyzer_bench_{FILE_LINES}.log"));
+        let mut out = std::io::BufWriter::new(std::fs::File::create(&path).expect("create bench file"));
+        let start = chrono::NaiveDate::from_ymd_opt(2025, 9, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        for i in 0..FILE_LINES {
+            let ts = start + chrono::Duration::milliseconds(i as i64 * 250);
+            let (level, msg) = match i % 20 {
+                0 => ("ERROR", format!("Failed to connect to DB host=db-{}", i % 7)),
+                1 | 2 => ("WARNING", format!("Slow query took {}ms", 100 + i % 900)),
+                _ => ("INFO", format!("Handled request id={i} status=200")),
+            };
+            writeln!(out, "{} {level} {msg}", ts.format("%Y-%m-%d %H:%M:%S,%3f")).unwrap();
+        }
+        out.flush().unwrap();
+        path
+    })
+}
+
+fn analyze_file() -> Analyzer {
+    let mut an = Analyzer::new(Granularity::Minute, Filters::default());
+    an.consume_file(&mut DefaultLogParser::new(), generated_file()).unwrap();
+    an
+}
+
+fn parse_line(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse_line");
+    group.throughput(Throughput::Elements(1));
+    for (name, line) in CORPORA {
+        let mut parser = DefaultLogParser::new();
+        group.bench_function(name, |b| b.iter(|| parser.parse_line(black_box(line))));
+    }
+    group.finish();
+}
+
+fn consume_file(c: &mut Criterion) {
+    generated_file();
+    let mut group = c.benchmark_group("consume_file");
+    group.sample_size(10).throughput(Throughput::Elements(FILE_LINES as u64));
+    group.bench_function("1m_lines", |b| b.iter(analyze_file));
+    group.finish();
+}
+
+fn summary(c: &mut Criterion) {
+    let an = analyze_file();
+    c.bench_function("build_summary/1m_lines", |b| b.iter(|| build_summary(black_box(&an), &ReportOptions::default())));
+}
+
+criterion_group!(benches, parse_line, consume_file, summary);
+criterion_main!(benches);
@@ -0,0 +1,176 @@
+//! Threshold alerts for follow mode, e.g. `errors > 100 per 5m`.
+
+use crate::analyze::{parse_duration, Analyzer};
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// Longest a webhook may take before the alert action counts as failed
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Metric { Entries, Info, Warnings, Errors, Malformed }
+
+impl Metric {
+    fn read(self, t: &Totals) -> u64 {
+        match self {
+            Metric::Entries => t.info + t.warning + t.error,
+            Metric::Info => t.info,
+            Metric::Warnings => t.warning,
+            Metric::Errors => t.error,
+            Metric::Malformed => t.malformed,
+        }
+    }
+}
+
+/// The analyzer's running totals, copied out so rules can be checked (and actions run) without
+/// holding on to the analyzer.
+#[derive(Clone, Debug, Default)]
+pub struct Totals {
+    pub info: u64,
+    pub warning: u64,
+    pub error: u64,
+    pub malformed: u64,
+    pub top_errors: Vec<(String, u64)>,
+}
+
+impl Totals {
+    pub fn of(an: &Analyzer) -> Self {
+        Self { info: an.info, warning: an.warning, error: an.error, malformed: an.malformed_lines, top_errors: an.error_messages.top(5) }
+    }
+}
+
+/// `<metric> > <n> per <window>`: fires when more than `n` matching entries arrive within `window`.
+#[derive(Clone, Debug)]
+pub struct AlertRule {
+    pub source: String,
+    pub metric: Metric,
+    /// Fires when the count within `window` exceeds this (`>= n` is stored as `> n - 1`)
+    pub threshold: u64,
+    pub window: Duration,
+}
+
+impl std::str::FromStr for AlertRule {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self> {
+        let words: Vec<&str> = s.split_whitespace().collect();
+        let [metric, op, n, per, window] = words[..] else {
+            bail!("Invalid alert rule '{s}' (expected e.g. 'errors > 100 per 5m')");
+        };
+        let metric = match metric.to_ascii_lowercase().as_str() {
+            "entries" => Metric::Entries,
+            "info" => Metric::Info,
+            "warnings" | "warning" => Metric::Warnings,
+            "errors" | "error" => Metric::Errors,
+            "malformed" => Metric::Malformed,
+            _ => bail!("Unknown alert metric '{metric}' (entries, info, warnings, errors, malformed)"),
+        };
+        let n: u64 = n.parse().with_context(|| format!("Invalid alert threshold '{n}'"))?;
+        let threshold = match op {
+            ">" => n,
+            ">=" => n.saturating_sub(1),
+            _ => bail!("Unsupported alert operator '{op}' (use > or >=)"),
+        };
+        if per != "per" { bail!("Invalid alert rule '{s}' (expected 'per <window>')"); }
+        Ok(AlertRule { source: s.to_string(), metric, threshold, window: parse_duration(window)? })
+    }
+}
+
+/// Where fired alerts go.
+#[derive(Clone, Debug, Default)]
+pub struct AlertActions {
+    /// POST the JSON payload here
+    pub webhook: Option<String>,
+    /// Run through the shell with the payload on stdin
+    pub command: Option<String>,
+}
+
+/// Sent to the webhook / command when a rule fires.
+#[derive(Serialize)]
+pub struct AlertPayload<'a> {
+    pub rule: &'a str,
+    pub value: u64,
+    pub window_secs: u64,
+    pub fired_at: String,
+    pub total_errors: u64,
+    pub top_errors: Vec<(String, u64)>,
+}
+
+struct RuleState {
+    rule: AlertRule,
+    /// (arrival time, count) of increments within the window
+    history: VecDeque<(Instant, u64)>,
+    last_total: u64,
+    /// Fired and not yet back under the threshold
+    active: bool,
+}
+
+/// Evaluates rules against an analyzer's running totals after each poll.
+pub struct Alerter {
+    rules: Vec<RuleState>,
+    actions: AlertActions,
+}
+
+impl Alerter {
+    pub fn new(rules: Vec<AlertRule>, actions: AlertActions, totals: &Totals) -> Self {
+        let rules = rules.into_iter()
+            .map(|rule| RuleState { last_total: rule.metric.read(totals), rule, history: VecDeque::new(), active: false })
+            .collect();
+        Self { rules, actions }
+    }
+
+    /// Fire every rule whose windowed count just went over its threshold. Alerts and action
+    /// failures are reported on stderr, so a flaky webhook doesn't stop the watch and stdout
+    /// stays clean for `--json`.
+    pub fn check(&mut self, totals: &Totals) {
+        let now = Instant::now();
+        for st in &mut self.rules {
+            let total = st.rule.metric.read(totals);
+            if total > st.last_total { st.history.push_back((now, total - st.last_total)); }
+            st.last_total = total;
+            while st.history.front().is_some_and(|(t, _)| now.duration_since(*t) > st.rule.window) { st.history.pop_front(); }
+            let value: u64 = st.history.iter().map(|(_, n)| n).sum();
+            let over = value > st.rule.threshold;
+            if over && !st.active {
+                let payload = AlertPayload {
+                    rule: &st.rule.source,
+                    value,
+                    window_secs: st.rule.window.as_secs(),
+                    fired_at: chrono::Local::now().to_rfc3339(),
+                    total_errors: totals.error,
+                    top_errors: totals.top_errors.clone(),
+                };
+                eprintln!("ALERT: {} (value {value})", st.rule.source);
+                if let Err(e) = fire(&self.actions, &payload) { eprintln!("Alert action failed: {e:#}"); }
+            }
+            st.active = over;
+        }
+    }
+}
+
+fn fire(actions: &AlertActions, payload: &AlertPayload) -> Result<()> {
+    let body = serde_json::to_string(payload)?;
+    if let Some(url) = &actions.webhook {
+        ureq::AgentBuilder::new().timeout(WEBHOOK_TIMEOUT).build()
+            .post(url)
+            .set("Content-Type", "application/json")
+            .send_string(&body)
+            .with_context(|| format!("POST {url}"))?;
+    }
+    if let Some(cmd) = &actions.command {
+        let (shell, flag) = if cfg!(windows) { ("cmd", "/C") } else { ("sh", "-c") };
+        let mut child = Command::new(shell).args([flag, cmd])
+            .env("ALERT_RULE", payload.rule)
+            .env("ALERT_VALUE", payload.value.to_string())
+            .stdin(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Running {cmd}"))?;
+        child.stdin.take().unwrap().write_all(body.as_bytes())?;
+        // Reap it in the background; the watch must not block on a slow hook
+        std::thread::spawn(move || child.wait());
+    }
+    Ok(())
+}
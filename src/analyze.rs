@@ -1,101 +1,1504 @@
-use crate::model::{LogEntry, Level};
-use crate::parse::LogParser;
-use chrono::{NaiveDateTime, Datelike, Timelike, NaiveDate};
-use std::collections::{BTreeMap, HashMap};
-use std::fs::File; use std::io::{BufRead, BufReader};
+use crate::model::{LogEntry, Level, LevelSet};
+use crate::parse::{LogParser, RECORD_LINE_SEP};
+use crate::parsed::Recorder;
+use crate::spill::Spill;
+use chrono::{NaiveDateTime, Datelike, Timelike, NaiveDate, NaiveTime, Duration, Months};
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::io::{BufRead, Read};
 use anyhow::Result;
+use serde::{Serialize, Deserialize};
 
-#[derive(Clone, Copy, Debug)]
-pub enum Granularity { Minute, Hour, Day }
+mod sketch;
+mod topk;
+
+pub use sketch::{QuantileSketch, DEFAULT_ACCURACY};
+pub use topk::{MessageExample, MessageLimits, TopK, DEFAULT_EXAMPLES, DEFAULT_MESSAGE_CAPACITY, DEFAULT_MESSAGE_LEN};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Granularity {
+    Second,
+    Minute,
+    #[default]
+    Hour,
+    Day,
+    /// Weeks starting on Monday
+    Week,
+    Month,
+    /// Fixed-length buckets of this many seconds, aligned to the Unix epoch (`--bucket 15m`)
+    Custom(u32),
+    /// Buckets of this many milliseconds, for intervals that aren't whole seconds (`--bucket 100ms`)
+    Millis(u32),
+    /// Picked from the time span of the data (~100-300 buckets) by [`Analyzer::settle_granularity`];
+    /// buckets like `Second` until then
+    Auto,
+}
+
+/// Bucket sizes [`Granularity::Auto`] chooses from, finest first
+const AUTO_STEPS: [Granularity; 20] = [
+    Granularity::Second, Granularity::Custom(5), Granularity::Custom(10), Granularity::Custom(15), Granularity::Custom(30),
+    Granularity::Minute, Granularity::Custom(120), Granularity::Custom(300), Granularity::Custom(600), Granularity::Custom(900),
+    Granularity::Custom(1800), Granularity::Hour, Granularity::Custom(7200), Granularity::Custom(10800), Granularity::Custom(21600),
+    Granularity::Custom(43200), Granularity::Day, Granularity::Custom(172800), Granularity::Week, Granularity::Month,
+];
+/// [`Granularity::Auto`] picks the finest step giving at most this many buckets
+const AUTO_MAX_BUCKETS: i64 = 300;
+/// An auto timeline with more buckets than this moves to the next of Minute, Hour, Day, Month
+/// while reading, to bound memory before the span is known
+const AUTO_WORKING_BUCKETS: usize = 10_000;
 
-impl Default for Granularity {
-    fn default() -> Self { Granularity::Hour }
+impl std::str::FromStr for Granularity {
+    type Err = String;
+    /// A named granularity or an interval such as `15m` / `6h`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "second" => Ok(Granularity::Second),
+            "minute" => Ok(Granularity::Minute),
+            "hour" => Ok(Granularity::Hour),
+            "day" => Ok(Granularity::Day),
+            "week" => Ok(Granularity::Week),
+            "month" => Ok(Granularity::Month),
+            "auto" => Ok(Granularity::Auto),
+            _ => Granularity::custom(s).map_err(|_| {
+                format!("unknown granularity: {s} (expected second, minute, hour, day, week, month, auto or an interval like 15m or 100ms)")
+            }),
+        }
+    }
 }
-#[derive(Clone, Debug)]
+
+impl Granularity {
+    /// Fixed-length buckets from an interval like `250ms`, `90s`, `15m` or `6h`
+    pub fn custom(interval: &str) -> Result<Self> {
+        let d = parse_duration(interval)?;
+        if d.subsec_millis() != 0 {
+            let ms = u32::try_from(d.as_millis()).map_err(|_| anyhow::anyhow!("Bucket interval out of range: {interval}"))?;
+            return Ok(Granularity::Millis(ms));
+        }
+        let secs = d.as_secs();
+        if secs == 0 || secs > u32::MAX as u64 { anyhow::bail!("Bucket interval out of range: {interval}"); }
+        Ok(Granularity::Custom(secs as u32))
+    }
+
+    /// The finest [`AUTO_STEPS`] size giving at most [`AUTO_MAX_BUCKETS`] buckets from `first` to `last`
+    pub fn auto(first: NaiveDateTime, last: NaiveDateTime) -> Self {
+        let span = (last - first).num_seconds();
+        AUTO_STEPS.into_iter().find(|g| span / g.approx_secs() < AUTO_MAX_BUCKETS).unwrap_or(Granularity::Month)
+    }
+
+    /// Bucket length in seconds (a month counts as its average length; sub-second buckets are 0)
+    pub fn approx_secs(&self) -> i64 {
+        match self {
+            Granularity::Millis(_) => 0,
+            Granularity::Second | Granularity::Auto => 1,
+            Granularity::Minute => 60,
+            Granularity::Hour => 3600,
+            Granularity::Day => 86400,
+            Granularity::Week => 604800,
+            Granularity::Month => 2_629_746,
+            Granularity::Custom(secs) => *secs as i64,
+        }
+    }
+
+    /// Start of the bucket following `bucket`
+    pub fn next_bucket(&self, bucket: NaiveDateTime) -> NaiveDateTime {
+        match self {
+            Granularity::Second | Granularity::Auto => bucket + Duration::seconds(1),
+            Granularity::Minute => bucket + Duration::minutes(1),
+            Granularity::Hour => bucket + Duration::hours(1),
+            Granularity::Day => bucket + Duration::days(1),
+            Granularity::Week => bucket + Duration::weeks(1),
+            Granularity::Month => bucket.checked_add_months(Months::new(1)).unwrap(),
+            Granularity::Custom(secs) => bucket + Duration::seconds(*secs as i64),
+            Granularity::Millis(ms) => bucket + Duration::milliseconds(*ms as i64),
+        }
+    }
+
+    /// Start of the bucket containing `ts`
+    pub fn bucket(&self, ts: NaiveDateTime) -> NaiveDateTime {
+        let midnight = |d: NaiveDate| d.and_hms_opt(0, 0, 0).unwrap();
+        match self {
+            Granularity::Second | Granularity::Auto => ts.with_nanosecond(0).unwrap(),
+            Granularity::Minute => ts.with_second(0).unwrap().with_nanosecond(0).unwrap(),
+            Granularity::Hour => ts.with_minute(0).unwrap().with_second(0).unwrap().with_nanosecond(0).unwrap(),
+            Granularity::Day => midnight(ts.date()),
+            Granularity::Week => midnight(ts.date() - Duration::days(ts.weekday().num_days_from_monday() as i64)),
+            Granularity::Month => midnight(NaiveDate::from_ymd_opt(ts.year(), ts.month(), 1).unwrap()),
+            Granularity::Custom(secs) => {
+                let secs = *secs as i64;
+                let start = ts.and_utc().timestamp().div_euclid(secs) * secs;
+                chrono::DateTime::from_timestamp(start, 0).unwrap().naive_utc()
+            }
+            Granularity::Millis(ms) => {
+                let ms = *ms as i64;
+                let start = ts.and_utc().timestamp_millis().div_euclid(ms) * ms;
+                chrono::DateTime::from_timestamp_millis(start).unwrap().naive_utc()
+            }
+        }
+    }
+
+    /// strftime format for chart labels: just enough precision to tell buckets apart
+    pub fn label_format(&self) -> &'static str {
+        match self {
+            Granularity::Second | Granularity::Auto => "%Y-%m-%d %H:%M:%S",
+            Granularity::Minute | Granularity::Hour => "%Y-%m-%d %H:%M",
+            Granularity::Day | Granularity::Week => "%Y-%m-%d",
+            Granularity::Month => "%Y-%m",
+            Granularity::Custom(secs) if secs % 86400 == 0 => "%Y-%m-%d",
+            Granularity::Custom(secs) if secs % 60 == 0 => "%Y-%m-%d %H:%M",
+            Granularity::Custom(_) => "%Y-%m-%d %H:%M:%S",
+            Granularity::Millis(_) => "%Y-%m-%d %H:%M:%S%.3f",
+        }
+    }
+}
+
+/// How finely timestamps are written in reports and error examples (`--ts-precision`); entries
+/// always keep what their line had.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimePrecision {
+    #[default]
+    Seconds,
+    Millis,
+    Micros,
+}
+
+impl TimePrecision {
+    pub fn pattern(self) -> &'static str {
+        match self {
+            TimePrecision::Seconds => "%Y-%m-%d %H:%M:%S",
+            TimePrecision::Millis => "%Y-%m-%d %H:%M:%S%.3f",
+            TimePrecision::Micros => "%Y-%m-%d %H:%M:%S%.6f",
+        }
+    }
+
+    pub fn format(self, ts: NaiveDateTime) -> String { ts.format(self.pattern()).to_string() }
+}
+
+/// `250ms`, `30s`, `5m`, `2h`, `1d`, `1w` (a bare number is seconds).
+pub fn parse_duration(s: &str) -> Result<std::time::Duration> {
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (n, unit) = s.split_at(split);
+    let n: u64 = n.parse().map_err(|_| anyhow::anyhow!("Invalid duration '{s}'"))?;
+    let unit_secs = match unit {
+        "ms" => return Ok(std::time::Duration::from_millis(n)),
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86400,
+        "w" => 7 * 86400,
+        _ => anyhow::bail!("Invalid duration unit in '{s}' (use ms, s, m, h, d or w)"),
+    };
+    let secs = n.checked_mul(unit_secs).ok_or_else(|| anyhow::anyhow!("Duration '{s}' is too long"))?;
+    Ok(std::time::Duration::from_secs(secs))
+}
+
+static VARIABLE_PARTS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}\b|\b0x[0-9a-f]+\b|\d+").unwrap()
+});
+
+/// Collapse variable parts of a message (numbers, hex ids, UUIDs) so similar messages share a template.
+pub fn message_template(msg: &str) -> String {
+    VARIABLE_PARTS.replace_all(msg, "<*>").into_owned()
+}
+
+/// Forms accepted by [`parse_datetime`] and [`TimeBound`], for error messages
+const DATETIME_FORMS: &str = "2025-09-05 14:00:00 or 2025-09-05T14:00 (optionally with fractional seconds), \
+    2025-09-05T14:00:00+02:00 or ...Z (RFC 3339, converted to UTC), 2025-09-05 (date), 1757080800 (Unix seconds)";
+
+/// Parse a user-supplied datetime such as "2025-09-05 14:00:00", "2025-09-05T14:00:00",
+/// "2025-09-05T14:00:00+02:00" (converted to UTC), "2025-09-05" (midnight) or Unix seconds.
+pub fn parse_datetime(val: &str) -> anyhow::Result<NaiveDateTime> {
+    let val = val.trim();
+    let tried = ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M"];
+    for f in tried { if let Ok(dt) = NaiveDateTime::parse_from_str(val, f) { return Ok(dt); } }
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(val) { return Ok(dt.naive_utc()); }
+    // If only date was given, set midnight
+    if let Ok(date) = NaiveDate::parse_from_str(val, "%Y-%m-%d") { return Ok(date.and_hms_opt(0,0,0).unwrap()); }
+    if let Some(dt) = val.parse::<i64>().ok().and_then(|secs| chrono::DateTime::from_timestamp(secs, 0)) { return Ok(dt.naive_utc()); }
+    anyhow::bail!("Could not parse datetime '{val}'; accepted forms: {DATETIME_FORMS}");
+}
+
+/// A `--from` / `--to` value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimeBound {
+    /// A point in time (see [`parse_datetime`])
+    At(NaiveDateTime),
+    /// A whole day: `--from` starts at its midnight, `--to` runs to its end
+    Day(NaiveDate),
+    /// `14:00` or `14:00:00`: a time of day, applied on every day of the logs
+    TimeOfDay(NaiveTime),
+}
+
+impl std::str::FromStr for TimeBound {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") { return Ok(TimeBound::Day(date)); }
+        for f in ["%H:%M:%S%.f", "%H:%M"] {
+            if let Ok(t) = NaiveTime::parse_from_str(s, f) { return Ok(TimeBound::TimeOfDay(t)); }
+        }
+        parse_datetime(s).map(TimeBound::At).map_err(|_| {
+            anyhow::anyhow!("Could not parse time bound '{s}'; accepted forms: {DATETIME_FORMS}, 14:00 or 14:00:00 (time of day, every day)")
+        })
+    }
+}
+
+/// The entries to analyze. Serializes as a JSON object (`--filters-json`); missing keys keep their
+/// defaults, timestamps are `2025-09-05T14:00:00` and times of day `22:00:00`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct Filters {
-    pub keyword: Option<String>,
+    /// Lowercased message substrings; an entry needs all of them, or any with `match_any`
+    pub keywords: Vec<String>,
+    pub match_any: bool,
+    /// Lowercased message substrings that drop an entry (`--not-keyword`)
+    pub not_keywords: Vec<String>,
     pub from: Option<NaiveDateTime>,
     pub to: Option<NaiveDateTime>,
-    pub level: Option<Level>,
+    /// Time-of-day window applied on every day (`--from 22:00 --to 06:00` wraps past midnight)
+    pub from_time: Option<NaiveTime>,
+    pub to_time: Option<NaiveTime>,
+    /// Keep only these levels (`--level`, repeatable); every level by default
+    pub levels: LevelSet,
+    /// Keep this severity and above
+    pub min_level: Option<Level>,
+    /// Extracted fields that must have exactly these values (`--field key=value`)
+    pub fields: Vec<(String, String)>,
+    /// Keep entries from any of these hosts (`--host`, case-insensitive, see [`entry_host`])
+    pub hosts: Vec<String>,
 }
 
 impl Filters {
-    pub fn from_cli(keyword: Option<&str>, from: Option<&str>, to: Option<&str>, level: Option<Level>) -> anyhow::Result<Self> {
-        use chrono::NaiveDateTime;
-        fn parse_opt(s: Option<&str>) -> anyhow::Result<Option<NaiveDateTime>> {
-            if let Some(val) = s {
-                let tried = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d"];
-                for f in tried { if let Ok(dt) = NaiveDateTime::parse_from_str(val, f) { return Ok(Some(dt)); } }
-                // If only date was given, set midnight
-                if let Ok(date) = chrono::NaiveDate::parse_from_str(val, "%Y-%m-%d") { return Ok(Some(date.and_hms_opt(0,0,0).unwrap())); }
-                anyhow::bail!("Could not parse datetime: {val}");
-            }
-            Ok(None)
-        }
-        Ok(Self { keyword: keyword.map(|s| s.to_lowercase()), from: parse_opt(from)?, to: parse_opt(to)?, level })
-    }
-
-    fn pass(&self, e: &LogEntry) -> bool {
-        if let Some(lv) = self.level { if e.level != lv { return false; } }
-        if let Some(f) = self.from { if e.ts < f { return false; } }
-        if let Some(t) = self.to { if e.ts >= t { return false; } }
-        if let Some(k) = &self.keyword { if !e.message.to_lowercase().contains(k) { return false; } }
+    /// `from` and `to` take any [`TimeBound`]; `to` is exclusive, except that a date includes that whole day.
+    /// An empty `levels` keeps every level.
+    pub fn from_cli(keyword: Option<&str>, from: Option<&str>, to: Option<&str>, levels: &[Level]) -> anyhow::Result<Self> {
+        let midnight = |d: NaiveDate| d.and_hms_opt(0, 0, 0).unwrap();
+        let (mut f, mut from_time, mut to_time) = (None, None, None);
+        match from.map(str::parse).transpose()? {
+            Some(TimeBound::At(ts)) => f = Some(ts),
+            Some(TimeBound::Day(d)) => f = Some(midnight(d)),
+            Some(TimeBound::TimeOfDay(t)) => from_time = Some(t),
+            None => {}
+        }
+        let to = match to.map(str::parse).transpose()? {
+            Some(TimeBound::At(ts)) => Some(ts),
+            Some(TimeBound::Day(d)) => Some(midnight(d) + Duration::days(1)),
+            Some(TimeBound::TimeOfDay(t)) => { to_time = Some(t); None }
+            None => None,
+        };
+        let keywords = keyword.map(|s| s.to_lowercase()).into_iter().collect();
+        let levels = if levels.is_empty() { LevelSet::ALL } else { levels.iter().copied().collect() };
+        Ok(Self {
+            keywords, match_any: false, not_keywords: Vec::new(), from: f, to, from_time, to_time, levels, min_level: None,
+            fields: Vec::new(), hosts: Vec::new(),
+        })
+    }
+
+    /// Deserialize a [`Filters`] object, e.g. `{"levels":["error"],"keywords":["timeout"]}`.
+    /// An empty `levels` keeps every level, like in [`from_cli`](Self::from_cli).
+    pub fn from_json(text: &str) -> anyhow::Result<Self> {
+        let mut f: Self = serde_json::from_str(text)?;
+        for k in f.keywords.iter_mut().chain(&mut f.not_keywords) { *k = k.to_lowercase(); }
+        if f.levels.is_empty() { f.levels = LevelSet::ALL; }
+        Ok(f)
+    }
+
+    pub fn pass(&self, e: &LogEntry) -> bool { self.in_time_range(e.ts) && self.pass_except_time(e) }
+
+    /// Any of `from`, `to`, `from_time` and `to_time` is set
+    pub fn has_time_range(&self) -> bool {
+        self.from.is_some() || self.to.is_some() || self.from_time.is_some() || self.to_time.is_some()
+    }
+
+    pub fn in_time_range(&self, ts: NaiveDateTime) -> bool {
+        if let Some(f) = self.from { if ts < f { return false; } }
+        if let Some(t) = self.to { if ts >= t { return false; } }
+        if self.from_time.is_some() || self.to_time.is_some() {
+            let t = ts.time();
+            let (after, before) = (self.from_time.is_none_or(|f| t >= f), self.to_time.is_none_or(|to| t < to));
+            let wraps = self.from_time.zip(self.to_time).is_some_and(|(f, to)| f > to);
+            if !(if wraps { after || before } else { after && before }) { return false; }
+        }
         true
     }
+
+    /// Every filter but the time range
+    pub fn pass_except_time(&self, e: &LogEntry) -> bool {
+        if !self.levels.contains(e.level) { return false; }
+        if let Some(min) = self.min_level { if e.level < min { return false; } }
+        if !self.fields.iter().all(|(k, v)| e.fields.get(k) == Some(v)) { return false; }
+        if !self.hosts.is_empty() && !entry_host(e).is_some_and(|h| self.hosts.iter().any(|w| w.eq_ignore_ascii_case(h))) { return false; }
+        if self.keywords.is_empty() && self.not_keywords.is_empty() { return true; }
+        let msg = e.message.to_lowercase();
+        let hit = |k: &String| msg.contains(k.as_str());
+        let matched = if self.match_any { self.keywords.is_empty() || self.keywords.iter().any(hit) } else { self.keywords.iter().all(hit) };
+        matched && !self.not_keywords.iter().any(hit)
+    }
+}
+
+/// Cheap screen run on raw lines before the parser's regexes, derived from the active [`Filters`].
+/// Each stage is only a necessary condition, so lines that pass are still parsed and filtered;
+/// lines that fail are skipped entirely (and so never counted as malformed).
+pub struct PreFilter {
+    /// Level stage: the line must contain one of these tokens
+    level_tokens: Vec<memchr::memmem::Finder<'static>>,
+    /// Keyword stage: ASCII-lowercased line must contain all (or with `match_any`, one) of these
+    keywords: Vec<memchr::memmem::Finder<'static>>,
+    match_any: bool,
+    scratch: Vec<u8>,
+}
+
+impl PreFilter {
+    /// None when no stage applies to these filters and parser
+    pub fn new<P: LogParser + ?Sized>(filters: &Filters, parser: &P) -> Option<Self> {
+        let finder = |s: &str| memchr::memmem::Finder::new(s.as_bytes()).into_owned();
+        let wanted = [Level::Info, Level::Warning, Level::Error].into_iter()
+            .filter(|l| filters.levels.contains(*l) && filters.min_level.is_none_or(|min| *l >= min));
+        let level_tokens: Vec<_> = wanted
+            .map(|l| parser.level_tokens(l))
+            .collect::<Option<Vec<_>>>()
+            .map(|tokens| tokens.into_iter().flatten().map(|t| finder(t)).collect())
+            .unwrap_or_default();
+        // Exclusions can't be screened: a term in the line may still be outside the message
+        let keywords: Vec<_> = if parser.verbatim_messages() && filters.keywords.iter().all(|k| k.is_ascii()) {
+            filters.keywords.iter().map(|k| finder(k)).collect()
+        } else {
+            Vec::new()
+        };
+        if level_tokens.is_empty() && keywords.is_empty() { return None; }
+        Some(Self { level_tokens, keywords, match_any: filters.match_any, scratch: Vec::new() })
+    }
+
+    /// False if the line cannot produce an entry passing the filters
+    pub fn keep(&mut self, line: &str) -> bool {
+        if !self.level_tokens.is_empty() && !self.level_tokens.iter().any(|f| f.find(line.as_bytes()).is_some()) {
+            return false;
+        }
+        // Unicode case folding can change lengths; leave non-ASCII lines to the real filter
+        if self.keywords.is_empty() || !line.is_ascii() { return true; }
+        self.scratch.clear();
+        self.scratch.extend(line.bytes().map(|b| b.to_ascii_lowercase()));
+        let hit = |k: &memchr::memmem::Finder| k.find(&self.scratch).is_some();
+        if self.match_any { self.keywords.iter().any(hit) } else { self.keywords.iter().all(hit) }
+    }
 }
 
-#[derive(Default)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub struct Analyzer {
+    /// Timeline bucket size; never `Auto` (see `auto_granularity`)
     pub granularity: Granularity,
+    /// Created with [`Granularity::Auto`]: `granularity` is coarsened as the timeline grows and
+    /// chosen by [`settle_granularity`](Self::settle_granularity)
+    #[serde(skip)]
+    pub auto_granularity: bool,
+    /// Entries failing these are dropped before aggregation
+    #[serde(skip)]
+    pub filters: Filters,
+    /// Extracted fields to break counts down by (`--group-by`)
+    #[serde(skip)]
+    pub group_by: Vec<String>,
+    /// Keep up to this many filtered entries in `entries` (None = keep none)
+    #[serde(skip)]
+    pub retain_entries: Option<usize>,
+    /// Keep up to this many unparsed lines in `malformed`
+    #[serde(skip)]
+    pub retain_malformed: usize,
+    /// Only parse a subset of lines
+    #[serde(skip)]
+    pub sampling: Option<Sampling>,
+    /// Collect one numeric value per entry into `hist_values`
+    #[serde(skip)]
+    pub histogram: Option<HistogramSource>,
+    /// Extracted fields whose values are counted in `field_values` (`--top-values`)
+    #[serde(skip)]
+    pub top_value_fields: Vec<String>,
+    /// Lowercased message substrings counted per timeline bucket in `keyword_buckets` (`--track`)
+    #[serde(skip)]
+    pub tracked_keywords: Vec<String>,
+    /// Numeric values sketched into `field_quantiles`, by label (`--quantiles`)
+    #[serde(skip)]
+    pub quantile_fields: Vec<HistogramSource>,
+    /// Screen raw lines with a [`PreFilter`] before parsing
+    #[serde(skip)]
+    pub prefilter: bool,
+    /// Track `error_history` with buckets of this size (`--top-errors-by`)
+    #[serde(skip)]
+    pub error_history_period: Option<Granularity>,
+    /// Cut lines to this many bytes before parsing; 0 = no limit
+    #[serde(skip)]
+    pub max_line_len: usize,
+    /// Count every entry's message template in `message_templates` (`--noisiest`)
+    #[serde(skip)]
+    pub track_noise: bool,
+    /// Write every parsed line to a `--save-parsed` file; turn `prefilter` off so none is skipped
+    #[serde(skip)]
+    pub recorder: Option<Arc<Mutex<Recorder>>>,
+    /// How error example timestamps are written
+    #[serde(skip)]
+    pub ts_precision: TimePrecision,
+    /// Spill `error_messages` to disk instead of evicting from it (`--max-memory`); call
+    /// [`finish_spill`](Self::finish_spill) after the last merge. Retained entries count against
+    /// the same budget and stop being kept at half of it.
+    #[serde(skip)]
+    pub spill: Option<Arc<Mutex<Spill>>>,
     // Global counts
     pub info: u64,
     pub warning: u64,
     pub error: u64,
     pub malformed_lines: u64,
+    // Lines read / lines actually parsed (they differ only when sampling)
+    pub lines_read: u64,
+    pub lines_sampled: u64,
+    /// Lines rejected by the pre-filter without being parsed
+    pub lines_prefiltered: u64,
+    /// Lines cut to `max_line_len`
+    pub lines_truncated: u64,
+    /// Entries restored from suppression notices (`message repeated N times`), see [`repeat_count`]
+    pub repeats_restored: u64,
+    /// Last entry parsed from each input, which a suppression notice repeats; dropped when a line
+    /// in between goes unparsed
+    #[serde(skip)]
+    last_entries: HashMap<String, LogEntry>,
+    /// Inputs not analyzed, e.g. binary files
+    pub skipped_inputs: Vec<SkippedInput>,
+    /// Source -> format the parser detected from its first lines (see [`LogParser::detect`])
+    pub format_detections: BTreeMap<String, String>,
+    /// Pattern name -> lines it matched, every pattern listed, for parsers with several
+    /// (see [`LogParser::pattern_names`])
+    pub pattern_matches: BTreeMap<String, u64>,
+    /// Lines such a parser matched with none of its patterns
+    pub pattern_misses: u64,
 
     // First/last timestamps
     pub first: Option<NaiveDateTime>,
     pub last: Option<NaiveDateTime>,
+    /// First and last timestamp seen per level
+    pub level_spans: BTreeMap<Level, (NaiveDateTime, NaiveDateTime)>,
 
     // Timeline counts (filtered later)
     pub timeline: BTreeMap<NaiveDateTime, u64>,
 
-    // Common error messages
-    pub error_messages: HashMap<String, u64>,
+    // (entries, errors) per minute regardless of granularity, for the worst rolling windows
+    pub per_minute: BTreeMap<NaiveDateTime, (u64, u64)>,
+
+    // Common error messages (bounded; its `limits` are configuration carried by partials)
+    pub error_messages: TopK,
+
+    // Error counts per logger (Log4j/Logback `logger` field)
+    pub logger_errors: HashMap<String, u64>,
+
+    // Entries per exception type (`exception` field, e.g. from Python tracebacks)
+    pub exceptions: HashMap<String, u64>,
+
+    // Previous level -> level -> entries, between consecutive entries of an input
+    pub transitions: BTreeMap<Level, BTreeMap<Level, u64>>,
+
+    // Messages of the non-error entries directly preceding an error (bounded like `error_messages`)
+    pub error_precursors: TopK,
+
+    // Entries showing a crash indicator (see `CRASH_INDICATORS`), the first `MAX_CRASH_SIGNS`
+    pub crash_signs: Vec<CrashSign>,
+    // Last entry per input, for `transitions`, `error_precursors` and crashes at the end of an input
+    pub input_tails: HashMap<String, InputTail>,
+
+    // GC pauses and OutOfMemoryErrors
+    pub jvm: JvmStats,
+
+    // Message templates of entries of every level, with `track_noise` (bounded like `error_messages`)
+    pub message_templates: TopK,
+
+    // Per error template: when it occurred, with `error_history_period`
+    pub error_history: HashMap<String, ErrorHistory>,
+    // Error templates occurring outside the time range that pass every other filter, with `error_history_period`
+    pub errors_outside_range: HashSet<String>,
+
+    // Error templates per timeline bucket (for incident detection)
+    pub error_buckets: BTreeMap<NaiveDateTime, HashMap<String, u64>>,
+    // Warnings per timeline bucket (for the error pressure chart)
+    pub warning_buckets: BTreeMap<NaiveDateTime, u64>,
+    // Per tracked keyword: entries mentioning it per timeline bucket
+    pub keyword_buckets: BTreeMap<String, BTreeMap<NaiveDateTime, u64>>,
+
+    // Per group-by field: field value -> stats
+    pub groups: BTreeMap<String, BTreeMap<String, GroupStats>>,
+    // Host (see `entry_host`) -> stats, for entries that name one
+    pub hosts: BTreeMap<String, GroupStats>,
+
+    // Filtered entries, when retention is enabled
+    pub entries: Vec<LogEntry>,
+    /// Estimated heap bytes of `entries`, held under half of the [`spill`](Self::spill) budget
+    #[serde(skip)]
+    entries_bytes: usize,
+
+    // First unparsed lines, for inspection
+    pub malformed: Vec<MalformedLine>,
+
+    // Lines read / malformed per source file
+    pub sources: BTreeMap<String, SourceStats>,
+
+    // Parse failure reason (templated) -> count and first few examples
+    pub parse_failures: HashMap<String, ParseFailure>,
+
+    // Values for the histogram, when enabled
+    pub hist_values: Vec<f64>,
+
+    // Per `--top-values` field: value -> entries
+    pub field_values: BTreeMap<String, HashMap<String, u64>>,
+
+    // Distributions of message length (characters), seconds between consecutive entries of an
+    // input and each `--quantiles` field
+    pub message_lengths: QuantileSketch,
+    pub inter_arrival: QuantileSketch,
+    pub field_quantiles: BTreeMap<String, QuantileSketch>,
+    /// Timestamp of the previous entry per input, for `inter_arrival`
+    #[serde(skip)]
+    last_arrival: HashMap<String, NaiveDateTime>,
+    /// Path of the input being consumed, shared by the entries it yields
+    #[serde(skip)]
+    source_path: Option<Arc<Path>>,
+}
+
+/// The numeric value a histogram is built from.
+#[derive(Clone, Debug, Default)]
+pub enum HistogramSource {
+    #[default]
+    MessageLength,
+    /// An extracted field, or `name=123` / `name: 123ms` inside the message when the entry lacks it
+    Field { name: String, in_message: Regex },
+}
+
+impl HistogramSource {
+    pub fn field(name: &str) -> Self {
+        let in_message = Regex::new(&format!(r"\b{}\s*[=:]\s*(-?\d+(?:\.\d+)?)", regex::escape(name))).unwrap();
+        HistogramSource::Field { name: name.to_string(), in_message }
+    }
+
+    pub fn label(&self) -> &str {
+        match self { HistogramSource::MessageLength => "message length", HistogramSource::Field { name, .. } => name }
+    }
+
+    fn value(&self, e: &LogEntry) -> Option<f64> {
+        match self {
+            HistogramSource::MessageLength => Some(e.message.chars().count() as f64),
+            HistogramSource::Field { name, in_message } => match e.fields.get(name) {
+                Some(v) => leading_number(v),
+                None => in_message.captures(&e.message).and_then(|c| c[1].parse().ok()),
+            },
+        }
+    }
+}
+
+/// `123`, `-4.5`, `250ms` -> the number at the start of `s`
+fn leading_number(s: &str) -> Option<f64> {
+    let s = s.trim();
+    let end = s.char_indices().find(|&(i, c)| !(c.is_ascii_digit() || c == '.' || (i == 0 && c == '-'))).map_or(s.len(), |(i, _)| i);
+    s[..end].parse().ok()
+}
+
+/// Line sampling for quick exploratory runs over huge inputs.
+#[derive(Clone, Copy, Debug)]
+pub enum Sampling {
+    /// Keep each line with this probability (deterministic per source and line number)
+    Fraction(f64),
+    /// Keep every n-th line
+    Every(u64),
+}
+
+impl Sampling {
+    /// Expected fraction of lines kept
+    pub fn rate(&self) -> f64 {
+        match *self { Sampling::Fraction(p) => p, Sampling::Every(n) => 1.0 / n.max(1) as f64 }
+    }
+
+    /// Whether to keep line `line_no` of the input whose [`Sampling::seed`] is `seed`
+    fn keep(&self, seed: u64, line_no: u64) -> bool {
+        match *self {
+            Sampling::Fraction(p) => {
+                // splitmix64 finalizer: a cheap, well-mixed hash so runs are reproducible
+                let mut z = (line_no ^ seed).wrapping_add(0x9E37_79B9_7F4A_7C15);
+                z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+                z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+                z ^= z >> 31;
+                (z as f64 / u64::MAX as f64) < p
+            }
+            Sampling::Every(n) => (line_no - 1).is_multiple_of(n.max(1)),
+        }
+    }
+
+    /// Per-input seed (FNV-1a of the source name), so inputs don't all keep the same line numbers
+    fn seed(source: &str) -> u64 {
+        source.bytes().fold(0xCBF2_9CE4_8422_2325, |h, b| (h ^ b as u64).wrapping_mul(0x0100_0000_01B3))
+    }
+}
+
+/// Unparsed lines kept by default (the summary's `malformed_samples`)
+pub const DEFAULT_MALFORMED_SAMPLES: usize = 10;
+
+/// Lines longer than this many bytes are cut by default (`--max-line-len`)
+pub const DEFAULT_MAX_LINE_LEN: usize = 1 << 20;
+
+/// At most this many lines, and this many bytes, of an input are sampled for [`LogParser::detect`]
+pub const DETECT_SAMPLE_LINES: usize = 1000;
+const DETECT_SAMPLE_BYTES: u64 = 1 << 20;
+
+/// An input with more than this share of NUL bytes at its start is taken for a binary file
+pub const BINARY_NUL_RATIO: f64 = 0.01;
+
+/// First pass over an input: show the parser its first lines so it can settle on a format, then
+/// hand back a reader that still starts at the first line, and what was detected
+pub(crate) fn detect_format<'a, P: LogParser + ?Sized>(parser: &mut P, mut r: Box<dyn BufRead + 'a>) -> Result<(Box<dyn BufRead + 'a>, Option<String>)> {
+    let mut head = Vec::new();
+    for _ in 0..DETECT_SAMPLE_LINES {
+        let budget = DETECT_SAMPLE_BYTES.saturating_sub(head.len() as u64);
+        if budget == 0 || (&mut r).take(budget).read_until(b'\n', &mut head)? == 0 { break; }
+    }
+    let text = String::from_utf8_lossy(&head);
+    let sample: Vec<&str> = text.lines().collect();
+    let found = parser.detect(&sample);
+    Ok((Box::new(std::io::Cursor::new(head).chain(r)), found))
+}
+
+/// Whether `head`, the start of an input, looks like binary data rather than text
+pub fn looks_binary(head: &[u8]) -> bool {
+    !head.is_empty() && memchr::memchr_iter(0, head).count() as f64 > head.len() as f64 * BINARY_NUL_RATIO
+}
+
+/// Read one line into `buf` (without its `\n` or `\r\n`), keeping at most `cap` bytes of it.
+/// Returns None at the end of input, else whether the line was cut.
+pub fn read_capped_line<R: BufRead + ?Sized>(r: &mut R, buf: &mut Vec<u8>, cap: usize) -> std::io::Result<Option<bool>> {
+    buf.clear();
+    let (mut read_any, mut cut) = (false, false);
+    loop {
+        let chunk = r.fill_buf()?;
+        if chunk.is_empty() { break; }
+        read_any = true;
+        let (take, done) = match memchr::memchr(b'\n', chunk) { Some(i) => (i, true), None => (chunk.len(), false) };
+        let room = cap.saturating_sub(buf.len());
+        if take > room { cut = true; }
+        buf.extend_from_slice(&chunk[..take.min(room)]);
+        r.consume(if done { take + 1 } else { take });
+        if done { break; }
+    }
+    if !read_any { return Ok(None); }
+    if buf.last() == Some(&b'\r') && !cut { buf.pop(); }
+    Ok(Some(cut))
+}
+
+/// The lines of `r` as strings, each cut to `cap` bytes and with invalid UTF-8 replaced, so a
+/// stray byte doesn't end the input the way [`BufRead::lines`] does
+pub fn lossy_lines<R: BufRead>(r: R, cap: usize) -> LossyLines<R> {
+    LossyLines { r, buf: Vec::new(), cap }
+}
+
+/// Iterator from [`lossy_lines`]
+pub struct LossyLines<R> {
+    r: R,
+    buf: Vec<u8>,
+    cap: usize,
+}
+
+impl<R: BufRead> Iterator for LossyLines<R> {
+    type Item = std::io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match read_capped_line(&mut self.r, &mut self.buf, self.cap) {
+            Ok(Some(_)) => Some(Ok(String::from_utf8_lossy(&self.buf).into_owned())),
+            Ok(None) => None,
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+/// Sampling, prefiltering and parsing of one input's lines, from [`Analyzer::line_stage`]
+pub struct LineStage {
+    sampling: Option<Sampling>,
+    /// [`Sampling::seed`] of the input
+    seed: u64,
+    prefilter: Option<PreFilter>,
+    track_patterns: bool,
+    cap: usize,
+    /// Last line the prefilter dropped, in case a suppression notice repeats it
+    skipped: Option<String>,
+}
+
+/// A line after [`LineStage::process`], for [`Analyzer::consume_outcome`]
+pub struct LineOutcome {
+    line_no: u64,
+    line: String,
+    truncated: bool,
+    kind: LineKind,
+}
+
+enum LineKind {
+    /// Left out by `--sample`
+    Sampled,
+    /// Taken in by the parser (a header or directive)
+    Absorbed,
+    Prefiltered,
+    Parsed {
+        result: Result<Option<LogEntry>, String>,
+        /// Pattern that matched, when the parser has named patterns
+        pattern: Option<Option<&'static str>>,
+        /// The dropped line before a suppression notice, which the notice repeats
+        repeated: Option<LogEntry>,
+    },
+}
+
+impl LineStage {
+    /// `line` cut to the analyzer's `max_line_len`, and whether it was; for lines not read
+    /// through [`read_capped_line`]
+    pub fn cap_line(&self, mut line: String) -> (String, bool) {
+        if line.len() <= self.cap { return (line, false); }
+        let mut end = self.cap;
+        while !line.is_char_boundary(end) { end -= 1; }
+        line.truncate(end);
+        (line, true)
+    }
+
+    /// Sample, screen and parse line `line_no` of the input; `truncated` tells whether it was cut
+    pub fn process<P: LogParser + ?Sized>(&mut self, parser: &mut P, line_no: u64, mut line: String, truncated: bool) -> LineOutcome {
+        // Suppression notices pass the prefilter, and repeat the line it dropped before them,
+        // which is parsed after all so filters treat the copies like they would the line
+        let kind = if self.sampling.is_some_and(|s| !s.keep(self.seed, line_no)) {
+            self.skipped = None;
+            LineKind::Sampled
+        } else if parser.absorb(&line) {
+            LineKind::Absorbed
+        } else if self.prefilter.as_mut().is_some_and(|p| !p.keep(&line)) && repeat_count(&line).is_none() {
+            self.skipped = Some(std::mem::take(&mut line));
+            LineKind::Prefiltered
+        } else {
+            let repeated = self.skipped.take()
+                .filter(|_| repeat_count(&line).is_some())
+                .and_then(|prev| parser.parse_line(&prev).ok().flatten());
+            let result = parser.parse_line(&line);
+            let pattern = self.track_patterns.then(|| parser.matched_pattern());
+            LineKind::Parsed { result, pattern, repeated }
+        };
+        LineOutcome { line_no, line, truncated, kind }
+    }
+}
+
+/// An input left out of the analysis, and why.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct SkippedInput {
+    pub source: String,
+    pub reason: String,
+}
+
+/// Text in an entry (its raw record, so stack traces count) hinting that the process died,
+/// matched case-insensitively.
+pub const CRASH_INDICATORS: [&str; 4] = ["panicked at", "Segmentation fault", "OutOfMemoryError", "core dumped"];
+
+/// Crash indicators kept per analysis; later ones are dropped
+pub const MAX_CRASH_SIGNS: usize = 100;
+
+/// Suppression notices: rsyslog and BSD syslogd `message repeated N times: [ ... ]`, syslog-ng
+/// `Last message '...' repeated N times`, kernel `N callbacks suppressed` and the generic
+/// `N similar messages suppressed`
+static REPEAT_NOTICE: LazyLock<Regex> = LazyLock::new(|| Regex::new(concat!(
+    r"(?i)\bmessage(?: '.*')? repeated (?P<a>\d+) times",
+    r"|\b(?P<b>\d+) (?:similar messages?|callbacks) (?:were )?suppressed",
+    r"|\bsuppressed (?P<c>\d+) similar messages?",
+)).unwrap());
+
+/// Copies of one entry restored per notice, against absurd counts
+pub const MAX_REPEATS: u64 = 1_000_000;
+
+/// How many more times the previous line of the input occurred, if `raw` is a suppression notice
+pub fn repeat_count(raw: &str) -> Option<u64> {
+    // Cheap check first: nearly no line is a notice
+    if !raw.contains("epeated") && !raw.contains("uppressed") { return None; }
+    let c = REPEAT_NOTICE.captures(raw)?;
+    let n = ["a", "b", "c"].iter().find_map(|g| c.name(g))?;
+    n.as_str().parse().ok().map(|n: u64| n.min(MAX_REPEATS))
+}
+
+static CRASH_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    let alternatives: Vec<String> = CRASH_INDICATORS.iter().map(|i| regex::escape(i)).collect();
+    Regex::new(&format!("(?i){}", alternatives.join("|"))).unwrap()
+});
+
+/// An entry matching one of [`CRASH_INDICATORS`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CrashSign {
+    pub ts: NaiveDateTime,
+    pub source: String,
+    pub line_no: u64,
+    pub indicator: String,
+    pub message: String,
+    /// Seconds until the next entry of the same input; None while none followed
+    pub silence_secs: Option<i64>,
 }
 
+/// The latest entry of an input.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct InputTail {
+    pub ts: NaiveDateTime,
+    pub line_no: u64,
+    pub level: Level,
+    pub message: String,
+    /// Index of its [`CrashSign`], still waiting for the silence after it
+    pub open_crash: Option<usize>,
+}
+
+/// `java.lang.OutOfMemoryError` timestamps kept per analysis
+pub const MAX_OOM_EVENTS: usize = 100;
+
+/// GC pauses (entries with a `gc_pause_ms` field, as from [`crate::parse::JvmParser`]) and
+/// OutOfMemoryErrors.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct JvmStats {
+    pub pauses_ms: QuantileSketch,
+    /// Pauses of full collections (`Full GC`, `Pause Full`)
+    pub full_gcs: u64,
+    /// Largest heap occupancy left after a collection, and largest heap size, in KiB
+    pub heap_after_max_kb: Option<u64>,
+    pub heap_total_max_kb: Option<u64>,
+    /// When a `java.lang.OutOfMemoryError` was logged, the first [`MAX_OOM_EVENTS`]
+    pub oom_events: Vec<NaiveDateTime>,
+}
+
+impl JvmStats {
+    fn add(&mut self, e: &LogEntry, n: u64) {
+        let Some(ms) = e.fields.get("gc_pause_ms").and_then(|v| v.parse::<f64>().ok()) else { return };
+        self.pauses_ms.add_n(ms, n);
+        if e.fields.get("gc").is_some_and(|gc| gc == "Full GC" || gc == "Pause Full") { self.full_gcs += n; }
+        let kb = |key: &str| e.fields.get(key).and_then(|v| v.parse::<u64>().ok());
+        if let Some(after) = kb("heap_after_kb") { self.heap_after_max_kb = self.heap_after_max_kb.max(Some(after)); }
+        if let Some(total) = kb("heap_total_kb") { self.heap_total_max_kb = self.heap_total_max_kb.max(Some(total)); }
+    }
+
+    fn merge(&mut self, other: JvmStats) {
+        self.pauses_ms.merge(other.pauses_ms);
+        self.full_gcs += other.full_gcs;
+        self.heap_after_max_kb = self.heap_after_max_kb.max(other.heap_after_max_kb);
+        self.heap_total_max_kb = self.heap_total_max_kb.max(other.heap_total_max_kb);
+        let room = MAX_OOM_EVENTS.saturating_sub(self.oom_events.len());
+        self.oom_events.extend(other.oom_events.into_iter().take(room));
+    }
+}
+
+/// A line no pattern could parse, with where it came from.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct MalformedLine {
+    pub source: String,
+    /// 1-based
+    pub line_no: u64,
+    pub text: String,
+    /// Why the parser rejected it
+    #[serde(default)]
+    pub reason: String,
+}
+
+impl std::fmt::Display for MalformedLine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}: {}", self.source, self.line_no, self.reason)
+    }
+}
+
+impl std::error::Error for MalformedLine {}
+
+/// Examples kept per parse failure reason
+pub const FAILURE_EXAMPLES: usize = 3;
+
+/// Line counts for one input.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SourceStats {
+    pub lines: u64,
+    pub malformed: u64,
+}
+
+/// One kind of parse failure (the parser's error with variable parts templated out).
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ParseFailure {
+    pub count: u64,
+    pub examples: Vec<MalformedLine>,
+}
+
+/// Counts for one value of a group-by field.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct GroupStats {
+    pub info: u64,
+    pub warning: u64,
+    pub error: u64,
+    pub error_messages: TopK,
+}
+
+impl GroupStats {
+    /// Error messages are tracked with `limits` but without examples
+    fn new(limits: MessageLimits) -> Self {
+        Self { error_messages: TopK::new(MessageLimits { examples: 0, ..limits }), ..Default::default() }
+    }
+
+    fn add(&mut self, e: &LogEntry, n: u64) {
+        match e.level { Level::Info => self.info += n, Level::Warning => self.warning += n, Level::Error => self.error += n }
+        if matches!(e.level, Level::Error) { self.error_messages.add_n(&e.message, n, Vec::new()); }
+    }
+
+    fn merge(&mut self, other: GroupStats) {
+        self.info += other.info;
+        self.warning += other.warning;
+        self.error += other.error;
+        self.error_messages.merge(other.error_messages);
+    }
+}
+
+/// Fields naming the host an entry came from, in order of preference: `host` (syslog, journald,
+/// GELF, Windows events), `hostname` (JSON loggers) and `kubernetes.host` (Fluent Bit's node name)
+pub const HOST_FIELDS: [&str; 3] = ["host", "hostname", "kubernetes.host"];
+
+/// The host `e` came from, when its parser extracted one
+pub fn entry_host(e: &LogEntry) -> Option<&str> {
+    HOST_FIELDS.iter().find_map(|k| e.fields.get(*k)).map(String::as_str)
+}
+
+/// Per-field bookkeeping of a retained entry beyond the text: key and value strings, map node
+const FIELD_OVERHEAD: usize = 64;
+
+/// Estimated heap memory held by a retained entry
+fn entry_bytes(e: &LogEntry) -> usize {
+    std::mem::size_of::<LogEntry>() + e.message.len() + e.fields.iter().map(|(k, v)| k.len() + v.len() + FIELD_OVERHEAD).sum::<usize>()
+}
+
+/// When one error template occurred, for `--top-errors-by`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ErrorHistory {
+    pub count: u64,
+    pub first_seen: NaiveDateTime,
+    pub last_seen: NaiveDateTime,
+    /// Period start -> errors
+    pub per_period: BTreeMap<NaiveDateTime, u64>,
+}
+
+impl ErrorHistory {
+    fn new(ts: NaiveDateTime) -> Self { Self { count: 0, first_seen: ts, last_seen: ts, per_period: BTreeMap::new() } }
+
+    fn merge(&mut self, other: ErrorHistory) {
+        self.count += other.count;
+        self.first_seen = self.first_seen.min(other.first_seen);
+        self.last_seen = self.last_seen.max(other.last_seen);
+        for (p, n) in other.per_period { *self.per_period.entry(p).or_default() += n; }
+    }
+}
+
+/// Group value used for entries lacking the group-by field.
+pub const NO_GROUP: &str = "(none)";
+
 impl Analyzer {
-    pub fn new(granularity: Granularity) -> Self { Self { granularity, ..Default::default() } }
+    pub fn new(granularity: Granularity, filters: Filters) -> Self {
+        let auto_granularity = granularity == Granularity::Auto;
+        let granularity = if auto_granularity { Granularity::Second } else { granularity };
+        Self {
+            granularity, auto_granularity, filters, retain_malformed: DEFAULT_MALFORMED_SAMPLES, prefilter: true,
+            max_line_len: DEFAULT_MAX_LINE_LEN, ..Default::default()
+        }
+    }
 
-    fn bucket(&self, ts: NaiveDateTime) -> NaiveDateTime {
-        match self.granularity {
-            Granularity::Minute => ts.with_second(0).unwrap().with_nanosecond(0).unwrap(),
-            Granularity::Hour => ts.with_minute(0).unwrap().with_second(0).unwrap().with_nanosecond(0).unwrap(),
-            Granularity::Day => NaiveDate::from_ymd_opt(ts.year(), ts.month(), ts.day()).unwrap().and_hms_opt(0,0,0).unwrap(),
+    /// Empty analyzer with the same configuration, for per-file partial results.
+    pub fn new_partial(&self) -> Self {
+        Self {
+            granularity: self.granularity,
+            auto_granularity: self.auto_granularity,
+            filters: self.filters.clone(),
+            group_by: self.group_by.clone(),
+            retain_entries: self.retain_entries,
+            retain_malformed: self.retain_malformed,
+            sampling: self.sampling,
+            histogram: self.histogram.clone(),
+            top_value_fields: self.top_value_fields.clone(),
+            tracked_keywords: self.tracked_keywords.clone(),
+            quantile_fields: self.quantile_fields.clone(),
+            prefilter: self.prefilter,
+            error_history_period: self.error_history_period,
+            max_line_len: self.max_line_len,
+            track_noise: self.track_noise,
+            recorder: self.recorder.clone(),
+            spill: self.spill.clone(),
+            ts_precision: self.ts_precision,
+            error_messages: TopK::new(self.error_messages.limits),
+            error_precursors: TopK::new(self.error_messages.limits),
+            message_templates: TopK::new(self.error_messages.limits),
+            ..Default::default()
         }
     }
 
-    pub fn consume_file<P: LogParser>(&mut self, parser: &mut P, path: &std::path::Path) -> Result<()> {
-        let f = File::open(path)?; let r = BufReader::new(f);
-        for line in r.lines() {
-            let line = line?;
-            match parser.parse_line(&line) {
-                Ok(Some(entry)) => { self.consume_entry(entry); },
-                Ok(None) => { self.malformed_lines += 1; },
-                Err(_) => { self.malformed_lines += 1; },
+    /// Bytes `entries` may take: half the [`spill`](Self::spill) budget, unlimited without one
+    fn entry_budget(&self) -> usize {
+        self.spill.as_ref().map_or(usize::MAX, |s| s.lock().unwrap_or_else(|e| e.into_inner()).budget / 2)
+    }
+
+    /// Merge the error messages spilled to disk back into `error_messages`; a no-op without
+    /// [`spill`](Self::spill).
+    pub fn finish_spill(&mut self) -> Result<()> {
+        let Some(spill) = &self.spill else { return Ok(()) };
+        spill.lock().unwrap_or_else(|e| e.into_inner()).merge_into(&mut self.error_messages)
+    }
+
+    /// Fold another partial result (e.g. from a single file) into this one.
+    pub fn merge(&mut self, mut other: Analyzer) {
+        // Auto partials may have coarsened differently
+        if other.granularity != self.granularity {
+            let g = if other.granularity.approx_secs() > self.granularity.approx_secs() { other.granularity } else { self.granularity };
+            self.rebucket(g);
+            other.rebucket(g);
+        }
+        self.info += other.info;
+        self.warning += other.warning;
+        self.error += other.error;
+        self.malformed_lines += other.malformed_lines;
+        self.lines_read += other.lines_read;
+        self.lines_sampled += other.lines_sampled;
+        self.lines_prefiltered += other.lines_prefiltered;
+        self.lines_truncated += other.lines_truncated;
+        self.repeats_restored += other.repeats_restored;
+        self.skipped_inputs.extend(other.skipped_inputs);
+        self.format_detections.extend(other.format_detections);
+        for (k, v) in other.pattern_matches { *self.pattern_matches.entry(k).or_default() += v; }
+        self.pattern_misses += other.pattern_misses;
+        self.first = match (self.first, other.first) { (Some(a), Some(b)) => Some(a.min(b)), (a, b) => a.or(b) };
+        self.last = match (self.last, other.last) { (Some(a), Some(b)) => Some(a.max(b)), (a, b) => a.or(b) };
+        for (level, (first, last)) in other.level_spans {
+            let span = self.level_spans.entry(level).or_insert((first, last));
+            *span = (span.0.min(first), span.1.max(last));
+        }
+        for (k, v) in other.timeline { *self.timeline.entry(k).or_default() += v; }
+        for (k, (entries, errors)) in other.per_minute {
+            let mine = self.per_minute.entry(k).or_default();
+            mine.0 += entries;
+            mine.1 += errors;
+        }
+        match &self.spill {
+            Some(spill) => {
+                let mut spill = spill.lock().unwrap_or_else(|e| e.into_inner());
+                self.error_messages.unbounded = true;
+                if self.error_messages.approx_bytes() + other.error_messages.approx_bytes() + self.entries_bytes > spill.budget {
+                    spill.spill(&mut other.error_messages);
+                }
+                self.error_messages.merge(other.error_messages);
             }
+            None => self.error_messages.merge(other.error_messages),
+        }
+        for (prev, next) in other.transitions {
+            let mine = self.transitions.entry(prev).or_default();
+            for (level, n) in next { *mine.entry(level).or_default() += n; }
+        }
+        self.error_precursors.merge(other.error_precursors);
+        let offset = self.crash_signs.len();
+        let room = MAX_CRASH_SIGNS.saturating_sub(offset);
+        for (source, mut tail) in other.input_tails {
+            tail.open_crash = tail.open_crash.filter(|&i| i < room).map(|i| i + offset);
+            self.input_tails.insert(source, tail);
+        }
+        self.crash_signs.extend(other.crash_signs.into_iter().take(room));
+        self.jvm.merge(other.jvm);
+        self.message_templates.merge(other.message_templates);
+        for (template, h) in other.error_history {
+            match self.error_history.get_mut(&template) {
+                Some(mine) => mine.merge(h),
+                None => { self.error_history.insert(template, h); }
+            }
+        }
+        self.errors_outside_range.extend(other.errors_outside_range);
+        for (k, v) in other.logger_errors { *self.logger_errors.entry(k).or_default() += v; }
+        for (k, v) in other.exceptions { *self.exceptions.entry(k).or_default() += v; }
+        for (b, templates) in other.error_buckets {
+            let mine = self.error_buckets.entry(b).or_default();
+            for (k, v) in templates { *mine.entry(k).or_default() += v; }
+        }
+        for (b, n) in other.warning_buckets { *self.warning_buckets.entry(b).or_default() += n; }
+        for (keyword, buckets) in other.keyword_buckets {
+            let mine = self.keyword_buckets.entry(keyword).or_default();
+            for (b, n) in buckets { *mine.entry(b).or_default() += n; }
+        }
+        for (field, values) in other.groups {
+            let mine = self.groups.entry(field).or_default();
+            for (value, stats) in values { mine.entry(value).or_default().merge(stats); }
+        }
+        for (host, stats) in other.hosts { self.hosts.entry(host).or_default().merge(stats); }
+        let (room, budget) = (self.retain_entries.unwrap_or(0).saturating_sub(self.entries.len()), self.entry_budget());
+        for e in other.entries.into_iter().take(room) {
+            let size = entry_bytes(&e);
+            if self.entries_bytes + size > budget { break; }
+            self.entries_bytes += size;
+            self.entries.push(e);
+        }
+        let room = self.retain_malformed.saturating_sub(self.malformed.len());
+        self.malformed.extend(other.malformed.into_iter().take(room));
+        for (k, v) in other.sources {
+            let mine = self.sources.entry(k).or_default();
+            mine.lines += v.lines;
+            mine.malformed += v.malformed;
+        }
+        for (k, v) in other.parse_failures {
+            let mine = self.parse_failures.entry(k).or_default();
+            mine.count += v.count;
+            let room = FAILURE_EXAMPLES.saturating_sub(mine.examples.len());
+            mine.examples.extend(v.examples.into_iter().take(room));
+        }
+        self.hist_values.extend(other.hist_values);
+        for (field, values) in other.field_values {
+            let mine = self.field_values.entry(field).or_default();
+            for (k, v) in values { *mine.entry(k).or_default() += v; }
+        }
+        self.message_lengths.merge(other.message_lengths);
+        self.inter_arrival.merge(other.inter_arrival);
+        for (field, sketch) in other.field_quantiles { self.field_quantiles.entry(field).or_default().merge(sketch); }
+        self.bound_auto_timeline();
+    }
+
+    /// Choose the final bucket size of an auto granularity from the time span seen so far (never
+    /// finer than the current one). Call before reporting; a no-op for fixed granularities.
+    pub fn settle_granularity(&mut self) {
+        if !self.auto_granularity { return; }
+        let (Some(first), Some(last)) = (self.first, self.last) else { return };
+        let target = Granularity::auto(first, last);
+        if target.approx_secs() > self.granularity.approx_secs() { self.rebucket(target); }
+    }
+
+    /// Move the timeline (and per-bucket error templates, warnings and keywords) to coarser buckets
+    fn rebucket(&mut self, to: Granularity) {
+        self.granularity = to;
+        for (b, n) in std::mem::take(&mut self.timeline) { *self.timeline.entry(to.bucket(b)).or_default() += n; }
+        for (b, templates) in std::mem::take(&mut self.error_buckets) {
+            let mine = self.error_buckets.entry(to.bucket(b)).or_default();
+            for (k, v) in templates { *mine.entry(k).or_default() += v; }
+        }
+        for (b, n) in std::mem::take(&mut self.warning_buckets) { *self.warning_buckets.entry(to.bucket(b)).or_default() += n; }
+        for buckets in self.keyword_buckets.values_mut() {
+            for (b, n) in std::mem::take(buckets) { *buckets.entry(to.bucket(b)).or_default() += n; }
+        }
+    }
+
+    pub fn consume_file<P: LogParser + ?Sized>(&mut self, parser: &mut P, path: &std::path::Path) -> Result<()> {
+        let r = crate::input::open(path)?;
+        self.consume_reader(parser, r, &path.display().to_string())
+    }
+
+    /// `source` labels malformed lines (a file name, `<upload>`, ...). Input that looks binary
+    /// is skipped and listed in `skipped_inputs`.
+    pub fn consume_reader<P: LogParser + ?Sized, R: BufRead>(&mut self, parser: &mut P, r: R, source: &str) -> Result<()> {
+        parser.reset();
+        self.last_arrival.remove(source);
+        let mut r = parser.decode(Box::new(r));
+        if self.skip_binary(&mut r, source)? { return Ok(()); }
+        let r = self.detect_format(parser, r, source)?;
+        self.consume_lines(parser, r, source, 0)
+    }
+
+    fn line_cap(&self) -> usize { if self.max_line_len == 0 { usize::MAX } else { self.max_line_len } }
+
+    fn detect_format<'a, P: LogParser + ?Sized>(&mut self, parser: &mut P, r: Box<dyn BufRead + 'a>, source: &str) -> Result<Box<dyn BufRead + 'a>> {
+        let (r, found) = detect_format(parser, r)?;
+        if let Some(found) = found { self.format_detections.insert(source.to_string(), found); }
+        Ok(r)
+    }
+
+    /// Record `source` as skipped when the start of `r` looks binary
+    fn skip_binary(&mut self, r: &mut dyn BufRead, source: &str) -> Result<bool> {
+        let head = r.fill_buf()?;
+        if !looks_binary(head) { return Ok(false); }
+        let nuls = memchr::memchr_iter(0, head).count();
+        let reason = format!("binary data ({nuls} NUL bytes in the first {} bytes)", head.len());
+        self.skipped_inputs.push(SkippedInput { source: source.to_string(), reason });
+        Ok(true)
+    }
+
+    /// [`consume_reader`](Self::consume_reader) in chunks of `chunk` lines for resumable runs:
+    /// skips the first `skip` lines (already aggregated into `self` by an interrupted run) and
+    /// calls `progress` with the lines consumed so far after each chunk. Parser state from the
+    /// skipped lines (e.g. the inferred syslog year) isn't restored.
+    pub fn consume_resumable<P: LogParser + ?Sized, R: BufRead>(
+        &mut self, parser: &mut P, r: R, source: &str, skip: u64, chunk: usize, mut progress: impl FnMut(&Self, u64) -> Result<()>,
+    ) -> Result<()> {
+        parser.reset();
+        self.last_arrival.remove(source);
+        let mut r = parser.decode(Box::new(r));
+        if self.skip_binary(&mut r, source)? { return Ok(()); }
+        let mut r = self.detect_format(parser, r, source)?;
+        let (mut buf, mut line) = (Vec::new(), Vec::new());
+        for _ in 0..skip {
+            if read_capped_line(&mut r, &mut line, 0)?.is_none() { anyhow::bail!("{source} is shorter than when it was checkpointed"); }
+        }
+        let mut done = skip;
+        loop {
+            buf.clear();
+            let mut lines = 0;
+            // One byte over the cap, so `consume_lines` still sees which lines are too long
+            while lines < chunk && read_capped_line(&mut r, &mut line, self.line_cap().saturating_add(1))?.is_some() {
+                buf.extend_from_slice(&line);
+                buf.push(b'\n');
+                lines += 1;
+            }
+            if lines == 0 { return Ok(()); }
+            self.consume_lines(parser, &buf[..], source, done)?;
+            done += lines as u64;
+            progress(self, done)?;
+        }
+    }
+
+    /// Continue an input whose first `lines_before` lines were already consumed (e.g. data appended
+    /// to a followed file); parser state is kept.
+    /// Lines are cut to `max_line_len` bytes and invalid UTF-8 is replaced.
+    pub fn consume_lines<P: LogParser + ?Sized, R: BufRead>(&mut self, parser: &mut P, mut r: R, source: &str, lines_before: u64) -> Result<()> {
+        let mut stage = self.line_stage(parser, source);
+        let mut buf = Vec::new();
+        let mut line_no = lines_before;
+        while let Some(cut) = read_capped_line(&mut r, &mut buf, self.line_cap())? {
+            line_no += 1;
+            let outcome = stage.process(parser, line_no, String::from_utf8_lossy(&buf).into_owned(), cut);
+            self.consume_outcome(source, outcome);
         }
         Ok(())
     }
 
-    fn consume_entry(&mut self, e: LogEntry) {
-        match e.level { Level::Info => self.info += 1, Level::Warning => self.warning += 1, Level::Error => self.error += 1 }
+    /// The per-line handling of [`consume_lines`](Self::consume_lines) up to the parse, for the
+    /// input `source` read by `parser`; for callers that parse apart from the analyzer, like the
+    /// async ingestion pipeline.
+    pub fn line_stage<P: LogParser + ?Sized>(&mut self, parser: &P, source: &str) -> LineStage {
+        let patterns = parser.pattern_names();
+        for name in &patterns { self.pattern_matches.entry(name.to_string()).or_default(); }
+        LineStage {
+            sampling: self.sampling,
+            seed: Sampling::seed(source),
+            prefilter: if self.prefilter { PreFilter::new(&self.filters, parser) } else { None },
+            track_patterns: !patterns.is_empty(),
+            cap: self.line_cap(),
+            skipped: None,
+        }
+    }
+
+    /// Count and aggregate a line handled by [`LineStage::process`]
+    pub fn consume_outcome(&mut self, source: &str, outcome: LineOutcome) {
+        let LineOutcome { line_no, line, truncated, kind } = outcome;
+        if truncated { self.lines_truncated += 1; }
+        self.lines_read += 1;
+        self.sources.entry(source.to_string()).or_default().lines += 1;
+        let (parsed, pattern, repeated) = match kind {
+            LineKind::Sampled => {
+                self.last_entries.remove(source);
+                return;
+            }
+            LineKind::Absorbed => {
+                self.lines_sampled += 1;
+                return;
+            }
+            LineKind::Prefiltered => {
+                self.lines_sampled += 1;
+                self.lines_prefiltered += 1;
+                self.last_entries.remove(source);
+                return;
+            }
+            LineKind::Parsed { result, pattern, repeated } => (result, pattern, repeated),
+        };
+        self.lines_sampled += 1;
+        if let Some(prev) = repeated { self.last_entries.insert(source.to_string(), prev); }
+        if let Some(r) = &self.recorder { r.lock().unwrap_or_else(|e| e.into_inner()).record(source, line_no, &line, &parsed); }
+        match pattern {
+            Some(Some(name)) => *self.pattern_matches.entry(name.to_string()).or_default() += 1,
+            Some(None) => self.pattern_misses += 1,
+            None => {}
+        }
+        self.consume_result(source, line_no, line, parsed);
+    }
+
+    /// Aggregate line `line_no` of `source` that was parsed elsewhere (e.g. on another thread)
+    /// into `parsed`; the line is counted as read like in [`consume_lines`](Self::consume_lines).
+    pub fn consume_parsed(&mut self, source: &str, line_no: u64, line: String, parsed: Result<Option<LogEntry>, String>) {
+        self.lines_read += 1;
+        self.lines_sampled += 1;
+        self.sources.entry(source.to_string()).or_default().lines += 1;
+        self.consume_result(source, line_no, line, parsed);
+    }
+
+    fn consume_result(&mut self, source: &str, line_no: u64, line: String, parsed: Result<Option<LogEntry>, String>) {
+        match parsed {
+            Ok(Some(mut entry)) => {
+                if self.source_path.as_deref().is_none_or(|p| p != Path::new(source)) {
+                    self.source_path = Some(Arc::from(Path::new(source)));
+                }
+                entry.source = self.source_path.clone();
+                entry.line_no = line_no;
+                let origin = LineOrigin { source, line_no, raw: &line };
+                // A suppression notice stands for that many more copies of the previous entry
+                if let Some((n, prev)) = repeat_count(&line).zip(self.last_entries.get(source)) {
+                    let repeat = LogEntry { ts: entry.ts, line_no, ..prev.clone() };
+                    if n > 0 { self.aggregate(&repeat, n, Some(origin)); }
+                    self.repeats_restored += n;
+                    return;
+                }
+                self.aggregate(&entry, 1, Some(origin));
+                match self.last_entries.get_mut(source) {
+                    Some(last) => *last = entry,
+                    None => { self.last_entries.insert(source.to_string(), entry); }
+                }
+            }
+            Ok(None) => { self.consume_malformed(source, line_no, line, "no pattern matched"); },
+            Err(reason) => { self.consume_malformed(source, line_no, line, &reason); },
+        }
+    }
+
+    fn consume_malformed(&mut self, source: &str, line_no: u64, text: String, reason: &str) {
+        self.malformed_lines += 1;
+        self.last_entries.remove(source);
+        self.sources.entry(source.to_string()).or_default().malformed += 1;
+        let failure = self.parse_failures.entry(message_template(reason)).or_default();
+        failure.count += 1;
+        if failure.examples.len() < FAILURE_EXAMPLES {
+            failure.examples.push(MalformedLine { source: source.to_string(), line_no, text: text.clone(), reason: reason.to_string() });
+        }
+        if self.malformed.len() < self.retain_malformed {
+            self.malformed.push(MalformedLine { source: source.to_string(), line_no, text, reason: reason.to_string() });
+        }
+    }
+
+    /// Aggregate one already-parsed entry (skipped if it fails the filters).
+    pub fn consume_entry(&mut self, e: LogEntry) { self.consume_entry_at(e, None); }
+
+    /// [`consume_entry`](Self::consume_entry) for an entry parsed from `origin`, which is kept as
+    /// an example if it's an error.
+    pub fn consume_entry_at(&mut self, e: LogEntry, origin: Option<LineOrigin>) { self.aggregate(&e, 1, origin); }
+
+    /// Count `e` as `n` identical entries (a suppression notice restores `n` copies at once)
+    fn aggregate(&mut self, e: &LogEntry, n: u64, origin: Option<LineOrigin>) {
+        if !self.filters.pass(e) {
+            if self.error_history_period.is_some() && e.level == Level::Error && self.filters.pass_except_time(e) {
+                self.errors_outside_range.insert(message_template(&e.message));
+            }
+            return;
+        }
+        match e.level { Level::Info => self.info += n, Level::Warning => self.warning += n, Level::Error => self.error += n }
         self.first = Some(self.first.map_or(e.ts, |cur| cur.min(e.ts)));
+        let span = self.level_spans.entry(e.level).or_insert((e.ts, e.ts));
+        *span = (span.0.min(e.ts), span.1.max(e.ts));
         self.last = Some(self.last.map_or(e.ts, |cur| cur.max(e.ts)));
-        let b = self.bucket(e.ts);
-        *self.timeline.entry(b).or_default() += 1;
+        let b = self.granularity.bucket(e.ts);
+        *self.timeline.entry(b).or_default() += n;
+        let minute = self.per_minute.entry(Granularity::Minute.bucket(e.ts)).or_default();
+        minute.0 += n;
+        if matches!(e.level, Level::Error) { minute.1 += n; }
+        for field in &self.group_by {
+            let value = e.fields.get(field).map_or(NO_GROUP, String::as_str);
+            let limits = self.error_messages.limits;
+            self.groups.entry(field.clone()).or_default().entry(value.to_string())
+                .or_insert_with(|| GroupStats::new(limits))
+                .add(e, n);
+        }
+        if let Some(host) = entry_host(e) {
+            let limits = self.error_messages.limits;
+            self.hosts.entry(host.to_string()).or_insert_with(|| GroupStats::new(limits)).add(e, n);
+        }
+        if let Some(v) = self.histogram.as_ref().and_then(|h| h.value(e)) { self.hist_values.extend(std::iter::repeat_n(v, n as usize)); }
+        self.message_lengths.add_n(e.message.chars().count() as f64, n);
+        let source = origin.map_or("", |o| o.source);
+        match self.last_arrival.get_mut(source) {
+            // An out-of-order entry leaves no gap but starts the next one
+            Some(prev) => {
+                if *prev <= e.ts { self.inter_arrival.add((e.ts - *prev).num_milliseconds() as f64 / 1000.0); }
+                *prev = e.ts;
+            }
+            None => { self.last_arrival.insert(source.to_string(), e.ts); }
+        }
+        // Copies arrive together
+        self.inter_arrival.add_n(0.0, n - 1);
+        let crash = CRASH_PATTERN.find(origin.map_or(e.message.as_str(), |o| o.raw)).map(|m| {
+            CRASH_INDICATORS.iter().find(|i| i.eq_ignore_ascii_case(m.as_str())).copied().unwrap_or_default()
+        });
+        if crash == Some("OutOfMemoryError") && self.jvm.oom_events.len() < MAX_OOM_EVENTS { self.jvm.oom_events.push(e.ts); }
+        self.jvm.add(e, n);
+        let open_crash = match crash {
+            Some(indicator) if self.crash_signs.len() < MAX_CRASH_SIGNS => {
+                self.crash_signs.push(CrashSign {
+                    ts: e.ts,
+                    source: source.to_string(),
+                    line_no: e.line_no,
+                    indicator: indicator.to_string(),
+                    message: e.message.clone(),
+                    silence_secs: None,
+                });
+                Some(self.crash_signs.len() - 1)
+            }
+            _ => None,
+        };
+        let tail = InputTail { ts: e.ts, line_no: e.line_no, level: e.level, message: e.message.clone(), open_crash };
+        if let Some(prev) = self.input_tails.insert(source.to_string(), tail) {
+            *self.transitions.entry(prev.level).or_default().entry(e.level).or_default() += 1;
+            if n > 1 { *self.transitions.entry(e.level).or_default().entry(e.level).or_default() += n - 1; }
+            if e.level == Level::Error && prev.level != Level::Error { self.error_precursors.add(&prev.message); }
+            if let Some(sign) = prev.open_crash.and_then(|i| self.crash_signs.get_mut(i)) {
+                sign.silence_secs = Some((e.ts - sign.ts).num_seconds());
+            }
+        }
+        for q in &self.quantile_fields {
+            if let Some(v) = q.value(e) { self.field_quantiles.entry(q.label().to_string()).or_default().add_n(v, n); }
+        }
+        for field in &self.top_value_fields {
+            if let Some(v) = e.fields.get(field) {
+                *self.field_values.entry(field.clone()).or_default().entry(v.clone()).or_default() += n;
+            }
+        }
+        if self.track_noise { self.message_templates.add_n(&message_template(&e.message), n, Vec::new()); }
+        if matches!(e.level, Level::Warning) { *self.warning_buckets.entry(b).or_default() += n; }
+        if !self.tracked_keywords.is_empty() {
+            let msg = e.message.to_lowercase();
+            for k in self.tracked_keywords.iter().filter(|k| msg.contains(k.as_str())) {
+                *self.keyword_buckets.entry(k.clone()).or_default().entry(b).or_default() += n;
+            }
+        }
+        if let Some(exc) = e.fields.get("exception") { *self.exceptions.entry(exc.clone()).or_default() += n; }
+        if let Some(cap) = self.retain_entries {
+            let size = entry_bytes(e);
+            let room = cap.saturating_sub(self.entries.len()).min(n as usize).min(self.entry_budget().saturating_sub(self.entries_bytes) / size);
+            self.entries_bytes += room * size;
+            self.entries.extend(std::iter::repeat_n(e, room).cloned());
+        }
         if matches!(e.level, Level::Error) {
-            *self.error_messages.entry(e.message).or_default() += 1;
+            if let Some(logger) = e.fields.get("logger") { *self.logger_errors.entry(logger.clone()).or_default() += n; }
+            let template = message_template(&e.message);
+            if let Some(period) = self.error_history_period {
+                let h = self.error_history.entry(template.clone()).or_insert_with(|| ErrorHistory::new(e.ts));
+                h.first_seen = h.first_seen.min(e.ts);
+                h.last_seen = h.last_seen.max(e.ts);
+                h.count += n;
+                *h.per_period.entry(period.bucket(e.ts)).or_default() += n;
+            }
+            *self.error_buckets.entry(b).or_default().entry(template).or_default() += n;
+            if let Some(spill) = &self.spill {
+                let mut spill = spill.lock().unwrap_or_else(|e| e.into_inner());
+                self.error_messages.unbounded = true;
+                if self.error_messages.approx_bytes() + self.entries_bytes >= spill.budget {
+                    spill.spill(&mut self.error_messages);
+                }
+            }
+            match origin {
+                Some(o) => self.error_messages.add_n_with_example(&e.message, n, MessageExample {
+                    ts: self.ts_precision.format(e.ts),
+                    source: o.source.to_string(),
+                    line_no: o.line_no,
+                    raw: o.raw.replace(RECORD_LINE_SEP, "\n"),
+                }),
+                None => self.error_messages.add_n(&e.message, n, Vec::new()),
+            }
         }
+        self.bound_auto_timeline();
     }
+
+    /// Coarsen an auto timeline that grew past [`AUTO_WORKING_BUCKETS`]
+    fn bound_auto_timeline(&mut self) {
+        if !self.auto_granularity || self.timeline.len() <= AUTO_WORKING_BUCKETS { return; }
+        let current = self.granularity.approx_secs();
+        let next = [Granularity::Minute, Granularity::Hour, Granularity::Day, Granularity::Month]
+            .into_iter().find(|g| g.approx_secs() > current);
+        if let Some(next) = next { self.rebucket(next); }
+    }
+}
+
+/// Where an entry was read from.
+#[derive(Clone, Copy, Debug)]
+pub struct LineOrigin<'a> {
+    pub source: &'a str,
+    /// 1-based
+    pub line_no: u64,
+    pub raw: &'a str,
 }
\ No newline at end of file
@@ -0,0 +1,117 @@
+//! Mergeable quantile sketch for single-pass percentiles.
+//!
+//! A DDSketch: values are counted in logarithmically sized bins, so any quantile is returned
+//! within `relative_accuracy` of the true value using memory that depends on the range of the
+//! values rather than their number. Sketches with the same accuracy merge exactly, which keeps
+//! per-file partials (cache, checkpoints, parallel runs) as precise as a single pass.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Default relative error of a reported quantile (1%)
+pub const DEFAULT_ACCURACY: f64 = 0.01;
+/// Bins kept per sign; beyond this the bins closest to zero are folded together
+pub const MAX_BINS: usize = 2048;
+/// Magnitudes below this count as zero
+const MIN_INDEXABLE: f64 = 1e-9;
+
+/// Approximate distribution of a stream of numbers.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct QuantileSketch {
+    pub relative_accuracy: f64,
+    /// Bin index -> values in it, for positive values and for the magnitudes of negative ones
+    positive: BTreeMap<i32, u64>,
+    negative: BTreeMap<i32, u64>,
+    zeros: u64,
+    pub count: u64,
+    pub sum: f64,
+    /// Exact extremes (0 while empty)
+    pub min: f64,
+    pub max: f64,
+}
+
+impl Default for QuantileSketch {
+    fn default() -> Self { Self::new(DEFAULT_ACCURACY) }
+}
+
+impl QuantileSketch {
+    pub fn new(relative_accuracy: f64) -> Self {
+        Self { relative_accuracy, positive: BTreeMap::new(), negative: BTreeMap::new(), zeros: 0, count: 0, sum: 0.0, min: 0.0, max: 0.0 }
+    }
+
+    fn gamma(&self) -> f64 { (1.0 + self.relative_accuracy) / (1.0 - self.relative_accuracy) }
+
+    /// Bin of magnitude `v`: values in (gamma^(k-1), gamma^k]
+    fn key(&self, v: f64) -> i32 { (v.ln() / self.gamma().ln()).ceil() as i32 }
+
+    /// Representative value of bin `k`, within `relative_accuracy` of anything in it
+    fn value(&self, k: i32) -> f64 { 2.0 * self.gamma().powi(k) / (self.gamma() + 1.0) }
+
+    /// Count `v`; NaN and infinities are ignored
+    pub fn add(&mut self, v: f64) { self.add_n(v, 1); }
+
+    /// Count `v` `n` times
+    pub fn add_n(&mut self, v: f64, n: u64) {
+        if !v.is_finite() || n == 0 { return; }
+        if self.count == 0 { (self.min, self.max) = (v, v); } else { (self.min, self.max) = (self.min.min(v), self.max.max(v)); }
+        self.count += n;
+        self.sum += v * n as f64;
+        if v.abs() < MIN_INDEXABLE {
+            self.zeros += n;
+            return;
+        }
+        let key = self.key(v.abs());
+        let bins = if v > 0.0 { &mut self.positive } else { &mut self.negative };
+        *bins.entry(key).or_default() += n;
+        if bins.len() > MAX_BINS { collapse(bins); }
+    }
+
+    /// Fold `other` in; its bins are re-keyed when its accuracy differs.
+    pub fn merge(&mut self, other: QuantileSketch) {
+        if other.count == 0 { return; }
+        if self.count == 0 { (self.min, self.max) = (other.min, other.max); } else { (self.min, self.max) = (self.min.min(other.min), self.max.max(other.max)); }
+        self.count += other.count;
+        self.sum += other.sum;
+        self.zeros += other.zeros;
+        let same = other.relative_accuracy == self.relative_accuracy;
+        let rekey = |bins: &BTreeMap<i32, u64>| -> Vec<(i32, u64)> {
+            bins.iter().map(|(&k, &n)| (if same { k } else { self.key(other.value(k)) }, n)).collect()
+        };
+        let (positive, negative) = (rekey(&other.positive), rekey(&other.negative));
+        for (k, n) in positive { *self.positive.entry(k).or_default() += n; }
+        for (k, n) in negative { *self.negative.entry(k).or_default() += n; }
+        if self.positive.len() > MAX_BINS { collapse(&mut self.positive); }
+        if self.negative.len() > MAX_BINS { collapse(&mut self.negative); }
+    }
+
+    pub fn is_empty(&self) -> bool { self.count == 0 }
+
+    pub fn mean(&self) -> Option<f64> { (self.count > 0).then(|| self.sum / self.count as f64) }
+
+    /// The `q` quantile (0..=1), None while empty
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        if self.count == 0 { return None; }
+        let rank = (q.clamp(0.0, 1.0) * (self.count - 1) as f64) as u64;
+        let mut seen = 0;
+        // Ascending: most negative first, then zero, then positive
+        for (&k, &n) in self.negative.iter().rev() {
+            seen += n;
+            if seen > rank { return Some((-self.value(k)).clamp(self.min, self.max)); }
+        }
+        seen += self.zeros;
+        if seen > rank { return Some(0f64.clamp(self.min, self.max)); }
+        for (&k, &n) in &self.positive {
+            seen += n;
+            if seen > rank { return Some(self.value(k).clamp(self.min, self.max)); }
+        }
+        Some(self.max)
+    }
+}
+
+/// Fold the lowest bins into one until at most [`MAX_BINS`] remain
+fn collapse(bins: &mut BTreeMap<i32, u64>) {
+    while bins.len() > MAX_BINS {
+        let (_, n) = bins.pop_first().unwrap();
+        *bins.first_entry().unwrap().get_mut() += n;
+    }
+}
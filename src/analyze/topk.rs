@@ -0,0 +1,180 @@
+//! Bounded "most frequent messages" counter.
+//!
+//! Uses the space-saving algorithm: at most `capacity` messages are tracked; a new message
+//! evicts the least frequent one and inherits its count, so counts are upper bounds and any
+//! message more frequent than `total / capacity` is guaranteed to be kept, even when every error
+//! carries a unique id.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+/// Messages tracked by default (`--max-error-messages`)
+pub const DEFAULT_MESSAGE_CAPACITY: usize = 10_000;
+/// Messages are cut to this many characters by default (`--max-message-len`)
+pub const DEFAULT_MESSAGE_LEN: usize = 500;
+/// Raw lines kept per message by default (`--error-examples`)
+pub const DEFAULT_EXAMPLES: usize = 3;
+
+/// Size limits for a [`TopK`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageLimits {
+    /// Distinct messages tracked at once
+    pub capacity: usize,
+    /// Longer messages (and example lines) are truncated to this many characters (with a trailing `…`)
+    pub max_len: usize,
+    /// Example occurrences kept per message
+    pub examples: usize,
+}
+
+impl Default for MessageLimits {
+    fn default() -> Self { Self { capacity: DEFAULT_MESSAGE_CAPACITY, max_len: DEFAULT_MESSAGE_LEN, examples: DEFAULT_EXAMPLES } }
+}
+
+/// One occurrence of a message: evidence behind its count.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(default)]
+pub struct MessageExample {
+    pub ts: String,
+    pub source: String,
+    /// 1-based
+    pub line_no: u64,
+    /// The raw line (multi-line records joined with newlines)
+    pub raw: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct Slot {
+    count: u64,
+    examples: Vec<MessageExample>,
+}
+
+/// Approximate message counts in bounded memory.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TopK {
+    pub limits: MessageLimits,
+    counts: HashMap<String, Slot>,
+    /// Messages evicted to make room; non-zero means counts may be overestimates
+    pub evicted: u64,
+    /// (count, message), for finding the least frequent message; rebuilt after deserializing
+    #[serde(skip)]
+    index: BTreeSet<(u64, String)>,
+    /// Estimated heap bytes of `counts` and `index`, for [`approx_bytes`](Self::approx_bytes)
+    #[serde(skip)]
+    bytes: usize,
+    /// Never evict: the owner spills to disk instead (see [`crate::spill`])
+    #[serde(skip)]
+    pub(crate) unbounded: bool,
+}
+
+/// Per-message bookkeeping beyond the text: hash map entry, index entry, example vectors
+const SLOT_OVERHEAD: usize = 128;
+
+fn example_bytes(examples: &[MessageExample]) -> usize {
+    examples.iter().map(|e| e.ts.len() + e.source.len() + e.raw.len() + std::mem::size_of::<MessageExample>()).sum()
+}
+
+/// The message is held twice, as key and in the index
+fn slot_bytes(msg: &str, slot: &Slot) -> usize { 2 * msg.len() + example_bytes(&slot.examples) + SLOT_OVERHEAD }
+
+impl TopK {
+    pub fn new(limits: MessageLimits) -> Self { Self { limits, ..Default::default() } }
+
+    /// Count one occurrence of `msg`.
+    pub fn add(&mut self, msg: &str) { self.add_n(msg, 1, Vec::new()); }
+
+    /// Count one occurrence of `msg`, keeping `example` if the message has room for more.
+    pub fn add_with_example(&mut self, msg: &str, example: MessageExample) { self.add_n_with_example(msg, 1, example); }
+
+    /// Count `n` occurrences of `msg` with one `example` among them.
+    pub fn add_n_with_example(&mut self, msg: &str, n: u64, mut example: MessageExample) {
+        example.raw = truncate(&example.raw, self.limits.max_len);
+        self.add_n(msg, n, vec![example]);
+    }
+
+    pub(crate) fn add_n(&mut self, msg: &str, n: u64, mut examples: Vec<MessageExample>) {
+        self.sync_index();
+        let key = truncate(msg, self.limits.max_len);
+        let keep = self.limits.examples;
+        if let Some(slot) = self.counts.get_mut(key.as_str()) {
+            self.index.remove(&(slot.count, key.clone()));
+            slot.count += n;
+            let room = keep.saturating_sub(slot.examples.len());
+            examples.truncate(room);
+            self.bytes += example_bytes(&examples);
+            slot.examples.extend(examples);
+            self.index.insert((slot.count, key));
+            return;
+        }
+        let mut count = n;
+        if !self.unbounded && self.is_full() {
+            let Some((min, victim)) = self.index.pop_first() else { return };
+            if let Some(slot) = self.counts.remove(&victim) { self.bytes -= slot_bytes(&victim, &slot); }
+            self.evicted += 1;
+            count += min;
+        }
+        examples.truncate(keep);
+        let slot = Slot { count, examples };
+        self.bytes += slot_bytes(&key, &slot);
+        self.index.insert((count, key.clone()));
+        self.counts.insert(key, slot);
+    }
+
+    /// A new message would evict one
+    pub fn is_full(&self) -> bool { self.counts.len() >= self.limits.capacity.max(1) }
+
+    /// Estimated heap memory held by the messages and their examples
+    pub fn approx_bytes(&self) -> usize { self.bytes }
+
+    /// Remove every message, returning (message, count, examples) in message order
+    pub fn take_sorted(&mut self) -> Vec<(String, u64, Vec<MessageExample>)> {
+        let mut out: Vec<_> = self.counts.drain().map(|(k, slot)| (k, slot.count, slot.examples)).collect();
+        out.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        self.index.clear();
+        self.bytes = 0;
+        out
+    }
+
+    /// Fold in another counter (e.g. a per-file partial result).
+    pub fn merge(&mut self, other: TopK) {
+        self.evicted += other.evicted;
+        let mut incoming: Vec<_> = other.counts.into_iter().collect();
+        // Largest first, so the heavy hitters of `other` aren't evicted by its own tail
+        incoming.sort_by(|a, b| b.1.count.cmp(&a.1.count).then_with(|| a.0.cmp(&b.0)));
+        for (msg, slot) in incoming { self.add_n(&msg, slot.count, slot.examples); }
+    }
+
+    /// The `n` most frequent messages, most frequent first (ties by message).
+    pub fn top(&self, n: usize) -> Vec<(String, u64)> {
+        let mut out: Vec<(String, u64)> = self.counts.iter().map(|(k, slot)| (k.clone(), slot.count)).collect();
+        out.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        out.truncate(n);
+        out
+    }
+
+    /// Example occurrences kept for `msg` (as returned by [`top`](Self::top))
+    pub fn examples(&self, msg: &str) -> &[MessageExample] {
+        self.counts.get(msg).map_or(&[], |slot| &slot.examples)
+    }
+
+    /// Whether counts may be overestimated because messages were evicted
+    pub fn is_approximate(&self) -> bool { self.evicted > 0 }
+
+    pub fn len(&self) -> usize { self.counts.len() }
+
+    pub fn is_empty(&self) -> bool { self.counts.is_empty() }
+
+    fn sync_index(&mut self) {
+        if self.index.len() != self.counts.len() {
+            self.index = self.counts.iter().map(|(k, slot)| (slot.count, k.clone())).collect();
+            self.bytes = self.counts.iter().map(|(k, slot)| slot_bytes(k, slot)).sum();
+        }
+    }
+}
+
+/// `msg` cut to `max_len` characters, marking the cut with `…`
+fn truncate(msg: &str, max_len: usize) -> String {
+    if max_len == 0 || msg.chars().nth(max_len).is_none() { return msg.to_string(); }
+    let mut out: String = msg.chars().take(max_len - 1).collect();
+    out.push('…');
+    out
+}
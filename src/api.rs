@@ -0,0 +1,166 @@
+#![cfg(feature = "api")]
+
+use crate::analyze::{Analyzer, Filters, Granularity};
+use crate::discover::{gather_log_files, DiscoveryOptions};
+use crate::model::{Level, LevelSet, LogEntry};
+use crate::parse::DefaultLogParser;
+use crate::report::{build_summary, JsonSummary, ReportOptions};
+use crate::spill::Spill;
+use axum::body::Bytes;
+use axum::extract::{DefaultBodyLimit, Query, State};
+use axum::http::StatusCode;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+/// Upper bound on entries kept for `GET /entries`
+const MAX_RETAINED_ENTRIES: usize = 1_000_000;
+const DEFAULT_ENTRIES_LIMIT: usize = 1000;
+
+/// How `serve-api` listens and what it may read
+#[derive(Clone, Debug)]
+pub struct ApiOptions {
+    /// `host:port`, or `:port` for localhost
+    pub listen: String,
+    /// Directory `path=` may point into; without it only uploads are analyzed
+    pub root: Option<PathBuf>,
+    /// Request body limit in bytes
+    pub max_upload: usize,
+    /// `--max-memory` of each analysis: error messages spill to disk beyond it, and entries kept
+    /// for `GET /entries` stop at half of it
+    pub max_memory: Option<usize>,
+}
+
+/// Result of the most recent `POST /analyze`
+struct Analysis {
+    summary: JsonSummary,
+    entries: Vec<LogEntry>,
+}
+
+struct AppState {
+    last: RwLock<Option<Analysis>>,
+    /// Canonical `--root`
+    root: Option<PathBuf>,
+    max_memory: Option<usize>,
+}
+
+type Shared = Arc<AppState>;
+type ApiError = (StatusCode, String);
+
+/// Query parameters shared by `/analyze` and `/entries`
+#[derive(Debug, Default, Deserialize)]
+struct Params {
+    /// File or directory under the server's `--root`; when absent `/analyze` reads the request
+    /// body as log text
+    path: Option<PathBuf>,
+    keyword: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+    level: Option<String>,
+    min_level: Option<String>,
+    granularity: Option<String>,
+    limit: Option<usize>,
+}
+
+impl Params {
+    fn filters(&self) -> Result<Filters, ApiError> {
+        let mut filters = Filters::from_cli(self.keyword.as_deref(), self.from.as_deref(), self.to.as_deref(), &[])
+            .map_err(|e| bad_request(e.to_string()))?;
+        // `level` takes a comma-separated set, e.g. `error,warning` or `all`
+        if let Some(levels) = &self.level { filters.levels = levels.parse::<LevelSet>().map_err(bad_request)?; }
+        filters.min_level = self.min_level.as_deref().map(str::parse::<Level>).transpose().map_err(bad_request)?;
+        Ok(filters)
+    }
+}
+
+fn bad_request(msg: String) -> ApiError { (StatusCode::BAD_REQUEST, msg) }
+
+/// `path` resolved under `root`; anything outside it (through `..` or symlinks) is refused
+fn resolve_under(root: Option<&Path>, path: &Path) -> Result<PathBuf, ApiError> {
+    let root = root.ok_or((StatusCode::FORBIDDEN, "Reading server paths needs serve-api --root".into()))?;
+    let resolved = root.join(path).canonicalize().map_err(|e| bad_request(format!("{}: {e}", path.display())))?;
+    if !resolved.starts_with(root) { return Err((StatusCode::FORBIDDEN, format!("{} is outside the API root", path.display()))); }
+    Ok(resolved)
+}
+
+/// Log files found at `path` (already resolved under the canonical `root`), each canonicalized;
+/// files that lead outside the root, like a symlink inside a directory, are left out
+fn files_under(root: &Path, path: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let files = gather_log_files(&[path.to_path_buf()], &DiscoveryOptions::default())?;
+    Ok(files.iter().filter_map(|f| f.canonicalize().ok()).filter(|f| f.starts_with(root)).collect())
+}
+
+/// The API's routes, reading server paths under `opts.root`
+pub fn router(opts: &ApiOptions) -> anyhow::Result<Router> {
+    let root = opts.root.as_deref().map(Path::canonicalize).transpose()?;
+    let state: Shared = Arc::new(AppState { last: RwLock::new(None), root, max_memory: opts.max_memory });
+    Ok(Router::new()
+        .route("/analyze", post(analyze))
+        .route("/summary", get(summary))
+        .route("/entries", get(entries))
+        .layer(DefaultBodyLimit::max(opts.max_upload))
+        .with_state(state))
+}
+
+/// Run the HTTP API until the process is killed.
+pub fn serve(opts: &ApiOptions) -> anyhow::Result<()> {
+    let addr = if opts.listen.starts_with(':') { format!("127.0.0.1{}", opts.listen) } else { opts.listen.clone() };
+    let app = router(opts)?;
+
+    tokio::runtime::Runtime::new()?.block_on(async {
+        let listener = tokio::net::TcpListener::bind(&addr).await?;
+        println!("Listening on http://{}", listener.local_addr()?);
+        axum::serve(listener, app).await?;
+        Ok(())
+    })
+}
+
+async fn analyze(State(state): State<Shared>, Query(params): Query<Params>, body: Bytes) -> Result<Json<JsonSummary>, ApiError> {
+    let filters = params.filters()?;
+    let gran = params.granularity.as_deref().map(str::parse::<Granularity>).transpose().map_err(bad_request)?.unwrap_or_default();
+    // A path resolves only when there is a root, which discovery checks each file against
+    let path = params.path.as_deref().map(|p| resolve_under(state.root.as_deref(), p)).transpose()?.zip(state.root.clone());
+    let max_memory = state.max_memory;
+
+    let analyzer = tokio::task::spawn_blocking(move || -> anyhow::Result<Analyzer> {
+        let mut parser = DefaultLogParser::new();
+        let mut analyzer = Analyzer::new(gran, filters);
+        analyzer.retain_entries = Some(MAX_RETAINED_ENTRIES);
+        analyzer.spill = max_memory.map(Spill::new).transpose()?;
+        match &path {
+            Some((path, root)) => {
+                let files = files_under(root, path)?;
+                if files.is_empty() { anyhow::bail!("No log files found at {}", path.display()); }
+                for f in files { analyzer.consume_file(&mut parser, &f)?; }
+            }
+            None => analyzer.consume_reader(&mut parser, &body[..], "<upload>")?,
+        }
+        analyzer.finish_spill()?;
+        analyzer.settle_granularity();
+        Ok(analyzer)
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map_err(|e| bad_request(e.to_string()))?;
+
+    let summary = build_summary(&analyzer, &ReportOptions::default());
+    let response = summary.clone();
+    *state.last.write().unwrap() = Some(Analysis { summary, entries: analyzer.entries });
+    Ok(Json(response))
+}
+
+async fn summary(State(state): State<Shared>) -> Result<Json<JsonSummary>, ApiError> {
+    state.last.read().unwrap().as_ref()
+        .map(|a| Json(a.summary.clone()))
+        .ok_or((StatusCode::NOT_FOUND, "Nothing analyzed yet; POST /analyze first".into()))
+}
+
+async fn entries(State(state): State<Shared>, Query(params): Query<Params>) -> Result<Json<Vec<LogEntry>>, ApiError> {
+    let filters = params.filters()?;
+    let guard = state.last.read().unwrap();
+    let analysis = guard.as_ref().ok_or((StatusCode::NOT_FOUND, "Nothing analyzed yet; POST /analyze first".into()))?;
+    let limit = params.limit.unwrap_or(DEFAULT_ENTRIES_LIMIT);
+    Ok(Json(analysis.entries.iter().filter(|e| filters.pass(e)).take(limit).cloned().collect()))
+}
@@ -0,0 +1,56 @@
+use crate::analyze::Analyzer;
+use anyhow::Result;
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+use std::fs::Metadata;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Bumped whenever the layout of cached aggregates changes.
+const CACHE_VERSION: u32 = 26;
+
+/// Per-file partial aggregates from previous runs, keyed by path, size and mtime.
+#[derive(Default, Serialize, Deserialize)]
+pub struct AnalysisCache {
+    version: u32,
+    /// Granularity + filters the partials were computed with; a mismatch invalidates everything
+    settings: String,
+    files: HashMap<PathBuf, CachedFile>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedFile {
+    size: u64,
+    mtime: Option<SystemTime>,
+    partial: Analyzer,
+}
+
+impl AnalysisCache {
+    /// Load the cache file, falling back to an empty cache if it is missing, unreadable or stale.
+    pub fn load(path: &Path, settings: String) -> Self {
+        let cached = std::fs::read(path).ok()
+            .and_then(|bytes| bincode::deserialize::<AnalysisCache>(&bytes).ok())
+            .filter(|c| c.version == CACHE_VERSION && c.settings == settings);
+        cached.unwrap_or(Self { version: CACHE_VERSION, settings, files: HashMap::new() })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, bincode::serialize(self)?)?;
+        Ok(())
+    }
+
+    /// Cached aggregates for `file` if it has not changed since they were stored.
+    pub fn lookup(&self, file: &Path, meta: &Metadata) -> Option<&Analyzer> {
+        let hit = self.files.get(&key(file))?;
+        (hit.size == meta.len() && hit.mtime == meta.modified().ok()).then_some(&hit.partial)
+    }
+
+    pub fn store(&mut self, file: &Path, meta: &Metadata, partial: &Analyzer) {
+        let entry = CachedFile { size: meta.len(), mtime: meta.modified().ok(), partial: partial.clone() };
+        self.files.insert(key(file), entry);
+    }
+}
+
+fn key(file: &Path) -> PathBuf {
+    file.canonicalize().unwrap_or_else(|_| file.to_path_buf())
+}
@@ -0,0 +1,92 @@
+//! `--checkpoint` / `--resume`: periodic snapshots of a long run, so a crash or Ctrl-C doesn't
+//! lose hours of work over huge archives.
+
+use crate::analyze::Analyzer;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Bumped whenever the checkpoint layout changes.
+const CHECKPOINT_VERSION: u32 = 18;
+
+/// Lines consumed between checks whether a checkpoint is due
+pub const CHUNK_LINES: usize = 50_000;
+
+/// State saved by an earlier run.
+#[derive(Deserialize)]
+pub struct Checkpoint {
+    version: u32,
+    settings: String,
+    inputs: Vec<PathBuf>,
+    /// Inputs fully processed (a prefix of the run's inputs), merged into `analyzer`
+    pub files_done: usize,
+    pub analyzer: Analyzer,
+    /// The input being read: lines consumed so far and their aggregates
+    pub current: Option<(u64, Analyzer)>,
+}
+
+/// Borrowed form of [`Checkpoint`], so saving doesn't clone the aggregates.
+#[derive(Serialize)]
+struct CheckpointRef<'a> {
+    version: u32,
+    settings: &'a str,
+    inputs: &'a [PathBuf],
+    files_done: usize,
+    analyzer: &'a Analyzer,
+    current: Option<(u64, &'a Analyzer)>,
+}
+
+/// Writes checkpoints for one run at most every `every`.
+pub struct Checkpointer {
+    path: PathBuf,
+    /// Same fingerprint the cache uses; resuming with other settings would mix incompatible aggregates
+    settings: String,
+    inputs: Vec<PathBuf>,
+    every: Duration,
+    last: Instant,
+}
+
+impl Checkpointer {
+    pub fn new(path: &Path, settings: String, inputs: &[PathBuf], every: Duration) -> Self {
+        Self { path: path.to_path_buf(), settings, inputs: inputs.to_vec(), every, last: Instant::now() }
+    }
+
+    /// The state saved by an interrupted run with the same settings and inputs, or None if there
+    /// is no checkpoint file.
+    pub fn resume(&self) -> Result<Option<Checkpoint>> {
+        let bytes = match std::fs::read(&self.path) {
+            Ok(b) => b,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).with_context(|| format!("Reading checkpoint {}", self.path.display())),
+        };
+        let cp: Checkpoint = bincode::deserialize(&bytes).with_context(|| format!("Corrupt checkpoint {}", self.path.display()))?;
+        if cp.version != CHECKPOINT_VERSION { bail!("Checkpoint {} is from an incompatible version", self.path.display()); }
+        if cp.settings != self.settings { bail!("Checkpoint {} was written with different options", self.path.display()); }
+        if cp.inputs != self.inputs { bail!("Checkpoint {} was written for different inputs", self.path.display()); }
+        Ok(Some(cp))
+    }
+
+    /// Save the progress if a checkpoint is due (or `force`).
+    pub fn save(&mut self, files_done: usize, analyzer: &Analyzer, current: Option<(u64, &Analyzer)>, force: bool) -> Result<()> {
+        if !force && self.last.elapsed() < self.every { return Ok(()); }
+        let cp = CheckpointRef {
+            version: CHECKPOINT_VERSION, settings: &self.settings, inputs: &self.inputs, files_done, analyzer, current,
+        };
+        // Write then rename, so a crash mid-write keeps the previous checkpoint intact
+        let tmp = self.path.with_extension("tmp");
+        std::fs::write(&tmp, bincode::serialize(&cp)?)
+            .and_then(|()| std::fs::rename(&tmp, &self.path))
+            .with_context(|| format!("Saving checkpoint {}", self.path.display()))?;
+        self.last = Instant::now();
+        Ok(())
+    }
+
+    /// Drop the checkpoint once the run has completed.
+    pub fn finish(&self) -> Result<()> {
+        match std::fs::remove_file(&self.path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,27 @@
+//! `--config` file: TOML settings too detailed for command-line flags.
+//!
+//! ```toml
+//! [health]
+//! error = 1.0      # weight of an ERROR entry in the health score
+//! warning = 0.2
+//! malformed = 0.1
+//! ```
+
+use crate::report::HealthWeights;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::Path;
+
+/// Settings read from a config file; missing sections and keys take their defaults.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub health: HealthWeights,
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path).with_context(|| format!("Failed reading config {}", path.display()))?;
+        toml::from_str(&text).with_context(|| format!("Invalid config {}", path.display()))
+    }
+}
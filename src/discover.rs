@@ -0,0 +1,122 @@
+use anyhow::{Context, Result};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use regex::Regex;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use walkdir::WalkDir;
+
+/// Controls which files are picked up when walking input directories.
+#[derive(Clone, Debug, Default)]
+pub struct DiscoveryOptions {
+    /// Globs matched against paths relative to the input directory; if non-empty, a file must match one
+    pub include: Vec<String>,
+    /// Globs matched against paths relative to the input directory; matching files are skipped
+    pub exclude: Vec<String>,
+    /// File extensions to scan, without the dot (empty = `log`)
+    pub extensions: Vec<String>,
+    /// Regex matched against the file name; matching files are scanned regardless of extension
+    pub file_pattern: Option<String>,
+    /// Maximum recursion depth below each input directory (1 = direct children only)
+    pub max_depth: Option<usize>,
+    /// Also pick up rotated siblings of each file (`app.log.1`, `app.log.2.gz`, `app.log-20250905`)
+    pub include_rotated: bool,
+}
+
+fn build_set(globs: &[String]) -> Result<GlobSet> {
+    let mut b = GlobSetBuilder::new();
+    for g in globs { b.add(Glob::new(g).with_context(|| format!("Invalid glob: {g}"))?); }
+    Ok(b.build()?)
+}
+
+/// Expand inputs into log files. Explicit file paths are always kept; directories are
+/// scanned recursively for matching extensions / file names subject to the include/exclude globs.
+pub fn gather_log_files(paths: &[PathBuf], opts: &DiscoveryOptions) -> Result<Vec<PathBuf>> {
+    let include = build_set(&opts.include)?;
+    let exclude = build_set(&opts.exclude)?;
+    let file_pattern = opts.file_pattern.as_deref()
+        .map(|p| Regex::new(p).with_context(|| format!("Invalid file pattern: {p}")))
+        .transpose()?;
+    let extensions: Vec<&str> = if opts.extensions.is_empty() {
+        vec!["log"]
+    } else {
+        opts.extensions.iter().map(|e| e.trim_start_matches('.')).collect()
+    };
+    let is_log = |path: &Path| {
+        path.extension().and_then(|e| e.to_str()).is_some_and(|e| extensions.contains(&e))
+            || file_pattern.as_ref().is_some_and(|re| {
+                path.file_name().and_then(|n| n.to_str()).is_some_and(|n| re.is_match(n))
+            })
+    };
+    let wanted = |root: &Path, path: &Path| {
+        let rel = path.strip_prefix(root).unwrap_or(path);
+        (include.is_empty() || include.is_match(rel)) && !exclude.is_match(rel)
+    };
+
+    let mut out = Vec::new();
+    for p in paths {
+        if crate::remote::is_url(p) {
+            out.extend(crate::remote::list(p)?);
+        } else if p.is_file() {
+            out.push(p.clone());
+        } else if p.is_dir() {
+            let mut walk = WalkDir::new(p);
+            if let Some(d) = opts.max_depth { walk = walk.max_depth(d); }
+            for entry in walk.into_iter().flatten() {
+                let path = entry.path();
+                if path.is_file() && is_log(path) && wanted(p, path) {
+                    out.push(path.to_path_buf());
+                }
+            }
+        }
+    }
+    if opts.include_rotated {
+        let mut expanded = Vec::new();
+        for f in out {
+            // Object store listings already include whatever siblings the URL matched
+            let set = if crate::remote::is_url(&f) { vec![f] } else { rotated_set(&f)? };
+            for r in set {
+                if !expanded.contains(&r) { expanded.push(r); }
+            }
+        }
+        out = expanded;
+    }
+    Ok(out)
+}
+
+/// Where a rotated file sits in its set; sorts oldest first.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum RotationAge {
+    /// `app.log-20250905`, `app.log.2025-09-05.gz`: older dates first
+    Dated(String),
+    /// `app.log.3.gz`: higher numbers are older
+    Numbered(std::cmp::Reverse<u64>),
+    /// The live file itself
+    Current,
+}
+
+static ROTATION_SUFFIX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^[.\-_](?:(?P<n>\d{1,4})|(?P<date>\d{4}-?\d{2}-?\d{2}(?:[-_T]?\d{2,6})?))(?:\.gz)?$").unwrap()
+});
+
+/// `path` and its rotated siblings in the same directory, oldest first, so they read as one stream.
+pub fn rotated_set(path: &Path) -> Result<Vec<PathBuf>> {
+    let (Some(dir), Some(name)) = (path.parent(), path.file_name().and_then(|n| n.to_str())) else {
+        return Ok(vec![path.to_path_buf()]);
+    };
+    let dir = if dir.as_os_str().is_empty() { Path::new(".") } else { dir };
+    let mut set = vec![(RotationAge::Current, path.to_path_buf())];
+    for entry in std::fs::read_dir(dir).with_context(|| format!("Listing {}", dir.display()))? {
+        let entry = entry?;
+        let file_name = entry.file_name();
+        let Some(suffix) = file_name.to_str().and_then(|f| f.strip_prefix(name)) else { continue };
+        let Some(caps) = ROTATION_SUFFIX.captures(suffix) else { continue };
+        let age = match (caps.name("n"), caps.name("date")) {
+            (Some(n), _) => RotationAge::Numbered(std::cmp::Reverse(n.as_str().parse()?)),
+            (_, Some(d)) => RotationAge::Dated(d.as_str().replace(['-', '_', 'T'], "")),
+            _ => continue,
+        };
+        set.push((age, path.with_file_name(&file_name)));
+    }
+    set.sort();
+    Ok(set.into_iter().map(|(_, p)| p).collect())
+}
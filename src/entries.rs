@@ -0,0 +1,187 @@
+//! Parsed entries as a lazy iterator, for library users who want to stream entries through their
+//! own logic instead of aggregating them with an [`Analyzer`](crate::analyze::Analyzer):
+//!
+//! ```no_run
+//! # fn main() -> anyhow::Result<()> {
+//! for entry in log_analyzer::entries::parse_file_iter("app.log")? {
+//!     let entry = entry?;
+//!     println!("{} {:?} {}", entry.ts, entry.level, entry.message);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::analyze::{detect_format, looks_binary, read_capped_line, Filters, MalformedLine, PreFilter, DEFAULT_MAX_LINE_LEN};
+use crate::discover::{gather_log_files, DiscoveryOptions};
+use crate::model::LogEntry;
+use crate::parse::{DefaultLogParser, LogParser};
+use anyhow::{Context, Result};
+use std::collections::VecDeque;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Error of an input left out because its start looks binary, as an analysis leaves it out
+#[derive(Clone, Debug)]
+pub struct BinaryInput {
+    pub source: PathBuf,
+}
+
+impl std::fmt::Display for BinaryInput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} looks like a binary file", self.source.display())
+    }
+}
+
+impl std::error::Error for BinaryInput {}
+
+/// Entries of one input, parsed a line at a time. Lines are cut to [`DEFAULT_MAX_LINE_LEN`]
+/// bytes and invalid UTF-8 is replaced, like during an analysis. Lines that don't parse are
+/// skipped unless [`with_malformed`](Self::with_malformed) asks for them; the iterator ends
+/// after the first read error.
+pub struct Entries {
+    parser: Box<dyn LogParser>,
+    lines: Box<dyn BufRead>,
+    source: Arc<Path>,
+    line_no: u64,
+    buf: Vec<u8>,
+    malformed: bool,
+    filters: Option<(Filters, Option<PreFilter>)>,
+    done: bool,
+}
+
+impl Entries {
+    /// Entries of `r`; `source` is what [`LogEntry::source`] and errors will name. Fails with
+    /// [`BinaryInput`] if the start of the input looks binary.
+    pub fn new(mut parser: Box<dyn LogParser>, r: impl BufRead + 'static, source: &Path) -> Result<Self> {
+        parser.reset();
+        let mut r = parser.decode(Box::new(r));
+        if looks_binary(r.fill_buf()?) { return Err(BinaryInput { source: source.to_path_buf() }.into()); }
+        let (lines, _) = detect_format(&mut *parser, r)?;
+        Ok(Self { parser, lines, source: Arc::from(source), line_no: 0, buf: Vec::new(), malformed: false, filters: None, done: false })
+    }
+
+    /// Entries of the file (or object store URL) at `path`; compressed files are decompressed
+    pub fn open(parser: Box<dyn LogParser>, path: &Path) -> Result<Self> {
+        let r = crate::input::open(path).with_context(|| format!("Failed reading {}", path.display()))?;
+        Self::new(parser, r, path)
+    }
+
+    /// Also yield lines that don't parse, as errors holding a [`MalformedLine`] (see
+    /// [`anyhow::Error::downcast_ref`])
+    pub fn with_malformed(mut self, yes: bool) -> Self {
+        self.malformed = yes;
+        self
+    }
+
+    /// Only yield entries passing `filters`; lines the analysis prefilter would skip aren't parsed
+    pub fn with_filters(mut self, filters: &Filters) -> Self {
+        let prefilter = PreFilter::new(filters, &*self.parser);
+        self.filters = Some((filters.clone(), prefilter));
+        self
+    }
+}
+
+impl Iterator for Entries {
+    type Item = Result<LogEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.done { return None; }
+            match read_capped_line(&mut self.lines, &mut self.buf, DEFAULT_MAX_LINE_LEN) {
+                Ok(Some(_)) => {}
+                Ok(None) => {
+                    self.done = true;
+                    return None;
+                }
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(anyhow::Error::new(e).context(format!("Failed reading {}", self.source.display()))));
+                }
+            }
+            self.line_no += 1;
+            let line = String::from_utf8_lossy(&self.buf);
+            if self.parser.absorb(&line) { continue; }
+            if let Some((_, Some(prefilter))) = &mut self.filters {
+                if !prefilter.keep(&line) { continue; }
+            }
+            let reason = match self.parser.parse_line(&line) {
+                Ok(Some(mut entry)) => {
+                    if self.filters.as_ref().is_some_and(|(f, _)| !f.pass(&entry)) { continue; }
+                    entry.source = Some(self.source.clone());
+                    entry.line_no = self.line_no;
+                    return Some(Ok(entry));
+                }
+                Ok(None) => "no pattern matched".to_string(),
+                Err(reason) => reason,
+            };
+            if self.malformed {
+                let source = self.source.display().to_string();
+                return Some(Err(MalformedLine { source, line_no: self.line_no, text: line.into_owned(), reason }.into()));
+            }
+        }
+    }
+}
+
+/// Entries of several files in turn, each read with a new parser. A file that can't be opened
+/// yields its error and the next one follows.
+pub struct FileEntries<'a> {
+    files: VecDeque<PathBuf>,
+    parser: Box<dyn FnMut() -> Result<Box<dyn LogParser>> + 'a>,
+    current: Option<Entries>,
+    malformed: bool,
+    filters: Option<Filters>,
+}
+
+impl<'a> FileEntries<'a> {
+    /// `parser` builds the parser for each file
+    pub fn new(parser: impl FnMut() -> Result<Box<dyn LogParser>> + 'a, files: impl IntoIterator<Item = PathBuf>) -> Self {
+        Self { files: files.into_iter().collect(), parser: Box::new(parser), current: None, malformed: false, filters: None }
+    }
+
+    /// See [`Entries::with_malformed`]
+    pub fn with_malformed(mut self, yes: bool) -> Self {
+        self.malformed = yes;
+        self
+    }
+
+    /// See [`Entries::with_filters`]
+    pub fn with_filters(mut self, filters: &Filters) -> Self {
+        self.filters = Some(filters.clone());
+        self
+    }
+}
+
+impl Iterator for FileEntries<'_> {
+    type Item = Result<LogEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(entry) = self.current.as_mut().and_then(Iterator::next) { return Some(entry); }
+            let path = self.files.pop_front()?;
+            match (self.parser)().and_then(|p| Entries::open(p, &path)) {
+                Ok(entries) => {
+                    let entries = entries.with_malformed(self.malformed);
+                    self.current = Some(match &self.filters { Some(f) => entries.with_filters(f), None => entries });
+                }
+                Err(e) => {
+                    self.current = None;
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
+
+/// Entries of the file at `path`, read with the built-in patterns ([`DefaultLogParser`]). Use
+/// [`Entries::open`] with a parser from [`crate::parse::parser_for`] for other formats.
+pub fn parse_file_iter(path: impl AsRef<Path>) -> Result<Entries> {
+    Entries::open(Box::new(DefaultLogParser::new()), path.as_ref())
+}
+
+/// Entries of every `.log` file under `dir` (see [`gather_log_files`]), file after file, read
+/// with the built-in patterns. Use [`FileEntries::new`] for other files or formats.
+pub fn parse_dir_iter(dir: impl AsRef<Path>) -> Result<FileEntries<'static>> {
+    let files = gather_log_files(&[dir.as_ref().to_path_buf()], &DiscoveryOptions::default())?;
+    Ok(FileEntries::new(|| Ok(Box::new(DefaultLogParser::new())), files))
+}
@@ -0,0 +1,122 @@
+#![cfg(feature = "parquet")]
+
+//! Columnar export of parsed entries (`--parquet-out`).
+
+use crate::analyze::Filters;
+use crate::entries::{BinaryInput, FileEntries};
+use crate::model::{Level, LogEntry};
+use crate::parse::LogParser;
+use anyhow::{Context, Result};
+use arrow_array::builder::{MapBuilder, StringBuilder, StringDictionaryBuilder, TimestampMicrosecondBuilder, UInt64Builder};
+use arrow_array::types::Int32Type;
+use arrow_array::{Array, ArrayRef, RecordBatch};
+use arrow_schema::{DataType, Field, Schema, TimeUnit};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Rows buffered before a record batch is flushed
+const BATCH_ROWS: usize = 64 * 1024;
+
+/// Streams entries into a Parquet file with columns `ts`, `level`, `message`, `file`, `line_no`
+/// and `fields` (a string map), so large exports never sit in memory at once.
+pub struct ParquetWriter {
+    writer: ArrowWriter<File>,
+    schema: Arc<Schema>,
+    ts: TimestampMicrosecondBuilder,
+    level: StringDictionaryBuilder<Int32Type>,
+    message: StringBuilder,
+    file: StringDictionaryBuilder<Int32Type>,
+    line_no: UInt64Builder,
+    fields: MapBuilder<StringBuilder, StringBuilder>,
+    rows: usize,
+}
+
+impl ParquetWriter {
+    pub fn create(path: &Path) -> Result<Self> {
+        let mut fields = MapBuilder::new(None, StringBuilder::new(), StringBuilder::new());
+        // Finishing the empty builder yields the exact map type its arrays will have
+        let map_type = fields.finish().data_type().clone();
+        let dict = DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8));
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("ts", DataType::Timestamp(TimeUnit::Microsecond, None), false),
+            Field::new("level", dict.clone(), false),
+            Field::new("message", DataType::Utf8, false),
+            Field::new("file", dict, false),
+            Field::new("line_no", DataType::UInt64, false),
+            Field::new("fields", map_type, false),
+        ]));
+        let out = File::create(path).with_context(|| format!("Creating {}", path.display()))?;
+        let props = WriterProperties::builder().set_compression(Compression::SNAPPY).build();
+        Ok(Self {
+            writer: ArrowWriter::try_new(out, schema.clone(), Some(props))?,
+            schema,
+            ts: TimestampMicrosecondBuilder::new(),
+            level: StringDictionaryBuilder::new(),
+            message: StringBuilder::new(),
+            file: StringDictionaryBuilder::new(),
+            line_no: UInt64Builder::new(),
+            fields,
+            rows: 0,
+        })
+    }
+
+    /// The `file` column is the entry's source path, empty without one
+    pub fn push(&mut self, entry: &LogEntry) -> Result<()> {
+        self.ts.append_value(entry.ts.and_utc().timestamp_micros());
+        self.level.append_value(match entry.level { Level::Info => "INFO", Level::Warning => "WARNING", Level::Error => "ERROR" });
+        self.message.append_value(&entry.message);
+        self.file.append_value(entry.source.as_deref().map(Path::to_string_lossy).unwrap_or_default());
+        self.line_no.append_value(entry.line_no);
+        for (k, v) in &entry.fields {
+            self.fields.keys().append_value(k);
+            self.fields.values().append_value(v);
+        }
+        self.fields.append(true)?;
+        self.rows += 1;
+        if self.rows >= BATCH_ROWS { self.flush()?; }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        if self.rows == 0 { return Ok(()); }
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(self.ts.finish()),
+            Arc::new(self.level.finish()),
+            Arc::new(self.message.finish()),
+            Arc::new(self.file.finish()),
+            Arc::new(self.line_no.finish()),
+            Arc::new(self.fields.finish()),
+        ];
+        self.writer.write(&RecordBatch::try_new(self.schema.clone(), columns)?)?;
+        self.rows = 0;
+        Ok(())
+    }
+
+    /// Flush buffered rows and write the file footer.
+    pub fn finish(mut self) -> Result<()> {
+        self.flush()?;
+        self.writer.close()?;
+        Ok(())
+    }
+}
+
+/// Parse `files` again, each with a parser from `new_parser`, and write every entry passing
+/// `filters` to `out`. Binary files are skipped, as in the analysis. Returns the row count.
+pub fn write_parquet(out: &Path, files: &[PathBuf], new_parser: impl FnMut() -> Result<Box<dyn LogParser>>, filters: &Filters) -> Result<u64> {
+    let mut writer = ParquetWriter::create(out)?;
+    let mut rows = 0;
+    for entry in FileEntries::new(new_parser, files.to_vec()).with_filters(filters) {
+        let entry = match entry {
+            Err(e) if e.is::<BinaryInput>() => continue,
+            entry => entry?,
+        };
+        writer.push(&entry)?;
+        rows += 1;
+    }
+    writer.finish()?;
+    Ok(rows)
+}
@@ -0,0 +1,58 @@
+use crate::analyze::Analyzer;
+use crate::parse::LogParser;
+use anyhow::{Context, Result};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// Where following `path` starts: its current size in bytes, and the lines `analyzer` read from it
+pub fn start_position(path: &Path, analyzer: &Analyzer) -> (u64, u64) {
+    let offset = std::fs::metadata(path).map_or(0, |m| m.len());
+    (offset, analyzer.sources.get(&path.display().to_string()).map_or(0, |s| s.lines))
+}
+
+/// Tails a set of files, feeding lines appended since the last poll into an analyzer.
+pub struct Follower {
+    files: Vec<FollowedFile>,
+}
+
+struct FollowedFile {
+    path: PathBuf,
+    /// Bytes consumed so far (up to the last complete line)
+    offset: u64,
+    /// Lines consumed so far, for line numbers in malformed reports
+    lines: u64,
+}
+
+impl Follower {
+    /// Start following `paths` from their current end; `analyzer` has already read them.
+    pub fn new(paths: Vec<PathBuf>, analyzer: &Analyzer) -> Self {
+        let files = paths.into_iter().map(|path| {
+            let (offset, lines) = start_position(&path, analyzer);
+            FollowedFile { path, offset, lines }
+        }).collect();
+        Self { files }
+    }
+
+    /// Read complete lines appended since the last poll. A file that shrank (truncated or
+    /// rotated in place) is read again from the start.
+    pub fn poll<P: LogParser + ?Sized>(&mut self, parser: &mut P, analyzer: &mut Analyzer) -> Result<()> {
+        for f in &mut self.files {
+            let Ok(meta) = std::fs::metadata(&f.path) else { continue };
+            if meta.len() < f.offset { f.offset = 0; f.lines = 0; parser.reset(); }
+            if meta.len() == f.offset { continue; }
+
+            let mut file = File::open(&f.path).with_context(|| format!("Failed reading {:?}", f.path))?;
+            file.seek(SeekFrom::Start(f.offset))?;
+            let mut buf = Vec::new();
+            file.take(meta.len() - f.offset).read_to_end(&mut buf)?;
+            // Leave a trailing partial line for the next poll
+            let Some(end) = buf.iter().rposition(|&b| b == b'\n') else { continue };
+            let complete = &buf[..=end];
+            analyzer.consume_lines(parser, complete, &f.path.display().to_string(), f.lines)?;
+            f.offset += complete.len() as u64;
+            f.lines += complete.iter().filter(|&&b| b == b'\n').count() as u64;
+        }
+        Ok(())
+    }
+}
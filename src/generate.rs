@@ -0,0 +1,201 @@
+//! Synthetic log files (`log_analyzer generate`): a known corpus for testing parsers and custom
+//! patterns, and for benchmarking. Output is reproducible for a given seed.
+
+use crate::model::Level;
+use chrono::{Duration, NaiveDateTime};
+use std::io::{self, Write};
+
+/// Line layouts the generator writes; each has a parser that reads it back.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Style {
+    /// `2025-09-05 14:32:10,123 ERROR message`
+    Iso,
+    /// `2025-09-05T14:32:10.123Z [ERROR] message`
+    IsoBracketed,
+    /// `Sep  5 14:32:10 web-1 app[1234]: [ERROR] message`
+    Syslog,
+    /// `2025-09-05 14:32:10,123 [worker-3] ERROR com.acme.Orders - message`
+    Log4j,
+    /// `2025-09-05 14:32:10,123 - app.db - ERROR - message`
+    Python,
+    /// `{"ts":"2025-09-05T14:32:10.123Z","level":"error","msg":"message"}`
+    Json,
+    /// `ts=2025-09-05T14:32:10.123Z level=error msg="message"`
+    Logfmt,
+}
+
+/// Relative share of each level, `info=90,warning=7,error=3`; levels left out get none.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LevelMix {
+    pub info: f64,
+    pub warning: f64,
+    pub error: f64,
+}
+
+impl Default for LevelMix {
+    fn default() -> Self { Self { info: 90.0, warning: 7.0, error: 3.0 } }
+}
+
+impl std::str::FromStr for LevelMix {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut mix = LevelMix { info: 0.0, warning: 0.0, error: 0.0 };
+        for part in s.split(',') {
+            let (level, v) = part.split_once('=').ok_or(format!("expected level=share, got: {part}"))?;
+            let v: f64 = v.parse().ok().filter(|v: &f64| v.is_finite() && *v >= 0.0).ok_or(format!("invalid share: {v}"))?;
+            match level.to_ascii_lowercase().as_str() {
+                "info" => mix.info = v,
+                "warning" | "warn" => mix.warning = v,
+                "error" => mix.error = v,
+                _ => return Err(format!("unknown level: {level} (expected info, warning or error)")),
+            }
+        }
+        if mix.info + mix.warning + mix.error <= 0.0 { return Err("at least one level needs a share".into()); }
+        Ok(mix)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct GenerateOptions {
+    pub lines: u64,
+    /// Timestamp of the first line
+    pub start: NaiveDateTime,
+    /// Mean lines per second; gaps between lines are exponentially distributed
+    pub rate: f64,
+    pub mix: LevelMix,
+    /// Each line picks one of these at random
+    pub styles: Vec<Style>,
+    /// Stretches of [`BURST_LINES`] lines at [`BURST_SPEEDUP`] times the rate, half of them errors
+    pub bursts: u32,
+    /// Fraction of lines that match no format
+    pub malformed: f64,
+    pub seed: u64,
+}
+
+impl Default for GenerateOptions {
+    fn default() -> Self {
+        Self {
+            lines: 10_000,
+            start: chrono::NaiveDate::from_ymd_opt(2025, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap(),
+            rate: 10.0,
+            mix: LevelMix::default(),
+            styles: vec![Style::Iso],
+            bursts: 0,
+            malformed: 0.0,
+            seed: 1,
+        }
+    }
+}
+
+/// Lines per burst (fewer when the whole output is shorter)
+pub const BURST_LINES: u64 = 200;
+pub const BURST_SPEEDUP: f64 = 10.0;
+
+const INFO_MESSAGES: [&str; 6] = [
+    "Handled request id={n} status=200",
+    "User {n} logged in",
+    "Cache hit ratio {n}%",
+    "Scheduled job {n} completed",
+    "Connection pool size {n}",
+    "Processed batch {n}",
+];
+const WARNING_MESSAGES: [&str; 4] = [
+    "Slow query took {n}ms",
+    "Disk space at {n}%",
+    "Retrying request {n} after timeout",
+    "Deprecated API called by client {n}",
+];
+const ERROR_MESSAGES: [&str; 5] = [
+    "Failed to connect to DB host=db-{n}",
+    "Timeout talking to cache after {n}ms",
+    "Payment declined for order {n}",
+    "NullPointerException in OrderService line {n}",
+    "Upstream returned 503 for request {n}",
+];
+const HOSTS: [&str; 3] = ["web-1", "web-2", "worker-1"];
+const LOGGERS: [&str; 4] = ["com.acme.Orders", "com.acme.Db", "com.acme.Auth", "com.acme.Cache"];
+
+/// splitmix64: small, fast and good enough for test data; no dependency needed
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in [0, 1)
+    fn unit(&mut self) -> f64 { (self.next() >> 11) as f64 / (1u64 << 53) as f64 }
+
+    fn below(&mut self, n: usize) -> usize { (self.next() % n as u64) as usize }
+}
+
+/// Write `opts.lines` lines to `out`; returns the entries written per level, indexed by
+/// `Level as usize` (malformed lines excluded).
+pub fn generate(opts: &GenerateOptions, out: &mut dyn Write) -> io::Result<[u64; 3]> {
+    let mut rng = Rng(opts.seed);
+    let styles = if opts.styles.is_empty() { &[Style::Iso][..] } else { &opts.styles };
+    let burst_len = BURST_LINES.min(opts.lines / u64::from(opts.bursts).max(1) / 2).max(1);
+    let mut burst_starts: Vec<u64> = (0..opts.bursts).map(|_| rng.next() % opts.lines.max(1)).collect();
+    burst_starts.sort_unstable();
+    let in_burst = |i: u64| burst_starts.iter().any(|&s| (s..s + burst_len).contains(&i));
+
+    let mut ts = opts.start;
+    let mut counts = [0u64; 3];
+    for i in 0..opts.lines {
+        let burst = opts.bursts > 0 && in_burst(i);
+        let rate = if burst { opts.rate * BURST_SPEEDUP } else { opts.rate };
+        // Exponential gaps: a Poisson process at `rate` lines per second
+        let gap = -(1.0 - rng.unit()).ln() / rate.max(f64::MIN_POSITIVE);
+        ts += Duration::microseconds((gap * 1e6).min(1e15) as i64);
+
+        if rng.unit() < opts.malformed {
+            writeln!(out, "garbled {:016x} -- no timestamp here", rng.next())?;
+            continue;
+        }
+        let level = if burst && rng.unit() < 0.5 { Level::Error } else { pick_level(&opts.mix, &mut rng) };
+        counts[level as usize] += 1;
+        let templates: &[&str] = match level {
+            Level::Info => &INFO_MESSAGES,
+            Level::Warning => &WARNING_MESSAGES,
+            Level::Error => &ERROR_MESSAGES,
+        };
+        let message = templates[rng.below(templates.len())].replace("{n}", &(rng.next() % 1000).to_string());
+        let style = styles[rng.below(styles.len())];
+        writeln!(out, "{}", format_line(style, ts, level, &message, &mut rng))?;
+    }
+    Ok(counts)
+}
+
+fn pick_level(mix: &LevelMix, rng: &mut Rng) -> Level {
+    let x = rng.unit() * (mix.info + mix.warning + mix.error);
+    if x < mix.error { Level::Error } else if x < mix.error + mix.warning { Level::Warning } else { Level::Info }
+}
+
+fn format_line(style: Style, ts: NaiveDateTime, level: Level, message: &str, rng: &mut Rng) -> String {
+    let upper = match level { Level::Info => "INFO", Level::Warning => "WARNING", Level::Error => "ERROR" };
+    let iso = ts.format("%Y-%m-%dT%H:%M:%S%.3fZ");
+    let comma = ts.format("%Y-%m-%d %H:%M:%S,%3f");
+    match style {
+        Style::Iso => format!("{comma} {upper} {message}"),
+        Style::IsoBracketed => format!("{iso} [{upper}] {message}"),
+        Style::Syslog => {
+            let host = HOSTS[rng.below(HOSTS.len())];
+            format!("{} {host} app[{}]: [{upper}] {message}", ts.format("%b %e %H:%M:%S"), 1000 + rng.below(9000))
+        }
+        Style::Log4j => {
+            let logger = LOGGERS[rng.below(LOGGERS.len())];
+            format!("{comma} [worker-{}] {upper} {logger} - {message}", rng.below(8))
+        }
+        Style::Python => format!("{comma} - app.{} - {upper} - {message}", ["db", "api", "jobs"][rng.below(3)]),
+        Style::Json => format!(
+            r#"{{"ts":"{iso}","level":"{}","msg":{}}}"#,
+            upper.to_ascii_lowercase(),
+            serde_json::Value::from(message)
+        ),
+        Style::Logfmt => format!("ts={iso} level={} msg={message:?}", upper.to_ascii_lowercase()),
+    }
+}
@@ -0,0 +1,97 @@
+//! `grep` subcommand: raw lines matching a regex, with context and the analyzer's level/time filters.
+
+use crate::analyze::{lossy_lines, Filters, DEFAULT_MAX_LINE_LEN};
+use crate::parse::{LogParser, RECORD_LINE_SEP};
+use crate::report::term::Term;
+use anyhow::Result;
+use regex::Regex;
+use std::collections::VecDeque;
+use std::io::Write;
+use std::path::PathBuf;
+
+pub struct GrepOptions {
+    pub pattern: Regex,
+    /// Context lines printed before / after each match (`-B` / `-A`)
+    pub before: usize,
+    pub after: usize,
+    /// Level and time filters; when any is set, only lines that parse into a passing entry match
+    pub filters: Filters,
+    pub line_numbers: bool,
+    /// Print only the number of matching lines
+    pub count: bool,
+    pub term: Term,
+}
+
+impl GrepOptions {
+    fn filtering(&self) -> bool {
+        let f = &self.filters;
+        !f.levels.is_all() || f.min_level.is_some() || f.from.is_some() || f.to.is_some() || f.from_time.is_some() || f.to_time.is_some()
+    }
+}
+
+/// Print matching lines grep-style (`file:line:` prefixes, `-` for context lines, `--` between
+/// separate groups). Returns the number of matching lines.
+pub fn run<P: LogParser + ?Sized>(opts: &GrepOptions, parser: &mut P, files: &[PathBuf]) -> Result<u64> {
+    let stdout = std::io::stdout();
+    let mut out = stdout.lock();
+    let mut matches = 0;
+    let mut printed_any = false;
+    for path in files {
+        parser.reset();
+        let name = (files.len() > 1).then(|| path.display().to_string());
+        let mut before: VecDeque<(usize, String)> = VecDeque::with_capacity(opts.before);
+        // Line number of the last printed line, and how many trailing context lines remain
+        let mut last_printed: Option<usize> = None;
+        let mut after_left = 0;
+        for (idx, line) in lossy_lines(parser.decode(crate::input::open(path)?), DEFAULT_MAX_LINE_LEN).enumerate() {
+            let (line_no, line) = (idx + 1, line?);
+            let absorbed = parser.absorb(&line);
+            let hit = opts.pattern.is_match(&line) && (!opts.filtering() || (!absorbed && matches!(
+                parser.parse_line(&line),
+                Ok(Some(ref e)) if opts.filters.pass(e)
+            )));
+            if !hit {
+                if after_left > 0 {
+                    after_left -= 1;
+                    print_line(&mut out, opts, name.as_deref(), line_no, &line, false)?;
+                    last_printed = Some(line_no);
+                } else if opts.before > 0 {
+                    if before.len() == opts.before { before.pop_front(); }
+                    before.push_back((line_no, line));
+                }
+                continue;
+            }
+            matches += 1;
+            if opts.count { continue; }
+            let first = before.front().map_or(line_no, |(n, _)| *n);
+            let contiguous = last_printed.is_some_and(|p| p + 1 >= first);
+            if printed_any && !contiguous && (opts.before > 0 || opts.after > 0) { writeln!(out, "--")?; }
+            for (n, l) in before.drain(..) { print_line(&mut out, opts, name.as_deref(), n, &l, false)?; }
+            print_line(&mut out, opts, name.as_deref(), line_no, &line, true)?;
+            printed_any = true;
+            last_printed = Some(line_no);
+            after_left = opts.after;
+        }
+    }
+    if opts.count { writeln!(out, "{matches}")?; }
+    Ok(matches)
+}
+
+fn print_line(out: &mut impl Write, opts: &GrepOptions, file: Option<&str>, line_no: usize, line: &str, is_match: bool) -> Result<()> {
+    let sep = if is_match { ':' } else { '-' };
+    // Multi-line records (`--format python`) print as their original lines
+    let line = &line.replace(RECORD_LINE_SEP, "\n");
+    if let Some(f) = file { write!(out, "{}{sep}", opts.term.dim(f))?; }
+    if opts.line_numbers { write!(out, "{}{sep}", opts.term.dim(&line_no.to_string()))?; }
+    if !is_match || !opts.term.color {
+        writeln!(out, "{line}")?;
+        return Ok(());
+    }
+    let mut last = 0;
+    for m in opts.pattern.find_iter(line) {
+        write!(out, "{}{}", &line[last..m.start()], opts.term.highlight(m.as_str()))?;
+        last = m.end();
+    }
+    writeln!(out, "{}", &line[last..])?;
+    Ok(())
+}
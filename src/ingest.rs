@@ -0,0 +1,183 @@
+#![cfg(feature = "tokio")]
+//! Async ingestion for streaming sources (followed files, sockets).
+//!
+//! Reader tasks hand lines to the parser, and the parser hands results to the analyzer, over
+//! bounded channels. When analysis falls behind, readers wait on a full channel instead of
+//! buffering: followed files are read later, TCP senders are slowed down by the socket's window
+//! and UDP datagrams queue (and eventually drop) in the kernel rather than in memory.
+
+use crate::analyze::{Analyzer, LineOutcome, LineStage};
+use crate::parse::LogParser;
+use anyhow::{Context, Result};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::io::SeekFrom;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncSeekExt};
+use tokio::net::{TcpListener, UdpSocket};
+use tokio::runtime::Handle;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// Lines (and parse results) buffered between two stages
+pub const DEFAULT_CAPACITY: usize = 1024;
+
+/// A line read by a reader task.
+pub struct RawLine {
+    /// File path or peer address
+    pub source: String,
+    /// 1-based within `source`; line 1 starts a new input and resets the parser's per-input state
+    pub line_no: u64,
+    pub text: String,
+}
+
+struct Parsed {
+    source: String,
+    outcome: LineOutcome,
+}
+
+/// Parser and analyzer stages fed by reader tasks through [`lines`](Self::lines).
+///
+/// The analyzer runs as a task on the runtime that started the pipeline; the parsers run on the
+/// thread calling [`run_parser`](Self::run_parser), so they need not be `Send`. Lines are sampled,
+/// prefiltered and cut to the analyzer's `max_line_len` like in [`Analyzer::consume_lines`].
+pub struct Pipeline {
+    lines: mpsc::Sender<RawLine>,
+    raw: mpsc::Receiver<RawLine>,
+    parsed: mpsc::Sender<Parsed>,
+    analyzer: Arc<Mutex<Analyzer>>,
+    aggregator: JoinHandle<()>,
+    runtime: Handle,
+}
+
+impl Pipeline {
+    /// Start the analyzer stage. Must be called within a Tokio runtime.
+    pub fn start(analyzer: Analyzer, capacity: usize) -> Self {
+        let (lines, raw) = mpsc::channel(capacity);
+        let (parsed, mut results) = mpsc::channel::<Parsed>(capacity);
+        let analyzer = Arc::new(Mutex::new(analyzer));
+        let shared = analyzer.clone();
+        let aggregator = tokio::spawn(async move {
+            while let Some(first) = results.recv().await {
+                // Take whatever else is queued under the same lock
+                let mut an = shared.lock().unwrap();
+                let mut next = Some(first);
+                while let Some(Parsed { source, outcome }) = next {
+                    an.consume_outcome(&source, outcome);
+                    next = results.try_recv().ok();
+                }
+            }
+        });
+        Self { lines, raw, parsed, analyzer, aggregator, runtime: Handle::current() }
+    }
+
+    /// Sender for a reader task
+    pub fn lines(&self) -> mpsc::Sender<RawLine> { self.lines.clone() }
+
+    /// The analyzer being fed, for live summaries; ingestion waits while the lock is held
+    pub fn analyzer(&self) -> Arc<Mutex<Analyzer>> { self.analyzer.clone() }
+
+    /// Parse lines on this thread until every reader has dropped its sender, then wait until
+    /// the analyzer has taken all results. Each source gets its own parser from `new_parser`,
+    /// replaced when the source starts over at line 1. Blocks, so call it outside the runtime's
+    /// tasks.
+    pub fn run_parser(self, mut new_parser: impl FnMut() -> Result<Box<dyn LogParser>>) -> Result<()> {
+        let Self { lines, mut raw, parsed, analyzer, aggregator, runtime } = self;
+        drop(lines);
+        let mut sources: HashMap<String, (Box<dyn LogParser>, LineStage)> = HashMap::new();
+        while let Some(RawLine { source, line_no, text }) = raw.blocking_recv() {
+            if line_no == 1 { sources.remove(&source); }
+            let (parser, stage) = match sources.entry(source.clone()) {
+                Entry::Occupied(e) => e.into_mut(),
+                Entry::Vacant(e) => {
+                    let parser = new_parser()?;
+                    let stage = analyzer.lock().unwrap().line_stage(&*parser, &source);
+                    e.insert((parser, stage))
+                }
+            };
+            let (text, cut) = stage.cap_line(text);
+            let outcome = stage.process(&mut **parser, line_no, text, cut);
+            if parsed.blocking_send(Parsed { source, outcome }).is_err() { break; }
+        }
+        drop(parsed);
+        runtime.block_on(aggregator)?;
+        Ok(())
+    }
+}
+
+/// Send the lines of `r` as `source`, decoding invalid UTF-8 lossily; stops early when the
+/// pipeline is gone. Returns the number of lines sent.
+pub async fn read_lines<R: AsyncBufRead + Unpin>(source: &str, mut r: R, tx: &mpsc::Sender<RawLine>) -> Result<u64> {
+    let mut buf = Vec::new();
+    let mut n = 0;
+    loop {
+        buf.clear();
+        if r.read_until(b'\n', &mut buf).await.with_context(|| format!("Failed reading {source}"))? == 0 { break; }
+        if buf.last() == Some(&b'\n') { buf.pop(); }
+        if buf.last() == Some(&b'\r') { buf.pop(); }
+        let text = String::from_utf8_lossy(&buf).into_owned();
+        n += 1;
+        if tx.send(RawLine { source: source.to_string(), line_no: n, text }).await.is_err() { break; }
+    }
+    Ok(n)
+}
+
+/// Accept connections on `listener` until the pipeline is gone, reading each as newline-delimited
+/// lines from `ip:port`. A connection that fails midway just ends.
+pub async fn read_tcp(listener: TcpListener, tx: mpsc::Sender<RawLine>) -> Result<()> {
+    while !tx.is_closed() {
+        let (stream, peer) = listener.accept().await?;
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            let _ = read_lines(&peer.to_string(), tokio::io::BufReader::new(stream), &tx).await;
+        });
+    }
+    Ok(())
+}
+
+/// Receive datagrams on `socket` until the pipeline is gone. Each datagram carries one or more
+/// lines and is tagged with the sender's IP (senders often use a new port per message).
+pub async fn read_udp(socket: UdpSocket, tx: mpsc::Sender<RawLine>) -> Result<()> {
+    let mut buf = vec![0u8; 65536];
+    let mut seen: HashMap<IpAddr, u64> = HashMap::new();
+    loop {
+        let (len, peer) = socket.recv_from(&mut buf).await?;
+        let n = seen.entry(peer.ip()).or_default();
+        for text in String::from_utf8_lossy(&buf[..len]).lines().filter(|l| !l.trim().is_empty()) {
+            *n += 1;
+            let line = RawLine { source: peer.ip().to_string(), line_no: *n, text: text.to_string() };
+            if tx.send(line).await.is_err() { return Ok(()); }
+        }
+    }
+}
+
+/// Follow `path` from byte `offset` (after `lines` lines), checking every `poll` for appended
+/// complete lines until the pipeline is gone. A file that shrank (truncated or rotated in place)
+/// is read again from the start.
+pub async fn tail_file(path: PathBuf, mut offset: u64, mut lines: u64, poll: Duration, tx: mpsc::Sender<RawLine>) -> Result<()> {
+    let source = path.display().to_string();
+    let mut tick = tokio::time::interval(poll);
+    while !tx.is_closed() {
+        tick.tick().await;
+        let Ok(meta) = tokio::fs::metadata(&path).await else { continue };
+        if meta.len() < offset { offset = 0; lines = 0; }
+        if meta.len() == offset { continue; }
+
+        let mut file = tokio::fs::File::open(&path).await.with_context(|| format!("Failed reading {source}"))?;
+        file.seek(SeekFrom::Start(offset)).await?;
+        let mut buf = Vec::new();
+        file.take(meta.len() - offset).read_to_end(&mut buf).await?;
+        // Leave a trailing partial line for the next poll
+        let Some(end) = buf.iter().rposition(|&b| b == b'\n') else { continue };
+        for text in buf[..end].split(|&b| b == b'\n') {
+            lines += 1;
+            let text = String::from_utf8_lossy(text).trim_end_matches('\r').to_string();
+            if tx.send(RawLine { source: source.clone(), line_no: lines, text }).await.is_err() { return Ok(()); }
+        }
+        offset += end as u64 + 1;
+    }
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use anyhow::{Context, Result};
+use flate2::read::MultiGzDecoder;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+
+/// Open an input file (or object store URL) as a stream of lines, decompressing `.gz` and
+/// converting container formats where needed.
+pub fn open(path: &Path) -> Result<Box<dyn BufRead>> {
+    open_with(path, |f| f)
+}
+
+/// Like [`open`], wrapping the raw byte stream before decoding (e.g. to count bytes read for progress).
+pub fn open_with<R: Read + 'static>(path: &Path, wrap: impl FnOnce(Box<dyn Read>) -> R) -> Result<Box<dyn BufRead>> {
+    #[cfg(feature = "evtx")]
+    if path.extension().is_some_and(|e| e.eq_ignore_ascii_case("evtx")) {
+        return evtx_as_xml_lines(path);
+    }
+    let raw: Box<dyn Read> = if crate::remote::is_url(path) {
+        crate::remote::open(path)?
+    } else {
+        Box::new(File::open(path).with_context(|| format!("Failed to open {}", path.display()))?)
+    };
+    let f = wrap(raw);
+    if path.extension().is_some_and(|e| e.eq_ignore_ascii_case("gz")) {
+        return Ok(Box::new(BufReader::new(MultiGzDecoder::new(f))));
+    }
+    Ok(Box::new(BufReader::new(f)))
+}
+
+/// Render each record of a binary `.evtx` file as one line of event XML,
+/// the same shape `wevtutil qe /f:xml` produces.
+#[cfg(feature = "evtx")]
+fn evtx_as_xml_lines(path: &Path) -> Result<Box<dyn BufRead>> {
+    let mut parser = evtx::EvtxParser::from_path(path)
+        .with_context(|| format!("Failed to open {} as EVTX", path.display()))?;
+    let mut out = String::new();
+    for record in parser.records() {
+        let record = record.with_context(|| format!("Corrupt record in {}", path.display()))?;
+        for line in record.data.lines() { out.push_str(line.trim()); }
+        out.push('\n');
+    }
+    Ok(Box::new(std::io::Cursor::new(out.into_bytes())))
+}
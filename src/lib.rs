@@ -0,0 +1,32 @@
+//! Log parsing and analysis library behind the `log_analyzer` binary.
+//!
+//! Parse lines with a [`parse::LogParser`], aggregate with [`analyze::Analyzer`] (or any
+//! [`sink::AnalyzerSink`]) and turn the result into a [`report::Summary`] (serialized as [`report::JsonSummary`]).
+//! To process entries yourself, iterate over them with [`entries::parse_file_iter`].
+
+pub mod model;
+pub mod parse;
+pub mod analyze;
+pub mod report;
+pub mod sink;
+pub mod entries;
+pub mod viz;
+pub mod cache;
+pub mod checkpoint;
+pub mod parsed;
+pub mod spill;
+pub mod discover;
+pub mod input;
+pub mod remote;
+pub mod query;
+pub mod grep;
+pub mod merge;
+pub mod otlp;
+pub mod follow;
+pub mod alert;
+pub mod config;
+pub mod generate;
+#[cfg(feature = "parquet")] pub mod export;
+#[cfg(feature = "gui")] pub mod ui;
+#[cfg(feature = "api")] pub mod api;
+#[cfg(feature = "tokio")] pub mod ingest;
@@ -1,53 +1,259 @@
-mod model;
-mod parse;
-mod analyze;
-mod report;
-mod viz;
-#[cfg(feature = "gui")] mod ui;
-
-use clap::{Parser, ValueEnum};
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use anyhow::{Context, Result};
 use std::path::PathBuf;
-use walkdir::WalkDir;
-use crate::parse::DefaultLogParser;
-use crate::analyze::{Analyzer, Filters, Granularity};
-use crate::model::Level; // Level lives in model
-use crate::report::build_summary;
-use crate::viz::{save_level_barchart, save_timeline_chart};
+use log_analyzer::parse::{parser_for, Format, MonthLocale, ParserOptions};
+use log_analyzer::analyze::{
+    Analyzer, Filters, Granularity, HistogramSource, MessageLimits, Sampling, TimePrecision, TopK, parse_datetime, parse_duration, DEFAULT_EXAMPLES, DEFAULT_MAX_LINE_LEN, DEFAULT_MESSAGE_CAPACITY, DEFAULT_MESSAGE_LEN,
+};
+use log_analyzer::model::Level; // Level lives in model
+use log_analyzer::report::{attach_error_context, compare_baseline, summarize, JsonSummary, ReportOptions, Summary, SummaryFormat};
+use log_analyzer::report::term::{human_duration, percent, thousands, ColorChoice, Term};
+use log_analyzer::viz::{save_histogram, save_keyword_chart, save_level_barchart, save_pressure_chart, save_timeline_chart, write_histogram_csv, write_keyword_csv, write_level_csv, write_pressure_csv, write_timeline_csv, ChartStyle, HistogramSpec, Palette, PressureWeights, Shading};
+use log_analyzer::cache::AnalysisCache;
+use log_analyzer::checkpoint::{Checkpointer, CHUNK_LINES};
+use log_analyzer::discover::{gather_log_files, DiscoveryOptions};
+use log_analyzer::query;
+use log_analyzer::grep::{self, GrepOptions};
+use log_analyzer::merge::{self, MergeOptions};
+#[cfg(not(feature = "tokio"))]
+use log_analyzer::follow::Follower;
+#[cfg(feature = "tokio")]
+use log_analyzer::follow::start_position;
+#[cfg(feature = "tokio")]
+use log_analyzer::ingest::{tail_file, Pipeline, DEFAULT_CAPACITY};
+use log_analyzer::alert::{AlertActions, AlertRule, Alerter, Totals};
+use log_analyzer::config::Config;
+use log_analyzer::parsed::{self, Recorder};
+use log_analyzer::spill::Spill;
+use log_analyzer::otlp::{self, OtlpExporter};
+use log_analyzer::generate::{generate, GenerateOptions, LevelMix, Style};
+use log_analyzer::parse::LogParser;
+use std::time::{Duration, Instant};
+use indicatif::{ProgressBar, ProgressStyle};
 
 /// Log File Analyzer (Rust)
 /// Parses .log files, filters, summarizes, exports JSON, and renders charts. Can also run a GUI.
 #[derive(Parser, Debug)]
-#[command(version, about)]
+#[command(version, about, args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// One or more files or directories (recursively scanned for *.log)
-    #[arg(required_unless_present = "gui")]
+    #[arg(required_unless_present_any = ["gui", "print_schema", "load_parsed"])]
     inputs: Vec<PathBuf>,
 
-    /// Keyword filter (case-insensitive substring)
+    #[command(flatten)]
+    discovery: DiscoveryArgs,
+
+    /// Input log format
+    #[arg(long, default_value_t = FormatArg::Default, value_enum)]
+    format: FormatArg,
+
+    /// Keyword filter (case-insensitive substring); repeatable, all must match unless --match-any
+    #[arg(long, env = "LOG_ANALYZER_KEYWORD")]
+    keyword: Vec<String>,
+
+    /// With several --keyword terms, keep entries matching any of them
+    #[arg(long, conflicts_with = "match_all", env = "LOG_ANALYZER_MATCH_ANY")]
+    match_any: bool,
+
+    /// With several --keyword terms, keep only entries matching all of them (the default)
     #[arg(long)]
-    keyword: Option<String>,
+    match_all: bool,
+
+    /// Drop entries whose message contains this (case-insensitive); repeatable
+    #[arg(long, value_name = "KEYWORD", env = "LOG_ANALYZER_NOT_KEYWORD")]
+    not_keyword: Vec<String>,
 
-    /// Minimum timestamp (inclusive). Examples: "2025-09-05 14:00:00", "2025-09-05T14:00:00"
-    #[arg(long, value_name = "DATETIME")]
+    /// Minimum timestamp (inclusive). Examples: "2025-09-05 14:00:00", "2025-09-05T14:00:00+02:00",
+    /// "2025-09-05", "1757080800" (Unix seconds), or a time of day like "14:00" for every day
+    #[arg(long, value_name = "DATETIME", env = "LOG_ANALYZER_FROM")]
     from: Option<String>,
 
-    /// Maximum timestamp (exclusive)
-    #[arg(long, value_name = "DATETIME")]
+    /// Maximum timestamp (exclusive); a date alone includes that whole day, a time of day applies to every day
+    #[arg(long, value_name = "DATETIME", env = "LOG_ANALYZER_TO")]
     to: Option<String>,
 
-    /// Only include this level
-    #[arg(long, value_enum)]
-    level: Option<LevelArg>,
+    /// Only include these levels; repeatable or comma-separated (`--level error,warning`), `all` keeps every level
+    #[arg(long, alias = "levels", value_enum, value_delimiter = ',', value_name = "LEVEL", env = "LOG_ANALYZER_LEVEL")]
+    level: Vec<LevelArg>,
+
+    /// Only include this level and more severe ones (e.g. `warning` keeps WARNING and ERROR)
+    #[arg(long, value_enum, value_name = "LEVEL", env = "LOG_ANALYZER_MIN_LEVEL")]
+    min_level: Option<LevelArg>,
+
+    /// Only include entries whose extracted field has this value, e.g. `host=db-1`; repeatable (all must match)
+    #[arg(long = "field", value_name = "KEY=VALUE", value_parser = parse_key_value, env = "LOG_ANALYZER_FIELD")]
+    fields: Vec<(String, String)>,
+
+    /// Only include entries from this host (`host`, `hostname` or `kubernetes.host` field, case-insensitive); repeatable
+    #[arg(long, value_name = "HOST", env = "LOG_ANALYZER_HOST")]
+    host: Vec<String>,
+
+    /// Filter set as a JSON object (inline, `@path` to read a file, or `-` for stdin), e.g.
+    /// '{"levels":["error"],"keywords":["timeout"],"from":"2025-09-05T14:00:00"}'; filter flags
+    /// given as well override its keys
+    #[arg(long, value_name = "JSON", env = "LOG_ANALYZER_FILTERS_JSON")]
+    filters_json: Option<String>,
+
+    /// Report the most common values of an extracted field; repeatable
+    #[arg(long, value_name = "FIELD")]
+    top_values: Vec<String>,
+
+    /// Count entries whose message mentions this keyword (case-insensitive) per timeline bucket; repeatable
+    #[arg(long, value_name = "KEYWORD")]
+    track: Vec<String>,
+
+    /// Report percentiles of a numeric field (or `name=123` in the message), computed in one pass; repeatable
+    #[arg(long, value_name = "FIELD")]
+    quantiles: Vec<String>,
+
+    /// Distinct error messages tracked for "Top error messages"; beyond this, rare ones are evicted
+    /// and counts become upper bounds
+    #[arg(long, value_name = "N", default_value_t = DEFAULT_MESSAGE_CAPACITY)]
+    max_error_messages: usize,
+
+    /// Memory the error-message counts and retained entries may use, e.g. 512M or 2G; beyond it
+    /// the counts are spilled to temporary files and merged at the end, so they stay exact. The
+    /// entries the GUI keeps for drill-down stop at half of it. Other aggregates stay in memory
+    #[arg(long, value_name = "SIZE", value_parser = parse_size, conflicts_with_all = ["cache", "checkpoint", "follow"])]
+    max_memory: Option<usize>,
+
+    /// Truncate error messages to this many characters before counting them
+    #[arg(long, value_name = "CHARS", default_value_t = DEFAULT_MESSAGE_LEN)]
+    max_message_len: usize,
+
+    /// Cut input lines longer than this many bytes before parsing (counted in the summary); 0 = no limit
+    #[arg(long, value_name = "BYTES", default_value_t = DEFAULT_MAX_LINE_LEN)]
+    max_line_len: usize,
+
+    /// Example raw lines (with timestamp and source) kept for each top error message; 0 keeps none
+    #[arg(long, value_name = "N", default_value_t = DEFAULT_EXAMPLES)]
+    error_examples: usize,
+
+    /// List the N most frequent message templates of any level with their share of all entries,
+    /// to find log spam worth silencing
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    noisiest: usize,
 
-    /// Timeline granularity
+    /// Re-read this many lines before and after the first occurrence of each top error message
+    /// into the report (at most 100); 0 keeps none
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    error_context: usize,
+
+    /// Compare error templates with a previous summary (JSON, or YAML/TOML by extension): list the
+    /// new ones, those gone and those whose share of entries grew more than --regression-threshold
+    #[arg(long, value_name = "SUMMARY")]
+    baseline: Option<PathBuf>,
+
+    /// Percent growth of an error template's share of entries that counts as a regression
+    #[arg(long, value_name = "PCT", default_value_t = 50.0, requires = "baseline")]
+    regression_threshold: f64,
+
+    /// Exit with status 3 when --baseline finds new or regressed error templates, e.g. to gate a release
+    #[arg(long, requires = "baseline", conflicts_with = "follow")]
+    fail_on_baseline: bool,
+
+    /// Report first seen, last seen and errors per period of the top error templates, and whether
+    /// each one is new in the --from/--to range
+    #[arg(long, value_name = "PERIOD", value_enum)]
+    top_errors_by: Option<PeriodArg>,
+
+    /// Break counts down by an extracted field, e.g. `host` or `app`; repeatable
+    #[arg(long, value_name = "FIELD")]
+    group_by: Vec<String>,
+
+    /// Year for syslog-style timestamps without one (default: current year, or the previous
+    /// year if that would put entries in the future; New Year rollovers within a file are detected)
+    #[arg(long, value_name = "YEAR")]
+    assume_year: Option<i32>,
+
+    /// Also accept month names in this language in syslog-style timestamps (`janv. 5 ...`);
+    /// repeatable or comma-separated
+    #[arg(long, value_enum, value_name = "LANG", value_delimiter = ',')]
+    month_locale: Vec<MonthLocaleArg>,
+
+    /// WebAssembly parser module for `--format plugin` (`.wasm` or `.wat`); repeatable, tried in order
+    #[arg(long, global = true, value_name = "MODULE")]
+    plugin: Vec<PathBuf>,
+
+    /// Analyze only this fraction of lines (0 < F <= 1); counts are reported with scaled estimates
+    #[arg(long, value_name = "F", value_parser = parse_fraction, conflicts_with = "sample_every")]
+    sample: Option<f64>,
+
+    /// Analyze only every N-th line
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    sample_every: Option<u64>,
+
+    /// Parse every line even when filters let raw lines be skipped early
+    /// (slower, but malformed counts then cover the whole input)
+    #[arg(long)]
+    no_prefilter: bool,
+
+    /// Timeline granularity; `auto` picks one giving ~100-300 buckets over the logs' time span
     #[arg(long, default_value_t = GranularityArg::Hour, value_enum)]
     granularity: GranularityArg,
 
+    /// Fixed timeline bucket length instead of --granularity, e.g. 15m, 90s, 6h or 100ms
+    #[arg(long, value_name = "INTERVAL", value_parser = parse_bucket, conflicts_with = "granularity")]
+    bucket: Option<Granularity>,
+
+    /// Fractional seconds kept in report and error-example timestamps (sub-second buckets use at least millis)
+    #[arg(long, default_value_t = TsPrecisionArg::Seconds, value_enum)]
+    ts_precision: TsPrecisionArg,
+
     /// Save summary JSON to this path
     #[arg(long)]
     json_out: Option<PathBuf>,
 
+    /// Save the summary as YAML to this path
+    #[arg(long, value_name = "PATH")]
+    yaml_out: Option<PathBuf>,
+
+    /// Save the summary as TOML to this path
+    #[arg(long, value_name = "PATH")]
+    toml_out: Option<PathBuf>,
+
+    /// Print the summary as JSON to stdout instead of the text report (same as --output-format json)
+    #[arg(long, conflicts_with = "output_format")]
+    json: bool,
+
+    /// Summary format on stdout; with anything but text, status messages go to stderr
+    #[arg(long, value_name = "FORMAT", default_value_t = OutputFormatArg::Text, value_enum)]
+    output_format: OutputFormatArg,
+
+    /// Color the text summary (auto: only on a terminal and when NO_COLOR is unset)
+    #[arg(long, value_name = "WHEN", default_value_t = ColorArg::Auto, value_enum)]
+    color: ColorArg,
+
+    /// Write every filtered entry (ts, level, message, file, fields) to this Parquet file
+    #[arg(long, value_name = "PATH")]
+    parquet_out: Option<PathBuf>,
+
+    /// Send every filtered entry to this OpenTelemetry collector as OTLP log records over
+    /// OTLP/gRPC (cleartext HTTP/2), e.g. http://collector:4317; port 4317 when none is given
+    #[arg(long, value_name = "URL", group = "otlp")]
+    otlp_endpoint: Option<String>,
+
+    /// Like --otlp-endpoint, but over OTLP/HTTP with JSON, e.g. http://collector:4318; port 4318
+    /// when none is given
+    #[arg(long, value_name = "URL", group = "otlp")]
+    otlp_http_endpoint: Option<String>,
+
+    /// `service.name` of the records sent with --otlp-endpoint or --otlp-http-endpoint
+    #[arg(long, value_name = "NAME", default_value = otlp::DEFAULT_SERVICE_NAME, requires = "otlp")]
+    otlp_service: String,
+
+    /// Write every unparsed line as `file:line: text` to this path
+    #[arg(long, value_name = "PATH")]
+    malformed_out: Option<PathBuf>,
+
+    /// Print malformed lines per file and the top parse failure reasons with example lines
+    #[arg(long)]
+    diagnose: bool,
+
     /// Save a bar chart (PNG) with counts per level
     #[arg(long)]
     bar_out: Option<PathBuf>,
@@ -56,50 +262,647 @@ struct Cli {
     #[arg(long)]
     timeline_out: Option<PathBuf>,
 
-    /// Launch GUI instead of CLI
+    /// Save an error pressure chart (PNG): severity-weighted entries per timeline bucket
+    #[arg(long)]
+    pressure_out: Option<PathBuf>,
+
+    /// Save a chart (PNG) with one line per --track keyword over time
+    #[arg(long, value_name = "PATH", requires = "track")]
+    track_out: Option<PathBuf>,
+
+    /// Weights per level for --pressure-out; levels left out keep their default
+    #[arg(long, value_name = "WEIGHTS", default_value = "error=5,warning=2,info=0")]
+    pressure_weights: PressureWeights,
+
+    /// Save a histogram (PNG) of a numeric field, e.g. 'field=latency,path=hist.png' (default: message length; optional bins=N)
+    #[arg(long, value_name = "SPEC")]
+    hist_out: Option<HistogramSpec>,
+
+    /// Also write the data behind each saved chart as CSV into DIR (levels.csv, timeline.csv, pressure.csv, tracked.csv, histogram.csv)
+    #[arg(long, value_name = "DIR")]
+    chart_data_out: Option<PathBuf>,
+
+    /// Errors per bucket for a bucket to count towards an incident (default: mean errors per bucket)
+    #[arg(long, value_name = "N")]
+    incident_threshold: Option<u64>,
+
+    /// TOML config file, e.g. `[health]` weights for the health score (`error`, `warning`, `malformed`)
+    #[arg(long, global = true, value_name = "FILE")]
+    config: Option<PathBuf>,
+
+    /// Chart dimensions in pixels, e.g. 1600x600 (applies to all charts)
+    #[arg(long, value_name = "WxH", value_parser = parse_chart_size)]
+    chart_size: Option<(u32, u32)>,
+
+    /// Caption for all charts (replaces the default titles)
+    #[arg(long, value_name = "TITLE")]
+    chart_title: Option<String>,
+
+    /// Chart color palette
+    #[arg(long, default_value_t = PaletteArg::Default, value_enum)]
+    palette: PaletteArg,
+
+    /// Shade weekends and/or nights (22:00-06:00) behind the timeline chart, e.g. `weekends,nights`
+    #[arg(long, value_enum, value_delimiter = ',', value_name = "BANDS")]
+    shade: Vec<ShadeArg>,
+
+    /// Shade detected incidents on the timeline chart and label the largest with their error count and top message
+    #[arg(long)]
+    mark_incidents: bool,
+
+    /// Also print entries and errors per hour of day and per weekday
+    #[arg(long)]
+    breakdowns: bool,
+
+    /// Print the JSON Schema of the summary (`--json`, `--json-out`) and exit
+    #[arg(long)]
+    print_schema: bool,
+
+    /// Also save what was parsed from each line (zstd-compressed) for fast re-analysis with --load-parsed;
+    /// turns the pre-filter off so every line is saved
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["cache", "resume"])]
+    save_parsed: Option<PathBuf>,
+
+    /// Analyze the lines saved with --save-parsed instead of reading and parsing inputs
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["inputs", "save_parsed", "cache", "checkpoint", "follow", "parquet_out", "otlp"])]
+    load_parsed: Option<PathBuf>,
+
+    /// Reuse per-file results from previous runs; only changed or new files are re-parsed
+    #[arg(long, value_name = "PATH", num_args = 0..=1, default_missing_value = ".log-analyzer-cache")]
+    cache: Option<PathBuf>,
+
+    /// Periodically save progress (finished files, offset and aggregates of the current one) here
+    #[arg(long, value_name = "PATH")]
+    checkpoint: Option<PathBuf>,
+
+    /// Seconds between checkpoints
+    #[arg(long, value_name = "SECS", default_value_t = 60, requires = "checkpoint")]
+    checkpoint_every: u64,
+
+    /// Continue an interrupted run from its --checkpoint (same inputs and options)
+    #[arg(long, requires = "checkpoint")]
+    resume: bool,
+
+    /// Instead of the report, print the entries of all inputs interleaved by timestamp, each line
+    /// prefixed with its file name (the level, time, keyword and field filters apply)
+    #[arg(long)]
+    merge: bool,
+
+    /// Write the merged stream (see --merge) to this file instead of stdout
+    #[arg(long, value_name = "PATH")]
+    merge_out: Option<PathBuf>,
+
+    /// After the report, keep watching the input files for appended lines (Ctrl-C to stop)
+    #[arg(long)]
+    follow: bool,
+
+    /// While following, rewrite the --json-out/--yaml-out/--toml-out summaries and the --bar-out/--timeline-out/
+    /// --pressure-out/--track-out charts (with their --chart-data-out CSVs) at this interval (e.g. 60s, 5m); each
+    /// file is replaced whole, so pollers never see a partial one
+    #[arg(long, value_name = "INTERVAL", value_parser = parse_interval, requires = "follow")]
+    snapshot_every: Option<Duration>,
+
+    /// Alert rule checked while following, e.g. 'errors > 100 per 5m'; repeatable
+    #[arg(long, value_name = "RULE", requires = "follow")]
+    alert: Vec<AlertRule>,
+
+    /// POST a JSON payload to this URL when an alert fires
+    #[arg(long, value_name = "URL", requires = "alert")]
+    alert_webhook: Option<String>,
+
+    /// Run this shell command when an alert fires (JSON payload on stdin, ALERT_RULE/ALERT_VALUE in the environment)
+    #[arg(long, value_name = "CMD", requires = "alert")]
+    alert_cmd: Option<String>,
+
+    /// No progress bar or throughput line
+    #[arg(long, short)]
+    quiet: bool,
+
+    /// Launch GUI instead of CLI; given inputs are opened and analyzed on startup
     #[arg(long)]
     gui: bool,
 }
 
+/// How input directories are scanned for log files
+#[derive(Args, Debug, Clone)]
+struct DiscoveryArgs {
+    /// Skip files whose path (relative to the input directory) matches this glob; repeatable
+    #[arg(long, value_name = "GLOB")]
+    exclude: Vec<String>,
+
+    /// Only scan files whose path (relative to the input directory) matches this glob; repeatable
+    #[arg(long, value_name = "GLOB")]
+    include: Vec<String>,
+
+    /// File extension to scan in directories (default: log); repeatable
+    #[arg(long = "ext", value_name = "EXT")]
+    extensions: Vec<String>,
+
+    /// Also scan files whose name matches this regex, e.g. '\.log\.\d{4}-\d{2}-\d{2}$'
+    #[arg(long, value_name = "REGEX")]
+    file_pattern: Option<String>,
+
+    /// Maximum directory recursion depth (1 = only files directly inside the input directory)
+    #[arg(long, value_name = "N")]
+    max_depth: Option<usize>,
+
+    /// Also analyze rotated siblings of each log (app.log.1, app.log.2.gz, app.log-20250905), oldest first
+    #[arg(long)]
+    include_rotated: bool,
+}
+
+impl From<DiscoveryArgs> for DiscoveryOptions {
+    fn from(a: DiscoveryArgs) -> Self {
+        DiscoveryOptions {
+            include: a.include, exclude: a.exclude, extensions: a.extensions, file_pattern: a.file_pattern,
+            max_depth: a.max_depth, include_rotated: a.include_rotated,
+        }
+    }
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Serve analysis over HTTP: POST /analyze, GET /summary, GET /entries
+    ServeApi {
+        /// Address to listen on, e.g. :8080 (localhost) or 0.0.0.0:8080 (all interfaces)
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        listen: String,
+        /// Directory whose files `POST /analyze?path=` may read; without it only uploaded log
+        /// text is analyzed
+        #[arg(long, value_name = "DIR")]
+        root: Option<PathBuf>,
+        /// Largest request body `POST /analyze` accepts, e.g. 64M
+        #[arg(long, value_name = "SIZE", value_parser = parse_size, default_value = "64M")]
+        max_upload: usize,
+        /// Memory each analysis's error-message counts and retained entries may use, e.g. 512M;
+        /// beyond it error messages are spilled to temporary files, and `GET /entries` keeps only
+        /// the entries that fit in half of it
+        #[arg(long, value_name = "SIZE", value_parser = parse_size)]
+        max_memory: Option<usize>,
+    },
+    /// Receive syslog messages over UDP and/or TCP and keep a live summary; press Enter to
+    /// print it, Ctrl-C prints it one last time and exits
+    Listen {
+        /// UDP address to receive on, e.g. 0.0.0.0:5140 or :5140 (all interfaces)
+        #[arg(long, value_name = "ADDR", required_unless_present = "tcp")]
+        udp: Option<String>,
+        /// TCP address to accept newline-framed messages on
+        #[arg(long, value_name = "ADDR")]
+        tcp: Option<String>,
+        /// Message format
+        #[arg(long, default_value_t = FormatArg::Syslog, value_enum)]
+        format: FormatArg,
+        /// Also print the summary every N seconds
+        #[arg(long, value_name = "SECS")]
+        print_every: Option<u64>,
+        /// Write the JSON summary to this file every --export-every seconds
+        #[arg(long, value_name = "PATH")]
+        export: Option<PathBuf>,
+        /// Seconds between --export writes
+        #[arg(long, value_name = "SECS", default_value_t = 60)]
+        export_every: u64,
+    },
+    /// Print lines whose parsed entry matches an expression,
+    /// e.g. `level == "error" && message ~ "timeout" && ts > 2025-09-05`
+    Query {
+        /// Filter expression over level, message, ts and extracted fields
+        expr: String,
+        /// Files or directories to search
+        #[arg(required = true)]
+        inputs: Vec<PathBuf>,
+        /// Print only the number of matching entries
+        #[arg(long)]
+        count: bool,
+        /// Input log format
+        #[arg(long, default_value_t = FormatArg::Default, value_enum)]
+        format: FormatArg,
+        #[command(flatten)]
+        discovery: DiscoveryArgs,
+    },
+    /// Print raw lines matching a regex, with optional context and level/time filters
+    Grep {
+        /// Regular expression searched in each raw line
+        pattern: String,
+        /// Files or directories to search
+        #[arg(required = true)]
+        inputs: Vec<PathBuf>,
+        /// Lines of context before each match
+        #[arg(short = 'B', long, value_name = "N", default_value_t = 0)]
+        before_context: usize,
+        /// Lines of context after each match
+        #[arg(short = 'A', long, value_name = "N", default_value_t = 0)]
+        after_context: usize,
+        /// Lines of context before and after each match (overridden by -A/-B)
+        #[arg(short = 'C', long, value_name = "N")]
+        context: Option<usize>,
+        /// Case-insensitive matching
+        #[arg(short, long)]
+        ignore_case: bool,
+        /// Prefix lines with their line number
+        #[arg(short = 'n', long)]
+        line_number: bool,
+        /// Print only the number of matching lines
+        #[arg(short, long)]
+        count: bool,
+        /// Only lines whose entry has one of these levels; repeatable or comma-separated
+        #[arg(long, alias = "levels", value_enum, value_delimiter = ',', value_name = "LEVEL")]
+        level: Vec<LevelArg>,
+        /// Only lines whose entry has this level or a more severe one
+        #[arg(long, value_enum, value_name = "LEVEL", conflicts_with = "level")]
+        min_level: Option<LevelArg>,
+        /// Only entries at or after this time
+        #[arg(long, value_name = "DATETIME")]
+        from: Option<String>,
+        /// Only entries before this time
+        #[arg(long, value_name = "DATETIME")]
+        to: Option<String>,
+        /// Highlight matches (auto: only on a terminal and when NO_COLOR is unset)
+        #[arg(long, value_name = "WHEN", default_value_t = ColorArg::Auto, value_enum)]
+        color: ColorArg,
+        /// Input log format
+        #[arg(long, default_value_t = FormatArg::Default, value_enum)]
+        format: FormatArg,
+        #[command(flatten)]
+        discovery: DiscoveryArgs,
+    },
+    /// Write a synthetic log file with a known mix of levels, formats, bursts and malformed lines
+    Generate {
+        /// Number of lines
+        #[arg(long, default_value_t = 10_000)]
+        lines: u64,
+        /// Mean lines per second
+        #[arg(long, default_value_t = 10.0, value_parser = parse_rate)]
+        rate: f64,
+        /// Relative share of each level
+        #[arg(long, value_name = "SHARES", default_value = "info=90,warning=7,error=3")]
+        mix: LevelMix,
+        /// Line formats; each line picks one at random when several are given
+        #[arg(long, value_enum, value_delimiter = ',', default_value = "iso")]
+        format: Vec<StyleArg>,
+        /// Error bursts: short stretches at ten times the rate where half the lines are errors
+        #[arg(long, value_name = "N", default_value_t = 0)]
+        bursts: u32,
+        /// Fraction of lines that match no format, e.g. 0.01
+        #[arg(long, value_name = "FRACTION", default_value_t = 0.0)]
+        malformed: f64,
+        /// Timestamp of the first line
+        #[arg(long, value_name = "DATETIME", default_value = "2025-01-01 00:00:00")]
+        start: String,
+        /// Seed for the random choices; the same seed gives the same output
+        #[arg(long, default_value_t = 1)]
+        seed: u64,
+        /// Write here instead of stdout
+        #[arg(short, long, value_name = "PATH")]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Clone, Debug, ValueEnum)]
+enum LevelArg { All, Info, Warning, Error }
+impl LevelArg {
+    /// The levels this selects, least severe first
+    fn levels(&self) -> &'static [Level] {
+        match self {
+            LevelArg::All => &[Level::Info, Level::Warning, Level::Error],
+            LevelArg::Info => &[Level::Info],
+            LevelArg::Warning => &[Level::Warning],
+            LevelArg::Error => &[Level::Error],
+        }
+    }
+
+    /// `--min-level all` is the same as `info`
+    fn min(&self) -> Level { self.levels()[0] }
+}
+
+/// The filter flags, on top of `--filters-json` when given
+fn cli_filters(cli: &Cli) -> Result<Filters> {
+    let flags = Filters::from_cli(None, cli.from.as_deref(), cli.to.as_deref(), &level_list(&cli.level))?;
+    let Some(spec) = &cli.filters_json else {
+        return Ok(Filters {
+            keywords: cli.keyword.iter().map(|k| k.to_lowercase()).collect(),
+            match_any: cli.match_any,
+            not_keywords: cli.not_keyword.iter().map(|k| k.to_lowercase()).collect(),
+            min_level: cli.min_level.as_ref().map(LevelArg::min),
+            fields: cli.fields.clone(),
+            hosts: cli.host.clone(),
+            ..flags
+        });
+    };
+    let json = match spec.as_str() {
+        "-" => std::io::read_to_string(std::io::stdin()).context("Reading --filters-json from stdin")?,
+        s => match s.strip_prefix('@') {
+            Some(path) => std::fs::read_to_string(path).with_context(|| format!("Reading --filters-json from {path}"))?,
+            None => s.to_string(),
+        },
+    };
+    let mut f = Filters::from_json(&json).context("Invalid --filters-json")?;
+    if cli.from.is_some() { (f.from, f.from_time) = (flags.from, flags.from_time); }
+    if cli.to.is_some() { (f.to, f.to_time) = (flags.to, flags.to_time); }
+    if !cli.level.is_empty() { f.levels = flags.levels; }
+    if !cli.keyword.is_empty() { f.keywords = cli.keyword.iter().map(|k| k.to_lowercase()).collect(); }
+    if cli.match_any || cli.match_all { f.match_any = cli.match_any; }
+    if !cli.not_keyword.is_empty() { f.not_keywords = cli.not_keyword.iter().map(|k| k.to_lowercase()).collect(); }
+    if let Some(level) = &cli.min_level { f.min_level = Some(level.min()); }
+    if !cli.fields.is_empty() { f.fields = cli.fields.clone(); }
+    if !cli.host.is_empty() { f.hosts = cli.host.clone(); }
+    Ok(f)
+}
+
+/// Flatten repeated `--level` values; an empty result keeps every level
+fn level_list(args: &[LevelArg]) -> Vec<Level> {
+    args.iter().flat_map(|a| a.levels().iter().copied()).collect()
+}
+
 #[derive(Clone, Debug, ValueEnum)]
-enum LevelArg { Info, Warning, Error }
-impl From<LevelArg> for Level {
-    fn from(v: LevelArg) -> Self {
-        match v { LevelArg::Info => Level::Info, LevelArg::Warning => Level::Warning, LevelArg::Error => Level::Error }
+enum FormatArg { Default, Gelf, Json, Logfmt, Journald, WindowsEvent, W3c, Haproxy, Traefik, Elb, Cloudfront, Syslog, Python, Postgres, Mysql, Jvm, Cef, Leef, Plugin }
+impl From<FormatArg> for Format {
+    fn from(v: FormatArg) -> Self {
+        match v {
+            FormatArg::Default => Format::Default,
+            FormatArg::Gelf => Format::Gelf,
+            FormatArg::Json => Format::Json,
+            FormatArg::Logfmt => Format::Logfmt,
+            FormatArg::Journald => Format::Journald,
+            FormatArg::Plugin => Format::Plugin,
+            FormatArg::WindowsEvent => Format::WindowsEvent,
+            FormatArg::W3c => Format::W3c,
+            FormatArg::Haproxy => Format::Haproxy,
+            FormatArg::Syslog => Format::Syslog,
+            FormatArg::Traefik => Format::Traefik,
+            FormatArg::Elb => Format::Elb,
+            FormatArg::Cloudfront => Format::CloudFront,
+            FormatArg::Python => Format::Python,
+            FormatArg::Postgres => Format::Postgres,
+            FormatArg::Mysql => Format::Mysql,
+            FormatArg::Jvm => Format::Jvm,
+            FormatArg::Cef => Format::Cef,
+            FormatArg::Leef => Format::Leef,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum MonthLocaleArg { Fr, De, Es, It, Pt, Nl, Ru, Uk, Pl }
+impl From<MonthLocaleArg> for MonthLocale {
+    fn from(v: MonthLocaleArg) -> Self {
+        match v {
+            MonthLocaleArg::Fr => MonthLocale::French,
+            MonthLocaleArg::De => MonthLocale::German,
+            MonthLocaleArg::Es => MonthLocale::Spanish,
+            MonthLocaleArg::It => MonthLocale::Italian,
+            MonthLocaleArg::Pt => MonthLocale::Portuguese,
+            MonthLocaleArg::Nl => MonthLocale::Dutch,
+            MonthLocaleArg::Ru => MonthLocale::Russian,
+            MonthLocaleArg::Uk => MonthLocale::Ukrainian,
+            MonthLocaleArg::Pl => MonthLocale::Polish,
+        }
     }
 }
 
 #[derive(Clone, Debug, ValueEnum)]
-enum GranularityArg { Minute, Hour, Day }
+enum GranularityArg { Second, Minute, Hour, Day, Week, Month, Auto }
 impl From<GranularityArg> for Granularity {
     fn from(v: GranularityArg) -> Self {
-        match v { GranularityArg::Minute => Granularity::Minute, GranularityArg::Hour => Granularity::Hour, GranularityArg::Day => Granularity::Day }
+        match v {
+            GranularityArg::Second => Granularity::Second,
+            GranularityArg::Minute => Granularity::Minute,
+            GranularityArg::Hour => Granularity::Hour,
+            GranularityArg::Day => Granularity::Day,
+            GranularityArg::Week => Granularity::Week,
+            GranularityArg::Month => Granularity::Month,
+            GranularityArg::Auto => Granularity::Auto,
+        }
     }
 }
 
-fn gather_log_files(paths: &[PathBuf]) -> Vec<PathBuf> {
-    let mut out = Vec::new();
-    for p in paths {
-        if p.is_file() {
-            out.push(p.clone());
-        } else if p.is_dir() {
-            for entry in WalkDir::new(p).into_iter().flatten() {
-                let path = entry.path();
-                if path.is_file() {
-                    if let Some(ext) = path.extension() { if ext == "log" { out.push(path.to_path_buf()); } }
-                }
-            }
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum PeriodArg { Hour, Day, Week, Month }
+impl From<PeriodArg> for Granularity {
+    fn from(v: PeriodArg) -> Self {
+        match v {
+            PeriodArg::Hour => Granularity::Hour,
+            PeriodArg::Day => Granularity::Day,
+            PeriodArg::Week => Granularity::Week,
+            PeriodArg::Month => Granularity::Month,
         }
     }
-    out
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum TsPrecisionArg { Seconds, Millis, Micros }
+impl From<TsPrecisionArg> for TimePrecision {
+    fn from(v: TsPrecisionArg) -> Self {
+        match v {
+            TsPrecisionArg::Seconds => TimePrecision::Seconds,
+            TsPrecisionArg::Millis => TimePrecision::Millis,
+            TsPrecisionArg::Micros => TimePrecision::Micros,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum OutputFormatArg { Text, Json, Yaml, Toml }
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum ColorArg { Auto, Always, Never }
+impl From<ColorArg> for ColorChoice {
+    fn from(v: ColorArg) -> Self {
+        match v { ColorArg::Auto => ColorChoice::Auto, ColorArg::Always => ColorChoice::Always, ColorArg::Never => ColorChoice::Never }
+    }
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum StyleArg { Iso, IsoBracketed, Syslog, Log4j, Python, Json, Logfmt }
+impl From<StyleArg> for Style {
+    fn from(v: StyleArg) -> Self {
+        match v {
+            StyleArg::Iso => Style::Iso,
+            StyleArg::IsoBracketed => Style::IsoBracketed,
+            StyleArg::Syslog => Style::Syslog,
+            StyleArg::Log4j => Style::Log4j,
+            StyleArg::Python => Style::Python,
+            StyleArg::Json => Style::Json,
+            StyleArg::Logfmt => Style::Logfmt,
+        }
+    }
+}
+
+#[derive(Clone, Debug, ValueEnum)]
+enum PaletteArg { Default, Colorblind, Grayscale }
+impl From<PaletteArg> for Palette {
+    fn from(v: PaletteArg) -> Self {
+        match v { PaletteArg::Default => Palette::Default, PaletteArg::Colorblind => Palette::Colorblind, PaletteArg::Grayscale => Palette::Grayscale }
+    }
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum ShadeArg { Weekends, Nights }
+impl From<ShadeArg> for Shading {
+    fn from(v: ShadeArg) -> Self {
+        match v { ShadeArg::Weekends => Shading::Weekends, ShadeArg::Nights => Shading::Nights }
+    }
+}
+
+fn parse_chart_size(s: &str) -> Result<(u32, u32), String> {
+    let (w, h) = s.split_once(['x', 'X']).ok_or("expected WIDTHxHEIGHT, e.g. 1200x500")?;
+    let w: u32 = w.trim().parse().map_err(|_| format!("invalid width: {w}"))?;
+    let h: u32 = h.trim().parse().map_err(|_| format!("invalid height: {h}"))?;
+    if w == 0 || h == 0 { return Err("chart size must be non-zero".into()); }
+    Ok((w, h))
+}
+
+fn parse_key_value(s: &str) -> Result<(String, String), String> {
+    let (k, v) = s.split_once('=').ok_or_else(|| format!("expected KEY=VALUE, got '{s}'"))?;
+    Ok((k.to_string(), v.to_string()))
+}
+
+fn parse_bucket(s: &str) -> Result<Granularity, String> {
+    Granularity::custom(s).map_err(|e| e.to_string())
+}
+
+fn parse_interval(s: &str) -> Result<Duration, String> {
+    match parse_duration(s) {
+        Ok(d) if d.is_zero() => Err("interval must be positive".into()),
+        r => r.map_err(|e| e.to_string()),
+    }
+}
+
+/// `2G`, `512M`, `64k` or plain bytes; units are binary
+fn parse_size(s: &str) -> Result<usize, String> {
+    let s = s.trim();
+    let (n, shift) = match s.char_indices().last() {
+        Some((i, 'k' | 'K')) => (&s[..i], 10),
+        Some((i, 'm' | 'M')) => (&s[..i], 20),
+        Some((i, 'g' | 'G')) => (&s[..i], 30),
+        _ => (s, 0),
+    };
+    let n: f64 = n.trim().parse().map_err(|_| format!("expected a size like 512M or 2G, got: {s}"))?;
+    if n > 0.0 && n.is_finite() { Ok((n * (1u64 << shift) as f64) as usize) } else { Err("size must be positive".into()) }
+}
+
+fn parse_rate(s: &str) -> Result<f64, String> {
+    let r: f64 = s.parse().map_err(|_| format!("not a number: {s}"))?;
+    if r > 0.0 && r.is_finite() { Ok(r) } else { Err("rate must be positive".into()) }
+}
+
+fn parse_fraction(s: &str) -> Result<f64, String> {
+    let f: f64 = s.parse().map_err(|_| format!("not a number: {s}"))?;
+    if f > 0.0 && f <= 1.0 { Ok(f) } else { Err("expected a fraction in (0, 1]".into()) }
 }
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
+    let config = cli.config.as_deref().map(Config::load).transpose()?.unwrap_or_default();
+    let make_parser = |format: Format| {
+        if !cli.plugin.is_empty() && format != Format::Plugin { anyhow::bail!("--plugin needs --format plugin"); }
+        parser_for(format, &ParserOptions {
+            assume_year: cli.assume_year,
+            month_locales: cli.month_locale.iter().map(|&l| l.into()).collect(),
+            plugins: cli.plugin.clone(),
+        })
+    };
+
+    match &cli.command {
+        Some(Command::ServeApi { listen, root, max_upload, max_memory }) => {
+            #[cfg(feature = "api")]
+            {
+                let opts = log_analyzer::api::ApiOptions { listen: listen.clone(), root: root.clone(), max_upload: *max_upload, max_memory: *max_memory };
+                return log_analyzer::api::serve(&opts);
+            }
+            #[cfg(not(feature = "api"))]
+            {
+                let _ = (listen, root, max_upload, max_memory);
+                eprintln!("This build has the API server disabled. Rebuild with `--features api`.");
+                std::process::exit(2);
+            }
+        }
+        Some(Command::Listen { udp, tcp, format, print_every, export, export_every }) => {
+            #[cfg(feature = "tokio")]
+            {
+                let opts = ListenOptions {
+                    udp: udp.clone(), tcp: tcp.clone(), print_every: *print_every, export: export.clone(), export_every: *export_every,
+                    report: ReportOptions { health: config.health, ..Default::default() },
+                };
+                return listen(&opts, || make_parser(format.clone().into()));
+            }
+            #[cfg(not(feature = "tokio"))]
+            {
+                let _ = (udp, tcp, format, print_every, export, export_every);
+                eprintln!("This build has the syslog listener disabled. Rebuild with `--features tokio`.");
+                std::process::exit(2);
+            }
+        }
+        Some(Command::Query { expr, inputs, count, format, discovery }) => {
+            let expr = query::Expr::parse(expr)?;
+            let files = gather_log_files(inputs, &discovery.clone().into())?;
+            if files.is_empty() { anyhow::bail!("No log files found in provided inputs"); }
+            let mut parser = make_parser(format.clone().into())?;
+            let matches = query::run(&expr, &mut *parser, &files, *count)?;
+            // grep convention: exit status 1 when nothing matched
+            if matches == 0 { std::process::exit(1); }
+            return Ok(());
+        }
+        Some(Command::Grep {
+            pattern, inputs, before_context, after_context, context, ignore_case, line_number, count,
+            level, min_level, from, to, color, format, discovery,
+        }) => {
+            let pattern = regex::RegexBuilder::new(pattern).case_insensitive(*ignore_case).build()?;
+            let mut filters = Filters::from_cli(None, from.as_deref(), to.as_deref(), &level_list(level))?;
+            filters.min_level = min_level.as_ref().map(LevelArg::min);
+            // Explicit -A/-B win over -C, like grep
+            let pick = |n: usize| if n > 0 { n } else { context.unwrap_or(0) };
+            let opts = GrepOptions {
+                pattern,
+                before: pick(*before_context),
+                after: pick(*after_context),
+                filters,
+                line_numbers: *line_number,
+                count: *count,
+                term: Term::new((*color).into()),
+            };
+            let files = gather_log_files(inputs, &discovery.clone().into())?;
+            if files.is_empty() { anyhow::bail!("No log files found in provided inputs"); }
+            let mut parser = make_parser(format.clone().into())?;
+            if grep::run(&opts, &mut *parser, &files)? == 0 { std::process::exit(1); }
+            return Ok(());
+        }
+        Some(Command::Generate { lines, rate, mix, format, bursts, malformed, start, seed, output }) => {
+            if !(0.0..=1.0).contains(malformed) { anyhow::bail!("--malformed must be a fraction between 0 and 1"); }
+            let opts = GenerateOptions {
+                lines: *lines,
+                start: parse_datetime(start)?,
+                rate: *rate,
+                mix: *mix,
+                styles: format.iter().map(|&f| f.into()).collect(),
+                bursts: *bursts,
+                malformed: *malformed,
+                seed: *seed,
+            };
+            let counts = match output {
+                Some(path) => {
+                    let mut out = std::io::BufWriter::new(std::fs::File::create(path).with_context(|| format!("Creating {}", path.display()))?);
+                    let counts = generate(&opts, &mut out)?;
+                    std::io::Write::flush(&mut out)?;
+                    counts
+                }
+                None => generate(&opts, &mut std::io::stdout().lock())?,
+            };
+            if let Some(path) = output {
+                eprintln!("Wrote {lines} lines -> {} (INFO={}, WARNING={}, ERROR={})", path.display(), counts[0], counts[1], counts[2]);
+            }
+            return Ok(());
+        }
+        None => {}
+    }
+
+    if cli.print_schema {
+        println!("{}", serde_json::to_string_pretty(&log_analyzer::report::json_schema())?);
+        return Ok(());
+    }
 
     if cli.gui {
         #[cfg(feature = "gui")]
-        { return ui::launch(); }
+        { return log_analyzer::ui::launch(cli.inputs, cli.max_memory); }
         #[cfg(not(feature = "gui"))]
         {
             eprintln!("This build has GUI disabled. Rebuild with `--features gui`.");
@@ -107,62 +910,788 @@ fn main() -> Result<()> {
         }
     }
 
-    let files = gather_log_files(&cli.inputs);
-    if files.is_empty() { anyhow::bail!("No .log files found in provided inputs"); }
+    #[cfg(not(feature = "parquet"))]
+    if cli.parquet_out.is_some() { anyhow::bail!("This build has Parquet export disabled. Rebuild with `--features parquet`."); }
 
-    let mut parser = DefaultLogParser::new();
+    // Set up before the analysis, so a build without gRPC fails right away
+    let otlp_exporter = match (cli.otlp_endpoint.as_deref(), cli.otlp_http_endpoint.as_deref()) {
+        (Some(endpoint), _) => Some((endpoint, OtlpExporter::grpc(endpoint, &cli.otlp_service)?)),
+        (None, Some(endpoint)) => Some((endpoint, OtlpExporter::new(endpoint, &cli.otlp_service))),
+        (None, None) => None,
+    };
 
-    let filters = Filters::from_cli(
-        cli.keyword.as_deref(),
-        cli.from.as_deref(),
-        cli.to.as_deref(),
-        cli.level.map(Into::into),
-    )?;
+    // Lines saved by --save-parsed stand in for the inputs
+    let files = if cli.load_parsed.is_some() { Vec::new() } else { gather_log_files(&cli.inputs, &cli.discovery.clone().into())? };
+    if files.is_empty() && cli.load_parsed.is_none() { anyhow::bail!("No log files found in provided inputs"); }
 
-    let gran: Granularity = cli.granularity.into();
-    let mut analyzer = Analyzer::new(gran);
+    let format: Format = cli.format.clone().into();
+    let mut parser = make_parser(format)?;
 
-    for path in files {
-        analyzer
-            .consume_file(&mut parser, &path)
-            .with_context(|| format!("Failed reading {path:?}"))?;
+    let filters = cli_filters(&cli)?;
+
+    if cli.merge || cli.merge_out.is_some() {
+        let entries = match cli.merge_out.as_deref() {
+            Some(path) => {
+                let opts = MergeOptions { filters, term: Term::new(ColorChoice::Never) };
+                let mut out = std::io::BufWriter::new(std::fs::File::create(path).with_context(|| format!("Creating {}", path.display()))?);
+                merge::run(&files, || make_parser(format), &opts, &mut out)?
+            }
+            None => {
+                let opts = MergeOptions { filters, term: Term::new(cli.color.into()) };
+                merge::run(&files, || make_parser(format), &opts, &mut std::io::stdout().lock())?
+            }
+        };
+        if let (Some(path), false) = (cli.merge_out.as_deref(), cli.quiet) {
+            eprintln!("Merged {entries} entries from {} files -> {}", files.len(), path.display());
+        }
+        return Ok(());
     }
 
-    let summary = build_summary(&analyzer, &filters);
+    let gran: Granularity = cli.bucket.unwrap_or(cli.granularity.into());
+    let mut analyzer = Analyzer::new(gran, filters);
+    analyzer.group_by = cli.group_by.clone();
+    analyzer.top_value_fields = cli.top_values.clone();
+    analyzer.tracked_keywords = cli.track.iter().map(|k| k.to_lowercase()).collect();
+    analyzer.quantile_fields = cli.quantiles.iter().map(|f| HistogramSource::field(f)).collect();
+    analyzer.error_messages = TopK::new(MessageLimits {
+        capacity: cli.max_error_messages, max_len: cli.max_message_len, examples: cli.error_examples,
+    });
+    analyzer.error_precursors = TopK::new(analyzer.error_messages.limits);
+    analyzer.error_history_period = cli.top_errors_by.map(Granularity::from);
+    analyzer.max_line_len = cli.max_line_len;
+    analyzer.ts_precision = cli.ts_precision.into();
+    analyzer.track_noise = cli.noisiest > 0;
+    if cli.malformed_out.is_some() { analyzer.retain_malformed = usize::MAX; }
+    analyzer.sampling = cli.sample.map(Sampling::Fraction).or(cli.sample_every.map(Sampling::Every));
+    analyzer.prefilter = !cli.no_prefilter && cli.save_parsed.is_none();
+    analyzer.recorder = cli.save_parsed.as_deref().map(Recorder::create).transpose()?;
+    analyzer.spill = cli.max_memory.map(Spill::new).transpose()?;
+    analyzer.histogram = cli.hist_out.as_ref().map(|h| h.field.as_deref().map_or(HistogramSource::MessageLength, HistogramSource::field));
 
-    // Print human summary
-    println!("====== SUMMARY ======");
-    println!("Total entries (filtered): {}", summary.total_entries);
-    println!(
-        "Counts: INFO={}, WARNING={}, ERROR={}",
-        summary.counts.info, summary.counts.warning, summary.counts.error
+    // Anything that changes per-file aggregates must be part of the cache settings
+    let settings = format!(
+        "{gran:?}|{format:?}|{:?}|{:?}|{:?}|{}|{:?}|{:?}|{:?}|{:?}|{}|{:?}|{:?}|{:?}|{:?}|{}|{}|{:?}|{:?}",
+        analyzer.filters, analyzer.group_by, cli.assume_year, analyzer.retain_malformed, analyzer.sampling,
+        analyzer.histogram.as_ref().map(|h| h.label()), analyzer.top_value_fields, cli.quantiles, analyzer.prefilter, cli.plugin,
+        analyzer.error_messages.limits, cli.month_locale, analyzer.error_history_period, analyzer.max_line_len, analyzer.track_noise,
+        analyzer.ts_precision, analyzer.tracked_keywords,
     );
-    println!("Malformed lines: {}", summary.malformed_lines);
-    if let Some(first) = summary.first_log.as_deref() { println!("First log: {first}"); }
-    if let Some(last) = summary.last_log.as_deref() { println!("Last log:  {last}"); }
-    if !summary.common_errors.is_empty() {
-        println!("
-Top error messages:");
-        for (msg, n) in &summary.common_errors { println!("  {n:>6}  {msg}"); }
+    let mut checkpointer = cli.checkpoint.as_deref()
+        .map(|p| Checkpointer::new(p, settings.clone(), &files, Duration::from_secs(cli.checkpoint_every)));
+    let mut cache = cli.cache.as_deref().map(|p| AnalysisCache::load(p, settings));
+
+    // Files already finished, and lines + aggregates of the one in progress, when resuming
+    let (mut files_done, mut current) = (0, None);
+    if let Some(cp) = checkpointer.as_ref().filter(|_| cli.resume).map(Checkpointer::resume).transpose()?.flatten() {
+        analyzer.merge(cp.analyzer);
+        files_done = cp.files_done;
+        current = cp.current.map(|(lines, p)| {
+            let mut partial = analyzer.new_partial();
+            partial.merge(p);
+            (lines, partial)
+        });
+        if !cli.quiet {
+            eprintln!("Resuming: {files_done} of {} files done{}", files.len(), current.as_ref().map_or(String::new(), |(l, _)| format!(", {l} lines into the next")));
+        }
+    }
+
+    let started = Instant::now();
+    let total_bytes = files.iter().filter_map(|f| std::fs::metadata(f).ok()).map(|m| m.len()).sum();
+    let progress = if cli.quiet { ProgressBar::hidden() } else { ProgressBar::new(total_bytes) };
+    progress.set_style(
+        ProgressStyle::with_template("{bar:40} {bytes}/{total_bytes} ({binary_bytes_per_sec}) {msg} [{elapsed_precise}]")?
+    );
+    progress.inc(files[..files_done].iter().filter_map(|f| std::fs::metadata(f).ok()).map(|m| m.len()).sum());
+    for (i, path) in files.iter().enumerate().skip(files_done) {
+        // Object store inputs have no local metadata, so they are never cached
+        let meta = if log_analyzer::remote::is_url(path) {
+            None
+        } else {
+            Some(std::fs::metadata(path).with_context(|| format!("Failed reading {path:?}"))?)
+        };
+        if let Some(hit) = meta.as_ref().and_then(|m| cache.as_ref()?.lookup(path, m)) {
+            analyzer.merge(hit.clone());
+            progress.inc(meta.as_ref().map_or(0, |m| m.len()));
+        } else {
+            let (skip, mut partial) = current.take().unwrap_or_else(|| (0, analyzer.new_partial()));
+            let reader = log_analyzer::input::open_with(path, |f| progress.wrap_read(f))
+                .with_context(|| format!("Failed reading {path:?}"))?;
+            let source = path.display().to_string();
+            match checkpointer.as_mut() {
+                Some(cp) => partial.consume_resumable(&mut *parser, reader, &source, skip, CHUNK_LINES, |p, lines| {
+                    cp.save(i, &analyzer, Some((lines, p)), false)
+                }),
+                None => partial.consume_reader(&mut *parser, reader, &source),
+            }
+            .with_context(|| format!("Failed reading {path:?}"))?;
+            for s in &partial.skipped_inputs { progress.suspend(|| eprintln!("Warning: skipping {}: {}", s.source, s.reason)); }
+            if let (Some(c), Some(meta)) = (cache.as_mut(), meta.as_ref()) { c.store(path, meta, &partial); }
+            analyzer.merge(partial);
+        }
+        if let Some(cp) = checkpointer.as_mut() { cp.save(i + 1, &analyzer, None, false)?; }
+        let lines_per_sec = analyzer.lines_read as f64 / started.elapsed().as_secs_f64().max(1e-3);
+        progress.set_message(format!("files {}/{} | {lines_per_sec:.0} lines/s", i + 1, files.len()));
+    }
+    if let Some(path) = cli.load_parsed.as_deref() {
+        parsed::load(path, &mut analyzer).with_context(|| format!("Loading parsed lines from {}", path.display()))?;
+    }
+    progress.finish_and_clear();
+    let elapsed = started.elapsed();
+    let saved_parsed = match (analyzer.recorder.take(), cli.save_parsed.as_deref()) {
+        (Some(r), Some(path)) => {
+            let lines = r.lock().unwrap_or_else(|e| e.into_inner()).finish();
+            Some(lines.with_context(|| format!("Saving parsed lines to {}", path.display()))?)
+        }
+        _ => None,
+    };
+    if let Some(cp) = &checkpointer { cp.finish()?; }
+    let spilled = match analyzer.spill.clone() {
+        Some(spill) => {
+            let (runs, bytes) = { let s = spill.lock().unwrap_or_else(|e| e.into_inner()); (s.runs(), s.bytes_written) };
+            analyzer.finish_spill()?;
+            analyzer.spill = None;
+            (runs > 0).then_some((runs, bytes))
+        }
+        None => None,
+    };
+
+    if let (Some(c), Some(path)) = (cache.as_ref(), cli.cache.as_deref()) {
+        c.save(path).with_context(|| format!("Saving cache to {}", path.display()))?;
+    }
+
+    analyzer.settle_granularity();
+    let opts = ReportOptions { incident_threshold: cli.incident_threshold, health: config.health, noisiest: cli.noisiest, ..Default::default() };
+    let mut summary = summarize(&analyzer, &opts);
+    if cli.error_context > 0 { attach_error_context(&mut summary, &*parser, cli.error_context)?; }
+    if let Some(path) = cli.baseline.as_deref() {
+        let format = match path.extension().and_then(|e| e.to_str()) {
+            Some("yaml" | "yml") => SummaryFormat::Yaml,
+            Some("toml") => SummaryFormat::Toml,
+            _ => SummaryFormat::Json,
+        };
+        let text = std::fs::read_to_string(path).with_context(|| format!("Reading baseline {}", path.display()))?;
+        let baseline = format.parse(&text).with_context(|| format!("Invalid baseline {}", path.display()))?;
+        compare_baseline(&mut summary, &baseline, &path.display().to_string(), cli.regression_threshold);
+    }
+
+    let output = if cli.json { OutputFormatArg::Json } else { cli.output_format };
+    // Machine-readable output owns stdout; everything else goes to stderr
+    let status = |msg: String| if output == OutputFormatArg::Text { println!("{msg}") } else { eprintln!("{msg}") };
+    let secs = elapsed.as_secs_f64().max(1e-3);
+    let throughput = (!cli.quiet).then(|| format!(
+        "Processed {} files, {} lines, {:.1} MiB in {secs:.2}s ({:.0} lines/s, {:.1} MiB/s)",
+        files.len(), analyzer.lines_read, total_bytes as f64 / 1048576.0,
+        analyzer.lines_read as f64 / secs, total_bytes as f64 / 1048576.0 / secs
+    ));
+    match output {
+        OutputFormatArg::Text => print_summary(&summary, throughput, cli.hist_out.is_some(), cli.breakdowns, Term::new(cli.color.into())),
+        OutputFormatArg::Json | OutputFormatArg::Yaml | OutputFormatArg::Toml => {
+            let format = match output {
+                OutputFormatArg::Yaml => SummaryFormat::Yaml,
+                OutputFormatArg::Toml => SummaryFormat::Toml,
+                _ => SummaryFormat::Json,
+            };
+            if let Some(line) = throughput { eprintln!("{line}"); }
+            let text = format.render(&JsonSummary::from(&summary))?;
+            print!("{text}");
+            if !text.ends_with('\n') { println!(); }
+        }
+    }
+    if cli.diagnose { status(diagnostics(&analyzer)); }
+
+    // Save JSON / YAML / TOML
+    let outputs = [(&cli.json_out, SummaryFormat::Json), (&cli.yaml_out, SummaryFormat::Yaml), (&cli.toml_out, SummaryFormat::Toml)];
+    for (path, format) in outputs.iter().filter_map(|(p, f)| p.as_deref().map(|p| (p, f))) {
+        std::fs::write(path, format.render(&JsonSummary::from(&summary))?).with_context(|| format!("Saving summary to {}", path.display()))?;
+        status(format!("Saved {} -> {}", format!("{format:?}").to_uppercase(), path.display()));
+    }
+
+    if let (Some(lines), Some(path)) = (saved_parsed, cli.save_parsed.as_deref()) {
+        status(format!("Saved {lines} parsed lines -> {}", path.display()));
+    }
+    if let (Some((runs, bytes)), false) = (spilled, cli.quiet) {
+        status(format!("Spilled error messages to disk in {runs} runs ({:.1} MiB) to stay within --max-memory", bytes as f64 / 1048576.0));
+    }
+
+    #[cfg(feature = "parquet")]
+    if let Some(path) = cli.parquet_out.as_deref() {
+        let rows = log_analyzer::export::write_parquet(path, &files, || make_parser(format), &analyzer.filters)
+            .with_context(|| format!("Saving Parquet to {}", path.display()))?;
+        status(format!("Saved {rows} entries -> {}", path.display()));
+    }
+
+    if let Some((endpoint, exporter)) = otlp_exporter {
+        let records = otlp::export_otlp(exporter, &files, || make_parser(format), &analyzer.filters)
+            .with_context(|| format!("Exporting to {endpoint}"))?;
+        status(format!("Exported {records} entries -> {endpoint}"));
     }
 
-    // Save JSON
-    if let Some(path) = cli.json_out.as_deref() {
-        std::fs::write(path, serde_json::to_vec_pretty(&summary)?)?;
-        println!("Saved JSON -> {}", path.display());
+    if let Some(path) = cli.malformed_out.as_deref() {
+        let mut out = String::new();
+        for m in &analyzer.malformed { out.push_str(&format!("{}:{}: {}\n", m.source, m.line_no, m.text)); }
+        std::fs::write(path, out).with_context(|| format!("Saving malformed lines to {}", path.display()))?;
+        status(format!("Saved {} malformed lines -> {}", analyzer.malformed.len(), path.display()));
     }
 
     // Charts
+    let style = ChartStyle {
+        size: cli.chart_size,
+        title: cli.chart_title.clone(),
+        palette: cli.palette.clone().into(),
+        shading: cli.shade.iter().map(|&s| s.into()).collect(),
+        mark_incidents: cli.mark_incidents,
+    };
+    let chart_data = match cli.chart_data_out.as_deref() {
+        Some(dir) if cli.bar_out.is_some() || cli.timeline_out.is_some() || cli.pressure_out.is_some() || cli.track_out.is_some() || cli.hist_out.is_some() => {
+            std::fs::create_dir_all(dir).with_context(|| format!("Creating {}", dir.display()))?;
+            Some(dir)
+        }
+        _ => None,
+    };
     if let Some(path) = cli.bar_out.as_deref() {
-        save_level_barchart(path, &summary)
+        save_level_barchart(path, &summary, &style)
             .with_context(|| format!("Saving bar chart to {}", path.display()))?;
-        println!("Saved bar chart -> {}", path.display());
+        status(format!("Saved bar chart -> {}", path.display()));
+        if let Some(dir) = chart_data {
+            let csv = dir.join("levels.csv");
+            write_level_csv(&csv, &summary).with_context(|| format!("Saving chart data to {}", csv.display()))?;
+            status(format!("Saved chart data -> {}", csv.display()));
+        }
     }
     if let Some(path) = cli.timeline_out.as_deref() {
-        save_timeline_chart(path, &summary, gran)
+        save_timeline_chart(path, &summary, &style)
             .with_context(|| format!("Saving timeline to {}", path.display()))?;
-        println!("Saved timeline -> {}", path.display());
+        status(format!("Saved timeline -> {}", path.display()));
+        if let Some(dir) = chart_data {
+            let csv = dir.join("timeline.csv");
+            write_timeline_csv(&csv, &summary).with_context(|| format!("Saving chart data to {}", csv.display()))?;
+            status(format!("Saved chart data -> {}", csv.display()));
+        }
+    }
+    if let Some(path) = cli.pressure_out.as_deref() {
+        save_pressure_chart(path, &summary, &cli.pressure_weights, &style)
+            .with_context(|| format!("Saving pressure chart to {}", path.display()))?;
+        status(format!("Saved pressure chart -> {}", path.display()));
+        if let Some(dir) = chart_data {
+            let csv = dir.join("pressure.csv");
+            write_pressure_csv(&csv, &summary, &cli.pressure_weights).with_context(|| format!("Saving chart data to {}", csv.display()))?;
+            status(format!("Saved chart data -> {}", csv.display()));
+        }
+    }
+    if let Some(path) = cli.track_out.as_deref() {
+        save_keyword_chart(path, &summary, &style)
+            .with_context(|| format!("Saving keyword chart to {}", path.display()))?;
+        status(format!("Saved keyword chart -> {}", path.display()));
+        if let Some(dir) = chart_data {
+            let csv = dir.join("tracked.csv");
+            write_keyword_csv(&csv, &summary).with_context(|| format!("Saving chart data to {}", csv.display()))?;
+            status(format!("Saved chart data -> {}", csv.display()));
+        }
+    }
+    if let (Some(spec), Some(stats)) = (cli.hist_out.as_ref(), summary.histogram.as_ref()) {
+        save_histogram(&spec.path, &analyzer.hist_values, stats, spec.bins, &style)
+            .with_context(|| format!("Saving histogram to {}", spec.path.display()))?;
+        status(format!("Saved histogram -> {}", spec.path.display()));
+        if let Some(dir) = chart_data {
+            let csv = dir.join("histogram.csv");
+            write_histogram_csv(&csv, &analyzer.hist_values, stats, spec.bins)
+                .with_context(|| format!("Saving chart data to {}", csv.display()))?;
+            status(format!("Saved chart data -> {}", csv.display()));
+        }
+    }
+
+    if cli.fail_on_baseline && summary.baseline.as_ref().is_some_and(|b| !b.new.is_empty() || !b.regressed.is_empty()) {
+        std::process::exit(3);
+    }
+
+    if cli.follow {
+        let actions = AlertActions { webhook: cli.alert_webhook, command: cli.alert_cmd };
+        let snapshots = cli.snapshot_every.map(|every| Snapshots {
+            every,
+            outputs: outputs.iter().filter_map(|(p, f)| Some(((*p).clone()?, *f))).collect(),
+            bar: cli.bar_out.clone(),
+            timeline: cli.timeline_out.clone(),
+            pressure: cli.pressure_out.clone().map(|p| (p, cli.pressure_weights)),
+            track: cli.track_out.clone(),
+            chart_data: chart_data.map(PathBuf::from),
+            style,
+            report: opts,
+        });
+        return follow(files, &mut || make_parser(format), analyzer, cli.alert, actions, snapshots);
     }
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// How often `--follow` checks the inputs for new data
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// `--snapshot-every`: the outputs rewritten while following
+struct Snapshots {
+    every: Duration,
+    outputs: Vec<(PathBuf, SummaryFormat)>,
+    bar: Option<PathBuf>,
+    timeline: Option<PathBuf>,
+    pressure: Option<(PathBuf, PressureWeights)>,
+    track: Option<PathBuf>,
+    /// `--chart-data-out`, where each chart's CSV is rewritten with it
+    chart_data: Option<PathBuf>,
+    style: ChartStyle,
+    report: ReportOptions,
+}
+
+impl Snapshots {
+    /// Rewrite every output from `summary`, reporting failures and carrying on
+    fn write(&self, summary: &Summary) {
+        for (path, format) in &self.outputs {
+            let written = format.render(&JsonSummary::from(summary))
+                .and_then(|text| replace_file(path, |tmp| Ok(std::fs::write(tmp, &text)?)));
+            if let Err(e) = written { eprintln!("Error: snapshot to {}: {e:#}", path.display()); }
+        }
+        self.chart(self.bar.as_deref(), "levels.csv", |p| save_level_barchart(p, summary, &self.style), |p| write_level_csv(p, summary));
+        self.chart(self.timeline.as_deref(), "timeline.csv", |p| save_timeline_chart(p, summary, &self.style), |p| write_timeline_csv(p, summary));
+        if let Some((path, weights)) = &self.pressure {
+            self.chart(Some(path), "pressure.csv", |p| save_pressure_chart(p, summary, weights, &self.style), |p| write_pressure_csv(p, summary, weights));
+        }
+        self.chart(self.track.as_deref(), "tracked.csv", |p| save_keyword_chart(p, summary, &self.style), |p| write_keyword_csv(p, summary));
+    }
+
+    /// Rewrite the chart at `path` with `save`, and its data as `csv` with `data` under `chart_data`
+    fn chart(&self, path: Option<&std::path::Path>, csv: &str, save: impl FnOnce(&std::path::Path) -> Result<()>, data: impl FnOnce(&std::path::Path) -> Result<()>) {
+        let Some(path) = path else { return };
+        if let Err(e) = replace_file(path, save) { eprintln!("Error: snapshot to {}: {e:#}", path.display()); }
+        if let Some(dir) = &self.chart_data {
+            let csv = dir.join(csv);
+            if let Err(e) = replace_file(&csv, data) { eprintln!("Error: snapshot to {}: {e:#}", csv.display()); }
+        }
+    }
+}
+
+/// Write `path` through `write` into a hidden sibling that keeps the extension (chart backends pick
+/// the image format from it), then rename it over `path` so readers never see a partial file
+fn replace_file(path: &std::path::Path, write: impl FnOnce(&std::path::Path) -> Result<()>) -> Result<()> {
+    let name = path.file_name().with_context(|| format!("{} is not a file path", path.display()))?;
+    let tmp = path.with_file_name(format!(".{}", name.to_string_lossy()));
+    write(&tmp)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// `--follow`: feed appended lines into the analyzer, print what arrived and check alert rules.
+#[cfg(not(feature = "tokio"))]
+fn follow(files: Vec<PathBuf>, new_parser: &mut dyn FnMut() -> Result<Box<dyn LogParser>>, mut analyzer: Analyzer, rules: Vec<AlertRule>, actions: AlertActions, snapshots: Option<Snapshots>) -> Result<()> {
+    let mut parser = new_parser()?;
+    let mut alerter = Alerter::new(rules, actions, &Totals::of(&analyzer));
+    eprintln!("\nFollowing {} file(s); Ctrl-C to stop", files.len());
+    let mut follower = Follower::new(files, &analyzer);
+    let mut last_snapshot = Instant::now();
+    loop {
+        std::thread::sleep(FOLLOW_POLL_INTERVAL);
+        let before = (analyzer.info, analyzer.warning, analyzer.error);
+        follower.poll(&mut *parser, &mut analyzer)?;
+        let (info, warning, error) = (analyzer.info - before.0, analyzer.warning - before.1, analyzer.error - before.2);
+        if info + warning + error > 0 {
+            eprintln!("{}  +{} entries (INFO={info}, WARNING={warning}, ERROR={error})", chrono::Local::now().format("%H:%M:%S"), info + warning + error);
+        }
+        alerter.check(&Totals::of(&analyzer));
+        if let Some(snap) = snapshots.as_ref().filter(|s| last_snapshot.elapsed() >= s.every) {
+            snap.write(&summarize(&analyzer, &snap.report));
+            last_snapshot = Instant::now();
+        }
+    }
+}
+
+/// `--follow` on the async ingestion pipeline: a task tails each file and a ticker prints what
+/// arrived and checks alert rules, while this thread parses.
+#[cfg(feature = "tokio")]
+fn follow(files: Vec<PathBuf>, new_parser: &mut dyn FnMut() -> Result<Box<dyn LogParser>>, analyzer: Analyzer, rules: Vec<AlertRule>, actions: AlertActions, snapshots: Option<Snapshots>) -> Result<()> {
+    let mut alerter = Alerter::new(rules, actions, &Totals::of(&analyzer));
+    eprintln!("\nFollowing {} file(s); Ctrl-C to stop", files.len());
+    let runtime = tokio::runtime::Runtime::new()?;
+    let _guard = runtime.enter();
+    let starts: Vec<_> = files.into_iter().map(|path| { let (offset, lines) = start_position(&path, &analyzer); (path, offset, lines) }).collect();
+    let pipeline = Pipeline::start(analyzer, DEFAULT_CAPACITY);
+    for (path, offset, lines) in starts {
+        let tx = pipeline.lines();
+        runtime.spawn(async move {
+            if let Err(e) = tail_file(path, offset, lines, FOLLOW_POLL_INTERVAL, tx).await { eprintln!("Error: {e:#}"); }
+        });
+    }
+    let shared = pipeline.analyzer();
+    if let Some(snap) = snapshots {
+        let (shared, snap) = (shared.clone(), std::sync::Arc::new(snap));
+        runtime.spawn(async move {
+            let mut tick = tokio::time::interval(snap.every);
+            tick.tick().await;
+            loop {
+                tick.tick().await;
+                // Only the summary is taken under the lock; charts render on a blocking thread
+                let summary = summarize(&shared.lock().unwrap(), &snap.report);
+                let snap = snap.clone();
+                if tokio::task::spawn_blocking(move || snap.write(&summary)).await.is_err() { break; }
+            }
+        });
+    }
+    runtime.spawn(async move {
+        let mut before = Totals::of(&shared.lock().unwrap());
+        let mut tick = tokio::time::interval(FOLLOW_POLL_INTERVAL);
+        loop {
+            tick.tick().await;
+            let now = Totals::of(&shared.lock().unwrap());
+            let (info, warning, error) = (now.info - before.info, now.warning - before.warning, now.error - before.error);
+            if info + warning + error > 0 {
+                eprintln!("{}  +{} entries (INFO={info}, WARNING={warning}, ERROR={error})", chrono::Local::now().format("%H:%M:%S"), info + warning + error);
+            }
+            // Actions may block on a slow webhook
+            let checked = tokio::task::spawn_blocking(move || { alerter.check(&now); (alerter, now) }).await;
+            let Ok(state) = checked else { break };
+            (alerter, before) = state;
+        }
+    });
+    pipeline.run_parser(new_parser)
+}
+
+#[cfg(feature = "tokio")]
+struct ListenOptions {
+    udp: Option<String>,
+    tcp: Option<String>,
+    print_every: Option<u64>,
+    export: Option<PathBuf>,
+    export_every: u64,
+    report: ReportOptions,
+}
+
+/// `listen`: analyze messages received on sockets through the ingestion pipeline, printing or
+/// exporting the summary while this thread parses.
+#[cfg(feature = "tokio")]
+fn listen(opts: &ListenOptions, new_parser: impl FnMut() -> Result<Box<dyn LogParser>>) -> Result<()> {
+    use log_analyzer::ingest::{read_tcp, read_udp};
+    use std::sync::{Arc, Mutex};
+
+    let runtime = tokio::runtime::Runtime::new()?;
+    let _guard = runtime.enter();
+    let pipeline = Pipeline::start(Analyzer::new(Granularity::Minute, Filters::default()), DEFAULT_CAPACITY);
+    let addr = |a: &str| if a.starts_with(':') { format!("0.0.0.0{a}") } else { a.to_string() };
+    if let Some(a) = opts.udp.as_deref() {
+        let socket = runtime.block_on(tokio::net::UdpSocket::bind(addr(a))).with_context(|| format!("Binding udp://{a}"))?;
+        eprintln!("Listening on udp://{}", socket.local_addr()?);
+        let tx = pipeline.lines();
+        runtime.spawn(async move { if let Err(e) = read_udp(socket, tx).await { eprintln!("Error: {e:#}"); } });
+    }
+    if let Some(a) = opts.tcp.as_deref() {
+        let listener = runtime.block_on(tokio::net::TcpListener::bind(addr(a))).with_context(|| format!("Binding tcp://{a}"))?;
+        eprintln!("Listening on tcp://{}", listener.local_addr()?);
+        let tx = pipeline.lines();
+        runtime.spawn(async move { if let Err(e) = read_tcp(listener, tx).await { eprintln!("Error: {e:#}"); } });
+    }
+    eprintln!("Press Enter to print the summary, Ctrl-C to stop");
+
+    let shared = pipeline.analyzer();
+    let report = opts.report;
+    let current = move |an: &Arc<Mutex<Analyzer>>| summarize(&an.lock().unwrap(), &report);
+    let show = move |an: &Arc<Mutex<Analyzer>>| print_summary(&current(an), None, false, false, Term::new(ColorChoice::Auto));
+    {
+        let shared = shared.clone();
+        std::thread::spawn(move || { for _ in std::io::stdin().lines() { show(&shared); } });
+    }
+    if let Some(secs) = opts.print_every {
+        let shared = shared.clone();
+        runtime.spawn(async move {
+            let mut tick = tokio::time::interval(Duration::from_secs(secs.max(1)));
+            tick.tick().await;
+            loop { tick.tick().await; show(&shared); }
+        });
+    }
+    if let Some(path) = opts.export.clone() {
+        let (shared, secs) = (shared.clone(), opts.export_every.max(1));
+        runtime.spawn(async move {
+            let mut tick = tokio::time::interval(Duration::from_secs(secs));
+            loop {
+                tick.tick().await;
+                let json = serde_json::to_string_pretty(&JsonSummary::from(&current(&shared))).expect("summary serializes");
+                if let Err(e) = replace_file(&path, |tmp| Ok(std::fs::write(tmp, json)?)) {
+                    eprintln!("Error: exporting summary to {}: {e:#}", path.display());
+                }
+            }
+        });
+    }
+    runtime.spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            show(&shared);
+            std::process::exit(0);
+        }
+    });
+    pipeline.run_parser(new_parser)
+}
+
+/// Text report printed to stdout by default.
+fn print_summary(summary: &Summary, throughput: Option<String>, hist_requested: bool, breakdowns: bool, term: Term) {
+    let stamp = |ts: &chrono::NaiveDateTime| summary.ts_precision.format(*ts);
+    println!("{}", term.bold("====== SUMMARY ======"));
+    println!("Total entries (filtered): {}", term.bold(&thousands(summary.total_entries)));
+    println!("{}", term.dim(&format!("  {:<9}{:>14}{:>9}", "Level", "Count", "Share")));
+    for level in [Level::Info, Level::Warning, Level::Error] {
+        let n = summary.count(level);
+        println!("  {}{:>14}{:>9}", term.level(level, 9), thousands(n), percent(n, summary.total_entries));
+    }
+    println!("Malformed lines: {}", thousands(summary.malformed_lines));
+    if let Some(h) = &summary.health {
+        println!(
+            "Health: {} ({:.1}/100, {:.1} weighted issues per 1k lines)",
+            term.bold(&h.grade), h.score, h.weighted_issues_per_1k
+        );
+    }
+    if summary.truncated_lines > 0 {
+        println!("Truncated lines: {} (longer than --max-line-len)", thousands(summary.truncated_lines));
+    }
+    if summary.repeats_restored > 0 {
+        println!("Restored repeats: {} (from `message repeated N times` and similar notices)", thousands(summary.repeats_restored));
+    }
+    if !summary.skipped_inputs.is_empty() {
+        println!("Skipped inputs: {}", summary.skipped_inputs.len());
+        for s in &summary.skipped_inputs { println!("  {}  {}", s.source, term.dim(&s.reason)); }
+    }
+    if summary.prefiltered_lines > 0 {
+        println!("Skipped by pre-filter: {} (use --no-prefilter to parse them)", thousands(summary.prefiltered_lines));
+    }
+    if let Some(line) = throughput { println!("{}", term.dim(&line)); }
+    if let Some(s) = &summary.sampling {
+        let e = &s.estimated_counts;
+        println!(
+            "Sampled {:.2}% of lines ({} of {}); estimated totals: entries~{}, INFO~{}, WARNING~{}, ERROR~{}",
+            s.rate * 100.0, thousands(s.lines_sampled), thousands(s.lines_read), thousands(s.estimated_total_entries),
+            thousands(e.info), thousands(e.warning), thousands(e.error)
+        );
+    }
+    if let Some(first) = &summary.first_log { println!("First log: {}", stamp(first)); }
+    if let Some(last) = &summary.last_log { println!("Last log:  {}", stamp(last)); }
+    for (level, (first, last)) in &summary.level_spans {
+        println!("  {} first {}  last {}", term.level(*level, 9), stamp(first), stamp(last));
+    }
+    if let Some(d) = summary.time_to_first_error {
+        println!("Time to first error: {}", term.bold(&human_duration(d.num_seconds())));
+    }
+    for (label, window) in [("5 minutes", &summary.worst_5m_window), ("hour", &summary.worst_1h_window)] {
+        let Some(w) = window else { continue };
+        println!(
+            "Worst {label}: {} errors of {} entries ({}) from {}",
+            term.bold(&thousands(w.errors)), thousands(w.entries), percent(w.errors, w.entries), stamp(&w.start),
+        );
+    }
+    if !summary.common_errors.is_empty() {
+        print!("\n{}", term.bold("Top error messages:"));
+        if summary.common_errors_approximate { print!(" {}", term.dim("(approximate: more distinct messages than --max-error-messages)")); }
+        println!();
+        for (msg, n) in &summary.common_errors {
+            println!("  {:>9}  {msg}", thousands(*n));
+            if let Some(ex) = summary.error_examples.get(msg).and_then(|ex| ex.first()) {
+                println!("  {:>9}  {}", "", term.dim(&format!("e.g. {}:{} at {}", ex.source, ex.line_no, ex.ts)));
+                if let Some(ctx) = summary.error_context.get(msg) {
+                    // Continuation lines of multi-line records go unnumbered
+                    let show = |n: u64, record: &str, hit: bool| for (i, line) in record.lines().enumerate() {
+                        let text = if i == 0 { format!("{n:>6}  {line}") } else { format!("{:>6}  {line}", "") };
+                        if hit { println!("  {:>9}  > {text}", ""); } else { println!("  {:>9}    {}", "", term.dim(&text)); }
+                    };
+                    let first = ctx.line_no - ctx.before.len() as u64;
+                    for (n, record) in (first..).zip(&ctx.before) { show(n, record, false); }
+                    show(ctx.line_no, &ex.raw, true);
+                    for (n, record) in (ctx.line_no + 1..).zip(&ctx.after) { show(n, record, false); }
+                }
+            }
+        }
+    }
+    if !summary.error_history.is_empty() {
+        println!("\n{}", term.bold("Top errors over time:"));
+        let period_format = match summary.error_history_period { Some(Granularity::Hour) => "%Y-%m-%d %H:00", Some(Granularity::Month) => "%Y-%m", _ => "%Y-%m-%d" };
+        for h in &summary.error_history {
+            let new = if h.new_in_range == Some(true) { format!(" {}", term.bold("[new]")) } else { String::new() };
+            println!("  {:>9}  {}{new}", thousands(h.count), h.template);
+            let periods = h.per_period.iter().map(|(p, n)| format!("{}={n}", p.format(period_format))).collect::<Vec<_>>().join(" ");
+            println!("  {:>9}  {}", "", term.dim(&format!("first {}  last {}  {periods}", stamp(&h.first_seen), stamp(&h.last_seen))));
+        }
+    }
+    if !summary.error_precursors.is_empty() {
+        println!("\n{}", term.bold("Top messages right before an error:"));
+        for (msg, n) in &summary.error_precursors { println!("  {:>9}  {msg}", thousands(*n)); }
+    }
+    if !summary.noisiest_messages.is_empty() {
+        println!("\n{}", term.bold("Noisiest messages:"));
+        for m in &summary.noisiest_messages {
+            println!("  {:>9}  {:>6}  {}", thousands(m.entries), format!("{:.1}%", m.share_pct), m.template);
+        }
+    }
+    if !summary.top_loggers.is_empty() {
+        println!("\n{}", term.bold("Top loggers by errors:"));
+        for (logger, n) in &summary.top_loggers { println!("  {:>9}  {logger}", thousands(*n)); }
+    }
+    if !summary.top_exceptions.is_empty() {
+        println!("\n{}", term.bold("Top exception types:"));
+        for (exc, n) in &summary.top_exceptions { println!("  {:>9}  {exc}", thousands(*n)); }
+    }
+    if !summary.tracked_keywords.is_empty() {
+        println!("\n{}", term.bold("Tracked keywords:"));
+        for k in &summary.tracked_keywords {
+            // Earliest bucket among equal peaks
+            let peak = k.counts.iter().zip(&summary.timeline).filter(|(n, _)| **n > 0)
+                .max_by(|a, b| a.0.cmp(b.0).then_with(|| b.1.0.cmp(&a.1.0)));
+            match (k.first_seen, peak) {
+                (Some(first), Some((n, (at, _)))) => println!(
+                    "  {:>9}  {}  first {}, peak {} at {}", thousands(k.total), k.keyword, stamp(&first), thousands(*n), stamp(at),
+                ),
+                _ => println!("  {:>9}  {}", 0, k.keyword),
+            }
+        }
+    }
+    for (field, values) in &summary.top_values {
+        println!("\n{}", term.bold(&format!("Top {field} values:")));
+        for (value, n) in values { println!("  {:>9}  {value}", thousands(*n)); }
+    }
+    for (field, groups) in &summary.groups {
+        println!("\n{}", term.bold(&format!("By {field}:")));
+        for g in groups {
+            let top = g.top_errors.first().map_or("", |(m, _)| m.as_str());
+            println!(
+                "  {:<24} total={:<10} errors={:<10} error_rate={:>5.1}%  {}",
+                g.value, thousands(g.total_entries), thousands(g.counts.error), g.error_rate * 100.0, top
+            );
+        }
+    }
+    if let Some(hosts) = &summary.hosts {
+        println!("\n{}", term.bold("Hosts:"));
+        for h in &hosts.hosts {
+            let top = h.top_errors.first().map_or("", |(m, _)| m.as_str());
+            println!(
+                "  {:<24} total={:<10} errors={:<10} error_rate={:>5.1}%  {}",
+                h.value, thousands(h.total_entries), thousands(h.counts.error), h.error_rate * 100.0, top
+            );
+        }
+        println!("  Noisiest: {}", hosts.noisiest);
+        if !hosts.outliers.is_empty() {
+            println!("  Error rate vs mean of {:.1}%:", hosts.mean_error_rate * 100.0);
+            for o in &hosts.outliers {
+                println!("    {:<22} {:>5.1}%  {:+.1} pts  z={:+.2}", o.host, o.error_rate * 100.0, o.deviation * 100.0, o.z_score);
+            }
+        }
+    }
+    if !summary.incidents.is_empty() {
+        println!("\n{}", term.bold("Incidents:"));
+        for inc in &summary.incidents {
+            println!(
+                "  {} .. {}  errors={} peak={}/bucket  {}",
+                stamp(&inc.start), stamp(&inc.end), inc.total_errors, inc.peak_rate, inc.top_template
+            );
+        }
+    }
+    if let Some(b) = &summary.baseline {
+        println!("\n{}", term.bold(&format!("Compared with baseline {}:", b.source)));
+        println!("  New error templates: {}", b.new.len());
+        for (t, n) in b.new.iter().take(10) { println!("    {n:>8}  {t}"); }
+        println!("  Regressed (share of entries up more than {}%): {}", b.threshold_pct, b.regressed.len());
+        for r in b.regressed.iter().take(10) {
+            println!("    {:>8} (was {}, {:+.0}%)  {}", r.current, r.baseline, r.change_pct, r.template);
+        }
+        println!("  Gone: {}", b.gone.len());
+        for (t, n) in b.gone.iter().take(10) { println!("    {n:>8}  {t}"); }
+    }
+    if !summary.probable_crashes.is_empty() {
+        println!("\n{}", term.bold("Probable crashes:"));
+        for c in &summary.probable_crashes {
+            let silence = c.silence_after_secs.map_or("nothing after".to_string(), |s| format!("silent {}", human_duration(s)));
+            println!("  {}  {}:{}  [{}] {}  {}", stamp(&c.ts), c.source, c.line_no, c.indicator, silence, c.message);
+        }
+    }
+
+    if let Some(t) = &summary.trends {
+        println!("\n{}", term.bold("Trends:"));
+        println!("  Error slope: {:+.3} errors/bucket per bucket", t.error_slope);
+        if let Some((hour, n)) = &t.busiest_hour { println!("  Busiest hour: {} ({n} entries)", hour.format("%Y-%m-%d %H:00")); }
+        if let Some((day, n)) = &t.busiest_day { println!("  Busiest day:  {day} ({n} entries)"); }
+        let pct = |p: Option<f64>| p.map_or("-".to_string(), |p| format!("{p:+.1}%"));
+        for w in &t.weeks {
+            println!(
+                "  Week of {}: entries={} ({}) errors={} ({})",
+                w.week_start, w.entries, pct(w.entries_change_pct), w.errors, pct(w.errors_change_pct)
+            );
+        }
+    }
+
+    if breakdowns {
+        for (title, header, rows) in [("By hour of day:", "Hour", &summary.by_hour_of_day), ("By weekday:", "Day", &summary.by_weekday)] {
+            if rows.is_empty() { continue; }
+            println!("\n{}", term.bold(title));
+            println!("{}", term.dim(&format!("  {header:<6}{:>12}{:>10}{:>8}", "Entries", "Errors", "Rate")));
+            let most = rows.iter().map(|r| r.errors).max().unwrap_or(0).max(1);
+            for r in rows.iter() {
+                let bar = "#".repeat((r.errors * 20).div_ceil(most) as usize);
+                let line = format!("  {:<6}{:>12}{:>10}{:>8}  {}", r.period, thousands(r.entries), thousands(r.errors), percent(r.errors, r.entries), term.highlight(&bar));
+                println!("{}", line.trim_end());
+            }
+        }
+    }
+
+    if let Some(q) = &summary.quantiles {
+        println!("\n{}", term.bold(&format!("Percentiles (within {}%):", q.relative_accuracy * 100.0)));
+        let rows = [("message length", q.message_length.as_ref()), ("inter-arrival (s)", q.inter_arrival_secs.as_ref())];
+        for (label, s) in rows.into_iter().filter_map(|(l, s)| Some((l, s?))).chain(q.fields.iter().map(|(f, s)| (f.as_str(), s))) {
+            println!(
+                "  {label:<20} n={:<10} p50={:<9.2} p90={:<9.2} p95={:<9.2} p99={:<9.2} p99.9={:<9.2} max={:.2}",
+                thousands(s.count), s.p50, s.p90, s.p95, s.p99, s.p999, s.max
+            );
+        }
+    }
+
+    if let Some(j) = &summary.jvm {
+        println!("\n{}", term.bold("JVM:"));
+        if let Some(p) = &j.pause_ms {
+            let share = j.pause_time_pct.map_or(String::new(), |pct| format!(" ({pct:.2}% of the time)"));
+            println!("  GC pauses: {} ({} full), {:.1} ms in total{share}", thousands(j.gc_pauses), thousands(j.full_gcs), j.total_pause_ms);
+            println!("  Pause ms: p50={:.2} p90={:.2} p99={:.2} max={:.2}", p.p50, p.p90, p.p99, p.max);
+        }
+        if let Some(after) = j.heap_after_max_kb {
+            let total = j.heap_total_max_kb.map_or(String::new(), |t| format!(" of {:.1} MiB", t as f64 / 1024.0));
+            println!("  Heap after GC: up to {:.1} MiB{total}", after as f64 / 1024.0);
+        }
+        if !j.oom_events.is_empty() {
+            let times: Vec<String> = j.oom_events.iter().map(stamp).collect();
+            println!("  OutOfMemoryError ({}): {}", j.oom_events.len(), times.join(", "));
+        }
+    }
+
+    if let Some(h) = &summary.histogram {
+        println!(
+            "\nHistogram of {}: n={} min={:.2} max={:.2} mean={:.2} p50={:.2} p95={:.2}",
+            h.source, h.count, h.min, h.max, h.mean, h.p50, h.p95
+        );
+    } else if hist_requested {
+        println!("\nHistogram: no numeric values found");
+    }
+}
+
+/// `--diagnose`: where parsing failed and why.
+fn diagnostics(analyzer: &Analyzer) -> String {
+    use std::fmt::Write;
+    let mut out = String::from("\nParse diagnostics:");
+    for (source, format) in &analyzer.format_detections {
+        let _ = write!(out, "\n  {source}: detected {format}");
+    }
+    if !analyzer.pattern_matches.is_empty() {
+        let total = analyzer.pattern_matches.values().sum::<u64>() + analyzer.pattern_misses;
+        let mut by_count: Vec<_> = analyzer.pattern_matches.iter().collect();
+        by_count.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        out.push_str("\n  Lines per pattern:");
+        for (name, n) in by_count.into_iter().map(|(k, v)| (k.as_str(), *v)).chain([("(none)", analyzer.pattern_misses)]) {
+            let _ = write!(out, "\n    {name:<14} {:>10}  {:>6}", thousands(n), percent(n, total));
+        }
+    }
+    for (source, s) in analyzer.sources.iter().filter(|(_, s)| s.malformed > 0) {
+        let _ = write!(out, "\n  {source}: {} of {} lines malformed ({:.1}%)", s.malformed, s.lines, s.malformed as f64 * 100.0 / s.lines as f64);
+    }
+    let mut failures: Vec<_> = analyzer.parse_failures.iter().collect();
+    failures.sort_by(|a, b| b.1.count.cmp(&a.1.count).then_with(|| a.0.cmp(b.0)));
+    if failures.is_empty() { out.push_str("\n  No parse failures"); }
+    for (reason, f) in failures.iter().take(10) {
+        let _ = write!(out, "\n  {:>6}  {reason}", f.count);
+        for ex in &f.examples { let _ = write!(out, "\n            {}:{}: {}", ex.source, ex.line_no, ex.text); }
+    }
+    out
+}
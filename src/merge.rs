@@ -0,0 +1,108 @@
+//! `--merge`: entries from several inputs interleaved by timestamp, each line tagged with its
+//! source, like lnav's merged view.
+
+use crate::analyze::{lossy_lines, Filters, LossyLines, DEFAULT_MAX_LINE_LEN};
+use crate::model::LogEntry;
+use crate::parse::{LogParser, RECORD_LINE_SEP};
+use crate::report::term::Term;
+use anyhow::{Context, Result};
+use chrono::NaiveDateTime;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+
+pub struct MergeOptions {
+    /// Entries failing these are left out, with their continuation lines
+    pub filters: Filters,
+    pub term: Term,
+}
+
+/// An entry's line plus the unparsed lines around it (stack traces, wrapped messages)
+struct Record {
+    entry: LogEntry,
+    lines: Vec<String>,
+}
+
+/// One input, read a record at a time.
+struct Stream {
+    label: String,
+    lines: LossyLines<Box<dyn BufRead>>,
+    parser: Box<dyn LogParser>,
+    /// Entry line already read that starts the next record
+    head: Option<(LogEntry, String)>,
+}
+
+impl Stream {
+    /// The next record; lines before the input's first entry go with that entry.
+    fn next_record(&mut self) -> Result<Option<Record>> {
+        let mut record = self.head.take().map(|(entry, line)| Record { entry, lines: vec![line] });
+        let mut leading = Vec::new();
+        for line in self.lines.by_ref() {
+            let line = line.with_context(|| format!("Failed reading {}", self.label))?;
+            // Headers and directives carry no entry of their own
+            if self.parser.absorb(&line) { continue; }
+            match (self.parser.parse_line(&line), &mut record) {
+                (Ok(Some(entry)), None) => {
+                    leading.push(line);
+                    record = Some(Record { entry, lines: std::mem::take(&mut leading) });
+                }
+                (Ok(Some(entry)), Some(_)) => {
+                    self.head = Some((entry, line));
+                    break;
+                }
+                (_, Some(r)) => r.lines.push(line),
+                (_, None) => leading.push(line),
+            }
+        }
+        Ok(record)
+    }
+}
+
+/// Write the entries of `files` in timestamp order (ties go to the earlier input) as
+/// `label | line`, where the label is the file name (the whole path when names repeat). Each
+/// input is assumed to be chronological by itself. `parser` builds one parser per input.
+/// Returns the number of entries written.
+pub fn run(
+    files: &[PathBuf],
+    mut parser: impl FnMut() -> Result<Box<dyn LogParser>>,
+    opts: &MergeOptions,
+    out: &mut impl Write,
+) -> Result<u64> {
+    let file_name = |p: &PathBuf| p.file_name().map(|n| n.to_string_lossy().into_owned());
+    let names: Vec<_> = files.iter().map(file_name).collect();
+    let unique = names.iter().all(|n| n.is_some() && names.iter().filter(|m| *m == n).count() == 1);
+    let mut streams = Vec::with_capacity(files.len());
+    for (path, name) in files.iter().zip(names) {
+        let parser = parser()?;
+        let input = parser.decode(crate::input::open(path)?);
+        let label = name.filter(|_| unique).unwrap_or_else(|| path.display().to_string());
+        streams.push(Stream { label, lines: lossy_lines(input, DEFAULT_MAX_LINE_LEN), parser, head: None });
+    }
+    let width = streams.iter().map(|s| s.label.chars().count()).max().unwrap_or(0);
+
+    // Current record of each stream, and a min-heap of (timestamp, stream)
+    let mut current: Vec<Option<Record>> = Vec::with_capacity(streams.len());
+    let mut heap: BinaryHeap<Reverse<(NaiveDateTime, usize)>> = BinaryHeap::new();
+    for (i, s) in streams.iter_mut().enumerate() {
+        let record = s.next_record()?;
+        if let Some(r) = &record { heap.push(Reverse((r.entry.ts, i))); }
+        current.push(record);
+    }
+
+    let mut written = 0;
+    while let Some(Reverse((_, i))) = heap.pop() {
+        let record = current[i].take().expect("queued stream has a record");
+        if opts.filters.pass(&record.entry) {
+            let tag = opts.term.dim(&format!("{:<width$} |", streams[i].label));
+            for line in &record.lines {
+                for part in line.split(RECORD_LINE_SEP) { writeln!(out, "{tag} {part}")?; }
+            }
+            written += 1;
+        }
+        current[i] = streams[i].next_record()?;
+        if let Some(r) = &current[i] { heap.push(Reverse((r.entry.ts, i))); }
+    }
+    out.flush()?;
+    Ok(written)
+}
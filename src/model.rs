@@ -1,14 +1,100 @@
 use chrono::{NaiveDateTime};
 use serde::{Serialize, Deserialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
 
 
+/// Ordered by severity: `Info < Warning < Error`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Level {
+    #[serde(alias = "info")]
+    Info,
+    #[serde(alias = "warning", alias = "warn")]
+    Warning,
+    #[serde(alias = "error")]
+    Error,
+}
+
+
+impl std::str::FromStr for Level {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "info" => Ok(Level::Info),
+            "warning" | "warn" => Ok(Level::Warning),
+            "error" => Ok(Level::Error),
+            _ => Err(format!("unknown level: {s} (expected info, warning or error)")),
+        }
+    }
+}
+
+
+/// A set of levels as a bitset; the default is every level. Serializes as a list of levels.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub enum Level { Info, Warning, Error }
+#[serde(from = "Vec<Level>", into = "Vec<Level>")]
+pub struct LevelSet(u8);
+
+impl LevelSet {
+    pub const ALL: LevelSet = LevelSet(0b111);
+    pub const NONE: LevelSet = LevelSet(0);
+
+    fn bit(level: Level) -> u8 { 1 << level as u8 }
+
+    pub fn insert(&mut self, level: Level) { self.0 |= Self::bit(level); }
+    pub fn contains(self, level: Level) -> bool { self.0 & Self::bit(level) != 0 }
+    pub fn is_all(self) -> bool { self == Self::ALL }
+    pub fn is_empty(self) -> bool { self == Self::NONE }
+}
+
+impl Default for LevelSet {
+    fn default() -> Self { Self::ALL }
+}
+
+impl FromIterator<Level> for LevelSet {
+    fn from_iter<I: IntoIterator<Item = Level>>(iter: I) -> Self {
+        let mut set = Self::NONE;
+        for level in iter { set.insert(level); }
+        set
+    }
+}
+
+impl From<Vec<Level>> for LevelSet {
+    fn from(levels: Vec<Level>) -> Self { levels.into_iter().collect() }
+}
+
+impl From<LevelSet> for Vec<Level> {
+    fn from(set: LevelSet) -> Self {
+        [Level::Info, Level::Warning, Level::Error].into_iter().filter(|l| set.contains(*l)).collect()
+    }
+}
+
+/// Comma-separated levels such as `error,warning`; `all` is every level.
+impl std::str::FromStr for LevelSet {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut set = Self::NONE;
+        for part in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            if part.eq_ignore_ascii_case("all") { return Ok(Self::ALL); }
+            set.insert(part.parse()?);
+        }
+        if set.is_empty() { return Err("no level given".to_string()); }
+        Ok(set)
+    }
+}
 
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct LogEntry {
 pub ts: NaiveDateTime,
 pub level: Level,
 pub message: String,
+/// extracted fields such as `host` or `app` (empty when the format has none)
+pub fields: BTreeMap<String, String>,
+/// input the entry was read from, shared by all its entries (None until the analyzer or an export sets it)
+#[serde(default)]
+pub source: Option<Arc<Path>>,
+/// line (or, for multi-line formats, record) number within `source`; 0 when unknown
+#[serde(default)]
+pub line_no: u64,
 }
\ No newline at end of file
@@ -0,0 +1,209 @@
+//! Parsed entries sent to an OpenTelemetry collector as OTLP log records, to backfill historical
+//! file logs into an OTel pipeline. Records go over OTLP/gRPC (`--otlp-endpoint`, `grpc` feature)
+//! or over OTLP/HTTP with the JSON encoding (`--otlp-http-endpoint`, `POST {endpoint}/v1/logs`).
+
+#[cfg(feature = "grpc")]
+mod grpc;
+
+use crate::analyze::Filters;
+use crate::entries::{BinaryInput, FileEntries};
+use crate::model::{Level, LogEntry};
+use crate::parse::LogParser;
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::borrow::Cow;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Records per export request
+const BATCH_RECORDS: usize = 1000;
+/// Attempts per request when the collector pushes back (HTTP 429 and 502-504, the retryable gRPC
+/// codes) or can't be reached
+const ATTEMPTS: u32 = 4;
+
+/// `service.name` of the exported resource unless `--otlp-service` says otherwise
+pub const DEFAULT_SERVICE_NAME: &str = "log_analyzer";
+/// OTLP/HTTP port of a stock collector, used when the endpoint names none
+pub const DEFAULT_HTTP_PORT: u16 = 4318;
+/// OTLP/gRPC port of a stock collector, used when the endpoint names none
+pub const DEFAULT_GRPC_PORT: u16 = 4317;
+
+/// OTel severity numbers and texts of the three levels
+fn severity(level: Level) -> (u8, &'static str) {
+    match level { Level::Info => (9, "INFO"), Level::Warning => (13, "WARN"), Level::Error => (17, "ERROR") }
+}
+
+/// Value of a record attribute
+enum AttrValue<'a> {
+    Str(Cow<'a, str>),
+    Int(u64),
+}
+
+/// Attributes of `entry`'s record: every field as a string, plus `log.file.path` and
+/// `log.file.line` when the entry has a source
+fn attributes(entry: &LogEntry) -> Vec<(&str, AttrValue<'_>)> {
+    let mut attributes: Vec<_> = entry.fields.iter().map(|(k, v)| (k.as_str(), AttrValue::Str(Cow::Borrowed(v)))).collect();
+    if let Some(source) = &entry.source {
+        attributes.push(("log.file.path", AttrValue::Str(source.to_string_lossy())));
+        attributes.push(("log.file.line", AttrValue::Int(entry.line_no)));
+    }
+    attributes
+}
+
+/// Timestamps have no zone, so they are sent as UTC
+fn time_unix_nano(entry: &LogEntry) -> u64 {
+    entry.ts.and_utc().timestamp_nanos_opt().unwrap_or_default().max(0) as u64
+}
+
+fn attribute(key: &str, value: Value) -> Value { json!({ "key": key, "value": value }) }
+
+/// `entry` as an OTLP/JSON `LogRecord`: the message is the body and every field a string
+/// attribute, plus `log.file.path` and `log.file.line` when the entry has a source
+pub fn log_record(entry: &LogEntry) -> Value {
+    let (number, text) = severity(entry.level);
+    let attributes: Vec<Value> = attributes(entry).into_iter().map(|(key, value)| match value {
+        AttrValue::Str(v) => attribute(key, json!({ "stringValue": v })),
+        AttrValue::Int(v) => attribute(key, json!({ "intValue": v.to_string() })),
+    }).collect();
+    json!({
+        "timeUnixNano": time_unix_nano(entry).to_string(),
+        "severityNumber": number,
+        "severityText": text,
+        "body": { "stringValue": entry.message },
+        "attributes": attributes,
+    })
+}
+
+/// `endpoint` with `http://` when it has no scheme and `default_port` when it has no port
+fn endpoint_url(endpoint: &str, default_port: u16) -> String {
+    let endpoint = endpoint.trim_end_matches('/');
+    let (scheme, rest) = endpoint.split_once("://").unwrap_or(("http", endpoint));
+    let (host, path) = rest.find('/').map_or((rest, ""), |i| rest.split_at(i));
+    let has_port = host.rsplit_once(':').is_some_and(|(_, port)| !port.is_empty() && !port.ends_with(']') && port.bytes().all(|b| b.is_ascii_digit()));
+    if has_port { format!("{scheme}://{host}{path}") } else { format!("{scheme}://{host}:{default_port}{path}") }
+}
+
+/// `entries` as an OTLP/JSON `ExportLogsServiceRequest` from `service_name`
+fn json_request(service_name: &str, entries: &[LogEntry]) -> String {
+    let records: Vec<Value> = entries.iter().map(log_record).collect();
+    json!({
+        "resourceLogs": [{
+            "resource": { "attributes": [attribute("service.name", json!({ "stringValue": service_name }))] },
+            "scopeLogs": [{
+                "scope": { "name": "log_analyzer", "version": env!("CARGO_PKG_VERSION") },
+                "logRecords": records,
+            }],
+        }],
+    }).to_string()
+}
+
+/// How batches reach the collector
+enum Transport {
+    /// OTLP/HTTP JSON requests to this `/v1/logs` URL
+    Http(String),
+    #[cfg(feature = "grpc")]
+    Grpc(grpc::Client),
+}
+
+/// A failed export request, and whether sending it again may succeed
+struct Rejected {
+    error: anyhow::Error,
+    retry: bool,
+}
+
+/// Batches entries into OTLP export requests to one collector
+pub struct OtlpExporter {
+    transport: Transport,
+    service_name: String,
+    batch: Vec<LogEntry>,
+    sent: u64,
+}
+
+impl OtlpExporter {
+    /// Exports over OTLP/HTTP. `endpoint` is the collector's base URL (`http://localhost:4318`, or
+    /// just `localhost`) or the full `/v1/logs` one
+    pub fn new(endpoint: &str, service_name: &str) -> Self {
+        let endpoint = endpoint_url(endpoint, DEFAULT_HTTP_PORT);
+        let url = if endpoint.ends_with("/v1/logs") { endpoint.to_string() } else { format!("{endpoint}/v1/logs") };
+        Self::with_transport(Transport::Http(url), service_name)
+    }
+
+    /// Exports over OTLP/gRPC in cleartext HTTP/2. `endpoint` is the collector's address
+    /// (`http://localhost:4317`, or just `localhost`)
+    #[cfg(feature = "grpc")]
+    pub fn grpc(endpoint: &str, service_name: &str) -> Result<Self> {
+        let client = grpc::Client::new(&endpoint_url(endpoint, DEFAULT_GRPC_PORT))?;
+        Ok(Self::with_transport(Transport::Grpc(client), service_name))
+    }
+
+    #[cfg(not(feature = "grpc"))]
+    pub fn grpc(_endpoint: &str, _service_name: &str) -> Result<Self> {
+        anyhow::bail!("This build has OTLP/gRPC export disabled. Rebuild with `--features grpc`, or use --otlp-http-endpoint.")
+    }
+
+    fn with_transport(transport: Transport, service_name: &str) -> Self {
+        Self { transport, service_name: service_name.to_string(), batch: Vec::with_capacity(BATCH_RECORDS), sent: 0 }
+    }
+
+    pub fn push(&mut self, entry: &LogEntry) -> Result<()> {
+        self.batch.push(entry.clone());
+        if self.batch.len() >= BATCH_RECORDS { self.flush()?; }
+        Ok(())
+    }
+
+    /// One attempt at exporting the batch
+    fn send(&mut self) -> Result<(), Rejected> {
+        match &mut self.transport {
+            Transport::Http(url) => {
+                let body = json_request(&self.service_name, &self.batch);
+                match ureq::post(url).set("Content-Type", "application/json").send_string(&body) {
+                    Ok(_) => Ok(()),
+                    Err(e) => {
+                        let retry = matches!(e, ureq::Error::Status(429 | 502..=504, _) | ureq::Error::Transport(_));
+                        Err(Rejected { error: anyhow::Error::new(e).context(format!("POST {url}")), retry })
+                    }
+                }
+            }
+            #[cfg(feature = "grpc")]
+            Transport::Grpc(client) => client.export(&grpc::export_request(&self.service_name, &self.batch)),
+        }
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        if self.batch.is_empty() { return Ok(()); }
+        let mut attempt = 1;
+        loop {
+            match self.send() {
+                Ok(()) => break,
+                Err(Rejected { retry: true, .. }) if attempt < ATTEMPTS => {
+                    std::thread::sleep(Duration::from_millis(500 << attempt));
+                    attempt += 1;
+                }
+                Err(rejected) => return Err(rejected.error),
+            }
+        }
+        self.sent += self.batch.len() as u64;
+        self.batch.clear();
+        Ok(())
+    }
+
+    /// Send what's left; returns the number of records exported
+    pub fn finish(mut self) -> Result<u64> {
+        self.flush()?;
+        Ok(self.sent)
+    }
+}
+
+/// Parse `files` again, each with a parser from `new_parser`, and export every entry passing
+/// `filters` through `exporter`. Binary files are skipped, as in the analysis. Returns the record
+/// count.
+pub fn export_otlp(mut exporter: OtlpExporter, files: &[PathBuf], new_parser: impl FnMut() -> Result<Box<dyn LogParser>>, filters: &Filters) -> Result<u64> {
+    for entry in FileEntries::new(new_parser, files.to_vec()).with_filters(filters) {
+        let entry = match entry {
+            Err(e) if e.is::<BinaryInput>() => continue,
+            entry => entry?,
+        };
+        exporter.push(&entry)?;
+    }
+    exporter.finish()
+}
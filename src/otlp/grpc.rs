@@ -0,0 +1,197 @@
+//! OTLP/gRPC transport: `ExportLogsServiceRequest`s encoded by hand (the few protobuf messages
+//! OTLP logs need, with the field numbers of opentelemetry-proto) and sent to the collector's
+//! `LogsService/Export` over cleartext HTTP/2.
+
+use super::{attributes, severity, time_unix_nano, AttrValue, Rejected};
+use crate::model::LogEntry;
+use anyhow::{anyhow, Context, Result};
+use bytes::{BufMut, Bytes, BytesMut};
+use h2::client::SendRequest;
+use http::{HeaderMap, Request, Uri};
+use tokio::net::TcpStream;
+use tokio::runtime::Runtime;
+
+/// gRPC method every batch is sent to
+const EXPORT_PATH: &str = "/opentelemetry.proto.collector.logs.v1.LogsService/Export";
+
+/// gRPC status codes worth retrying per the OTLP spec: CANCELLED, DEADLINE_EXCEEDED,
+/// RESOURCE_EXHAUSTED, ABORTED, OUT_OF_RANGE, UNAVAILABLE and DATA_LOSS
+const RETRYABLE_CODES: [u32; 7] = [1, 4, 8, 10, 11, 14, 15];
+
+/// A protobuf message being encoded
+#[derive(Default)]
+struct Message(Vec<u8>);
+
+impl Message {
+    fn varint(&mut self, mut v: u64) {
+        while v >= 0x80 {
+            self.0.push(v as u8 | 0x80);
+            v >>= 7;
+        }
+        self.0.push(v as u8);
+    }
+
+    fn tag(&mut self, field: u32, wire_type: u8) { self.varint(u64::from(field) << 3 | u64::from(wire_type)); }
+
+    /// A varint field; zero is the default and left out
+    fn uint(&mut self, field: u32, v: u64) {
+        if v != 0 {
+            self.tag(field, 0);
+            self.varint(v);
+        }
+    }
+
+    fn fixed64(&mut self, field: u32, v: u64) {
+        self.tag(field, 1);
+        self.0.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn bytes(&mut self, field: u32, b: &[u8]) {
+        self.tag(field, 2);
+        self.varint(b.len() as u64);
+        self.0.extend_from_slice(b);
+    }
+
+    fn string(&mut self, field: u32, s: &str) { self.bytes(field, s.as_bytes()); }
+
+    fn message(&mut self, field: u32, m: Message) { self.bytes(field, &m.0); }
+}
+
+/// `KeyValue { key = 1, value = 2 }` holding an `AnyValue { string_value = 1, int_value = 3 }`
+fn key_value(key: &str, value: &AttrValue) -> Message {
+    let mut any = Message::default();
+    match value {
+        AttrValue::Str(s) => any.string(1, s),
+        AttrValue::Int(i) => {
+            any.tag(3, 0);
+            any.varint(*i);
+        }
+    }
+    let mut kv = Message::default();
+    kv.string(1, key);
+    kv.message(2, any);
+    kv
+}
+
+/// `LogRecord { time_unix_nano = 1, severity_number = 2, severity_text = 3, body = 5,
+/// attributes = 6 }`, with the same content as [`super::log_record`]
+fn log_record(entry: &LogEntry) -> Message {
+    let (number, text) = severity(entry.level);
+    let mut record = Message::default();
+    record.fixed64(1, time_unix_nano(entry));
+    record.uint(2, number.into());
+    record.string(3, text);
+    let mut body = Message::default();
+    body.string(1, &entry.message);
+    record.message(5, body);
+    for (key, value) in attributes(entry) { record.message(6, key_value(key, &value)); }
+    record
+}
+
+/// `entries` as an `ExportLogsServiceRequest { resource_logs = 1 }` of one `ResourceLogs
+/// { resource = 1, scope_logs = 2 }` from `service_name`, in one `ScopeLogs { scope = 1,
+/// log_records = 2 }`
+pub(super) fn export_request(service_name: &str, entries: &[LogEntry]) -> Vec<u8> {
+    let mut resource = Message::default();
+    resource.message(1, key_value("service.name", &AttrValue::Str(service_name.into())));
+    let mut scope = Message::default();
+    scope.string(1, "log_analyzer");
+    scope.string(2, env!("CARGO_PKG_VERSION"));
+    let mut scope_logs = Message::default();
+    scope_logs.message(1, scope);
+    for entry in entries { scope_logs.message(2, log_record(entry)); }
+    let mut resource_logs = Message::default();
+    resource_logs.message(1, resource);
+    resource_logs.message(2, scope_logs);
+    let mut request = Message::default();
+    request.message(1, resource_logs);
+    request.0
+}
+
+fn retry(error: impl Into<anyhow::Error>) -> Rejected { Rejected { error: error.into(), retry: true } }
+
+/// `grpc-status` and `grpc-message` of a reply, from its trailers or, for a trailers-only
+/// reply, its headers
+fn grpc_status(headers: &HeaderMap) -> Option<(u32, String)> {
+    let code = headers.get("grpc-status")?.to_str().ok()?.parse().ok()?;
+    let message = headers.get("grpc-message").and_then(|m| m.to_str().ok()).unwrap_or_default();
+    Some((code, message.to_string()))
+}
+
+/// One HTTP/2 connection to a collector, opened on the first export and again after it breaks
+pub(super) struct Client {
+    runtime: Runtime,
+    /// `host:port` to connect to
+    authority: String,
+    uri: Uri,
+    connection: Option<SendRequest<Bytes>>,
+}
+
+impl Client {
+    /// `url` is the collector's `http://host:port`
+    pub(super) fn new(url: &str) -> Result<Self> {
+        let base: Uri = url.parse().with_context(|| format!("Invalid OTLP/gRPC endpoint {url}"))?;
+        match base.scheme_str() {
+            Some("http") => {}
+            Some("https") => anyhow::bail!("OTLP/gRPC over TLS isn't supported; use an http:// endpoint for {url}"),
+            _ => anyhow::bail!("Invalid OTLP/gRPC endpoint {url}: expected http://host:port"),
+        }
+        if !matches!(base.path(), "" | "/") { anyhow::bail!("Invalid OTLP/gRPC endpoint {url}: gRPC endpoints have no path"); }
+        let authority = base.authority().ok_or_else(|| anyhow!("Invalid OTLP/gRPC endpoint {url}: no host"))?.to_string();
+        let uri = format!("http://{authority}{EXPORT_PATH}").parse()?;
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+        Ok(Self { runtime, authority, uri, connection: None })
+    }
+
+    /// Send one encoded `ExportLogsServiceRequest` and wait for the collector's status
+    pub(super) fn export(&mut self, request: &[u8]) -> Result<(), Rejected> {
+        // Length-prefixed message, uncompressed
+        let mut frame = BytesMut::with_capacity(5 + request.len());
+        frame.put_u8(0);
+        frame.put_u32(request.len() as u32);
+        frame.put_slice(request);
+        let result = self.runtime.block_on(Self::call(&mut self.connection, &self.authority, &self.uri, frame.freeze()));
+        result.map_err(|mut rejected| {
+            rejected.error = rejected.error.context(format!("OTLP/gRPC export to {}", self.authority));
+            rejected
+        })
+    }
+
+    async fn call(connection: &mut Option<SendRequest<Bytes>>, authority: &str, uri: &Uri, frame: Bytes) -> Result<(), Rejected> {
+        let sender = match connection.take() {
+            Some(sender) => sender,
+            None => {
+                let tcp = TcpStream::connect(authority).await.map_err(retry)?;
+                let (sender, driver) = h2::client::handshake(tcp).await.map_err(retry)?;
+                tokio::spawn(async move { let _ = driver.await; });
+                sender
+            }
+        };
+        let mut sender = sender.ready().await.map_err(retry)?;
+        let request = Request::post(uri.clone())
+            .header("content-type", "application/grpc")
+            .header("te", "trailers")
+            .body(())
+            .expect("valid request");
+        let (response, mut stream) = sender.send_request(request, false).map_err(retry)?;
+        stream.send_data(frame, true).map_err(retry)?;
+        let (head, mut body) = response.await.map_err(retry)?.into_parts();
+        // The connection stays open for the next batch
+        *connection = Some(sender);
+
+        if !head.status.is_success() {
+            let retry = matches!(head.status.as_u16(), 429 | 502..=504);
+            return Err(Rejected { error: anyhow!("HTTP status {}", head.status), retry });
+        }
+        while let Some(chunk) = body.data().await {
+            let chunk = chunk.map_err(retry)?;
+            let _ = body.flow_control().release_capacity(chunk.len());
+        }
+        let trailers = body.trailers().await.map_err(retry)?;
+        match trailers.as_ref().and_then(grpc_status).or_else(|| grpc_status(&head.headers)) {
+            Some((0, _)) => Ok(()),
+            Some((code, message)) => Err(Rejected { error: anyhow!("gRPC status {code}: {message}"), retry: RETRYABLE_CODES.contains(&code) }),
+            None => Err(retry(anyhow!("reply without a gRPC status"))),
+        }
+    }
+}
@@ -1,41 +1,262 @@
 use crate::model::{Level, LogEntry};
-use chrono::{NaiveDateTime, Datelike, Local};
+use serde::{Serialize, Deserialize};
+use std::collections::BTreeMap;
+use std::io::BufRead;
+use chrono::{NaiveDate, NaiveDateTime, Datelike, Duration, Local};
 use regex::Regex;
 
+mod cef;
+mod cloudfront;
+mod custom;
+mod elb;
+mod gelf;
+mod haproxy;
+mod journald;
+mod json;
+mod jvm;
+mod logfmt;
+mod months;
+mod mysql;
+mod postgres;
+mod python;
+mod suggest;
+mod syslog;
+mod traefik;
+mod w3c;
+#[cfg(feature = "wasm")]
+mod wasm;
+mod winevent;
+
+pub use cef::{CefParser, LeefParser};
+pub use cloudfront::CloudFrontParser;
+pub use custom::RegexParser;
+pub use elb::ElbParser;
+pub use gelf::GelfParser;
+pub use haproxy::HaproxyParser;
+pub use journald::{ExportToJson, JournaldParser};
+pub use json::JsonLinesParser;
+pub use jvm::JvmParser;
+pub use logfmt::LogfmtParser;
+pub use months::MonthLocale;
+use months::MonthNames;
+pub use mysql::MysqlParser;
+pub use postgres::PostgresParser;
+pub use python::{JoinContinuations, PythonParser, RECORD_LINE_SEP};
+pub use suggest::{suggest_format, Delimiter, FormatHints, TimestampCandidate};
+pub use syslog::SyslogParser;
+pub use traefik::TraefikParser;
+pub use w3c::W3cParser;
+#[cfg(feature = "wasm")]
+pub use wasm::WasmParser;
+pub use winevent::WinEventParser;
+
+/// Input format selected with `--format`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Format {
+    /// Built-in regex patterns (ISO-like, syslog, Log4j, ...)
+    #[default]
+    Default,
+    /// GELF JSON, one message per line
+    Gelf,
+    /// Structured JSON lines (`{"time": ..., "level": ..., "msg": ...}`); other keys become fields
+    Json,
+    /// logfmt (`time=... level=... msg="..." key=value`); other keys become fields
+    Logfmt,
+    /// systemd journal: `journalctl -o json` or `-o export`
+    Journald,
+    /// Windows Event Log XML (`wevtutil qe /f:xml`, or `.evtx` files with the `evtx` feature)
+    WindowsEvent,
+    /// Python `logging` (`asctime - name - levelname - message`) with tracebacks kept with their record
+    Python,
+    /// W3C extended log format (IIS, some proxies); columns come from the `#Fields:` directive
+    W3c,
+    /// HAProxy HTTP logs (`option httplog`), with or without the syslog prefix
+    Haproxy,
+    /// Traefik access logs, common log format or JSON
+    Traefik,
+    /// AWS Application Load Balancer and Classic ELB access logs
+    Elb,
+    /// Amazon CloudFront standard access logs (W3C, tab-separated)
+    CloudFront,
+    /// Syslog messages as sent over the network (RFC 3164 or RFC 5424, level from `<PRI>`)
+    Syslog,
+    /// PostgreSQL server logs (`%t [%p] ` or `%m [%p] ` prefix), slow statements with `duration_ms`
+    Postgres,
+    /// MySQL/MariaDB error and slow query logs, slow queries with `duration_ms`
+    Mysql,
+    /// JVM GC logs (unified `-Xlog:gc*` or JDK 8 `-XX:+PrintGCDetails`) with pause and heap fields,
+    /// mixed with application lines; `java.lang.OutOfMemoryError` lines are errors
+    Jvm,
+    /// ArcSight Common Event Format (`CEF:0|vendor|product|version|id|name|severity|ext`),
+    /// extension keys as fields
+    Cef,
+    /// IBM QRadar Log Event Extended Format (`LEEF:1.0|vendor|product|version|id|attrs`),
+    /// attributes as fields
+    Leef,
+    /// WebAssembly plugins given in [`ParserOptions::plugins`] (`wasm` feature)
+    Plugin,
+}
+
+/// Options that apply to the parser built by [`parser_for`].
+#[derive(Clone, Debug, Default)]
+pub struct ParserOptions {
+    pub assume_year: Option<i32>,
+    /// Month name languages accepted in syslog-style timestamps besides English
+    pub month_locales: Vec<MonthLocale>,
+    /// `.wasm` (or `.wat`) parser modules for [`Format::Plugin`], tried in order
+    pub plugins: Vec<std::path::PathBuf>,
+}
+
+/// Build the parser for `format`.
+pub fn parser_for(format: Format, opts: &ParserOptions) -> anyhow::Result<Box<dyn LogParser>> {
+    Ok(match format {
+        Format::Default => Box::new(DefaultLogParser::new().with_assume_year(opts.assume_year).with_month_locales(&opts.month_locales)),
+        Format::Gelf => Box::new(GelfParser),
+        Format::Json => Box::new(JsonLinesParser),
+        Format::Logfmt => Box::new(LogfmtParser),
+        Format::Journald => Box::new(JournaldParser),
+        Format::WindowsEvent => Box::new(WinEventParser::default()),
+        Format::W3c => Box::new(W3cParser::default()),
+        Format::Python => Box::new(PythonParser),
+        Format::Haproxy => Box::new(HaproxyParser),
+        Format::Traefik => Box::new(TraefikParser),
+        Format::Elb => Box::new(ElbParser),
+        Format::CloudFront => Box::new(CloudFrontParser::default()),
+        Format::Syslog => Box::new(SyslogParser::new(opts.assume_year)),
+        Format::Postgres => Box::new(PostgresParser),
+        Format::Mysql => Box::new(MysqlParser::default()),
+        Format::Jvm => Box::new(JvmParser::new(
+            DefaultLogParser::new().with_assume_year(opts.assume_year).with_month_locales(&opts.month_locales),
+        )),
+        Format::Cef => Box::new(CefParser::new(opts.assume_year)),
+        Format::Leef => Box::new(LeefParser::new(opts.assume_year)),
+        Format::Plugin => {
+            if opts.plugins.is_empty() { anyhow::bail!("--format plugin needs at least one --plugin module"); }
+            #[cfg(feature = "wasm")]
+            { Box::new(WasmParser::load(&opts.plugins)?) }
+            #[cfg(not(feature = "wasm"))]
+            anyhow::bail!("This build has WebAssembly plugins disabled. Rebuild with `--features wasm`.")
+        }
+    })
+}
+
 pub trait LogParser {
     /// Parse a single line into an optional LogEntry
     fn parse_line(&mut self, line: &str) -> Result<Option<LogEntry>, String>;
+
+    /// Called before each new input (file, upload, stream) to drop per-input state
+    fn reset(&mut self) {}
+
+    /// Take a line that carries no entry by itself (a header, or part of a multi-line record
+    /// still being assembled) so it is neither parsed nor counted as malformed
+    fn absorb(&mut self, _line: &str) -> bool { false }
+
+    /// Convert raw input before it is split into lines, e.g. a record-based serialization
+    /// into one line per record
+    fn decode<'a>(&self, input: Box<dyn BufRead + 'a>) -> Box<dyn BufRead + 'a> { input }
+
+    /// Substrings one of which must occur in a line for it to yield an entry of `level`,
+    /// if the parser can tell (used by [`crate::analyze::PreFilter`])
+    fn level_tokens(&self, _level: Level) -> Option<&'static [&'static str]> { None }
+
+    /// Whether messages are copied verbatim from their line (no unescaping or multi-line
+    /// assembly), so a keyword missing from the line can't be in the message
+    fn verbatim_messages(&self) -> bool { false }
+
+    /// Look at the first lines of a new input (after [`reset`](Self::reset)) and settle on the
+    /// format to try first for it. Returns what was detected, for `--diagnose`.
+    fn detect(&mut self, _sample: &[&str]) -> Option<String> { None }
+
+    /// Names of the alternative patterns a parser tries, for parsers that have several
+    fn pattern_names(&self) -> Vec<&'static str> { Vec::new() }
+
+    /// Which of [`pattern_names`](Self::pattern_names) the last [`parse_line`](Self::parse_line)
+    /// matched (None if it matched none)
+    fn matched_pattern(&self) -> Option<&'static str> { None }
 }
 
 /// Regex-based parser that tries multiple common patterns.
 pub struct DefaultLogParser {
     patterns: Vec<Pattern>,
+    /// Year for syslog-style timestamps, which carry none (default: inferred from the current date)
+    assume_year: Option<i32>,
+    /// Year and timestamp of the previous syslog-style line in the current input
+    syslog_state: Option<(i32, NaiveDateTime)>,
+    /// Non-English month names accepted in syslog-style timestamps
+    months: MonthNames,
+    /// Pattern most of the current input matched in [`LogParser::detect`]; tried first, the
+    /// others only on a miss
+    preferred: Option<usize>,
+    /// Pattern the last line matched
+    matched: Option<usize>,
 }
 
-struct Pattern { re: Regex, ts_group: &'static str, level_group: &'static str, msg_group: &'static str }
+/// A syslog timestamp this far behind the previous one means the year rolled over (Dec -> Jan)
+const YEAR_ROLLOVER_GAP_DAYS: i64 = 180;
+
+struct Pattern { name: &'static str, re: Regex, ts_group: &'static str, level_group: &'static str, msg_group: &'static str, field_groups: &'static [&'static str] }
+
+impl Pattern {
+    fn fields(&self, caps: &regex::Captures) -> BTreeMap<String, String> {
+        self.field_groups.iter()
+            .filter_map(|k| caps.name(k).map(|v| (k.to_string(), v.as_str().to_string())))
+            .collect()
+    }
+}
 
 impl DefaultLogParser {
     pub fn new() -> Self {
         // Pattern 1: `2025-09-05 14:32:10,123 INFO Message...`
         let p1 = Pattern {
+            name: "iso",
             re: Regex::new(r"^(?P<ts>\d{4}-\d{2}-\d{2}[ T]\d{2}:\d{2}:\d{2}(?:[.,]\d{1,6})?)\s+(?P<level>INFO|ERROR|WARNING|WARN)\s+(?P<msg>.*)$").unwrap(),
-            ts_group: "ts", level_group: "level", msg_group: "msg"
+            ts_group: "ts", level_group: "level", msg_group: "msg", field_groups: &[]
         };
         // Pattern 2: `2025-09-05T14:32:10Z [WARNING] Message...` (ignores TZ)
         let p2 = Pattern {
+            name: "iso-bracketed",
             re: Regex::new(r"^(?P<ts>\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}(?:\.\d+)?(?:Z|[+-]\d{2}:?\d{2})?)\s*\[(?P<level>INFO|ERROR|WARNING|WARN)\]\s*(?P<msg>.*)$").unwrap(),
-            ts_group: "ts", level_group: "level", msg_group: "msg"
+            ts_group: "ts", level_group: "level", msg_group: "msg", field_groups: &[]
         };
         // Pattern 3: `Sep  5 14:32:10 host app[123]: [ERROR] Message...` (assume current year)
         let p3 = Pattern {
-            re: Regex::new(r"^(?P<mon>Jan|Feb|Mar|Apr|May|Jun|Jul|Aug|Sep|Oct|Nov|Dec)\s+(?P<day>\d{1,2})\s+(?P<time>\d{2}:\d{2}:\d{2}).*?(?P<level>INFO|ERROR|WARNING|WARN|\[INFO\]|\[ERROR\]|\[WARNING\]|\[WARN\]).*?\s(?P<msg>[^\r\n]*)$").unwrap(),
-            ts_group: "time", level_group: "level", msg_group: "msg"
+            name: "syslog",
+            re: syslog_regex(&MonthNames::default()),
+            ts_group: "time", level_group: "level", msg_group: "msg", field_groups: &["host", "app", "pid"]
+        };
+        // Pattern 4: `2025-09-05 14:32:10,123 [pool-1-thread-7] ERROR com.acme.Service - Message...` (Log4j/Logback)
+        let p4 = Pattern {
+            name: "log4j",
+            re: Regex::new(r"^(?P<ts>\d{4}-\d{2}-\d{2}[ T]\d{2}:\d{2}:\d{2}(?:[.,]\d{1,6})?)\s+\[(?P<thread>[^\]]+)\]\s+(?P<level>TRACE|DEBUG|INFO|WARNING|WARN|ERROR|FATAL)\s+(?P<logger>[\w.$]+)\s+-\s+(?P<msg>.*)$").unwrap(),
+            ts_group: "ts", level_group: "level", msg_group: "msg", field_groups: &["thread", "logger"]
+        };
+        // Pattern 5: `2025-09-05 14:32:10,123 - app.db - ERROR - Message...` (Python logging; see
+        // `--format python` for tracebacks)
+        let p5 = Pattern {
+            name: "python",
+            re: Regex::new(python::PYTHON_PATTERN).unwrap(),
+            ts_group: "ts", level_group: "level", msg_group: "msg", field_groups: &["logger"]
         };
-        Self { patterns: vec![p1, p2, p3] }
+        Self { patterns: vec![p1, p2, p3, p4, p5], assume_year: None, syslog_state: None, months: MonthNames::default(), preferred: None, matched: None }
+    }
+
+    /// Also accept these locales' month names in syslog-style timestamps
+    pub fn with_month_locales(mut self, locales: &[MonthLocale]) -> Self {
+        self.months = MonthNames::new(locales);
+        self.patterns[2].re = syslog_regex(&self.months);
+        self
+    }
+
+    /// Use a fixed year for syslog-style timestamps instead of inferring it
+    pub fn with_assume_year(mut self, year: Option<i32>) -> Self {
+        self.assume_year = year;
+        self
     }
 }
 
 fn parse_ts(ts: &str) -> Option<NaiveDateTime> {
+    // Python/log4j style `,123` milliseconds; chrono only understands `.123`
+    let ts = &ts.replacen(',', ".", 1);
     // Try a bunch of formats
     let fmts = [
         "%Y-%m-%d %H:%M:%S%.f",
@@ -49,39 +270,162 @@ fn parse_ts(ts: &str) -> Option<NaiveDateTime> {
     None
 }
 
-fn mon_to_num(mon: &str) -> u32 {
-    match mon {"Jan"=>1,"Feb"=>2,"Mar"=>3,"Apr"=>4,"May"=>5,"Jun"=>6,"Jul"=>7,"Aug"=>8,"Sep"=>9,"Oct"=>10,"Nov"=>11,"Dec"=>12,_=>1}
+/// Attach a year to a year-less syslog date. The first line of an input uses `--assume-year`
+/// or the current year (minus one if that would put it in the future); later lines keep that
+/// year and bump it when the date jumps backwards by months, i.e. the log crossed New Year.
+fn syslog_ts(state: &mut Option<(i32, NaiveDateTime)>, assume_year: Option<i32>, month: u32, day: u32, time: &str) -> Option<NaiveDateTime> {
+    let at = |year: i32| {
+        NaiveDate::from_ymd_opt(year, month, day)
+            .and_then(|d| NaiveDateTime::parse_from_str(&format!("{d} {time}"), "%Y-%m-%d %H:%M:%S%.f").ok())
+    };
+    let ts = match *state {
+        Some((year, prev)) => {
+            let ts = at(year)?;
+            if ts < prev - Duration::days(YEAR_ROLLOVER_GAP_DAYS) { at(year + 1)? } else { ts }
+        }
+        None => match assume_year {
+            Some(year) => at(year)?,
+            None => {
+                let now = Local::now().naive_local();
+                let ts = at(now.year())?;
+                if ts > now + Duration::days(1) { at(now.year() - 1)? } else { ts }
+            }
+        },
+    };
+    *state = Some((ts.year(), ts));
+    Some(ts)
+}
+
+fn mon_to_num(mon: &str) -> Option<u32> {
+    match mon {"Jan"=>Some(1),"Feb"=>Some(2),"Mar"=>Some(3),"Apr"=>Some(4),"May"=>Some(5),"Jun"=>Some(6),"Jul"=>Some(7),"Aug"=>Some(8),"Sep"=>Some(9),"Oct"=>Some(10),"Nov"=>Some(11),"Dec"=>Some(12),_=>None}
+}
+
+/// Pattern 3 after the month: day, time, optional `host app[pid]:`, level and message
+const SYSLOG_AFTER_MONTH: &str = r"\s+(?P<day>\d{1,2})\s+(?P<time>\d{2}:\d{2}:\d{2})(?:\s+(?P<host>[^\s\[\]]+)\s+(?:(?P<app>[^\s\[\]:]+)(?:\[(?P<pid>\d+)\])?:)?)?.*?(?P<level>INFO|ERROR|WARNING|WARN|\[INFO\]|\[ERROR\]|\[WARNING\]|\[WARN\]).*?\s(?P<msg>[^\r\n]*)$";
+
+/// Pattern 3; `months` adds non-English month names (any case, optional trailing dot)
+fn syslog_regex(months: &MonthNames) -> Regex {
+    let mon = match months.is_empty() {
+        true => "(?P<mon>Jan|Feb|Mar|Apr|May|Jun|Jul|Aug|Sep|Oct|Nov|Dec)".to_string(),
+        false => format!(r"(?P<mon>Jan|Feb|Mar|Apr|May|Jun|Jul|Aug|Sep|Oct|Nov|Dec|(?i:{}))\.?", months.alternation()),
+    };
+    Regex::new(&format!("^{mon}{SYSLOG_AFTER_MONTH}")).unwrap()
 }
 
 fn parse_level(s: &str) -> Level {
     match s.trim_matches(['[',']']).to_ascii_uppercase().as_str() {
-        "ERROR" => Level::Error,
+        "ERROR" | "FATAL" | "ERR" | "CRITICAL" | "CRIT" | "SEVERE" | "PANIC" | "ALERT" | "EMERG" => Level::Error,
         "WARNING" | "WARN" => Level::Warning,
         _ => Level::Info,
     }
 }
 
+/// Keys structured formats use for the timestamp, level and message, in order of preference
+const TS_KEYS: [&str; 6] = ["time", "timestamp", "ts", "@timestamp", "datetime", "date"];
+const LEVEL_KEYS: [&str; 5] = ["level", "severity", "lvl", "loglevel", "log.level"];
+const MSG_KEYS: [&str; 4] = ["msg", "message", "@message", "text"];
+
+/// Timestamps in structured logs: the formats [`parse_ts`] knows, RFC 3339 with any offset
+/// (wall-clock time is kept, like the text patterns do) or Unix epoch seconds / millis / micros / nanos.
+fn parse_structured_ts(s: &str) -> Option<NaiveDateTime> {
+    if let Some(ts) = parse_ts(s) { return Some(ts); }
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(s) { return Some(dt.naive_local()); }
+    let n: f64 = s.parse().ok()?;
+    let secs = match n.abs() {
+        x if x < 1e11 => n,
+        x if x < 1e14 => n / 1e3,
+        x if x < 1e17 => n / 1e6,
+        _ => n / 1e9,
+    };
+    chrono::DateTime::from_timestamp(secs.floor() as i64, (secs.fract() * 1e9) as u32).map(|d| d.naive_utc())
+}
+
+/// HTTP status to level: 5xx ERROR, 4xx WARNING, anything else INFO
+fn status_level(status: &str) -> Level {
+    match status.parse::<u16>() {
+        Ok(500..) => Level::Error,
+        Ok(400..=499) => Level::Warning,
+        _ => Level::Info,
+    }
+}
+
+/// Numeric levels: syslog severities (0-7) or pino/bunyan levels (10-60)
+fn numeric_level(n: i64) -> Level {
+    match n {
+        0..=3 | 50.. => Level::Error,
+        4 | 40..=49 => Level::Warning,
+        _ => Level::Info,
+    }
+}
+
+/// Build an entry from the key/value pairs of a structured line; keys other than the
+/// timestamp, level and message become fields.
+fn structured_entry(mut pairs: BTreeMap<String, String>) -> Result<LogEntry, String> {
+    let mut take = |keys: &[&str]| keys.iter().find_map(|k| pairs.remove(*k));
+    let ts_raw = take(&TS_KEYS).ok_or("Structured line without a timestamp")?;
+    let ts = parse_structured_ts(&ts_raw).ok_or_else(|| format!("Could not parse timestamp: {ts_raw}"))?;
+    let level = take(&LEVEL_KEYS).map_or(Level::Info, |l| l.parse().map_or_else(|_| parse_level(&l), numeric_level));
+    let message = take(&MSG_KEYS).unwrap_or_default();
+    Ok(LogEntry { ts, level, message, fields: pairs, source: None, line_no: 0 })
+}
+
+impl Default for DefaultLogParser {
+    fn default() -> Self { Self::new() }
+}
+
 impl LogParser for DefaultLogParser {
+    fn reset(&mut self) {
+        self.syslog_state = None;
+        self.preferred = None;
+    }
+
+    fn detect(&mut self, sample: &[&str]) -> Option<String> {
+        let hits: Vec<usize> = self.patterns.iter().map(|p| sample.iter().filter(|l| p.re.is_match(l)).count()).collect();
+        // Ties go to the earlier pattern, like the order lines are tried in without detection
+        let (best, &n) = hits.iter().enumerate().rev().max_by_key(|(_, n)| **n)?;
+        if n == 0 { return None; }
+        self.preferred = Some(best);
+        Some(format!("{} ({n} of {} sample lines)", self.patterns[best].name, sample.len()))
+    }
+
+    fn level_tokens(&self, level: Level) -> Option<&'static [&'static str]> {
+        match level {
+            // Anything that isn't a warning or error counts as INFO
+            Level::Info => None,
+            Level::Warning => Some(&["WARN"]),
+            Level::Error => Some(&["ERROR", "FATAL", "CRITICAL"]),
+        }
+    }
+
+    fn verbatim_messages(&self) -> bool { true }
+
+    fn pattern_names(&self) -> Vec<&'static str> { self.patterns.iter().map(|p| p.name).collect() }
+
+    fn matched_pattern(&self) -> Option<&'static str> { self.matched.map(|i| self.patterns[i].name) }
+
     fn parse_line(&mut self, line: &str) -> Result<Option<LogEntry>, String> {
-        for p in &self.patterns {
+        self.matched = None;
+        let order = self.preferred.into_iter().chain((0..self.patterns.len()).filter(|i| Some(*i) != self.preferred));
+        for i in order {
+            let p = &self.patterns[i];
             if let Some(caps) = p.re.captures(line) {
+                self.matched = Some(i);
                 // Special handling for pattern 3 (month/day format)
                 if p.re.as_str().starts_with("^(?P<mon>") {
                     let mon = caps.name("mon").unwrap().as_str();
                     let day: u32 = caps.name("day").unwrap().as_str().parse().unwrap_or(1);
                     let time = caps.name("time").unwrap().as_str();
-                    let year = Local::now().year();
-                    let ts_str = format!("{year}-{m:02}-{d:02} {time}", m = mon_to_num(mon), d = day);
-                    if let Ok(ts) = NaiveDateTime::parse_from_str(&ts_str, "%Y-%m-%d %H:%M:%S") {
-                        return Ok(Some(LogEntry { ts, level: parse_level(caps.name(p.level_group).unwrap().as_str()), message: caps.name(p.msg_group).unwrap().as_str().to_string() }));
-                    } else { return Err(format!("Failed to parse datetime: {ts_str}")); }
+                    let month = mon_to_num(mon).or_else(|| self.months.month(mon)).ok_or_else(|| format!("Unknown month: {mon}"))?;
+                    if let Some(ts) = syslog_ts(&mut self.syslog_state, self.assume_year, month, day, time) {
+                        return Ok(Some(LogEntry { ts, level: parse_level(caps.name(p.level_group).unwrap().as_str()), message: caps.name(p.msg_group).unwrap().as_str().to_string(), fields: p.fields(&caps), source: None, line_no: 0 }));
+                    } else { return Err(format!("Failed to parse datetime: {mon} {day} {time}")); }
                 }
 
                 let ts_raw = caps.name(p.ts_group).unwrap().as_str();
                 if let Some(ts) = parse_ts(ts_raw) {
                     let level = parse_level(caps.name(p.level_group).unwrap().as_str());
                     let message = caps.name(p.msg_group).unwrap().as_str().to_string();
-                    return Ok(Some(LogEntry { ts, level, message }));
+                    return Ok(Some(LogEntry { ts, level, message, fields: p.fields(&caps), source: None, line_no: 0 }));
                 } else {
                     return Err(format!("Could not parse timestamp: {ts_raw}"));
                 }
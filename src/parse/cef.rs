@@ -0,0 +1,211 @@
+use crate::model::{Level, LogEntry};
+use crate::parse::{mon_to_num, parse_structured_ts, syslog_ts, LogParser};
+use chrono::NaiveDateTime;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::sync::LazyLock;
+
+/// What a SIEM or syslog relay puts before `CEF:`/`LEEF:`: `<134>Sep 19 08:26:10 host ` or an
+/// RFC 3339 timestamp and host
+static PREFIX: LazyLock<Regex> = LazyLock::new(|| Regex::new(concat!(
+    r"^(?:<\d{1,3}>)?(?:1 )?(?:(?P<mon>Jan|Feb|Mar|Apr|May|Jun|Jul|Aug|Sep|Oct|Nov|Dec)\s+(?P<day>\d{1,2}) (?P<time>\d{2}:\d{2}:\d{2})|(?P<ts>\d{4}-\d{2}-\d{2}T\S+))",
+    r"(?:\s+(?P<host>\S+))?",
+)).unwrap());
+/// `rt`/`devTime` values: `Sep 19 2025 08:26:10.123`, optionally followed by a zone name
+static EVENT_TIME: LazyLock<Regex> = LazyLock::new(|| Regex::new(
+    r"^(?P<mon>[A-Z][a-z]{2}) (?P<day>\d{1,2}) (?P<year>\d{4}) (?P<time>\d{2}:\d{2}:\d{2}(?:\.\d+)?)(?: \S+)?$",
+).unwrap());
+/// CEF extension keys; a value runs until the next ` key=`
+static CEF_KEY: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?:^|\s)([A-Za-z0-9_.\[\]-]+)=").unwrap());
+/// CEF header fields after the version, in order
+const CEF_HEADER: [&str; 6] = ["vendor", "product", "device_version", "signature_id", "name", "severity"];
+/// LEEF header fields after the version, in order
+const LEEF_HEADER: [&str; 4] = ["vendor", "product", "device_version", "event_id"];
+
+/// CEF and LEEF severities: 0-3 (CEF `Low`) INFO, 4-6 (`Medium`) WARNING, 7-10 (`High`,
+/// `Very-High`) ERROR
+fn severity_level(sev: &str) -> Level {
+    match sev.trim().to_ascii_lowercase().as_str() {
+        "high" | "very-high" => Level::Error,
+        "medium" => Level::Warning,
+        s => match s.parse::<u8>() { Ok(7..) => Level::Error, Ok(4..=6) => Level::Warning, _ => Level::Info },
+    }
+}
+
+/// Epoch milliseconds, RFC 3339 or `MMM dd yyyy HH:mm:ss[.SSS] [zone]` (the zone is ignored: wall-clock time is kept)
+fn event_time(s: &str) -> Option<NaiveDateTime> {
+    if let Some(c) = EVENT_TIME.captures(s.trim()) {
+        let date = chrono::NaiveDate::from_ymd_opt(c["year"].parse().ok()?, mon_to_num(&c["mon"])?, c["day"].parse().ok()?)?;
+        let time = chrono::NaiveTime::parse_from_str(&c["time"], "%H:%M:%S%.f").ok()?;
+        return Some(date.and_time(time));
+    }
+    parse_structured_ts(s.trim())
+}
+
+/// Split `s` at unescaped `sep` into at most `max` parts, resolving `\sep` and `\\`; the last
+/// part is kept as is
+fn split_escaped(s: &str, sep: char, max: usize) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        let open = parts.len() < max;
+        let last = parts.last_mut().expect("never empty");
+        match c {
+            '\\' if open => match chars.next() {
+                Some(n) if n == sep || n == '\\' => last.push(n),
+                Some(n) => { last.push('\\'); last.push(n); }
+                None => last.push('\\'),
+            },
+            c if c == sep && open => parts.push(String::new()),
+            c => last.push(c),
+        }
+    }
+    parts
+}
+
+/// `\=`, `\\`, `\n` and `\r` in CEF extension values
+fn unescape_value(v: &str) -> String {
+    let mut out = String::with_capacity(v.len());
+    let mut chars = v.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some(n) => out.push(n),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// `src=10.0.0.1 msg=Login failed for admin act=blocked`: values may hold spaces, and `=` in a
+/// value is escaped as `\=`
+fn cef_extension(ext: &str) -> Vec<(String, String)> {
+    // An escaped `=` never follows a key character, so it isn't taken for a key
+    let keys: Vec<_> = CEF_KEY.captures_iter(ext).filter_map(|c| c.get(1)).collect();
+    keys.iter().enumerate().map(|(i, k)| {
+        let end = keys.get(i + 1).map_or(ext.len(), |next| next.start());
+        (k.as_str().to_string(), unescape_value(ext[k.end() + 1..end].trim()))
+    }).collect()
+}
+
+/// Timestamp and host from the syslog (or SIEM) prefix before `CEF:`/`LEEF:`
+fn prefix_time(prefix: &str, state: &mut Option<(i32, NaiveDateTime)>, assume_year: Option<i32>, fields: &mut BTreeMap<String, String>) -> Option<NaiveDateTime> {
+    let c = PREFIX.captures(prefix.trim())?;
+    if let Some(host) = c.name("host") { fields.insert("host".into(), host.as_str().trim_end_matches(':').to_string()); }
+    match c.name("ts") {
+        Some(ts) => parse_structured_ts(ts.as_str()),
+        None => syslog_ts(state, assume_year, mon_to_num(&c["mon"])?, c["day"].parse().ok()?, &c["time"]),
+    }
+}
+
+/// Parser for ArcSight Common Event Format: `CEF:0|Vendor|Product|Version|SignatureID|Name|Severity|ext`,
+/// alone or after a syslog prefix (`Sep 19 08:26:10 host CEF:0|...`).
+///
+/// The message is the event name and the severity (0-10, or `Low` to `Very-High`) sets the level:
+/// 7 and up ERROR, 4-6 WARNING, the rest INFO. Fields: `cef_version`, `vendor`, `product`,
+/// `device_version`, `signature_id`, `severity`, `host` (from the prefix) and every extension key
+/// as written (`src`, `dst`, `suser`, `act`, ...). The timestamp is the extension's `rt` (epoch
+/// millis or `MMM dd yyyy HH:mm:ss`), else the prefix's, else `start` or `end`.
+#[derive(Default)]
+pub struct CefParser {
+    assume_year: Option<i32>,
+    /// Year and timestamp of the previous year-less prefix in the current input
+    state: Option<(i32, NaiveDateTime)>,
+}
+
+impl CefParser {
+    pub fn new(assume_year: Option<i32>) -> Self { Self { assume_year, state: None } }
+}
+
+impl LogParser for CefParser {
+    fn reset(&mut self) { self.state = None; }
+
+    fn verbatim_messages(&self) -> bool { true }
+
+    fn parse_line(&mut self, line: &str) -> Result<Option<LogEntry>, String> {
+        let Some(at) = line.find("CEF:") else { return Ok(None) };
+        let mut parts = split_escaped(&line[at + 4..], '|', CEF_HEADER.len() + 2).into_iter();
+        let version = parts.next().unwrap_or_default();
+        let header: Vec<String> = parts.by_ref().take(CEF_HEADER.len()).collect();
+        if header.len() < CEF_HEADER.len() { return Err(format!("CEF header has {} of 7 fields", header.len() + 1)); }
+        let mut fields: BTreeMap<String, String> = cef_extension(&parts.next().unwrap_or_default()).into_iter().collect();
+        fields.insert("cef_version".into(), version.trim().to_string());
+        for (k, v) in CEF_HEADER.iter().zip(&header).filter(|(k, _)| **k != "name") { fields.insert(k.to_string(), v.clone()); }
+
+        let prefix = prefix_time(&line[..at], &mut self.state, self.assume_year, &mut fields);
+        let ts = fields.get("rt").and_then(|t| event_time(t))
+            .or(prefix)
+            .or_else(|| ["start", "end"].iter().find_map(|k| fields.get(*k).and_then(|t| event_time(t))))
+            .ok_or("CEF event without a timestamp (no rt, start or end, and no syslog prefix)")?;
+        let level = severity_level(&header[5]);
+        Ok(Some(LogEntry { ts, level, message: header[4].clone(), fields, source: None, line_no: 0 }))
+    }
+}
+
+/// Parser for IBM QRadar Log Event Extended Format: `LEEF:1.0|Vendor|Product|Version|EventID|attrs`
+/// with tab-separated `key=value` attributes, or `LEEF:2.0|...|EventID|^|attrs` with its own
+/// delimiter (a character or hex such as `x5E`); a syslog prefix is allowed like for CEF.
+///
+/// The message is the event ID, and the `sev` attribute (1-10) sets the level like CEF's
+/// severity (INFO without one). Fields: `leef_version`, `vendor`, `product`, `device_version`,
+/// `event_id`, `host` (from the prefix) and every attribute. The timestamp is `devTime` (epoch
+/// millis, RFC 3339 or `MMM dd yyyy HH:mm:ss`; `devTimeFormat` isn't interpreted), else the prefix's.
+#[derive(Default)]
+pub struct LeefParser {
+    assume_year: Option<i32>,
+    /// Year and timestamp of the previous year-less prefix in the current input
+    state: Option<(i32, NaiveDateTime)>,
+}
+
+impl LeefParser {
+    pub fn new(assume_year: Option<i32>) -> Self { Self { assume_year, state: None } }
+}
+
+/// LEEF 2.0 delimiter: `^`, `x5E` or `0x5E`
+fn leef_delimiter(s: &str) -> Option<char> {
+    let hex = s.strip_prefix("0x").or_else(|| s.strip_prefix('x')).filter(|h| !h.is_empty());
+    match hex {
+        Some(h) => u32::from_str_radix(h, 16).ok().and_then(char::from_u32),
+        None => s.chars().next().filter(|_| s.chars().count() == 1),
+    }
+}
+
+impl LogParser for LeefParser {
+    fn reset(&mut self) { self.state = None; }
+
+    fn verbatim_messages(&self) -> bool { true }
+
+    fn parse_line(&mut self, line: &str) -> Result<Option<LogEntry>, String> {
+        let Some(at) = line.find("LEEF:") else { return Ok(None) };
+        let rest = &line[at + 5..];
+        let version = rest.split('|').next().unwrap_or_default().trim().to_string();
+        // 2.0 adds the delimiter field before the attributes
+        let header_len = if version.starts_with('2') { LEEF_HEADER.len() + 1 } else { LEEF_HEADER.len() };
+        let mut parts = rest.splitn(header_len + 2, '|').skip(1);
+        let header: Vec<&str> = parts.by_ref().take(header_len).collect();
+        if header.len() < header_len { return Err(format!("LEEF header has {} of {} fields", header.len() + 1, header_len + 1)); }
+        let delimiter = match header.get(LEEF_HEADER.len()) {
+            Some(d) if !d.is_empty() => leef_delimiter(d).ok_or_else(|| format!("Invalid LEEF delimiter: {d}"))?,
+            _ => '\t',
+        };
+        let mut fields: BTreeMap<String, String> = parts.next().unwrap_or_default()
+            .split(delimiter)
+            .filter_map(|kv| kv.split_once('='))
+            .map(|(k, v)| (k.trim().to_string(), v.to_string()))
+            .collect();
+        fields.insert("leef_version".into(), version);
+        for (k, v) in LEEF_HEADER.iter().zip(&header) { fields.insert(k.to_string(), v.to_string()); }
+
+        let prefix = prefix_time(&line[..at], &mut self.state, self.assume_year, &mut fields);
+        let ts = fields.get("devTime").and_then(|t| event_time(t))
+            .or(prefix)
+            .ok_or("LEEF event without a timestamp (no devTime and no syslog prefix)")?;
+        let level = fields.get("sev").map_or(Level::Info, |s| severity_level(s));
+        Ok(Some(LogEntry { ts, level, message: header[3].to_string(), fields, source: None, line_no: 0 }))
+    }
+}
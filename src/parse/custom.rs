@@ -0,0 +1,45 @@
+use crate::model::{Level, LogEntry};
+use crate::parse::{parse_level, parse_structured_ts, LogParser};
+use anyhow::bail;
+use chrono::NaiveDateTime;
+use regex::Regex;
+
+/// Parser for a user-supplied regex, e.g. `^(?P<ts>\S+ \S+) \[(?P<level>\w+)\] (?P<msg>.*)$`.
+///
+/// The `ts` group is required; `level` (INFO when absent) and `msg` (the whole line when absent)
+/// are optional, and any other named group becomes a field. `ts` is read with `ts_format` (a
+/// chrono format such as `%d/%m/%Y %H:%M:%S`) when given, else like structured timestamps
+/// (ISO-like, RFC 3339 or epoch seconds/millis).
+pub struct RegexParser {
+    re: Regex,
+    ts_format: Option<String>,
+}
+
+impl RegexParser {
+    pub fn new(pattern: &str, ts_format: Option<&str>) -> anyhow::Result<Self> {
+        let re = Regex::new(pattern)?;
+        if !re.capture_names().flatten().any(|n| n == "ts") { bail!("The pattern needs a (?P<ts>...) group"); }
+        Ok(Self { re, ts_format: ts_format.filter(|f| !f.is_empty()).map(str::to_string) })
+    }
+
+    pub fn regex(&self) -> &Regex { &self.re }
+}
+
+impl LogParser for RegexParser {
+    fn parse_line(&mut self, line: &str) -> Result<Option<LogEntry>, String> {
+        let Some(caps) = self.re.captures(line) else { return Ok(None) };
+        let ts_raw = caps.name("ts").map_or("", |m| m.as_str());
+        let ts = match &self.ts_format {
+            Some(format) => NaiveDateTime::parse_from_str(ts_raw, format)
+                .map_err(|e| format!("Timestamp {ts_raw:?} doesn't match {format:?}: {e}"))?,
+            None => parse_structured_ts(ts_raw).ok_or_else(|| format!("Could not parse timestamp: {ts_raw}"))?,
+        };
+        let level = caps.name("level").map_or(Level::Info, |m| parse_level(m.as_str()));
+        let message = caps.name("msg").map_or(line, |m| m.as_str()).to_string();
+        let fields = self.re.capture_names().flatten()
+            .filter(|n| !matches!(*n, "ts" | "level" | "msg"))
+            .filter_map(|n| caps.name(n).map(|m| (n.to_string(), m.as_str().to_string())))
+            .collect();
+        Ok(Some(LogEntry { ts, level, message, fields, source: None, line_no: 0 }))
+    }
+}
@@ -0,0 +1,82 @@
+use crate::model::{Level, LogEntry};
+use crate::parse::{parse_structured_ts, status_level, w3c::columns, LogParser};
+use std::collections::BTreeMap;
+
+/// ALB record types, the first column of Application Load Balancer logs
+const ALB_TYPES: [&str; 6] = ["http", "https", "h2", "grpcs", "ws", "wss"];
+
+/// Columns after the leading `type` of an ALB line (classic ELB lines stop after `ssl_protocol`)
+const COLUMNS: [&str; 28] = [
+    "time", "elb", "client", "target", "request_processing_time", "target_processing_time", "response_processing_time",
+    "elb_status", "target_status", "received_bytes", "sent_bytes", "request", "user_agent", "ssl_cipher", "ssl_protocol",
+    "target_group", "trace_id", "domain", "chosen_cert_arn", "matched_rule_priority", "request_creation_time",
+    "actions_executed", "redirect_url", "error_reason", "target_list", "target_status_list", "classification", "classification_reason",
+];
+
+/// Timing columns (seconds, -1 when the request never reached that stage) and their fields in milliseconds
+const TIMERS: [(&str, &str); 3] = [
+    ("request_processing_time", "request_ms"), ("target_processing_time", "target_ms"), ("response_processing_time", "response_ms"),
+];
+
+/// Parser for AWS load balancer access logs as delivered to S3 (`.log.gz` objects): Application
+/// Load Balancer lines (`https 2018-07-02T22:23:00.186641Z app/my-lb/50dc6c495c0c9188 ...`) and
+/// Classic ELB lines (the same columns without `type`, up to `ssl_protocol`).
+///
+/// The load balancer's status sets the level (5xx ERROR, 4xx WARNING) and the message reads
+/// `GET /path 502`. Fields: `client_ip`, `target` (`ip:port`), `status` and `elb_status` (the
+/// load balancer's), `target_status` (the target's; absent when no target answered), `method`,
+/// `path`, `url`, `received_bytes`, `sent_bytes`, `user_agent`, the timers `request_ms`,
+/// `target_ms`, `response_ms` and their sum `duration_ms`, plus for ALB `type`, `target_group`,
+/// `trace_id`, `domain`, `error_reason` and the other named columns. `-` values are left out.
+#[derive(Default)]
+pub struct ElbParser;
+
+/// `0.000086` seconds -> `0.086` (milliseconds); None for -1 (stage not reached)
+fn secs_to_ms(secs: &str) -> Option<f64> {
+    secs.parse::<f64>().ok().filter(|s| *s >= 0.0).map(|s| (s * 1e6).round() / 1e3)
+}
+
+impl LogParser for ElbParser {
+    fn parse_line(&mut self, line: &str) -> Result<Option<LogEntry>, String> {
+        let mut values = columns(line);
+        let alb = values.first().is_some_and(|t| ALB_TYPES.contains(&t.as_str()));
+        let kind = if alb { Some(values.remove(0)) } else { None };
+        // Classic ELB lines have 15 columns; ALB keeps adding optional ones at the end
+        if values.len() < 15 || (alb && values.len() < 18) { return Ok(None); }
+        let Some(ts) = parse_structured_ts(&values[0]) else { return Ok(None) };
+        let mut cols: BTreeMap<&str, String> = COLUMNS.iter().copied().zip(values).skip(1).filter(|(_, v)| v != "-").collect();
+
+        let mut fields = BTreeMap::new();
+        if let Some(kind) = kind { fields.insert("type".to_string(), kind); }
+        if let Some(client) = cols.remove("client") {
+            let ip = client.rsplit_once(':').map_or(client.as_str(), |(ip, _)| ip);
+            fields.insert("client_ip".into(), ip.to_string());
+        }
+        let mut total = None;
+        for (column, name) in TIMERS {
+            if let Some(ms) = cols.remove(column).as_deref().and_then(secs_to_ms) {
+                fields.insert(name.to_string(), ms.to_string());
+                total = Some(total.unwrap_or(0.0) + ms);
+            }
+        }
+        if let Some(total) = total { fields.insert("duration_ms".into(), ((total * 1e3).round() / 1e3).to_string()); }
+        if let Some(request) = cols.remove("request") {
+            let mut parts = request.split_whitespace();
+            if let Some(method) = parts.next().filter(|m| *m != "-") { fields.insert("method".into(), method.into()); }
+            if let Some(url) = parts.next().filter(|u| *u != "-://-:-") {
+                // `https://host:443/path?q` -> `/path?q`
+                let path = url.split_once("://").map_or(url, |(_, rest)| rest.find('/').map_or("/", |i| &rest[i..]));
+                fields.insert("path".into(), path.to_string());
+                fields.insert("url".into(), url.to_string());
+            }
+        }
+        let status = cols.get("elb_status").cloned();
+        if let Some(status) = &status { fields.insert("status".into(), status.clone()); }
+        fields.extend(cols.into_iter().map(|(k, v)| (k.to_string(), v)));
+
+        let level = status.as_deref().map_or(Level::Info, status_level);
+        let message = [fields.get("method"), fields.get("path"), status.as_ref()].into_iter().flatten()
+            .map(String::as_str).collect::<Vec<_>>().join(" ");
+        Ok(Some(LogEntry { ts, level, message, fields, source: None, line_no: 0 }))
+    }
+}
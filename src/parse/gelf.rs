@@ -0,0 +1,52 @@
+use crate::model::{Level, LogEntry};
+use crate::parse::LogParser;
+use chrono::DateTime;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+/// Parser for GELF (Graylog Extended Log Format) messages, one JSON object per line.
+///
+/// `short_message` becomes the message; `host`, `full_message` and `_`-prefixed additional
+/// fields (without the underscore) become fields. Numeric syslog severities map to levels.
+#[derive(Default)]
+pub struct GelfParser;
+
+/// Syslog severity (0 = emergency .. 7 = debug) to our three levels
+pub fn syslog_severity_level(severity: i64) -> Level {
+    match severity {
+        i64::MIN..=3 => Level::Error,
+        4 => Level::Warning,
+        _ => Level::Info,
+    }
+}
+
+fn field_value(v: &Value) -> String {
+    match v {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+impl LogParser for GelfParser {
+    fn parse_line(&mut self, line: &str) -> Result<Option<LogEntry>, String> {
+        if !line.trim_start().starts_with('{') { return Ok(None); }
+        let obj: Map<String, Value> = serde_json::from_str(line).map_err(|e| format!("Invalid GELF JSON: {e}"))?;
+
+        let message = obj.get("short_message").and_then(Value::as_str).ok_or("GELF message without short_message")?;
+        let secs = obj.get("timestamp").and_then(Value::as_f64).ok_or("GELF message without numeric timestamp")?;
+        let ts = DateTime::from_timestamp(secs.trunc() as i64, (secs.fract() * 1e9).round() as u32)
+            .ok_or_else(|| format!("GELF timestamp out of range: {secs}"))?
+            .naive_utc();
+        // The spec defaults a missing level to 1 (ALERT)
+        let level = syslog_severity_level(obj.get("level").and_then(Value::as_i64).unwrap_or(1));
+
+        let mut fields = BTreeMap::new();
+        for (k, v) in &obj {
+            match k.as_str() {
+                "host" | "full_message" => { fields.insert(k.clone(), field_value(v)); }
+                _ => if let Some(name) = k.strip_prefix('_') { fields.insert(name.to_string(), field_value(v)); },
+            }
+        }
+        Ok(Some(LogEntry { ts, level, message: message.to_string(), fields, source: None, line_no: 0 }))
+    }
+}
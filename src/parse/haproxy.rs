@@ -0,0 +1,58 @@
+use crate::model::{Level, LogEntry};
+use crate::parse::{status_level, LogParser};
+use chrono::NaiveDateTime;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::sync::LazyLock;
+
+/// `10.0.1.2:33317 [06/Feb/2009:12:14:14.655] http-in static/srv1 10/0/30/69/109 200 2750 - - ---- 1/1/1/1/0 0/0 {hdrs} "GET / HTTP/1.1"`,
+/// possibly after a syslog prefix (`Feb  6 12:14:14 lb1 haproxy[14389]: `)
+static LINE: LazyLock<Regex> = LazyLock::new(|| Regex::new(concat!(
+    r#"(?:^|\s)(?P<client>\S+):(?P<port>\d+) \[(?P<ts>\d{2}/\w{3}/\d{4}:\d{2}:\d{2}:\d{2}(?:\.\d+)?)\] "#,
+    r#"(?P<frontend>\S+) (?P<backend>[^/\s]+)/(?P<server>\S+) "#,
+    r#"(?P<timers>-?\d+/-?\d+/-?\d+/-?\d+/\+?-?\d+) (?P<status>-?\d+) \+?(?P<bytes>\d+) "#,
+    r#"\S+ \S+ (?P<termination>\S+) (?P<conns>\S+) (?P<queues>\S+)(?: \{[^}]*\})*(?: "(?P<request>[^"]*)")?\s*$"#,
+)).unwrap());
+
+/// Names of the five `TR/Tw/Tc/Tr/Ta` timers, in milliseconds
+const TIMERS: [&str; 5] = ["request_ms", "queue_ms", "connect_ms", "response_ms", "duration_ms"];
+
+/// Parser for HAProxy HTTP logs (`option httplog`), as sent to syslog or written to stdout.
+///
+/// The status sets the level (5xx ERROR, 4xx WARNING, -1 for no response at all also ERROR) and
+/// the message reads `GET /path 503`. Fields: `client_ip`, `frontend`, `backend`, `server`,
+/// `status`, `bytes`, `termination_state`, `method`, `path` and the timers `request_ms`,
+/// `queue_ms`, `connect_ms`, `response_ms` and `duration_ms` (timers HAProxy reports as -1, e.g.
+/// for aborted requests, are left out).
+#[derive(Default)]
+pub struct HaproxyParser;
+
+impl LogParser for HaproxyParser {
+    fn parse_line(&mut self, line: &str) -> Result<Option<LogEntry>, String> {
+        let Some(caps) = LINE.captures(line) else { return Ok(None) };
+        let ts = NaiveDateTime::parse_from_str(&caps["ts"], "%d/%b/%Y:%H:%M:%S%.f")
+            .map_err(|_| format!("Could not parse timestamp: {}", &caps["ts"]))?;
+        let mut fields: BTreeMap<String, String> = ["frontend", "backend", "server", "status", "bytes"].iter()
+            .map(|k| (k.to_string(), caps[*k].to_string()))
+            .collect();
+        fields.insert("client_ip".into(), caps["client"].to_string());
+        fields.insert("termination_state".into(), caps["termination"].to_string());
+        for (name, value) in TIMERS.iter().zip(caps["timers"].split('/')) {
+            let value = value.trim_start_matches('+');
+            if value != "-1" { fields.insert(name.to_string(), value.to_string()); }
+        }
+
+        let status = &caps["status"];
+        let mut message = String::new();
+        if let Some(request) = caps.name("request") {
+            let mut parts = request.as_str().split_whitespace();
+            if let Some(method) = parts.next() { fields.insert("method".into(), method.into()); }
+            if let Some(path) = parts.next() { fields.insert("path".into(), path.into()); }
+            message = [fields.get("method"), fields.get("path")].into_iter().flatten()
+                .map(String::as_str).collect::<Vec<_>>().join(" ");
+        }
+        let message = format!("{message} {status}").trim_start().to_string();
+        let level = if status == "-1" { Level::Error } else { status_level(status) };
+        Ok(Some(LogEntry { ts, level, message, fields, source: None, line_no: 0 }))
+    }
+}
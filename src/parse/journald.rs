@@ -0,0 +1,146 @@
+use crate::analyze::DEFAULT_MAX_LINE_LEN;
+use crate::model::LogEntry;
+use crate::parse::gelf::syslog_severity_level;
+use crate::parse::LogParser;
+use chrono::DateTime;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, Read};
+
+/// Parser for systemd journal output: `journalctl -o json` (one object per line) and
+/// `journalctl -o export` (converted to the same JSON on the fly, see [`ExportToJson`]).
+///
+/// `PRIORITY` maps to levels like syslog severities; `_HOSTNAME`, `SYSLOG_IDENTIFIER`, `_PID`
+/// and `_SYSTEMD_UNIT` become the `host`, `app`, `pid` and `unit` fields.
+#[derive(Default)]
+pub struct JournaldParser;
+
+/// Journal fields kept, and the field names they are stored under
+const KEPT_FIELDS: [(&str, &str); 4] = [("_HOSTNAME", "host"), ("SYSLOG_IDENTIFIER", "app"), ("_PID", "pid"), ("_SYSTEMD_UNIT", "unit")];
+
+/// String value of a journal field; `-o json` writes non-UTF-8 or binary values as byte arrays
+fn text(v: &Value) -> Option<String> {
+    match v {
+        Value::String(s) => Some(s.clone()),
+        Value::Array(bytes) => {
+            let bytes: Vec<u8> = bytes.iter().filter_map(|b| b.as_u64().map(|b| b as u8)).collect();
+            Some(String::from_utf8_lossy(&bytes).into_owned())
+        }
+        Value::Null => None,
+        other => Some(other.to_string()),
+    }
+}
+
+impl LogParser for JournaldParser {
+    fn decode<'a>(&self, input: Box<dyn BufRead + 'a>) -> Box<dyn BufRead + 'a> {
+        Box::new(ExportToJson::new(input))
+    }
+
+    fn parse_line(&mut self, line: &str) -> Result<Option<LogEntry>, String> {
+        if !line.trim_start().starts_with('{') { return Ok(None); }
+        let obj: Map<String, Value> = serde_json::from_str(line).map_err(|e| format!("Invalid journal JSON: {e}"))?;
+        let get = |k: &str| obj.get(k).and_then(text);
+
+        let micros: i64 = get("__REALTIME_TIMESTAMP").or_else(|| get("_SOURCE_REALTIME_TIMESTAMP"))
+            .ok_or("Journal entry without __REALTIME_TIMESTAMP")?
+            .parse()
+            .map_err(|e| format!("Bad journal timestamp: {e}"))?;
+        let ts = DateTime::from_timestamp_micros(micros).ok_or("Journal timestamp out of range")?.naive_utc();
+        // Entries without a priority are informational
+        let level = syslog_severity_level(get("PRIORITY").and_then(|p| p.parse().ok()).unwrap_or(6));
+        let fields: BTreeMap<String, String> = KEPT_FIELDS.iter()
+            .filter_map(|(k, name)| get(k).map(|v| (name.to_string(), v)))
+            .collect();
+        Ok(Some(LogEntry { ts, level, message: get("MESSAGE").unwrap_or_default(), fields, source: None, line_no: 0 }))
+    }
+}
+
+/// Turns the journal export format (`KEY=value` lines, binary fields as `KEY\n<u64 LE length><data>\n`,
+/// a blank line after each entry) into one JSON object per line. Input that already starts with `{`
+/// (`-o json`) passes through untouched. A binary field longer than [`DEFAULT_MAX_LINE_LEN`], or
+/// shorter than its length says, is an [`io::ErrorKind::InvalidData`] error.
+pub struct ExportToJson<R> {
+    inner: R,
+    /// None until the first byte shows which format this is
+    export: Option<bool>,
+    /// Current JSON line and how much of it was consumed
+    out: Vec<u8>,
+    pos: usize,
+}
+
+impl<R: BufRead> ExportToJson<R> {
+    pub fn new(inner: R) -> Self { Self { inner, export: None, out: Vec::new(), pos: 0 } }
+
+    /// Read the next entry into `out`; leaves `out` empty at end of input.
+    fn next_entry(&mut self) -> io::Result<()> {
+        self.out.clear();
+        self.pos = 0;
+        let mut obj = Map::new();
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            if self.inner.read_until(b'\n', &mut line)? == 0 { break; }
+            if line.last() == Some(&b'\n') { line.pop(); }
+            if line.is_empty() {
+                if obj.is_empty() { continue; }
+                break;
+            }
+            let (key, value) = match line.iter().position(|&b| b == b'=') {
+                Some(eq) => (String::from_utf8_lossy(&line[..eq]).into_owned(), line[eq + 1..].to_vec()),
+                None => {
+                    let mut len = [0u8; 8];
+                    self.inner.read_exact(&mut len)?;
+                    let len = u64::from_le_bytes(len);
+                    if len > DEFAULT_MAX_LINE_LEN as u64 {
+                        return Err(io::Error::new(io::ErrorKind::InvalidData, format!("journal field of {len} bytes")));
+                    }
+                    let mut data = Vec::with_capacity(len as usize);
+                    if (&mut self.inner).take(len).read_to_end(&mut data)? < len as usize {
+                        return Err(io::Error::new(io::ErrorKind::InvalidData, "journal field cut short"));
+                    }
+                    // Trailing newline after the data
+                    self.inner.read_exact(&mut [0u8; 1])?;
+                    (String::from_utf8_lossy(&line).into_owned(), data)
+                }
+            };
+            obj.insert(key, Value::String(String::from_utf8_lossy(&value).into_owned()));
+        }
+        if !obj.is_empty() {
+            serde_json::to_writer(&mut self.out, &obj)?;
+            self.out.push(b'\n');
+        }
+        Ok(())
+    }
+}
+
+impl<R: BufRead> Read for ExportToJson<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = {
+            let available = self.fill_buf()?;
+            let n = available.len().min(buf.len());
+            buf[..n].copy_from_slice(&available[..n]);
+            n
+        };
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+impl<R: BufRead> BufRead for ExportToJson<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        let export = match self.export {
+            Some(e) => e,
+            None => {
+                let first = self.inner.fill_buf()?.iter().find(|b| !b.is_ascii_whitespace()).copied();
+                *self.export.insert(first.is_some_and(|b| b != b'{'))
+            }
+        };
+        if !export { return self.inner.fill_buf(); }
+        if self.pos >= self.out.len() { self.next_entry()?; }
+        Ok(&self.out[self.pos..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        if self.export == Some(true) { self.pos += amt; } else { self.inner.consume(amt); }
+    }
+}
@@ -0,0 +1,34 @@
+use crate::model::LogEntry;
+use crate::parse::{structured_entry, LogParser};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+/// Parser for JSON lines as written by structured loggers (serde/tracing, logrus, zap, pino,
+/// bunyan, python-json-logger, ...): one object per line.
+///
+/// Well-known keys (`time`/`timestamp`, `level`, `msg`/`message`) make up the entry; every other
+/// key becomes a field, with nested objects flattened to dotted names (`http.status`).
+#[derive(Default)]
+pub struct JsonLinesParser;
+
+pub(super) fn flatten(prefix: &str, obj: &Map<String, Value>, out: &mut BTreeMap<String, String>) {
+    for (k, v) in obj {
+        let key = if prefix.is_empty() { k.clone() } else { format!("{prefix}.{k}") };
+        match v {
+            Value::Object(inner) => flatten(&key, inner, out),
+            Value::String(s) => { out.insert(key, s.clone()); }
+            Value::Null => {}
+            other => { out.insert(key, other.to_string()); }
+        }
+    }
+}
+
+impl LogParser for JsonLinesParser {
+    fn parse_line(&mut self, line: &str) -> Result<Option<LogEntry>, String> {
+        if !line.trim_start().starts_with('{') { return Ok(None); }
+        let obj: Map<String, Value> = serde_json::from_str(line).map_err(|e| format!("Invalid JSON: {e}"))?;
+        let mut pairs = BTreeMap::new();
+        flatten("", &obj, &mut pairs);
+        structured_entry(pairs).map(Some)
+    }
+}
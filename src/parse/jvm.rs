@@ -0,0 +1,132 @@
+use crate::model::{Level, LogEntry};
+use crate::parse::{parse_level, DefaultLogParser, LogParser};
+use chrono::NaiveDateTime;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::sync::LazyLock;
+
+/// Unified logging decorations (`-Xlog:gc*:file=gc.log:time,level,tags`, JDK 9+) in any order:
+/// `[2025-09-05T14:32:10.123+0000][12.345s][info][gc,heap] message`
+static DECORATIONS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^((?:\[[^\]]*\])+)\s*(?P<msg>.*)$").unwrap());
+/// `GC(12) Pause Young (Normal) (G1 Evacuation Pause) 24M->4M(256M) 3.456ms`; ZGC and Shenandoah
+/// pauses (`GC(3) Pause Mark Start 0.012ms`) have no heap sizes
+static UNIFIED_PAUSE: LazyLock<Regex> = LazyLock::new(|| Regex::new(concat!(
+    r"^GC\((?P<id>\d+)\) (?P<kind>Pause(?: [A-Z]\w*)+)(?P<causes>(?: \(.*?\))*)",
+    r"(?: (?P<before>\d+)(?P<before_unit>[KMG])->(?P<after>\d+)(?P<after_unit>[KMG])\((?P<total>\d+)(?P<total_unit>[KMG])\))? (?P<ms>\d+(?:\.\d+)?)ms$",
+)).unwrap());
+/// `-XX:+PrintGCDetails -XX:+PrintGCDateStamps` (JDK 8): `2025-09-05T14:32:10.123+0000: 12.345: [GC
+/// (Allocation Failure) [PSYoungGen: 33280K->5104K(38400K)] 33280K->5112K(125952K), 0.0061230 secs]`;
+/// the whole-heap sizes are the ones followed by the pause
+static LEGACY: LazyLock<Regex> = LazyLock::new(|| Regex::new(concat!(
+    r"^(?P<ts>\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}(?:\.\d+)?[+-]\d{4}): (?:\d+\.\d+: )?\[(?P<kind>Full GC|GC)(?: \((?P<cause>.*?)\))? ",
+    r".*?(?P<before>\d+)K->(?P<after>\d+)K\((?P<total>\d+)K\), (?P<secs>\d+\.\d+) secs\]",
+)).unwrap());
+/// Unified logging levels; other alphabetic decorations are tags
+const LEVELS: [&str; 5] = ["trace", "debug", "info", "warning", "error"];
+/// `java.lang.OutOfMemoryError: Java heap space` anywhere in a line
+static OOM: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"java\.lang\.OutOfMemoryError(?::\s*(?P<detail>[^\r\n]*))?").unwrap());
+
+/// Parser for JVM logs: GC logs in unified (`-Xlog:gc*`) or JDK 8 (`-XX:+PrintGCDateStamps`)
+/// format, and application lines, which go to [`DefaultLogParser`].
+///
+/// GC pauses get `gc` (`Pause Young`, `Full GC`, ...), `gc_cause`, `gc_pause_ms` and, where
+/// logged, `heap_before_kb`, `heap_after_kb` and `heap_total_kb`; unified lines also `gc_id` and
+/// `tags`. Full collections are WARNING, other pauses INFO unless the line's level says more.
+/// Any line mentioning `java.lang.OutOfMemoryError` is an ERROR with `exception` set to it and
+/// `oom` to its detail (`Java heap space`, `Metaspace`, ...). Unified lines need the `time` or
+/// `utctime` decoration; with uptime alone there is no timestamp and they count as malformed.
+#[derive(Default)]
+pub struct JvmParser {
+    app: DefaultLogParser,
+}
+
+impl JvmParser {
+    /// `app` parses the lines that aren't GC log lines
+    pub fn new(app: DefaultLogParser) -> Self { Self { app } }
+}
+
+/// `24M` -> KiB
+fn kib(n: &str, unit: &str) -> String {
+    let n: u64 = n.parse().unwrap_or(0);
+    match unit { "G" => n << 20, "M" => n << 10, _ => n }.to_string()
+}
+
+fn jvm_ts(s: &str) -> Option<NaiveDateTime> {
+    // Wall-clock time is kept, like the other parsers do with offsets
+    chrono::DateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f%z").ok().map(|d| d.naive_local())
+        .or_else(|| super::parse_ts(s))
+}
+
+impl JvmParser {
+    fn parse_unified(&self, decorations: &str, message: &str) -> Result<Option<LogEntry>, String> {
+        let parts: Vec<&str> = decorations.trim_start_matches('[').trim_end_matches(']').split("][").collect();
+        let Some(ts) = parts.iter().find_map(|p| jvm_ts(p)) else {
+            return Err("no wall-clock time among the decorations (add `time` to -Xlog)".to_string());
+        };
+        let mut level = parts.iter().find(|p| LEVELS.contains(p)).map_or(Level::Info, |p| parse_level(p));
+        let mut fields = BTreeMap::new();
+        // Times, uptimes, pids and tids start with a digit
+        if let Some(tags) = parts.iter().find(|p| p.starts_with(|c: char| c.is_ascii_alphabetic()) && !LEVELS.contains(p)) {
+            fields.insert("tags".to_string(), tags.to_string());
+        }
+        if let Some(c) = UNIFIED_PAUSE.captures(message) {
+            fields.insert("gc_id".into(), c["id"].to_string());
+            fields.insert("gc".into(), c["kind"].to_string());
+            // ` (Normal) (G1 Evacuation Pause)` -> `Normal, G1 Evacuation Pause`
+            if let Some(causes) = c["causes"].trim().strip_prefix('(').and_then(|c| c.strip_suffix(')')) {
+                fields.insert("gc_cause".into(), causes.replace(") (", ", "));
+            }
+            fields.insert("gc_pause_ms".into(), c["ms"].to_string());
+            if let (Some(before), Some(after), Some(total)) = (c.name("before"), c.name("after"), c.name("total")) {
+                fields.insert("heap_before_kb".into(), kib(before.as_str(), &c["before_unit"]));
+                fields.insert("heap_after_kb".into(), kib(after.as_str(), &c["after_unit"]));
+                fields.insert("heap_total_kb".into(), kib(total.as_str(), &c["total_unit"]));
+            }
+            if &c["kind"] == "Pause Full" { level = level.max(Level::Warning); }
+        }
+        Ok(Some(LogEntry { ts, level, message: message.to_string(), fields, source: None, line_no: 0 }))
+    }
+
+    fn parse_legacy(c: &regex::Captures, line: &str) -> Result<Option<LogEntry>, String> {
+        let ts = jvm_ts(&c["ts"]).ok_or_else(|| format!("Could not parse timestamp: {}", &c["ts"]))?;
+        let kind = &c["kind"];
+        let mut fields = BTreeMap::from([
+            ("gc".to_string(), kind.to_string()),
+            ("gc_pause_ms".to_string(), format!("{:.3}", c["secs"].parse::<f64>().unwrap_or(0.0) * 1000.0)),
+            ("heap_before_kb".to_string(), c["before"].to_string()),
+            ("heap_after_kb".to_string(), c["after"].to_string()),
+            ("heap_total_kb".to_string(), c["total"].to_string()),
+        ]);
+        if let Some(cause) = c.name("cause") { fields.insert("gc_cause".into(), cause.as_str().to_string()); }
+        let level = if kind == "Full GC" { Level::Warning } else { Level::Info };
+        let message = line.split_once(": [").map_or(line, |(_, rest)| rest).trim_end_matches(']');
+        Ok(Some(LogEntry { ts, level, message: message.to_string(), fields, source: None, line_no: 0 }))
+    }
+}
+
+impl LogParser for JvmParser {
+    fn reset(&mut self) { self.app.reset(); }
+
+    fn detect(&mut self, sample: &[&str]) -> Option<String> { self.app.detect(sample) }
+
+    fn parse_line(&mut self, line: &str) -> Result<Option<LogEntry>, String> {
+        let mut parsed = if line.starts_with('[') {
+            match DECORATIONS.captures(line) {
+                Some(c) => self.parse_unified(c.get(1).map_or("", |d| d.as_str()), &c["msg"]),
+                None => self.app.parse_line(line),
+            }
+        } else if let Some(c) = LEGACY.captures(line) {
+            Self::parse_legacy(&c, line)
+        } else {
+            self.app.parse_line(line)
+        };
+        if let (Ok(Some(e)), Some(c)) = (&mut parsed, OOM.captures(line)) {
+            e.level = Level::Error;
+            e.fields.insert("exception".into(), "java.lang.OutOfMemoryError".into());
+            if let Some(detail) = c.name("detail").filter(|d| !d.as_str().trim().is_empty()) {
+                e.fields.insert("oom".into(), detail.as_str().trim().to_string());
+            }
+        }
+        parsed
+    }
+}
@@ -0,0 +1,50 @@
+use crate::model::LogEntry;
+use crate::parse::{structured_entry, LogParser};
+use std::collections::BTreeMap;
+
+/// Parser for logfmt lines: `time=2025-09-05T10:00:00Z level=error msg="db timeout" host=db-1`.
+///
+/// Well-known keys (`time`/`ts`, `level`, `msg`/`message`) make up the entry; every other key
+/// becomes a field. A bare key without `=` is stored as `true`.
+#[derive(Default)]
+pub struct LogfmtParser;
+
+/// Split a logfmt line into key/value pairs, unquoting `"..."` values (with `\"` escapes).
+fn pairs(line: &str) -> BTreeMap<String, String> {
+    let mut out = BTreeMap::new();
+    let mut chars = line.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        let key: String = std::iter::from_fn(|| chars.next_if(|&c| c != '=' && !c.is_whitespace())).collect();
+        if key.is_empty() {
+            // Stray `=` or end of line
+            if chars.next().is_none() { break; }
+            continue;
+        }
+        if chars.next_if_eq(&'=').is_none() {
+            out.insert(key, "true".into());
+            continue;
+        }
+        let mut value = String::new();
+        if chars.next_if_eq(&'"').is_some() {
+            while let Some(c) = chars.next() {
+                match c {
+                    '\\' => value.extend(chars.next()),
+                    '"' => break,
+                    c => value.push(c),
+                }
+            }
+        } else {
+            value.extend(std::iter::from_fn(|| chars.next_if(|c| !c.is_whitespace())));
+        }
+        out.insert(key, value);
+    }
+    out
+}
+
+impl LogParser for LogfmtParser {
+    fn parse_line(&mut self, line: &str) -> Result<Option<LogEntry>, String> {
+        if !line.contains('=') { return Ok(None); }
+        structured_entry(pairs(line)).map(Some)
+    }
+}
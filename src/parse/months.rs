@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Languages whose month names syslog-style timestamps (`janv.  5 14:32:10 ...`) may use
+/// besides English, as written by appliances running a non-English locale.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MonthLocale { French, German, Spanish, Italian, Portuguese, Dutch, Russian, Ukrainian, Polish }
+
+type Table = [&'static [&'static str]; 12];
+
+/// Abbreviations (without their trailing dot) and full names, lowercase, per month
+const FRENCH: Table = [
+    &["janv", "janvier"], &["févr", "fevr", "février", "fevrier"], &["mars"], &["avr", "avril"], &["mai"], &["juin"],
+    &["juil", "juillet"], &["août", "aout"], &["sept", "septembre"], &["oct", "octobre"], &["nov", "novembre"],
+    &["déc", "dec", "décembre", "decembre"],
+];
+const GERMAN: Table = [
+    &["jan", "januar", "jän", "jänner"], &["feb", "februar"], &["mär", "mrz", "märz"], &["apr", "april"], &["mai"],
+    &["jun", "juni"], &["jul", "juli"], &["aug", "august"], &["sep", "sept", "september"], &["okt", "oktober"],
+    &["nov", "november"], &["dez", "dezember"],
+];
+const SPANISH: Table = [
+    &["ene", "enero"], &["feb", "febrero"], &["mar", "marzo"], &["abr", "abril"], &["may", "mayo"], &["jun", "junio"],
+    &["jul", "julio"], &["ago", "agosto"], &["sep", "sept", "septiembre"], &["oct", "octubre"], &["nov", "noviembre"],
+    &["dic", "diciembre"],
+];
+const ITALIAN: Table = [
+    &["gen", "gennaio"], &["feb", "febbraio"], &["mar", "marzo"], &["apr", "aprile"], &["mag", "maggio"],
+    &["giu", "giugno"], &["lug", "luglio"], &["ago", "agosto"], &["set", "settembre"], &["ott", "ottobre"],
+    &["nov", "novembre"], &["dic", "dicembre"],
+];
+const PORTUGUESE: Table = [
+    &["jan", "janeiro"], &["fev", "fevereiro"], &["mar", "março", "marco"], &["abr", "abril"], &["mai", "maio"],
+    &["jun", "junho"], &["jul", "julho"], &["ago", "agosto"], &["set", "setembro"], &["out", "outubro"],
+    &["nov", "novembro"], &["dez", "dezembro"],
+];
+const DUTCH: Table = [
+    &["jan", "januari"], &["feb", "februari"], &["mrt", "maart"], &["apr", "april"], &["mei"], &["jun", "juni"],
+    &["jul", "juli"], &["aug", "augustus"], &["sep", "september"], &["okt", "oktober"], &["nov", "november"],
+    &["dec", "december"],
+];
+/// Nominative and genitive forms (`5 мая`, `май`)
+const RUSSIAN: Table = [
+    &["янв", "январь", "января"], &["фев", "февр", "февраль", "февраля"], &["мар", "март", "марта"],
+    &["апр", "апрель", "апреля"], &["май", "мая"], &["июн", "июнь", "июня"], &["июл", "июль", "июля"],
+    &["авг", "август", "августа"], &["сен", "сент", "сентябрь", "сентября"], &["окт", "октябрь", "октября"],
+    &["ноя", "нояб", "ноябрь", "ноября"], &["дек", "декабрь", "декабря"],
+];
+const UKRAINIAN: Table = [
+    &["січ", "січень", "січня"], &["лют", "лютий", "лютого"], &["бер", "березень", "березня"],
+    &["кві", "квіт", "квітень", "квітня"], &["тра", "трав", "травень", "травня"], &["чер", "черв", "червень", "червня"],
+    &["лип", "липень", "липня"], &["сер", "серп", "серпень", "серпня"], &["вер", "вересень", "вересня"],
+    &["жов", "жовт", "жовтень", "жовтня"], &["лис", "листоп", "листопад", "листопада"], &["гру", "груд", "грудень", "грудня"],
+];
+const POLISH: Table = [
+    &["sty", "styczeń", "stycznia"], &["lut", "luty", "lutego"], &["mar", "marzec", "marca"], &["kwi", "kwiecień", "kwietnia"],
+    &["maj", "maja"], &["cze", "czerwiec", "czerwca"], &["lip", "lipiec", "lipca"], &["sie", "sierpień", "sierpnia"],
+    &["wrz", "wrzesień", "września"], &["paź", "paz", "październik", "października"], &["lis", "listopad", "listopada"],
+    &["gru", "grudzień", "grudnia"],
+];
+
+impl MonthLocale {
+    fn table(self) -> &'static Table {
+        match self {
+            MonthLocale::French => &FRENCH,
+            MonthLocale::German => &GERMAN,
+            MonthLocale::Spanish => &SPANISH,
+            MonthLocale::Italian => &ITALIAN,
+            MonthLocale::Portuguese => &PORTUGUESE,
+            MonthLocale::Dutch => &DUTCH,
+            MonthLocale::Russian => &RUSSIAN,
+            MonthLocale::Ukrainian => &UKRAINIAN,
+            MonthLocale::Polish => &POLISH,
+        }
+    }
+}
+
+/// Month names of several locales, matched case-insensitively; earlier locales win on clashes.
+#[derive(Default)]
+pub(crate) struct MonthNames(HashMap<String, u32>);
+
+impl MonthNames {
+    pub(crate) fn new(locales: &[MonthLocale]) -> Self {
+        let mut names = HashMap::new();
+        for locale in locales {
+            for (month, tokens) in (1..).zip(locale.table()) {
+                for t in *tokens { names.entry(t.to_string()).or_insert(month); }
+            }
+        }
+        Self(names)
+    }
+
+    pub(crate) fn is_empty(&self) -> bool { self.0.is_empty() }
+
+    /// Month number (1-12) of `name` (a trailing dot is ignored)
+    pub(crate) fn month(&self, name: &str) -> Option<u32> {
+        self.0.get(&name.trim_end_matches('.').to_lowercase()).copied()
+    }
+
+    /// Regex alternation of all names, longest first so `mars` isn't read as `mar`
+    pub(crate) fn alternation(&self) -> String {
+        let mut names: Vec<&String> = self.0.keys().collect();
+        names.sort_by(|a, b| b.chars().count().cmp(&a.chars().count()).then_with(|| a.cmp(b)));
+        names.iter().map(|n| regex::escape(n)).collect::<Vec<_>>().join("|")
+    }
+}
@@ -0,0 +1,129 @@
+use crate::model::{Level, LogEntry};
+use crate::parse::{parse_level, parse_structured_ts, JoinContinuations, LogParser, RECORD_LINE_SEP};
+use chrono::NaiveDateTime;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::io::BufRead;
+use std::sync::LazyLock;
+
+/// Error log line: `2025-09-05T14:32:10.123456Z 0 [ERROR] [MY-010119] [Server] msg` (8.0),
+/// `2025-09-05T14:32:10.123456Z 0 [Warning] msg` (5.7) or `150905 14:32:10 [ERROR] msg` (5.5)
+static ERROR_LINE: LazyLock<Regex> = LazyLock::new(|| Regex::new(concat!(
+    r"^(?P<ts>\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}:\d{2}(?:\.\d+)?(?:Z|[+-]\d{2}:\d{2})?|\d{6} +\d{1,2}:\d{2}:\d{2})",
+    r"(?:\s+(?P<thread>\d+))?\s+\[(?P<prio>[A-Za-z]+)\](?:\s+\[(?P<code>MY-\d+)\])?(?:\s+\[(?P<subsystem>[A-Za-z]+)\])?\s+(?P<msg>.*)$",
+)).unwrap());
+/// An error log line, a slow log block (`# Time:` or, for further queries in the same second,
+/// `# User@Host:`) or a header written on server start; anything else continues the record before
+static RECORD_START: LazyLock<regex::bytes::Regex> = LazyLock::new(|| regex::bytes::Regex::new(concat!(
+    r"^(?:\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}:\d{2}|\d{6} +\d{1,2}:\d{2}:\d{2}|# Time: |# User@Host: ",
+    r"|\S+, Version: |Tcp port: |Time +Id +Command)",
+)).unwrap());
+/// `# User@Host: app[app] @ localhost [127.0.0.1]  Id:    12`
+static USER_HOST: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^# User@Host: (?P<user>[^\[\s]*)\[[^\]]*\] @ (?P<host>\S*) \[(?P<ip>[^\]]*)\](?:\s+Id:\s+(?P<id>\d+))?").unwrap()
+});
+/// `Key: value` pairs of the other `#` lines (`Query_time: 2.000123  Lock_time: 0.000101 ...`)
+static STAT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?P<key>\w+): (?P<value>\S+)").unwrap());
+static SET_TIMESTAMP: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^SET timestamp=(?P<secs>\d+);$").unwrap());
+static USE_DB: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^use (?P<db>`[^`]+`|\S+);$").unwrap());
+
+/// Parser for MySQL (and MariaDB) error logs and slow query logs, which may be mixed in one input.
+///
+/// Error log lines take their level from the priority (`[ERROR]`, `[Warning]`, `[Note]`/`[System]`
+/// as INFO) with `thread`, `code` (`MY-010119`) and `subsystem` fields where present. Each slow
+/// query block becomes one INFO entry whose message is the statement; its fields are `user`,
+/// `host`, `ip`, `thread_id`, `db` and the `#` statistics lowercased, with `Query_time` and
+/// `Lock_time` converted to `duration_ms` and `lock_time_ms`. Lines of a block and of multi-line
+/// error messages stay together; line numbers then count records.
+#[derive(Default)]
+pub struct MysqlParser {
+    /// The last `# Time:` of the slow log; later queries in the same second omit it
+    time: Option<NaiveDateTime>,
+}
+
+/// `2025-09-05T14:32:10.123456Z`, or `150905 14:32:10` with a possibly unpadded hour
+fn mysql_ts(s: &str) -> Option<NaiveDateTime> {
+    parse_structured_ts(s).or_else(|| {
+        let s = s.split_whitespace().collect::<Vec<_>>().join(" ");
+        NaiveDateTime::parse_from_str(&s, "%y%m%d %H:%M:%S").ok()
+    })
+}
+
+impl MysqlParser {
+    fn slow_query(&self, lines: &[&str]) -> Result<LogEntry, String> {
+        let mut fields = BTreeMap::new();
+        if let Some(c) = USER_HOST.captures(lines[0]) {
+            for (group, key) in [("user", "user"), ("host", "host"), ("ip", "ip"), ("id", "thread_id")] {
+                if let Some(v) = c.name(group).filter(|v| !v.is_empty()) { fields.insert(key.to_string(), v.as_str().to_string()); }
+            }
+        }
+        let mut set_ts = None;
+        let mut statement = Vec::new();
+        for l in &lines[1..] {
+            if l.starts_with('#') {
+                for c in STAT.captures_iter(l) {
+                    let value = &c["value"];
+                    let ms = |v: &str| v.parse::<f64>().map(|secs| format!("{:.3}", secs * 1000.0)).unwrap_or_else(|_| v.to_string());
+                    match &c["key"] {
+                        "Query_time" => fields.insert("duration_ms".into(), ms(value)),
+                        "Lock_time" => fields.insert("lock_time_ms".into(), ms(value)),
+                        key => fields.insert(key.to_ascii_lowercase(), value.to_string()),
+                    };
+                }
+            } else if let Some(c) = SET_TIMESTAMP.captures(l) {
+                set_ts = c["secs"].parse().ok().and_then(|s| chrono::DateTime::from_timestamp(s, 0)).map(|d| d.naive_utc());
+            } else if let Some(c) = USE_DB.captures(l) {
+                fields.insert("db".into(), c["db"].trim_matches('`').to_string());
+            } else {
+                statement.push(*l);
+            }
+        }
+        let ts = self.time.or(set_ts).ok_or("Slow query without a timestamp")?;
+        Ok(LogEntry { ts, level: Level::Info, message: statement.join("\n").trim().to_string(), fields, source: None, line_no: 0 })
+    }
+}
+
+impl LogParser for MysqlParser {
+    fn reset(&mut self) { self.time = None; }
+
+    fn decode<'a>(&self, input: Box<dyn BufRead + 'a>) -> Box<dyn BufRead + 'a> {
+        Box::new(JoinContinuations::with_record_start(input, &RECORD_START))
+    }
+
+    /// Server start banners, and `# Time:` lines, which only date the slow queries after them
+    fn absorb(&mut self, line: &str) -> bool {
+        if let Some(rest) = line.strip_prefix("# Time: ") {
+            let ts = rest.split(RECORD_LINE_SEP).next().unwrap_or_default().trim();
+            self.time = mysql_ts(ts).or(self.time);
+            return true;
+        }
+        line.contains(", Version: ") && line.contains("started with:")
+            || line.starts_with("Tcp port: ")
+            || line.starts_with("Time ") && line.contains(" Command ")
+    }
+
+    fn level_tokens(&self, level: Level) -> Option<&'static [&'static str]> {
+        match level {
+            Level::Info => None,
+            Level::Warning => Some(&["[Warning]"]),
+            Level::Error => Some(&["[ERROR]"]),
+        }
+    }
+
+    fn verbatim_messages(&self) -> bool { true }
+
+    fn parse_line(&mut self, line: &str) -> Result<Option<LogEntry>, String> {
+        let lines: Vec<&str> = line.split(RECORD_LINE_SEP).collect();
+        if lines[0].starts_with("# User@Host: ") { return self.slow_query(&lines).map(Some); }
+        let Some(caps) = ERROR_LINE.captures(lines[0]) else { return Ok(None) };
+        let ts = mysql_ts(&caps["ts"]).ok_or_else(|| format!("Could not parse timestamp: {}", &caps["ts"]))?;
+        let mut fields = BTreeMap::new();
+        for key in ["thread", "code", "subsystem"] {
+            if let Some(v) = caps.name(key) { fields.insert(key.to_string(), v.as_str().to_string()); }
+        }
+        let mut message = caps["msg"].to_string();
+        for l in &lines[1..] { message.push('\n'); message.push_str(l); }
+        let message = message.trim_end().to_string();
+        Ok(Some(LogEntry { ts, level: parse_level(&caps["prio"]), message, fields, source: None, line_no: 0 }))
+    }
+}
@@ -0,0 +1,91 @@
+use crate::model::{Level, LogEntry};
+use crate::parse::{parse_ts, JoinContinuations, LogParser, RECORD_LINE_SEP};
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::io::BufRead;
+use std::sync::LazyLock;
+
+/// `log_line_prefix` forms understood before the severity: `%t [%p] ` or `%m [%p] `, optionally
+/// followed by `: [%l-1]` and `%u@%d`
+const PREFIX: &str = r"^(?P<ts>\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2}(?:\.\d+)?)(?: (?:[A-Za-z]+|[+-]\d{2}(?::?\d{2})?))? \[(?P<pid>\d+)\](?::? \[\d+-\d+\])?(?: (?P<user>[^@\s]*)@(?P<db>[^\s:]*))? ";
+
+static LINE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(&format!(r"{PREFIX}(?P<severity>[A-Z][A-Z0-9]*):\s+(?P<msg>.*)$")).unwrap());
+/// A line with a primary severity; `DETAIL:`, `STATEMENT:` and the like belong to the record before
+static RECORD_START: LazyLock<regex::bytes::Regex> = LazyLock::new(|| {
+    regex::bytes::Regex::new(&format!(r"{PREFIX}(?:DEBUG[1-5]|INFO|NOTICE|WARNING|ERROR|LOG|FATAL|PANIC):")).unwrap()
+});
+/// `log_min_duration_statement` output: `duration: 12.345 ms  statement: SELECT ...`
+static DURATION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?s)^duration: (?P<ms>\d+(?:\.\d+)?) ms(?:\s+(?:statement|(?:execute|parse|bind) [^:]*): (?P<stmt>.*))?$").unwrap()
+});
+
+/// Severities that only annotate the record before them
+const SECONDARY: [&str; 6] = ["DETAIL", "HINT", "QUERY", "CONTEXT", "STATEMENT", "LOCATION"];
+
+/// Parser for PostgreSQL server logs (`2025-09-05 14:32:10.123 UTC [12345] ERROR:  message`).
+///
+/// ERROR, FATAL and PANIC are ERROR, WARNING is WARNING and everything else (LOG, NOTICE, ...)
+/// INFO. Fields: `pid`, `severity`, `user` and `db` when the prefix has them, and one lowercased
+/// field per `DETAIL:`/`HINT:`/`STATEMENT:`/... line following the record. Slow statements
+/// (`duration: 12.345 ms  statement: ...`) get `duration_ms` and `statement`. Tab-indented lines
+/// of multi-line statements stay with their record; line numbers then count records.
+#[derive(Default)]
+pub struct PostgresParser;
+
+impl LogParser for PostgresParser {
+    fn decode<'a>(&self, input: Box<dyn BufRead + 'a>) -> Box<dyn BufRead + 'a> {
+        Box::new(JoinContinuations::with_record_start(input, &RECORD_START))
+    }
+
+    fn level_tokens(&self, level: Level) -> Option<&'static [&'static str]> {
+        match level {
+            Level::Info => None,
+            Level::Warning => Some(&["WARNING:"]),
+            Level::Error => Some(&["ERROR:", "FATAL:", "PANIC:"]),
+        }
+    }
+
+    fn verbatim_messages(&self) -> bool { true }
+
+    fn parse_line(&mut self, line: &str) -> Result<Option<LogEntry>, String> {
+        let mut lines = line.split(RECORD_LINE_SEP);
+        let Some(caps) = lines.next().and_then(|head| LINE.captures(head)) else { return Ok(None) };
+        let ts = parse_ts(&caps["ts"]).ok_or_else(|| format!("Could not parse timestamp: {}", &caps["ts"]))?;
+        let severity = &caps["severity"];
+        let level = match severity {
+            "ERROR" | "FATAL" | "PANIC" => Level::Error,
+            "WARNING" => Level::Warning,
+            _ => Level::Info,
+        };
+
+        // The message, then one section per secondary line, each with its continuation lines
+        let mut sections = vec![(String::new(), caps["msg"].to_string())];
+        for l in lines {
+            match LINE.captures(l).filter(|c| SECONDARY.contains(&&c["severity"])) {
+                Some(c) => sections.push((c["severity"].to_ascii_lowercase(), c["msg"].to_string())),
+                None => {
+                    let text = &mut sections.last_mut().expect("starts with the message").1;
+                    text.push('\n');
+                    text.push_str(l.trim_start_matches('\t'));
+                }
+            }
+        }
+        let mut sections = sections.into_iter();
+        let message = sections.next().map(|(_, m)| m.trim_end().to_string()).unwrap_or_default();
+
+        let mut fields = BTreeMap::from([
+            ("pid".to_string(), caps["pid"].to_string()),
+            ("severity".to_string(), severity.to_string()),
+        ]);
+        for key in ["user", "db"] {
+            if let Some(v) = caps.name(key).filter(|v| !v.is_empty()) { fields.insert(key.into(), v.as_str().into()); }
+        }
+        if let Some(d) = DURATION.captures(&message) {
+            fields.insert("duration_ms".into(), d["ms"].to_string());
+            if let Some(stmt) = d.name("stmt") { fields.insert("statement".into(), stmt.as_str().to_string()); }
+        }
+        for (key, text) in sections { fields.insert(key, text.trim_end().to_string()); }
+        Ok(Some(LogEntry { ts, level, message, fields, source: None, line_no: 0 }))
+    }
+}
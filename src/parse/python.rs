@@ -0,0 +1,158 @@
+use crate::model::{Level, LogEntry};
+use crate::parse::{parse_level, parse_ts, LogParser};
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, Read};
+use std::sync::LazyLock;
+
+/// Python `logging` with `format="%(asctime)s - %(name)s - %(levelname)s - %(message)s"`
+pub(crate) const PYTHON_PATTERN: &str =
+    r"^(?P<ts>\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2}(?:,\d{3})?) - (?P<logger>.+?) - (?P<level>DEBUG|INFO|WARNING|WARN|ERROR|CRITICAL|FATAL) - (?P<msg>.*)$";
+
+static LINE: LazyLock<Regex> = LazyLock::new(|| Regex::new(PYTHON_PATTERN).unwrap());
+/// Start of a record; anything else continues the previous one
+static RECORD_START: LazyLock<regex::bytes::Regex> =
+    LazyLock::new(|| regex::bytes::Regex::new(r"^\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2}(?:,\d{3})? - ").unwrap());
+/// `ValueError` or `requests.exceptions.ConnectionError`
+static EXCEPTION_TYPE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^[A-Za-z_][\w.]*$").unwrap());
+
+/// Separates the lines of a record joined by [`JoinContinuations`] (Unicode LINE SEPARATOR)
+pub const RECORD_LINE_SEP: char = '\u{2028}';
+
+/// A record longer than this stops collecting lines; the rest pass through (and show up as malformed)
+const MAX_RECORD_LINES: usize = 10_000;
+
+/// Parser for Python `logging` output (`2025-09-05 14:32:10,123 - app.db - ERROR - msg`),
+/// including records spanning several lines.
+///
+/// `logger.exception()` tracebacks are kept with their record: the final exception type becomes
+/// the `exception` field (e.g. `ValueError`) and the traceback the `traceback` field, while the
+/// message stays the logged one. Other continuation lines are appended to the message. Line
+/// numbers count records, not physical lines.
+#[derive(Default)]
+pub struct PythonParser;
+
+/// The raised exception type of a traceback: the last unindented line after the stack (chained
+/// exceptions print several tracebacks; the last one is what propagated)
+fn exception_type(lines: &[&str]) -> Option<String> {
+    let line = lines.iter().rev().find(|l| {
+        !l.is_empty() && !l.starts_with(char::is_whitespace) && !l.starts_with("Traceback ")
+            && !l.starts_with("During handling") && !l.starts_with("The above exception")
+    })?;
+    let name = line.split_once(':').map_or(*line, |(name, _)| name).trim();
+    EXCEPTION_TYPE.is_match(name).then(|| name.to_string())
+}
+
+impl LogParser for PythonParser {
+    fn decode<'a>(&self, input: Box<dyn BufRead + 'a>) -> Box<dyn BufRead + 'a> {
+        Box::new(JoinContinuations::new(input))
+    }
+
+    fn level_tokens(&self, level: Level) -> Option<&'static [&'static str]> {
+        match level {
+            Level::Info => None,
+            Level::Warning => Some(&["WARN"]),
+            Level::Error => Some(&["ERROR", "CRITICAL", "FATAL"]),
+        }
+    }
+
+    fn verbatim_messages(&self) -> bool { true }
+
+    fn parse_line(&mut self, line: &str) -> Result<Option<LogEntry>, String> {
+        let mut lines = line.split(RECORD_LINE_SEP);
+        let Some(caps) = lines.next().and_then(|head| LINE.captures(head)) else { return Ok(None) };
+        let ts = parse_ts(&caps["ts"]).ok_or_else(|| format!("Could not parse timestamp: {}", &caps["ts"]))?;
+        let mut message = caps["msg"].to_string();
+        let mut fields = BTreeMap::from([("logger".to_string(), caps["logger"].to_string())]);
+
+        let rest: Vec<&str> = lines.collect();
+        match rest.iter().position(|l| l.starts_with("Traceback (most recent call last):")) {
+            Some(start) => {
+                // Lines between the message and the traceback still belong to the message
+                for l in &rest[..start] { message.push('\n'); message.push_str(l); }
+                let traceback = &rest[start..];
+                if let Some(exc) = exception_type(traceback) { fields.insert("exception".into(), exc); }
+                fields.insert("traceback".into(), traceback.join("\n").trim_end().to_string());
+            }
+            None => for l in &rest { message.push('\n'); message.push_str(l); },
+        }
+        let message = message.trim_end().to_string();
+        Ok(Some(LogEntry { ts, level: parse_level(&caps["level"]), message, fields, source: None, line_no: 0 }))
+    }
+}
+
+/// Joins every line that doesn't start a new record (tracebacks, multi-line messages) onto the
+/// record before it, separated by [`RECORD_LINE_SEP`], so each record is one line.
+pub struct JoinContinuations<R> {
+    inner: R,
+    /// Matches the first line of a record
+    start: &'static regex::bytes::Regex,
+    /// First line of the next record, already read
+    next: Option<Vec<u8>>,
+    /// Current record and how much of it was consumed
+    out: Vec<u8>,
+    pos: usize,
+}
+
+impl<R: BufRead> JoinContinuations<R> {
+    /// Records start like Python `logging` lines
+    pub fn new(inner: R) -> Self { Self::with_record_start(inner, &RECORD_START) }
+
+    /// Records start with a line matching `start`
+    pub fn with_record_start(inner: R, start: &'static regex::bytes::Regex) -> Self {
+        Self { inner, start, next: None, out: Vec::new(), pos: 0 }
+    }
+
+    fn read_line(&mut self) -> io::Result<Option<Vec<u8>>> {
+        let mut line = Vec::new();
+        if self.inner.read_until(b'\n', &mut line)? == 0 { return Ok(None); }
+        while matches!(line.last(), Some(b'\n' | b'\r')) { line.pop(); }
+        Ok(Some(line))
+    }
+
+    /// Read the next record into `out`; leaves `out` empty at end of input.
+    fn next_record(&mut self) -> io::Result<()> {
+        self.out.clear();
+        self.pos = 0;
+        let Some(first) = self.next.take().map_or_else(|| self.read_line(), |l| Ok(Some(l)))? else { return Ok(()) };
+        let starts_record = self.start.is_match(&first);
+        self.out.extend_from_slice(&first);
+        if starts_record {
+            let mut sep = [0u8; 4];
+            let sep = RECORD_LINE_SEP.encode_utf8(&mut sep).as_bytes();
+            for _ in 1..MAX_RECORD_LINES {
+                let Some(line) = self.read_line()? else { break };
+                if self.start.is_match(&line) {
+                    self.next = Some(line);
+                    break;
+                }
+                self.out.extend_from_slice(sep);
+                self.out.extend_from_slice(&line);
+            }
+        }
+        self.out.push(b'\n');
+        Ok(())
+    }
+}
+
+impl<R: BufRead> Read for JoinContinuations<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = {
+            let available = self.fill_buf()?;
+            let n = available.len().min(buf.len());
+            buf[..n].copy_from_slice(&available[..n]);
+            n
+        };
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+impl<R: BufRead> BufRead for JoinContinuations<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.pos >= self.out.len() { self.next_record()?; }
+        Ok(&self.out[self.pos..])
+    }
+
+    fn consume(&mut self, amt: usize) { self.pos += amt; }
+}
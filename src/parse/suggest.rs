@@ -0,0 +1,112 @@
+use crate::parse::{parse_structured_ts, LogParser, RegexParser};
+use chrono::NaiveDateTime;
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Timestamp shapes worth offering, with the [`RegexParser`] timestamp format that reads them
+/// ("" when the built-in parsing does). Day- and month-first dates share a shape; whichever fails on the samples drops out.
+static TIMESTAMPS: LazyLock<Vec<(Regex, &str)>> = LazyLock::new(|| [
+    (r"\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}:\d{2}(?:[.,]\d+)?(?:Z|[+-]\d{2}:\d{2})?", ""),
+    (r"\d{4}/\d{2}/\d{2} \d{2}:\d{2}:\d{2}", "%Y/%m/%d %H:%M:%S"),
+    (r"\d{2}/[A-Z][a-z]{2}/\d{4}:\d{2}:\d{2}:\d{2}", "%d/%b/%Y:%H:%M:%S"),
+    (r"\d{2}/\d{2}/\d{4} \d{2}:\d{2}:\d{2}", "%d/%m/%Y %H:%M:%S"),
+    (r"\d{2}/\d{2}/\d{4} \d{2}:\d{2}:\d{2}", "%m/%d/%Y %H:%M:%S"),
+    (r"\d{2}\.\d{2}\.\d{4} \d{2}:\d{2}:\d{2}", "%d.%m.%Y %H:%M:%S"),
+    (r"[A-Z][a-z]{2} [A-Z][a-z]{2} [ \d]\d \d{2}:\d{2}:\d{2} \d{4}", "%a %b %e %H:%M:%S %Y"),
+    (r"\b1\d{9}(?:\.\d{1,9}|\d{3}|\d{6}|\d{9})?\b", ""),
+].into_iter().map(|(re, format)| (Regex::new(re).unwrap(), format)).collect());
+const LEVELS: &str = "(?i:trace|debug|info|notice|warn|warning|error|err|fatal|critical|crit|severe)";
+static LEVEL_WORD: LazyLock<Regex> = LazyLock::new(|| Regex::new(&format!(r"\b{LEVELS}\b")).unwrap());
+/// Characters tried as field separators, besides runs of spaces
+const DELIMITERS: [char; 4] = ['|', '\t', ';', ','];
+/// Share of the sample lines a hint must hold for
+const MAJORITY: f64 = 0.8;
+
+/// A separator found the same number of times on most sample lines
+#[derive(Clone, Debug, PartialEq)]
+pub struct Delimiter {
+    pub sep: char,
+    /// Fields per line it splits into
+    pub fields: usize,
+}
+
+/// A timestamp shape found in the sample lines
+#[derive(Clone, Debug, PartialEq)]
+pub struct TimestampCandidate {
+    /// First match, as written
+    pub example: String,
+    /// Regex matching it
+    pub regex: &'static str,
+    /// Timestamp format for [`RegexParser`]; empty when the built-in parsing reads it
+    pub format: &'static str,
+    /// Sample lines it was found (and read) on
+    pub lines: usize,
+    /// Whether it starts most of those lines (after brackets and the like)
+    pub at_start: bool,
+}
+
+/// What [`suggest_format`] makes of lines none of the parsers understood
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FormatHints {
+    /// Non-empty sample lines
+    pub lines: usize,
+    pub delimiters: Vec<Delimiter>,
+    /// Most frequent first
+    pub timestamps: Vec<TimestampCandidate>,
+    /// A [`RegexParser`] pattern and timestamp format built from the above, when one parses at
+    /// least half of the lines
+    pub pattern: Option<(String, &'static str)>,
+    /// Sample lines the pattern parses
+    pub parsed: usize,
+}
+
+fn reads(format: &str, ts: &str) -> Option<NaiveDateTime> {
+    if format.is_empty() { parse_structured_ts(ts) } else { NaiveDateTime::parse_from_str(ts, format).ok() }
+}
+
+/// Separators, timestamps and a starting pattern for lines of an unknown format, for a user
+/// writing a custom pattern (the GUI offers them when most of a file doesn't parse)
+pub fn suggest_format(lines: &[&str]) -> FormatHints {
+    let lines: Vec<&str> = lines.iter().map(|l| l.trim_end()).filter(|l| !l.is_empty()).collect();
+    let majority = ((lines.len() as f64 * MAJORITY).ceil() as usize).max(1);
+    let mut hints = FormatHints { lines: lines.len(), ..Default::default() };
+
+    for sep in DELIMITERS {
+        let mut per_count = std::collections::HashMap::new();
+        for l in &lines { *per_count.entry(l.matches(sep).count()).or_insert(0usize) += 1; }
+        if let Some((&n, &count)) = per_count.iter().filter(|(n, _)| **n > 0).max_by_key(|(n, c)| (**c, **n)) {
+            if count >= majority { hints.delimiters.push(Delimiter { sep, fields: n + 1 }); }
+        }
+    }
+
+    for (re, format) in TIMESTAMPS.iter() {
+        let found: Vec<_> = lines.iter()
+            .filter_map(|l| re.find(l).filter(|m| reads(format, m.as_str()).is_some()).map(|m| (m, l)))
+            .collect();
+        let Some((first, _)) = found.first() else { continue };
+        let at_start = found.iter().filter(|(m, l)| l[..m.start()].chars().all(|c| !c.is_alphanumeric())).count() * 2 > found.len();
+        hints.timestamps.push(TimestampCandidate { example: first.as_str().to_string(), regex: re.as_str(), format, lines: found.len(), at_start });
+    }
+    hints.timestamps.sort_by_key(|t| std::cmp::Reverse(t.lines));
+
+    // A pattern for the leading timestamp: the rest splits at the delimiter, with a level among
+    // the first few fields if most lines have one
+    let Some(ts) = hints.timestamps.iter().find(|t| t.at_start) else { return hints };
+    let (sep, field) = match hints.delimiters.first() {
+        Some(d) => {
+            let d = regex::escape(&d.sep.to_string());
+            (format!(r"\s*{d}\s*"), format!("[^{d}]"))
+        }
+        None => (r"\s+".to_string(), r"\S".to_string()),
+    };
+    let with_level = lines.iter().filter(|l| LEVEL_WORD.is_match(l)).count() as f64 >= (ts.lines as f64 * MAJORITY).ceil();
+    let level = if with_level { format!(r"(?:(?:{field}+{sep}){{0,3}}?[\[(<]?(?P<level>{LEVELS})[\])>]?:?{sep})?") } else { String::new() };
+    let pattern = format!(r"^\W*(?P<ts>{}){field}*?{sep}{level}(?P<msg>.*)$", ts.regex);
+    let Ok(mut parser) = RegexParser::new(&pattern, Some(ts.format)) else { return hints };
+    let parsed = lines.iter().filter(|l| matches!(parser.parse_line(l), Ok(Some(_)))).count();
+    if parsed * 2 >= lines.len() {
+        hints.pattern = Some((pattern, ts.format));
+        hints.parsed = parsed;
+    }
+    hints
+}
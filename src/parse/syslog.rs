@@ -0,0 +1,86 @@
+use crate::model::{Level, LogEntry};
+use crate::parse::{mon_to_num, parse_structured_ts, syslog_ts, LogParser};
+use chrono::NaiveDateTime;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::sync::LazyLock;
+
+/// RFC 3164: `<34>Oct 11 22:14:15 mymachine su[42]: 'su root' failed` (host and tag optional)
+static BSD: LazyLock<Regex> = LazyLock::new(|| Regex::new(concat!(
+    r"^(?:<(?P<pri>\d{1,3})>)?(?P<mon>Jan|Feb|Mar|Apr|May|Jun|Jul|Aug|Sep|Oct|Nov|Dec)\s+(?P<day>\d{1,2}) (?P<time>\d{2}:\d{2}:\d{2})\s+",
+    r"(?:(?P<host>[^\s\[\]:]+)\s+)?(?:(?P<app>[^\s\[\]:]+)(?:\[(?P<pid>\d+)\])?:\s*)?(?P<msg>.*)$",
+)).unwrap());
+
+/// RFC 5424: `<165>1 2003-10-11T22:14:15.003Z host app 1234 ID47 [exampleSDID@32473 iut="3"] message`
+static IETF: LazyLock<Regex> = LazyLock::new(|| Regex::new(concat!(
+    r"^<(?P<pri>\d{1,3})>1 (?P<ts>\S+) (?P<host>\S+) (?P<app>\S+) (?P<pid>\S+) (?P<msgid>\S+) ",
+    r#"(?P<sd>-|(?:\[(?:[^\]"\\]|\\.|"(?:[^"\\]|\\.)*")*\])+)(?: (?P<msg>.*))?$"#,
+)).unwrap());
+
+const FACILITIES: [&str; 24] = [
+    "kern", "user", "mail", "daemon", "auth", "syslog", "lpr", "news", "uucp", "cron", "authpriv", "ftp",
+    "ntp", "security", "console", "solaris-cron", "local0", "local1", "local2", "local3", "local4", "local5", "local6", "local7",
+];
+const SEVERITIES: [&str; 8] = ["emerg", "alert", "crit", "err", "warning", "notice", "info", "debug"];
+
+/// Parser for syslog messages as sent over the network: RFC 3164 (`<34>Oct 11 22:14:15 host app[pid]: msg`,
+/// the `<PRI>` optional) and RFC 5424 (`<165>1 2003-10-11T22:14:15Z host app pid msgid [sd] msg`).
+///
+/// The PRI severity sets the level (emerg..err ERROR, warning WARNING, the rest INFO; INFO
+/// without PRI). Fields: `host`, `app`, `pid`, `facility` and `severity` (names such as
+/// `local0` and `err`), plus `msgid` and `structured_data` for RFC 5424; `-` (nil) values are
+/// left out. RFC 3164 dates get their year like the default parser's syslog lines.
+#[derive(Default)]
+pub struct SyslogParser {
+    assume_year: Option<i32>,
+    /// Year and timestamp of the previous RFC 3164 line in the current input
+    state: Option<(i32, NaiveDateTime)>,
+}
+
+impl SyslogParser {
+    pub fn new(assume_year: Option<i32>) -> Self { Self { assume_year, state: None } }
+}
+
+/// Level, facility and severity names of a PRI value
+fn priority(pri: &str) -> Result<(Level, &'static str, &'static str), String> {
+    let pri: usize = pri.parse().ok().filter(|p| *p < FACILITIES.len() * 8).ok_or_else(|| format!("Invalid PRI: {pri}"))?;
+    let level = match pri % 8 { 0..=3 => Level::Error, 4 => Level::Warning, _ => Level::Info };
+    Ok((level, FACILITIES[pri / 8], SEVERITIES[pri % 8]))
+}
+
+impl LogParser for SyslogParser {
+    fn reset(&mut self) { self.state = None; }
+
+    fn verbatim_messages(&self) -> bool { true }
+
+    fn parse_line(&mut self, line: &str) -> Result<Option<LogEntry>, String> {
+        let (caps, ts) = if let Some(caps) = IETF.captures(line) {
+            let ts = parse_structured_ts(&caps["ts"]).ok_or_else(|| format!("Could not parse timestamp: {}", &caps["ts"]))?;
+            (caps, ts)
+        } else if let Some(caps) = BSD.captures(line) {
+            let (mon, day, time) = (&caps["mon"], &caps["day"], &caps["time"]);
+            let month = mon_to_num(mon).ok_or_else(|| format!("Unknown month: {mon}"))?;
+            let ts = syslog_ts(&mut self.state, self.assume_year, month, day.parse().unwrap_or(1), time)
+                .ok_or_else(|| format!("Failed to parse datetime: {mon} {day} {time}"))?;
+            (caps, ts)
+        } else {
+            return Ok(None);
+        };
+
+        let mut fields: BTreeMap<String, String> = [("host", "host"), ("app", "app"), ("pid", "pid"), ("msgid", "msgid"), ("structured_data", "sd")]
+            .iter()
+            .filter_map(|(name, group)| caps.name(group).filter(|v| v.as_str() != "-").map(|v| (name.to_string(), v.as_str().to_string())))
+            .collect();
+        let level = match caps.name("pri") {
+            Some(pri) => {
+                let (level, facility, severity) = priority(pri.as_str())?;
+                fields.insert("facility".into(), facility.into());
+                fields.insert("severity".into(), severity.into());
+                level
+            }
+            None => Level::Info,
+        };
+        let message = caps.name("msg").map_or("", |m| m.as_str()).trim_start_matches('\u{feff}').to_string();
+        Ok(Some(LogEntry { ts, level, message, fields, source: None, line_no: 0 }))
+    }
+}
@@ -0,0 +1,85 @@
+use crate::model::{Level, LogEntry};
+use crate::parse::{json::flatten, parse_structured_ts, status_level, structured_entry, LogParser};
+use chrono::DateTime;
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::sync::LazyLock;
+
+/// `ip - user [ts] "GET /path HTTP/1.1" 200 512 "referer" "agent" 42 "router@docker" "http://10.0.0.5:80" 3ms`
+static CLF: LazyLock<Regex> = LazyLock::new(|| Regex::new(concat!(
+    r#"^(?P<client>\S+) \S+ (?P<user>\S+) \[(?P<ts>[^\]]+)\] "(?P<request>[^"]*)" (?P<status>\d{3}|-) (?P<bytes>\d+|-) "#,
+    r#""(?P<referer>[^"]*)" "(?P<agent>[^"]*)" (?P<count>\d+) "(?P<router>[^"]*)" "(?P<backend>[^"]*)" (?P<duration>\d+)ms\s*$"#,
+)).unwrap());
+
+/// JSON access log keys and the field names they are stored under
+const JSON_FIELDS: [(&str, &str); 8] = [
+    ("ClientHost", "client_ip"), ("RequestMethod", "method"), ("RequestPath", "path"), ("RouterName", "router"),
+    ("ServiceName", "backend"), ("ServiceURL", "server"), ("DownstreamContentSize", "bytes"), ("RequestProtocol", "protocol"),
+];
+
+/// Parser for Traefik access logs, in the common log format or as JSON (`format: json`).
+///
+/// The status sets the level (5xx ERROR, 4xx WARNING) and the message reads `GET /path 502`.
+/// Fields: `client_ip`, `method`, `path`, `status`, `bytes`, `router`, `backend` and
+/// `duration_ms`. `backend` is the service name in JSON logs (whose service URL is `server`) and
+/// the server URL in CLF logs, which lack the name. Other JSON keys are kept as they are, and JSON
+/// lines that aren't access records (Traefik's own log) are read like [`Format::Json`](super::Format::Json).
+#[derive(Default)]
+pub struct TraefikParser;
+
+fn http_message(fields: &BTreeMap<String, String>) -> String {
+    [fields.get("method"), fields.get("path"), fields.get("status")].into_iter().flatten()
+        .map(String::as_str).collect::<Vec<_>>().join(" ")
+}
+
+fn http_level(fields: &BTreeMap<String, String>) -> Level {
+    fields.get("status").map_or(Level::Info, |s| status_level(s))
+}
+
+fn parse_clf(line: &str) -> Result<Option<LogEntry>, String> {
+    let Some(caps) = CLF.captures(line) else { return Ok(None) };
+    let ts = DateTime::parse_from_str(&caps["ts"], "%d/%b/%Y:%H:%M:%S %z")
+        .map_err(|_| format!("Could not parse timestamp: {}", &caps["ts"]))?
+        .naive_local();
+    let mut fields: BTreeMap<String, String> = [
+        ("client_ip", "client"), ("user", "user"), ("status", "status"), ("bytes", "bytes"), ("referer", "referer"),
+        ("user_agent", "agent"), ("router", "router"), ("backend", "backend"), ("duration_ms", "duration"),
+    ].iter()
+        .filter(|(_, group)| !matches!(&caps[*group], "-" | ""))
+        .map(|(name, group)| (name.to_string(), caps[*group].to_string()))
+        .collect();
+    let mut request = caps["request"].split_whitespace();
+    if let Some(method) = request.next() { fields.insert("method".into(), method.into()); }
+    if let Some(path) = request.next() { fields.insert("path".into(), path.into()); }
+    Ok(Some(LogEntry { ts, level: http_level(&fields), message: http_message(&fields), fields, source: None, line_no: 0 }))
+}
+
+fn parse_json(line: &str) -> Result<Option<LogEntry>, String> {
+    let obj: Map<String, Value> = serde_json::from_str(line).map_err(|e| format!("Invalid JSON: {e}"))?;
+    let mut pairs = BTreeMap::new();
+    flatten("", &obj, &mut pairs);
+    let Some(status) = pairs.remove("DownstreamStatus").or_else(|| pairs.remove("OriginStatus")) else {
+        return structured_entry(pairs).map(Some);
+    };
+    let ts_raw = ["StartUTC", "StartLocal", "time"].iter().find_map(|k| pairs.remove(*k))
+        .ok_or("Access record without a timestamp")?;
+    let ts = parse_structured_ts(&ts_raw).ok_or_else(|| format!("Could not parse timestamp: {ts_raw}"))?;
+    // The access log's own `level`/`msg` are always `info`/empty
+    for k in ["level", "msg", "time", "StartLocal"] { pairs.remove(k); }
+    let mut fields = BTreeMap::from([("status".to_string(), status)]);
+    for (key, name) in JSON_FIELDS {
+        if let Some(v) = pairs.remove(key) { fields.insert(name.into(), v); }
+    }
+    if let Some(ns) = pairs.remove("Duration").and_then(|d| d.parse::<f64>().ok()) {
+        fields.insert("duration_ms".into(), (ns / 1e6).to_string());
+    }
+    fields.extend(pairs);
+    Ok(Some(LogEntry { ts, level: http_level(&fields), message: http_message(&fields), fields, source: None, line_no: 0 }))
+}
+
+impl LogParser for TraefikParser {
+    fn parse_line(&mut self, line: &str) -> Result<Option<LogEntry>, String> {
+        if line.trim_start().starts_with('{') { parse_json(line) } else { parse_clf(line) }
+    }
+}
@@ -0,0 +1,85 @@
+use crate::model::{Level, LogEntry};
+use crate::parse::{status_level, LogParser};
+use chrono::{NaiveDate, NaiveTime};
+use std::collections::BTreeMap;
+
+/// Parser for the W3C extended log format written by IIS and some proxies.
+///
+/// The `#Fields:` directive names the space-separated columns of the lines that follow (it may
+/// change mid-file, e.g. after an IIS restart). `date` + `time` make the timestamp (`time` alone
+/// is relative to the `#Date:` directive), `sc-status` sets the level (5xx ERROR, 4xx WARNING)
+/// and the message reads `GET /path?query 404`. Every other column is kept as a field under its
+/// W3C name (`c-ip`, `cs(User-Agent)`, `time-taken`, ...); `-` (no value) columns are skipped.
+#[derive(Default)]
+pub struct W3cParser {
+    /// Column names from the last `#Fields:` directive
+    columns: Vec<String>,
+    /// Date from the last `#Date:` directive, for logs without a `date` column
+    date: Option<NaiveDate>,
+}
+
+/// Split a line into columns; `"..."` columns may contain spaces (`""` escapes a quote)
+pub(super) fn columns(line: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut chars = line.chars().peekable();
+    loop {
+        while chars.next_if(|c| *c == ' ' || *c == '\t').is_some() {}
+        let Some(first) = chars.next() else { break };
+        let mut value = String::new();
+        if first == '"' {
+            while let Some(c) = chars.next() {
+                if c == '"' && chars.next_if_eq(&'"').is_none() { break; }
+                value.push(c);
+            }
+        } else {
+            value.push(first);
+            value.extend(std::iter::from_fn(|| chars.next_if(|c| *c != ' ' && *c != '\t')));
+        }
+        out.push(value);
+    }
+    out
+}
+
+impl LogParser for W3cParser {
+    fn reset(&mut self) {
+        self.columns.clear();
+        self.date = None;
+    }
+
+    fn absorb(&mut self, line: &str) -> bool {
+        let Some(directive) = line.strip_prefix('#') else { return line.trim().is_empty() };
+        if let Some(fields) = directive.strip_prefix("Fields:") {
+            self.columns = fields.split_whitespace().map(str::to_string).collect();
+        } else if let Some(date) = directive.strip_prefix("Date:") {
+            self.date = date.split_whitespace().next().and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok());
+        }
+        // #Software, #Version, #Start-Date, #Remark, ...
+        true
+    }
+
+    fn parse_line(&mut self, line: &str) -> Result<Option<LogEntry>, String> {
+        if self.columns.is_empty() { return Err("Line before any #Fields: directive".into()); }
+        let values = columns(line);
+        if values.len() != self.columns.len() {
+            return Err(format!("Expected {} fields (per #Fields:), found {}", self.columns.len(), values.len()));
+        }
+        let mut fields: BTreeMap<String, String> = self.columns.iter().cloned().zip(values)
+            .filter(|(_, v)| v != "-")
+            .collect();
+
+        let date = match fields.remove("date") {
+            Some(d) => NaiveDate::parse_from_str(&d, "%Y-%m-%d").map_err(|_| format!("Bad date column: {d}"))?,
+            None => self.date.ok_or("Line without a date column or #Date: directive")?,
+        };
+        let time = fields.remove("time").ok_or("Line without a time column")?;
+        let time = NaiveTime::parse_from_str(&time, "%H:%M:%S%.f").map_err(|_| format!("Bad time column: {time}"))?;
+
+        let status = fields.get("sc-status").cloned();
+        let level = status.as_deref().map_or(Level::Info, status_level);
+        let mut message = [fields.get("cs-method"), fields.get("cs-uri-stem")]
+            .into_iter().flatten().map(String::as_str).collect::<Vec<_>>().join(" ");
+        if let Some(query) = fields.get("cs-uri-query") { message = format!("{message}?{query}"); }
+        if let Some(status) = status { message = format!("{message} {status}").trim_start().to_string(); }
+        Ok(Some(LogEntry { ts: date.and_time(time), level, message, fields, source: None, line_no: 0 }))
+    }
+}
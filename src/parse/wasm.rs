@@ -0,0 +1,101 @@
+//! Parsers loaded from WebAssembly modules (`--plugin parser.wasm`).
+//!
+//! A plugin module exports:
+//!
+//! - `memory`
+//! - `alloc(len: i32) -> i32`: a buffer the host copies each line into
+//! - `parse_line(ptr: i32, len: i32) -> i64`: parses the UTF-8 line at `ptr`; returns 0 when the
+//!   line isn't in the plugin's format, else `(out_ptr << 32) | out_len` of a UTF-8 JSON object in
+//!   its memory. The object is read like `--format json` output (`time`, `level`, `msg`, other keys
+//!   as fields), or is `{"error": "reason"}` for a line the plugin recognizes but can't parse.
+//! - optionally `dealloc(ptr: i32, len: i32)`, called on both buffers once the host is done.
+//!
+//! Each line gets [`PLUGIN_FUEL`] units of fuel (about one per instruction); a plugin that runs
+//! out, e.g. stuck in a loop, fails that line instead of hanging the run.
+
+use crate::model::LogEntry;
+use crate::parse::{JsonLinesParser, LogParser};
+use anyhow::{anyhow, Context, Result};
+use std::path::{Path, PathBuf};
+use wasmtime::{Config, Engine, Instance, Memory, Module, Store, Trap, TypedFunc};
+
+/// Fuel for instantiating a plugin, and for its calls on each line
+pub const PLUGIN_FUEL: u64 = 100_000_000;
+
+/// Tries each plugin in order; the first to return an entry (or an error) wins.
+pub struct WasmParser {
+    plugins: Vec<Plugin>,
+    json: JsonLinesParser,
+}
+
+struct Plugin {
+    path: PathBuf,
+    store: Store<()>,
+    memory: Memory,
+    alloc: TypedFunc<i32, i32>,
+    dealloc: Option<TypedFunc<(i32, i32), ()>>,
+    parse: TypedFunc<(i32, i32), i64>,
+}
+
+impl WasmParser {
+    pub fn load(paths: &[PathBuf]) -> Result<Self> {
+        let engine = Engine::new(Config::new().consume_fuel(true))?;
+        let plugins = paths.iter()
+            .map(|p| Plugin::load(&engine, p).with_context(|| format!("Loading plugin {}", p.display())))
+            .collect::<Result<_>>()?;
+        Ok(Self { plugins, json: JsonLinesParser })
+    }
+}
+
+impl Plugin {
+    fn load(engine: &Engine, path: &Path) -> Result<Self> {
+        let module = Module::from_file(engine, path)?;
+        let mut store = Store::new(engine, ());
+        store.set_fuel(PLUGIN_FUEL)?;
+        let instance = Instance::new(&mut store, &module, &[])?;
+        let memory = instance.get_memory(&mut store, "memory").ok_or_else(|| anyhow!("missing `memory` export"))?;
+        let alloc = instance.get_typed_func(&mut store, "alloc")?;
+        let parse = instance.get_typed_func(&mut store, "parse_line")?;
+        let dealloc = instance.get_typed_func(&mut store, "dealloc").ok();
+        Ok(Self { path: path.to_path_buf(), store, memory, alloc, dealloc, parse })
+    }
+
+    /// The plugin's JSON for `line`, or None if it isn't in the plugin's format
+    fn call(&mut self, line: &str) -> Result<Option<String>> {
+        let len = i32::try_from(line.len())?;
+        self.store.set_fuel(PLUGIN_FUEL)?;
+        let ptr = self.alloc.call(&mut self.store, len)?;
+        self.memory.write(&mut self.store, ptr as u32 as usize, line.as_bytes())?;
+        let packed = self.parse.call(&mut self.store, (ptr, len))?;
+        let out = if packed == 0 {
+            None
+        } else {
+            let (out_ptr, out_len) = ((packed >> 32) as u32 as usize, packed as u32 as usize);
+            let bytes = self.memory.data(&self.store)
+                .get(out_ptr..out_ptr + out_len)
+                .ok_or_else(|| anyhow!("result out of bounds"))?;
+            let json = String::from_utf8_lossy(bytes).into_owned();
+            if let Some(dealloc) = &self.dealloc { dealloc.call(&mut self.store, (out_ptr as i32, out_len as i32))?; }
+            Some(json)
+        };
+        if let Some(dealloc) = &self.dealloc { dealloc.call(&mut self.store, (ptr, len))?; }
+        Ok(out)
+    }
+}
+
+impl LogParser for WasmParser {
+    fn parse_line(&mut self, line: &str) -> Result<Option<LogEntry>, String> {
+        for plugin in &mut self.plugins {
+            let json = plugin.call(line).map_err(|e| match e.downcast_ref::<Trap>() {
+                Some(Trap::OutOfFuel) => format!("Plugin {} ran out of fuel ({PLUGIN_FUEL}) on this line", plugin.path.display()),
+                _ => format!("Plugin {} failed: {e}", plugin.path.display()),
+            })?;
+            let Some(json) = json else { continue };
+            if let Ok(serde_json::Value::Object(obj)) = serde_json::from_str::<serde_json::Value>(&json) {
+                if let Some(reason) = obj.get("error").and_then(|e| e.as_str()) { return Err(reason.to_string()); }
+            }
+            return self.json.parse_line(&json);
+        }
+        Ok(None)
+    }
+}
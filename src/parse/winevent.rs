@@ -0,0 +1,91 @@
+use crate::model::{Level, LogEntry};
+use crate::parse::LogParser;
+use chrono::DateTime;
+use std::collections::BTreeMap;
+
+/// Parser for Windows Event Log XML, as produced by `wevtutil qe <log> /f:xml` (one `<Event>`
+/// per line) or pretty-printed exports where an event spans several lines.
+///
+/// `Provider`, `EventID`, `Channel`, `Computer` and `EventRecordID` become fields. The message
+/// is the rendered message when present (`/f:RenderedXml`), otherwise the `EventData` values.
+#[derive(Default)]
+pub struct WinEventParser {
+    /// Lines of an `<Event>` that hasn't been closed yet
+    pending: String,
+}
+
+/// Windows EventLevel to our three levels (0 = LogAlways, 1 = Critical .. 5 = Verbose)
+fn event_level(level: &str) -> Level {
+    match level.trim() {
+        "1" | "2" => Level::Error,
+        "3" => Level::Warning,
+        _ => Level::Info,
+    }
+}
+
+fn child<'a, 'i>(parent: roxmltree::Node<'a, 'i>, name: &str) -> Option<roxmltree::Node<'a, 'i>> {
+    parent.children().find(|n| n.has_tag_name(name))
+}
+
+fn parse_event(xml: &str) -> Result<LogEntry, String> {
+    let doc = roxmltree::Document::parse(xml).map_err(|e| format!("Invalid event XML: {e}"))?;
+    let event = doc.root_element();
+    let system = child(event, "System").ok_or("Event without <System>")?;
+    let text = |name: &str| child(system, name).and_then(|n| n.text()).map(str::trim);
+
+    let created = child(system, "TimeCreated").and_then(|n| n.attribute("SystemTime")).ok_or("Event without TimeCreated")?;
+    let ts = DateTime::parse_from_rfc3339(created)
+        .map_err(|e| format!("Bad TimeCreated {created}: {e}"))?
+        .naive_utc();
+
+    let mut fields = BTreeMap::new();
+    if let Some(p) = child(system, "Provider").and_then(|n| n.attribute("Name")) { fields.insert("provider".into(), p.to_string()); }
+    for (name, key) in [("EventID", "event_id"), ("Channel", "channel"), ("Computer", "host"), ("EventRecordID", "record_id")] {
+        if let Some(v) = text(name) { fields.insert(key.into(), v.to_string()); }
+    }
+
+    let rendered = child(event, "RenderingInfo").and_then(|r| child(r, "Message")).and_then(|m| m.text());
+    let message = match rendered {
+        Some(m) => m.trim().to_string(),
+        None => {
+            let data: Vec<String> = child(event, "EventData").or_else(|| child(event, "UserData"))
+                .map(|d| d.descendants().filter(|n| n.is_element() && n.text().is_some_and(|t| !t.trim().is_empty()))
+                    .map(|n| match n.attribute("Name") {
+                        Some(name) => format!("{name}={}", n.text().unwrap().trim()),
+                        None => n.text().unwrap().trim().to_string(),
+                    })
+                    .collect())
+                .unwrap_or_default();
+            if data.is_empty() {
+                format!("{} event {}", fields.get("provider").map_or("Unknown", String::as_str), fields.get("event_id").map_or("?", String::as_str))
+            } else {
+                data.join("; ")
+            }
+        }
+    };
+
+    Ok(LogEntry { ts, level: event_level(text("Level").unwrap_or("4")), message, fields, source: None, line_no: 0 })
+}
+
+impl LogParser for WinEventParser {
+    fn reset(&mut self) { self.pending.clear(); }
+
+    fn absorb(&mut self, line: &str) -> bool {
+        let t = line.trim();
+        // Wrappers around the event list
+        if self.pending.is_empty() && (t.is_empty() || t.starts_with("<?xml") || t.starts_with("<Events") || t == "</Events>") {
+            return true;
+        }
+        if t.contains("</Event>") || (self.pending.is_empty() && !t.starts_with("<Event")) { return false; }
+        self.pending.push_str(line);
+        self.pending.push('\n');
+        true
+    }
+
+    fn parse_line(&mut self, line: &str) -> Result<Option<LogEntry>, String> {
+        let xml = std::mem::take(&mut self.pending) + line;
+        let xml = xml.trim();
+        if !xml.starts_with("<Event") { return Ok(None); }
+        parse_event(xml).map(Some)
+    }
+}
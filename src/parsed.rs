@@ -0,0 +1,107 @@
+//! Parsed entries saved to disk (`--save-parsed` / `--load-parsed`): a zstd-compressed stream of
+//! bincode records holding what the parser returned for each line, so later runs with other
+//! filters, granularities or reports skip reading and parsing the original logs.
+
+use crate::analyze::{repeat_count, Analyzer};
+use crate::model::{Level, LogEntry};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// Bumped whenever the layout of records (or of [`LogEntry`]) changes.
+const PARSED_VERSION: u32 = 1;
+const MAGIC: [u8; 4] = *b"LGZP";
+const ZSTD_LEVEL: i32 = 3;
+
+#[derive(Serialize, Deserialize)]
+struct Header {
+    magic: [u8; 4],
+    version: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+enum Record {
+    /// The lines that follow come from this input
+    Input(String),
+    /// Raw text is only kept where the analysis uses it: for errors (as examples), for lines
+    /// that didn't parse and for suppression notices
+    Line { line_no: u64, raw: String, parsed: Result<Option<LogEntry>, String> },
+    /// Marks a complete file; a file cut short by a crash lacks it
+    End,
+}
+
+/// Writes every line an [`Analyzer`] parses (see [`Analyzer::recorder`]). Shared by the partials
+/// of a run, which record in turn.
+pub struct Recorder {
+    out: Option<zstd::Encoder<'static, BufWriter<File>>>,
+    source: Option<String>,
+    lines: u64,
+    /// First write error; recording stops there and [`finish`](Self::finish) reports it
+    error: Option<std::io::Error>,
+}
+
+impl Recorder {
+    pub fn create(path: &Path) -> Result<Arc<Mutex<Self>>> {
+        let file = File::create(path).with_context(|| format!("Creating {}", path.display()))?;
+        let mut out = zstd::Encoder::new(BufWriter::new(file), ZSTD_LEVEL)?;
+        bincode::serialize_into(&mut out, &Header { magic: MAGIC, version: PARSED_VERSION })?;
+        Ok(Arc::new(Mutex::new(Self { out: Some(out), source: None, lines: 0, error: None })))
+    }
+
+    pub(crate) fn record(&mut self, source: &str, line_no: u64, raw: &str, parsed: &Result<Option<LogEntry>, String>) {
+        if self.error.is_some() { return; }
+        let Some(out) = self.out.as_mut() else { return };
+        let mut write = |r: &Record| bincode::serialize_into(&mut *out, r).map_err(|e| match *e {
+            bincode::ErrorKind::Io(e) => e,
+            e => std::io::Error::other(e.to_string()),
+        });
+        let written = (|| {
+            if self.source.as_deref() != Some(source) {
+                write(&Record::Input(source.to_string()))?;
+                self.source = Some(source.to_string());
+            }
+            let keep_raw = !matches!(parsed, Ok(Some(e)) if e.level != Level::Error) || repeat_count(raw).is_some();
+            let raw = if keep_raw { raw.to_string() } else { String::new() };
+            write(&Record::Line { line_no, raw, parsed: parsed.clone() })
+        })();
+        self.lines += 1;
+        if let Err(e) = written { self.error = Some(e); }
+    }
+
+    /// Complete the file; call once after the analysis. Returns the number of lines saved.
+    pub fn finish(&mut self) -> Result<u64> {
+        if let Some(e) = self.error.take() { return Err(e.into()); }
+        let Some(mut out) = self.out.take() else { return Ok(self.lines) };
+        bincode::serialize_into(&mut out, &Record::End)?;
+        out.finish()?.flush()?;
+        Ok(self.lines)
+    }
+}
+
+/// Feed the lines saved in `path` into `analyzer` as if parsed now; returns how many were read.
+pub fn load(path: &Path, analyzer: &mut Analyzer) -> Result<u64> {
+    let file = File::open(path).with_context(|| format!("Opening {}", path.display()))?;
+    let mut r = BufReader::new(zstd::Decoder::new(file)?);
+    let header: Header = bincode::deserialize_from(&mut r).ok()
+        .filter(|h: &Header| h.magic == MAGIC)
+        .with_context(|| format!("{} is not a --save-parsed file", path.display()))?;
+    if header.version != PARSED_VERSION {
+        anyhow::bail!("{} was saved by another version (format {}, expected {PARSED_VERSION}); save it again", path.display(), header.version);
+    }
+    let (mut source, mut lines) = (String::new(), 0);
+    loop {
+        let record: Record = bincode::deserialize_from(&mut r)
+            .with_context(|| format!("{} is truncated or corrupt after {lines} lines", path.display()))?;
+        match record {
+            Record::Input(s) => source = s,
+            Record::Line { line_no, raw, parsed } => {
+                analyzer.consume_parsed(&source, line_no, raw, parsed);
+                lines += 1;
+            }
+            Record::End => return Ok(lines),
+        }
+    }
+}
@@ -0,0 +1,249 @@
+//! A small filter expression language over parsed entries, e.g.
+//! `level == "error" && message ~ "timeout" && ts > 2025-09-05`.
+//!
+//! Fields: `level`, `message` (or `msg`), `ts`, and any extracted field by name (`host`, `thread`, ...).
+//! Operators: `==`, `!=`, `~` / `!~` (regex match), `<`, `<=`, `>`, `>=`; combine with `&&`, `||`, `!` and parentheses.
+
+use crate::analyze::{lossy_lines, parse_datetime, DEFAULT_MAX_LINE_LEN};
+use crate::model::{Level, LogEntry};
+use crate::parse::{LogParser, RECORD_LINE_SEP};
+use anyhow::{bail, Context, Result};
+use chrono::NaiveDateTime;
+use regex::Regex;
+use std::cmp::Ordering;
+use std::io::Write;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Cmp(Cmp),
+}
+
+#[derive(Debug)]
+pub enum Cmp {
+    Level { eq: bool, level: Level },
+    Ts { op: Op, ts: NaiveDateTime },
+    Message(Match),
+    Field { name: String, m: Match },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Op { Eq, Ne, Lt, Le, Gt, Ge, Re, NotRe }
+
+/// A comparison against a string-valued field.
+#[derive(Debug)]
+pub enum Match {
+    Compare(Op, String),
+    Regex { negated: bool, re: Regex },
+}
+
+impl Op {
+    fn holds(self, ord: Ordering) -> bool {
+        match self {
+            Op::Eq => ord == Ordering::Equal,
+            Op::Ne => ord != Ordering::Equal,
+            Op::Lt => ord == Ordering::Less,
+            Op::Le => ord != Ordering::Greater,
+            Op::Gt => ord == Ordering::Greater,
+            Op::Ge => ord != Ordering::Less,
+            Op::Re | Op::NotRe => false,
+        }
+    }
+}
+
+impl Match {
+    fn new(op: Op, value: String) -> Result<Self> {
+        Ok(match op {
+            Op::Re | Op::NotRe => Match::Regex {
+                negated: op == Op::NotRe,
+                re: Regex::new(&value).with_context(|| format!("Invalid regex: {value}"))?,
+            },
+            _ => Match::Compare(op, value),
+        })
+    }
+
+    /// Numbers compare numerically, everything else lexicographically.
+    fn test(&self, actual: &str) -> bool {
+        match self {
+            Match::Regex { negated, re } => re.is_match(actual) != *negated,
+            Match::Compare(op, want) => {
+                let ord = match (actual.parse::<f64>(), want.parse::<f64>()) {
+                    (Ok(a), Ok(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+                    _ => actual.cmp(want.as_str()),
+                };
+                op.holds(ord)
+            }
+        }
+    }
+}
+
+impl Expr {
+    pub fn parse(src: &str) -> Result<Expr> {
+        let tokens = tokenize(src)?;
+        let mut p = ExprParser { tokens, pos: 0 };
+        let expr = p.or()?;
+        if let Some(t) = p.tokens.get(p.pos) { bail!("Unexpected {t:?} in query"); }
+        Ok(expr)
+    }
+
+    pub fn matches(&self, e: &LogEntry) -> bool {
+        match self {
+            Expr::And(a, b) => a.matches(e) && b.matches(e),
+            Expr::Or(a, b) => a.matches(e) || b.matches(e),
+            Expr::Not(a) => !a.matches(e),
+            Expr::Cmp(Cmp::Level { eq, level }) => (e.level == *level) == *eq,
+            Expr::Cmp(Cmp::Ts { op, ts }) => op.holds(e.ts.cmp(ts)),
+            Expr::Cmp(Cmp::Message(m)) => m.test(&e.message),
+            // A missing field only satisfies negative tests
+            Expr::Cmp(Cmp::Field { name, m }) => match e.fields.get(name) {
+                Some(v) => m.test(v),
+                None => matches!(m, Match::Compare(Op::Ne, _) | Match::Regex { negated: true, .. }),
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token { Ident(String), Str(String), Op(Op), And, Or, Not, LParen, RParen }
+
+fn tokenize(src: &str) -> Result<Vec<Token>> {
+    let mut out = Vec::new();
+    let mut chars = src.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() { chars.next(); continue; }
+        // Operators that may (or must) be followed by a second character
+        let mut two = |second: char, yes: Token, no: Option<Token>| -> Result<Token> {
+            chars.next();
+            if chars.next_if_eq(&second).is_some() { return Ok(yes); }
+            no.with_context(|| format!("Expected '{c}{second}' in query"))
+        };
+        let tok = match c {
+            '(' => { chars.next(); Token::LParen }
+            ')' => { chars.next(); Token::RParen }
+            '~' => { chars.next(); Token::Op(Op::Re) }
+            '&' => two('&', Token::And, None)?,
+            '|' => two('|', Token::Or, None)?,
+            '=' => two('=', Token::Op(Op::Eq), None)?,
+            '<' => two('=', Token::Op(Op::Le), Some(Token::Op(Op::Lt)))?,
+            '>' => two('=', Token::Op(Op::Ge), Some(Token::Op(Op::Gt)))?,
+            '!' => {
+                chars.next();
+                match chars.peek() {
+                    Some('=') => { chars.next(); Token::Op(Op::Ne) }
+                    Some('~') => { chars.next(); Token::Op(Op::NotRe) }
+                    _ => Token::Not,
+                }
+            }
+            '"' | '\'' => {
+                chars.next();
+                let mut s = String::new();
+                loop {
+                    match chars.next() {
+                        Some('\\') => s.extend(chars.next()),
+                        Some(q) if q == c => break,
+                        Some(ch) => s.push(ch),
+                        None => bail!("Unterminated string in query"),
+                    }
+                }
+                Token::Str(s)
+            }
+            _ => {
+                let mut s = String::new();
+                while let Some(&ch) = chars.peek() {
+                    if ch.is_whitespace() || "()!=<>~&|\"'".contains(ch) { break; }
+                    s.push(ch);
+                    chars.next();
+                }
+                Token::Ident(s)
+            }
+        };
+        out.push(tok);
+    }
+    Ok(out)
+}
+
+struct ExprParser { tokens: Vec<Token>, pos: usize }
+
+impl ExprParser {
+    fn next(&mut self) -> Option<Token> {
+        let t = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        t
+    }
+
+    fn eat(&mut self, t: &Token) -> bool {
+        if self.tokens.get(self.pos) == Some(t) { self.pos += 1; true } else { false }
+    }
+
+    fn or(&mut self) -> Result<Expr> {
+        let mut lhs = self.and()?;
+        while self.eat(&Token::Or) { lhs = Expr::Or(Box::new(lhs), Box::new(self.and()?)); }
+        Ok(lhs)
+    }
+
+    fn and(&mut self) -> Result<Expr> {
+        let mut lhs = self.unary()?;
+        while self.eat(&Token::And) { lhs = Expr::And(Box::new(lhs), Box::new(self.unary()?)); }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        if self.eat(&Token::Not) { return Ok(Expr::Not(Box::new(self.unary()?))); }
+        if self.eat(&Token::LParen) {
+            let e = self.or()?;
+            if !self.eat(&Token::RParen) { bail!("Expected ')' in query"); }
+            return Ok(e);
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr> {
+        let Some(Token::Ident(field)) = self.next() else { bail!("Expected a field name in query") };
+        let Some(Token::Op(op)) = self.next() else { bail!("Expected an operator after '{field}'") };
+        let value = match self.next() {
+            Some(Token::Ident(v)) | Some(Token::Str(v)) => v,
+            _ => bail!("Expected a value after '{field}'"),
+        };
+        let cmp = match field.as_str() {
+            "level" => match op {
+                Op::Eq | Op::Ne => Cmp::Level { eq: op == Op::Eq, level: value.parse().map_err(anyhow::Error::msg)? },
+                _ => bail!("level supports only == and !="),
+            },
+            "ts" => match op {
+                Op::Re | Op::NotRe => bail!("ts does not support regex matching"),
+                _ => Cmp::Ts { op, ts: parse_datetime(&value)? },
+            },
+            "message" | "msg" => Cmp::Message(Match::new(op, value)?),
+            _ => Cmp::Field { m: Match::new(op, value)?, name: field },
+        };
+        Ok(Expr::Cmp(cmp))
+    }
+}
+
+/// Print the raw lines of entries matching `expr` (prefixed with `file:line:` when
+/// searching several files). Returns the number of matches.
+pub fn run<P: LogParser + ?Sized>(expr: &Expr, parser: &mut P, files: &[PathBuf], count_only: bool) -> Result<u64> {
+    let stdout = std::io::stdout();
+    let mut out = stdout.lock();
+    let mut matches = 0;
+    for path in files {
+        parser.reset();
+        let reader = parser.decode(crate::input::open(path)?);
+        for (idx, line) in lossy_lines(reader, DEFAULT_MAX_LINE_LEN).enumerate() {
+            let line = line?;
+            if parser.absorb(&line) { continue; }
+            let Ok(Some(entry)) = parser.parse_line(&line) else { continue };
+            if !expr.matches(&entry) { continue; }
+            matches += 1;
+            if count_only { continue; }
+            if files.len() > 1 { write!(out, "{}:{}:", path.display(), idx + 1)?; }
+            // Multi-line records (`--format python`) print as their original lines
+            writeln!(out, "{}", line.replace(RECORD_LINE_SEP, "\n"))?;
+        }
+    }
+    if count_only { writeln!(out, "{matches}")?; }
+    Ok(matches)
+}
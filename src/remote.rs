@@ -0,0 +1,129 @@
+//! Object store inputs: `s3://`, `gs://` and `az://` URLs, streamed rather than downloaded.
+
+use anyhow::Result;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// URL schemes handled here instead of the local filesystem
+const SCHEMES: [&str; 6] = ["s3://", "s3a://", "gs://", "az://", "abfs://", "abfss://"];
+
+/// Whether an input names an object store location rather than a local path
+pub fn is_url(path: &Path) -> bool {
+    path.to_str().is_some_and(|p| SCHEMES.iter().any(|s| p.starts_with(s)))
+}
+
+#[cfg(not(feature = "remote"))]
+fn disabled(url: &Path) -> anyhow::Error {
+    anyhow::anyhow!("{} is an object store URL; this build has object store input disabled. Rebuild with `--features remote`.", url.display())
+}
+
+#[cfg(not(feature = "remote"))]
+pub fn list(url: &Path) -> Result<Vec<PathBuf>> {
+    Err(disabled(url))
+}
+
+#[cfg(not(feature = "remote"))]
+pub fn open(url: &Path) -> Result<Box<dyn Read>> {
+    Err(disabled(url))
+}
+
+#[cfg(feature = "remote")]
+pub use imp::{list, open};
+
+#[cfg(feature = "remote")]
+mod imp {
+    use super::*;
+    use anyhow::{anyhow, Context};
+    use bytes::Bytes;
+    use futures::stream::{BoxStream, StreamExt, TryStreamExt};
+    use globset::Glob;
+    use object_store::aws::AmazonS3Builder;
+    use object_store::azure::MicrosoftAzureBuilder;
+    use object_store::gcp::GoogleCloudStorageBuilder;
+    use object_store::path::Path as ObjectPath;
+    use object_store::{ObjectStore, ObjectStoreExt};
+    use std::sync::{Arc, LazyLock};
+    use tokio::runtime::Runtime;
+    use url::Url;
+
+    /// Drives the async object store clients from the synchronous readers
+    static RUNTIME: LazyLock<Runtime> = LazyLock::new(|| {
+        tokio::runtime::Builder::new_current_thread().enable_all().build().expect("tokio runtime")
+    });
+
+    /// Client for the URL's bucket/container, configured from the usual environment
+    /// (`AWS_*`, `GOOGLE_*`, `AZURE_*`; instance/workload credentials when those are unset)
+    fn store_for(url: &Url) -> Result<Arc<dyn ObjectStore>> {
+        let store: Arc<dyn ObjectStore> = match url.scheme() {
+            "s3" | "s3a" => Arc::new(AmazonS3Builder::from_env().with_url(url.as_str()).build()?),
+            "gs" => Arc::new(GoogleCloudStorageBuilder::from_env().with_url(url.as_str()).build()?),
+            "az" | "abfs" | "abfss" => Arc::new(MicrosoftAzureBuilder::from_env().with_url(url.as_str()).build()?),
+            other => return Err(anyhow!("Unsupported object store scheme: {other}")),
+        };
+        Ok(store)
+    }
+
+    /// The URL (for the store's bucket/container) and the object key. The key is taken verbatim
+    /// from after the host, since [`Url::path`] would drop a `?` or `#` and percent-encode `{}`.
+    fn parse(url: &Path) -> Result<(Url, String)> {
+        let s = url.to_str().ok_or_else(|| anyhow!("Invalid URL: {}", url.display()))?;
+        let parsed = Url::parse(s).with_context(|| format!("Invalid URL: {s}"))?;
+        let after_scheme = s.split_once("://").map_or("", |(_, rest)| rest);
+        let key = after_scheme.split_once('/').map_or("", |(_, key)| key).to_string();
+        Ok((parsed, key))
+    }
+
+    /// Expand a URL into object URLs: a glob (`s3://bucket/logs/*.log.gz`) matches keys under
+    /// its literal prefix, a trailing `/` takes every object below it, anything else is one object.
+    pub fn list(url: &Path) -> Result<Vec<PathBuf>> {
+        let (parsed, key) = parse(url)?;
+        let glob_at = key.find(['*', '?', '[', '{']);
+        if glob_at.is_none() && !key.is_empty() && !key.ends_with('/') { return Ok(vec![url.to_path_buf()]); }
+        let prefix = match glob_at {
+            Some(i) => key[..i].rfind('/').map_or("", |slash| &key[..slash]),
+            None => key.trim_end_matches('/'),
+        };
+        let matcher = glob_at.map(|_| Glob::new(&key).map(|g| g.compile_matcher())).transpose()?;
+        let store = store_for(&parsed)?;
+        let prefix = (!prefix.is_empty()).then(|| ObjectPath::from(prefix));
+        let objects: Vec<_> = RUNTIME
+            .block_on(store.list(prefix.as_ref()).try_collect::<Vec<_>>())
+            .with_context(|| format!("Listing {}", url.display()))?;
+        let base = &parsed[..url::Position::BeforePath];
+        let mut out: Vec<PathBuf> = objects.into_iter()
+            .map(|o| o.location.to_string())
+            .filter(|k| matcher.as_ref().is_none_or(|m| m.is_match(k)))
+            .map(|k| PathBuf::from(format!("{base}/{k}")))
+            .collect();
+        out.sort();
+        Ok(out)
+    }
+
+    /// Stream one object's bytes; chunks are fetched as the reader asks for them
+    pub fn open(url: &Path) -> Result<Box<dyn Read>> {
+        let (parsed, key) = parse(url)?;
+        let store = store_for(&parsed)?;
+        let result = RUNTIME.block_on(store.get(&ObjectPath::from(key))).with_context(|| format!("Fetching {}", url.display()))?;
+        Ok(Box::new(ObjectReader { stream: result.into_stream(), chunk: Bytes::new() }))
+    }
+
+    struct ObjectReader {
+        stream: BoxStream<'static, object_store::Result<Bytes>>,
+        chunk: Bytes,
+    }
+
+    impl Read for ObjectReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            while self.chunk.is_empty() {
+                match RUNTIME.block_on(self.stream.next()) {
+                    Some(Ok(bytes)) => self.chunk = bytes,
+                    Some(Err(e)) => return Err(std::io::Error::other(e)),
+                    None => return Ok(0),
+                }
+            }
+            let n = buf.len().min(self.chunk.len());
+            buf[..n].copy_from_slice(&self.chunk.split_to(n));
+            Ok(n)
+        }
+    }
+}
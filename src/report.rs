@@ -1,48 +1,1065 @@
-use crate::analyze::{Analyzer, Filters};
 use crate::model::Level;
-use chrono::NaiveDateTime;
+use crate::parse::{LogParser, RECORD_LINE_SEP};
+use crate::analyze::{lossy_lines, message_template, Analyzer, Granularity, GroupStats, MalformedLine, MessageExample, QuantileSketch, SkippedInput, TimePrecision, DEFAULT_MALFORMED_SAMPLES, DEFAULT_MAX_LINE_LEN};
+use chrono::{Datelike, Duration, NaiveDateTime, Timelike};
+use anyhow::Context;
+use schemars::JsonSchema;
 use serde::{Serialize, Deserialize};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
 
+pub mod markdown;
+pub mod summary;
+pub mod term;
 
-#[derive(Serialize, Deserialize)]
+pub use summary::Summary;
+
+/// Version of the [`JsonSummary`] layout. Minor bumps only add fields, so readers of any 1.x
+/// summary keep working (missing fields take defaults, unknown ones are ignored); renaming,
+/// removing or retyping a field bumps the major version.
+pub const SCHEMA_VERSION: &str = "1.17";
+
+/// JSON Schema of [`JsonSummary`] (`--print-schema`)
+pub fn json_schema() -> serde_json::Value {
+    let mut schema = serde_json::to_value(schemars::schema_for!(JsonSummary)).expect("schema serializes");
+    schema["x-schema-version"] = SCHEMA_VERSION.into();
+    schema
+}
+
+fn major(version: &str) -> &str { version.split('.').next().unwrap_or_default() }
+
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
 pub struct Counts { pub info: u64, pub warning: u64, pub error: u64 }
 
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
 pub struct JsonSummary {
+/// `MAJOR.MINOR` layout version, see [`SCHEMA_VERSION`]
+pub schema_version: String,
+/// present when only a sample of lines was analyzed; all other counts are raw sample counts
+pub sampling: Option<SamplingInfo>,
 pub total_entries: u64,
 pub counts: Counts,
 pub malformed_lines: u64,
+/// one-number verdict from weighted error, warning and malformed rates; None without lines (since 1.5)
+pub health: Option<Health>,
+/// lines skipped by the pre-filter without parsing (malformed ones among them aren't counted)
+pub prefiltered_lines: u64,
+/// first unparsed lines, with source and line number
+pub malformed_samples: Vec<MalformedLine>,
+/// lines cut to `--max-line-len` bytes before parsing (since 1.10)
+pub truncated_lines: u64,
+/// entries restored from suppression notices such as `message repeated 5 times`, included in the counts (since 1.16)
+pub repeats_restored: u64,
+/// inputs left out of the analysis, e.g. binary files caught by `*.log` (since 1.10)
+pub skipped_inputs: Vec<SkippedInput>,
+/// lines matched per built-in pattern of the default format, and lines matching none (since 1.12)
+pub pattern_stats: Option<PatternStats>,
 pub first_log: Option<String>,
 pub last_log: Option<String>,
+/// first/last occurrence per level (`info`, `warning`, `error`), for levels that occur
+pub level_spans: BTreeMap<String, LevelSpan>,
+/// seconds from the first entry to the first ERROR
+pub time_to_first_error_secs: Option<i64>,
 pub common_errors: Vec<(String, u64)>,
+/// more distinct error messages occurred than `--max-error-messages`; counts are upper bounds
+pub common_errors_approximate: bool,
+/// first occurrences (raw line, timestamp, source) of each `common_errors` message (since 1.1)
+pub error_examples: BTreeMap<String, Vec<MessageExample>>,
+/// records around the first example of each `common_errors` message, with `--error-context` (since 1.7)
+pub error_context: BTreeMap<String, ErrorContext>,
+/// entries per (previous level, level) of consecutive entries of an input, e.g. `info` -> `error` (since 1.8)
+pub transitions: BTreeMap<String, BTreeMap<String, u64>>,
+/// messages of the non-error entries directly preceding an error, most frequent first (since 1.8)
+pub error_precursors: Vec<(String, u64)>,
+/// most frequent error templates with first/last occurrence and errors per `--top-errors-by` period (since 1.9)
+pub error_history: Vec<ErrorHistory>,
+/// every error template with its count, most frequent first (up to 1000), e.g. for a later `--baseline` (since 1.14)
+pub error_templates: Vec<(String, u64)>,
+/// error templates new, gone or grown relative to the `--baseline` summary (since 1.14)
+pub baseline: Option<BaselineDiff>,
+/// most frequent message templates of any level, with `--noisiest` (since 1.11)
+pub noisiest_messages: Vec<NoisyMessage>,
+/// loggers (Log4j/Logback layouts) ranked by error count
+pub top_loggers: Vec<(String, u64)>,
+/// exception types (`exception` field, e.g. Python tracebacks) ranked by entry count
+pub top_exceptions: Vec<(String, u64)>,
 /// timeline buckets in RFC3339-like naive format for portability
 pub timeline: Vec<(String, u64)>,
+/// entries mentioning each `--track` keyword over the same buckets as `timeline` (since 1.17)
+pub tracked_keywords: Vec<TrackedKeyword>,
+/// runs of consecutive error-heavy buckets
+pub incidents: Vec<Incident>,
+/// entries suggesting the process died (a crash indicator such as `panicked at`, or an error ending its input), in time order (since 1.13)
+pub probable_crashes: Vec<ProbableCrash>,
+/// 5 minutes with the most errors (minute-aligned, independent of the granularity; since 1.2)
+pub worst_5m_window: Option<ErrorWindow>,
+/// hour with the most errors (minute-aligned; since 1.2)
+pub worst_1h_window: Option<ErrorWindow>,
+/// per `--group-by` field, its values ordered by entry count
+pub groups: BTreeMap<String, Vec<GroupSummary>>,
+/// per-host counts, when the parser extracts a host (`host`, `hostname`, `kubernetes.host`; since 1.3)
+pub hosts: Option<HostsSummary>,
+/// distribution of the `--hist-out` value
+pub histogram: Option<HistogramStats>,
+/// single-pass percentiles of message length, inter-arrival time and `--quantiles` fields (since 1.4)
+pub quantiles: Option<Quantiles>,
+/// GC pause percentiles and heap sizes (`--format jvm`) and OutOfMemoryError times; None without either (since 1.15)
+pub jvm: Option<JvmSummary>,
+/// most common values of each `--top-values` field
+pub top_values: BTreeMap<String, Vec<(String, u64)>>,
+/// error trend, busiest periods and week-over-week change; None without timestamps
+pub trends: Option<Trends>,
+/// entries and errors per hour of day, `00` to `23`; empty without timestamps (since 1.6)
+pub by_hour_of_day: Vec<PeriodCounts>,
+/// entries and errors per weekday, `Mon` to `Sun`; empty without timestamps (since 1.6)
+pub by_weekday: Vec<PeriodCounts>,
+}
+
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct Health {
+    /// 0-100: 100 minus `weighted_issues_per_1k`, floored at 0
+    pub score: f64,
+    /// A (score >= 90), B (>= 80), C (>= 70), D (>= 60) or F
+    pub grade: String,
+    /// weighted errors, warnings and malformed lines per 1000 lines (entries plus malformed lines)
+    pub weighted_issues_per_1k: f64,
+    pub weights: HealthWeights,
+}
+
+
+/// Per-line weights of the health score (`[health]` in the config file).
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct HealthWeights {
+    pub error: f64,
+    pub warning: f64,
+    pub malformed: f64,
+}
+
+impl Default for HealthWeights {
+    fn default() -> Self { Self { error: 1.0, warning: 0.2, malformed: 0.1 } }
+}
+
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct LevelSpan {
+    pub first: String,
+    pub last: String,
+}
+
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct GroupSummary {
+    pub value: String,
+    pub total_entries: u64,
+    pub counts: Counts,
+    /// errors / total entries in this group
+    pub error_rate: f64,
+    pub top_errors: Vec<(String, u64)>,
+}
+
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct HostsSummary {
+    /// hosts ordered by entry count (`value` is the host name)
+    pub hosts: Vec<GroupSummary>,
+    /// host with the most entries
+    pub noisiest: String,
+    /// mean of the per-host error rates
+    pub mean_error_rate: f64,
+    /// hosts whose error rate is furthest from the mean, most deviating first
+    pub outliers: Vec<HostOutlier>,
+}
+
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct HostOutlier {
+    pub host: String,
+    pub error_rate: f64,
+    /// `error_rate - mean_error_rate`
+    pub deviation: f64,
+    /// deviation in standard deviations of the per-host error rates
+    pub z_score: f64,
+}
+
+
+/// Error templates compared with those of a baseline summary (`--baseline`).
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct BaselineDiff {
+    /// the baseline summary file
+    pub source: String,
+    /// templates absent from the baseline, with their count, most frequent first
+    pub new: Vec<(String, u64)>,
+    /// baseline templates that no longer occur, with their baseline count
+    pub gone: Vec<(String, u64)>,
+    /// templates whose share of all entries grew by more than `threshold_pct`, largest growth first
+    pub regressed: Vec<Regression>,
+    pub threshold_pct: f64,
+}
+
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct Regression {
+    pub template: String,
+    pub baseline: u64,
+    pub current: u64,
+    /// growth of the template's share of all entries, in percent
+    pub change_pct: f64,
+}
+
+
+/// How often each alternative pattern of the parser matched.
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct PatternStats {
+    /// pattern name -> lines it matched, patterns that never matched included
+    pub matches: BTreeMap<String, u64>,
+    /// lines no pattern matched
+    pub unmatched: u64,
+}
+
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct SamplingInfo {
+    /// expected fraction of lines analyzed
+    pub rate: f64,
+    pub lines_read: u64,
+    pub lines_sampled: u64,
+    /// counts scaled up by 1/rate
+    pub estimated_total_entries: u64,
+    pub estimated_counts: Counts,
+}
+
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct HistogramStats {
+    /// field name or `message length`
+    pub source: String,
+    pub count: u64,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub p50: f64,
+    pub p95: f64,
+}
+
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct Quantiles {
+    /// bound on the relative error of each percentile (min, max and mean are exact)
+    pub relative_accuracy: f64,
+    /// message length in characters
+    pub message_length: Option<QuantileStats>,
+    /// seconds between consecutive entries of an input (out-of-order entries are skipped)
+    pub inter_arrival_secs: Option<QuantileStats>,
+    /// per `--quantiles` field
+    pub fields: BTreeMap<String, QuantileStats>,
+}
+
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct QuantileStats {
+    pub count: u64,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub p50: f64,
+    pub p90: f64,
+    pub p95: f64,
+    pub p99: f64,
+    pub p999: f64,
+}
+
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct JvmSummary {
+    /// entries with a `gc_pause_ms` field
+    pub gc_pauses: u64,
+    /// pauses of full collections (`Full GC`, `Pause Full`)
+    pub full_gcs: u64,
+    /// pause percentiles in milliseconds
+    pub pause_ms: Option<QuantileStats>,
+    pub total_pause_ms: f64,
+    /// share of the time from the first to the last entry spent in GC pauses
+    pub pause_time_pct: Option<f64>,
+    /// largest heap occupancy left after a collection, in KiB
+    pub heap_after_max_kb: Option<u64>,
+    /// largest heap size, in KiB
+    pub heap_total_max_kb: Option<u64>,
+    /// timestamps of the `java.lang.OutOfMemoryError` entries (the first 100)
+    pub oom_events: Vec<String>,
+}
+
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct Trends {
+    /// least-squares slope of errors per bucket, in errors per bucket step (empty buckets count as 0)
+    pub error_slope: f64,
+    /// hour with the most entries and its count; None when buckets are coarser than an hour
+    pub busiest_hour: Option<(String, u64)>,
+    /// day with the most entries and its count; None when buckets are coarser than a day
+    pub busiest_day: Option<(String, u64)>,
+    /// per calendar week (Monday start), only when the range spans more than one week
+    pub weeks: Vec<WeekTrend>,
+}
+
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ErrorContext {
+    pub source: String,
+    /// 1-based record number of the error
+    pub line_no: u64,
+    /// Records just before and after it, in file order
+    pub before: Vec<String>,
+    pub after: Vec<String>,
+}
+
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct NoisyMessage {
+    /// message with numbers, ids and addresses replaced by `<*>`
+    pub template: String,
+    pub entries: u64,
+    /// percent of all entries
+    pub share_pct: f64,
+}
+
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct PeriodCounts {
+    /// `03` for an hour of day, `Wed` for a weekday
+    pub period: String,
+    pub entries: u64,
+    pub errors: u64,
+}
+
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct WeekTrend {
+    pub week_start: String,
+    pub entries: u64,
+    pub errors: u64,
+    /// percentage change against the previous week; None for the first week or after a week with none
+    pub entries_change_pct: Option<f64>,
+    pub errors_change_pct: Option<f64>,
+}
+
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ErrorHistory {
+    /// error message with numbers, ids and addresses replaced by `<*>`
+    pub template: String,
+    pub count: u64,
+    pub first_seen: String,
+    pub last_seen: String,
+    /// period start -> errors, in time order, for periods with errors
+    pub per_period: Vec<(String, u64)>,
+    /// true when the template never occurs outside `--from`/`--to`; None without a time range
+    pub new_in_range: Option<bool>,
+}
+
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct TrackedKeyword {
+    pub keyword: String,
+    pub total: u64,
+    /// first bucket mentioning it; None without mentions
+    pub first_seen: Option<String>,
+    /// mentions per `timeline` bucket, zeros included
+    pub timeline: Vec<(String, u64)>,
+}
+
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct Incident {
+    pub start: String,
+    /// end of the last bucket in the run (exclusive)
+    pub end: String,
+    pub total_errors: u64,
+    pub top_template: String,
+    /// highest error count seen in a single bucket
+    pub peak_rate: u64,
+}
+
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ProbableCrash {
+    pub ts: String,
+    pub source: String,
+    pub line_no: u64,
+    /// the crash indicator found (`panicked at`, `Segmentation fault`, `OutOfMemoryError`, `core dumped`) or `last entry is an error`
+    pub indicator: String,
+    pub message: String,
+    /// seconds until the next entry of the same input; null when nothing followed
+    pub silence_after_secs: Option<i64>,
+}
+
+
+/// The rolling window of a given length with the most errors.
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ErrorWindow {
+    pub start: String,
+    /// exclusive
+    pub end: String,
+    pub errors: u64,
+    pub entries: u64,
+    /// errors / entries within the window
+    pub error_rate: f64,
+}
+
+
+/// Serializations of [`JsonSummary`]; all share its serde derives.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SummaryFormat { Json, Yaml, Toml }
+
+impl SummaryFormat {
+    pub fn render(self, summary: &JsonSummary) -> anyhow::Result<String> {
+        Ok(match self {
+            SummaryFormat::Json => serde_json::to_string_pretty(summary)?,
+            SummaryFormat::Yaml => serde_yaml::to_string(summary)?,
+            SummaryFormat::Toml => toml::to_string_pretty(summary)?,
+        })
+    }
+
+    /// Read a summary written by this or another minor version of the schema.
+    pub fn parse(self, text: &str) -> anyhow::Result<JsonSummary> {
+        let summary: JsonSummary = match self {
+            SummaryFormat::Json => serde_json::from_str(text)?,
+            SummaryFormat::Yaml => serde_yaml::from_str(text)?,
+            SummaryFormat::Toml => toml::from_str(text)?,
+        };
+        // Summaries from before versioning have no schema_version; they are 1.x
+        let theirs = if summary.schema_version.is_empty() { "1" } else { major(&summary.schema_version) };
+        if theirs != major(SCHEMA_VERSION) {
+            anyhow::bail!("Summary schema version {} is incompatible with {SCHEMA_VERSION}", summary.schema_version);
+        }
+        Ok(summary)
+    }
 }
 
 
-pub fn build_summary(an: &Analyzer, _filters: &Filters) -> JsonSummary {
+/// Knobs for summary building that don't affect aggregation.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ReportOptions {
+    /// Errors per bucket for a bucket to count towards an incident (default: mean errors per bucket, at least 1)
+    pub incident_threshold: Option<u64>,
+    pub health: HealthWeights,
+    /// Message templates in `noisiest_messages` (needs [`Analyzer::track_noise`])
+    pub noisiest: usize,
+    /// Messages in `common_errors` (default: [`DEFAULT_TOP_ERRORS`])
+    pub top_errors: Option<usize>,
+}
+
+pub const DEFAULT_TOP_ERRORS: usize = 10;
+
+
+/// [`summarize`] as its serialization view
+pub fn build_summary(an: &Analyzer, opts: &ReportOptions) -> JsonSummary { JsonSummary::from(&summarize(an, opts)) }
+
+
+pub fn summarize(an: &Analyzer, opts: &ReportOptions) -> Summary {
 let total = an.info + an.warning + an.error;
 
 
-// Sort error messages by freq desc, then message asc, take top 10
-let mut errs: Vec<(String, u64)> = an.error_messages.iter().map(|(k,v)| (k.clone(), *v)).collect();
-errs.sort_by(|a,b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
-errs.truncate(10);
+let errs = an.error_messages.top(opts.top_errors.unwrap_or(DEFAULT_TOP_ERRORS));
 
 
-let timeline: Vec<(String, u64)> = an.timeline.iter()
-.map(|(k,v)| (format!("{}", k.format("%Y-%m-%d %H:%M:%S")), *v)).collect();
+let sampling = an.sampling.map(|s| {
+    let scale = |n: u64| (n as f64 / s.rate()).round() as u64;
+    SamplingInfo {
+        rate: s.rate(),
+        lines_read: an.lines_read,
+        lines_sampled: an.lines_sampled,
+        estimated_total_entries: scale(total),
+        estimated_counts: Counts { info: scale(an.info), warning: scale(an.warning), error: scale(an.error) },
+    }
+});
 
 
-JsonSummary {
+Summary {
+sampling,
 total_entries: total,
-counts: Counts { info: an.info, warning: an.warning, error: an.error },
+counts: BTreeMap::from([(Level::Info, an.info), (Level::Warning, an.warning), (Level::Error, an.error)]),
 malformed_lines: an.malformed_lines,
-first_log: an.first.map(|d| d.format("%Y-%m-%d %H:%M:%S").to_string()),
-last_log: an.last.map(|d| d.format("%Y-%m-%d %H:%M:%S").to_string()),
+health: health(an, opts.health),
+prefiltered_lines: an.lines_prefiltered,
+malformed_samples: an.malformed.iter().take(DEFAULT_MALFORMED_SAMPLES).cloned().collect(),
+truncated_lines: an.lines_truncated,
+repeats_restored: an.repeats_restored,
+skipped_inputs: an.skipped_inputs.clone(),
+pattern_stats: (!an.pattern_matches.is_empty()).then(|| PatternStats { matches: an.pattern_matches.clone(), unmatched: an.pattern_misses }),
+first_log: an.first,
+last_log: an.last,
+level_spans: an.level_spans.clone(),
+time_to_first_error: an.first.zip(an.level_spans.get(&Level::Error)).map(|(start, (err, _))| *err - start),
+error_examples: errs.iter()
+    .map(|(msg, _)| (msg.clone(), an.error_messages.examples(msg).to_vec()))
+    .filter(|(_, examples)| !examples.is_empty())
+    .collect(),
+// Needs the source files, so it's filled in by attach_error_context
+error_context: BTreeMap::new(),
 common_errors: errs,
-timeline,
+common_errors_approximate: an.error_messages.is_approximate(),
+transitions: an.transitions.clone(),
+error_precursors: an.error_precursors.top(10),
+error_history: error_history(an, 10),
+error_history_period: an.error_history_period,
+error_templates: error_templates(an),
+// Needs the baseline file, so it's filled in by compare_baseline
+baseline: None,
+noisiest_messages: an.message_templates.top(opts.noisiest).into_iter()
+    .map(|(template, entries)| NoisyMessage { template, entries, share_pct: entries as f64 * 100.0 / total.max(1) as f64 })
+    .collect(),
+top_loggers: top_messages(&an.logger_errors, 10),
+top_exceptions: top_messages(&an.exceptions, 10),
+granularity: an.granularity,
+// Sub-second buckets would collide when written to the second
+ts_precision: match (an.granularity, an.ts_precision) {
+    (Granularity::Millis(_), TimePrecision::Seconds) => TimePrecision::Millis,
+    (_, p) => p,
+},
+timeline: an.timeline.iter().map(|(k, v)| (*k, *v)).collect(),
+timeline_levels: an.timeline.iter().map(|(b, n)| {
+    let error = an.error_buckets.get(b).map_or(0, |t| t.values().sum());
+    let warning = an.warning_buckets.get(b).copied().unwrap_or(0);
+    Counts { info: n - error - warning, warning, error }
+}).collect(),
+tracked_keywords: an.tracked_keywords.iter().map(|k| {
+    let buckets = an.keyword_buckets.get(k);
+    summary::TrackedKeyword {
+        keyword: k.clone(),
+        total: buckets.map_or(0, |b| b.values().sum()),
+        first_seen: buckets.and_then(|b| b.keys().next().copied()),
+        counts: an.timeline.keys().map(|t| buckets.and_then(|b| b.get(t)).copied().unwrap_or(0)).collect(),
+    }
+}).collect(),
+incidents: detect_incidents(an, opts),
+probable_crashes: probable_crashes(an),
+worst_5m_window: worst_window(an, Duration::minutes(5)),
+worst_1h_window: worst_window(an, Duration::hours(1)),
+groups: an.groups.iter().map(|(field, values)| (field.clone(), group_summaries(values))).collect(),
+hosts: hosts_summary(an),
+histogram: an.histogram.as_ref().and_then(|h| histogram_stats(h.label(), &an.hist_values)),
+quantiles: quantiles(an),
+jvm: jvm_summary(an),
+top_values: an.field_values.iter().map(|(field, values)| (field.clone(), top_messages(values, 10))).collect(),
+trends: trends(an),
+by_hour_of_day: cyclic_counts(an, 24, |ts| ts.hour() as usize, |h| format!("{h:02}")),
+by_weekday: cyclic_counts(an, 7, |ts| ts.weekday().num_days_from_monday() as usize, |d| WEEKDAYS[d].to_string()),
+}
+}
+
+
+/// `info`, `warning` or `error`
+fn level_key(level: Level) -> String { format!("{level:?}").to_lowercase() }
+
+impl From<&Summary> for JsonSummary {
+    fn from(s: &Summary) -> Self {
+        let timestamp = |ts: NaiveDateTime| s.ts_precision.format(ts);
+        let window = |w: &summary::ErrorWindow| ErrorWindow {
+            start: timestamp(w.start), end: timestamp(w.end), errors: w.errors, entries: w.entries, error_rate: w.error_rate,
+        };
+        JsonSummary {
+            schema_version: SCHEMA_VERSION.to_string(),
+            sampling: s.sampling.clone(),
+            total_entries: s.total_entries,
+            counts: Counts { info: s.count(Level::Info), warning: s.count(Level::Warning), error: s.count(Level::Error) },
+            malformed_lines: s.malformed_lines,
+            health: s.health.clone(),
+            prefiltered_lines: s.prefiltered_lines,
+            malformed_samples: s.malformed_samples.clone(),
+            truncated_lines: s.truncated_lines,
+            repeats_restored: s.repeats_restored,
+            skipped_inputs: s.skipped_inputs.clone(),
+            pattern_stats: s.pattern_stats.clone(),
+            first_log: s.first_log.map(timestamp),
+            last_log: s.last_log.map(timestamp),
+            level_spans: s.level_spans.iter()
+                .map(|(level, (first, last))| (level_key(*level), LevelSpan { first: timestamp(*first), last: timestamp(*last) }))
+                .collect(),
+            time_to_first_error_secs: s.time_to_first_error.map(|d| d.num_seconds()),
+            common_errors: s.common_errors.clone(),
+            common_errors_approximate: s.common_errors_approximate,
+            error_examples: s.error_examples.clone(),
+            error_context: s.error_context.clone(),
+            transitions: s.transitions.iter()
+                .map(|(prev, next)| (level_key(*prev), next.iter().map(|(l, n)| (level_key(*l), *n)).collect()))
+                .collect(),
+            error_precursors: s.error_precursors.clone(),
+            error_history: s.error_history.iter().map(|h| ErrorHistory {
+                template: h.template.clone(),
+                count: h.count,
+                first_seen: timestamp(h.first_seen),
+                last_seen: timestamp(h.last_seen),
+                per_period: h.per_period.iter().map(|(p, n)| (timestamp(*p), *n)).collect(),
+                new_in_range: h.new_in_range,
+            }).collect(),
+            error_templates: s.error_templates.clone(),
+            baseline: s.baseline.clone(),
+            noisiest_messages: s.noisiest_messages.clone(),
+            top_loggers: s.top_loggers.clone(),
+            top_exceptions: s.top_exceptions.clone(),
+            timeline: s.timeline.iter().map(|(b, n)| (timestamp(*b), *n)).collect(),
+            tracked_keywords: s.tracked_keywords.iter().map(|k| TrackedKeyword {
+                keyword: k.keyword.clone(),
+                total: k.total,
+                first_seen: k.first_seen.map(timestamp),
+                timeline: s.timeline.iter().zip(&k.counts).map(|((b, _), n)| (timestamp(*b), *n)).collect(),
+            }).collect(),
+            incidents: s.incidents.iter().map(|inc| Incident {
+                start: timestamp(inc.start),
+                end: timestamp(inc.end),
+                total_errors: inc.total_errors,
+                top_template: inc.top_template.clone(),
+                peak_rate: inc.peak_rate,
+            }).collect(),
+            probable_crashes: s.probable_crashes.iter().map(|c| ProbableCrash {
+                ts: timestamp(c.ts),
+                source: c.source.clone(),
+                line_no: c.line_no,
+                indicator: c.indicator.clone(),
+                message: c.message.clone(),
+                silence_after_secs: c.silence_after_secs,
+            }).collect(),
+            worst_5m_window: s.worst_5m_window.as_ref().map(window),
+            worst_1h_window: s.worst_1h_window.as_ref().map(window),
+            groups: s.groups.clone(),
+            hosts: s.hosts.clone(),
+            histogram: s.histogram.clone(),
+            quantiles: s.quantiles.clone(),
+            jvm: s.jvm.as_ref().map(|j| JvmSummary {
+                gc_pauses: j.gc_pauses,
+                full_gcs: j.full_gcs,
+                pause_ms: j.pause_ms.clone(),
+                total_pause_ms: j.total_pause_ms,
+                pause_time_pct: j.pause_time_pct,
+                heap_after_max_kb: j.heap_after_max_kb,
+                heap_total_max_kb: j.heap_total_max_kb,
+                oom_events: j.oom_events.iter().map(|ts| timestamp(*ts)).collect(),
+            }),
+            top_values: s.top_values.clone(),
+            trends: s.trends.as_ref().map(|t| Trends {
+                error_slope: t.error_slope,
+                busiest_hour: t.busiest_hour.map(|(h, n)| (h.format("%Y-%m-%d %H:00").to_string(), n)),
+                busiest_day: t.busiest_day.map(|(d, n)| (d.format("%Y-%m-%d").to_string(), n)),
+                weeks: t.weeks.iter().map(|w| WeekTrend {
+                    week_start: w.week_start.format("%Y-%m-%d").to_string(),
+                    entries: w.entries,
+                    errors: w.errors,
+                    entries_change_pct: w.entries_change_pct,
+                    errors_change_pct: w.errors_change_pct,
+                }).collect(),
+            }),
+            by_hour_of_day: s.by_hour_of_day.clone(),
+            by_weekday: s.by_weekday.clone(),
+        }
+    }
+}
+
+
+/// The `len` window (starting on a minute) with the most errors, earliest on ties; None without errors
+fn worst_window(an: &Analyzer, len: Duration) -> Option<summary::ErrorWindow> {
+    let minutes: Vec<_> = an.per_minute.iter().collect();
+    let (mut best, mut end, mut entries, mut errors) = (None, 0, 0, 0);
+    // The best window can always start on a non-empty minute; slide its end along
+    for (start, &(&from, _)) in minutes.iter().enumerate() {
+        while end < minutes.len() && *minutes[end].0 < from + len {
+            entries += minutes[end].1.0;
+            errors += minutes[end].1.1;
+            end += 1;
+        }
+        if errors > 0 && best.is_none_or(|(_, _, most)| errors > most) { best = Some((from, entries, errors)); }
+        entries -= minutes[start].1.0;
+        errors -= minutes[start].1.1;
+    }
+    best.map(|(from, entries, errors)| summary::ErrorWindow {
+        start: from,
+        end: from + len,
+        errors,
+        entries,
+        error_rate: errors as f64 / entries as f64,
+    })
+}
+
+
+/// Most records [`attach_error_context`] keeps on each side of an error
+pub const MAX_ERROR_CONTEXT: usize = 100;
+
+/// Fill `summary.error_context` with up to `lines` records (at most [`MAX_ERROR_CONTEXT`]) before
+/// and after the first example of each top error message, re-read from its source with `parser`'s
+/// record framing. Sources that can no longer be opened (e.g. stdin) are skipped.
+pub fn attach_error_context<P: LogParser + ?Sized>(summary: &mut Summary, parser: &P, lines: usize) -> anyhow::Result<()> {
+    let lines = lines.min(MAX_ERROR_CONTEXT) as u64;
+    // source -> (record number, message)
+    let mut wanted: BTreeMap<&str, Vec<(u64, &str)>> = BTreeMap::new();
+    for (msg, _) in &summary.common_errors {
+        let Some(ex) = summary.error_examples.get(msg).and_then(|ex| ex.first()) else { continue };
+        wanted.entry(ex.source.as_str()).or_default().push((ex.line_no, msg.as_str()));
+    }
+    let mut found = BTreeMap::new();
+    for (source, errors) in wanted {
+        let Ok(reader) = crate::input::open(Path::new(source)) else { continue };
+        let last = errors.iter().map(|(n, _)| n + lines).max().unwrap_or(0);
+        let near = |n: u64| errors.iter().any(|(at, _)| n.abs_diff(*at) <= lines);
+        let mut records = BTreeMap::new();
+        for (idx, line) in lossy_lines(parser.decode(reader), DEFAULT_MAX_LINE_LEN).enumerate() {
+            let n = idx as u64 + 1;
+            if n > last { break; }
+            let line = line.with_context(|| format!("Failed reading {source}"))?;
+            if near(n) { records.insert(n, line.replace(RECORD_LINE_SEP, "\n")); }
+        }
+        for (at, msg) in errors {
+            let text = |range: std::ops::Range<u64>| records.range(range).map(|(_, r)| r.clone()).collect();
+            found.insert(msg.to_string(), ErrorContext {
+                source: source.to_string(),
+                line_no: at,
+                before: text(at.saturating_sub(lines).max(1)..at),
+                after: text(at + 1..at + lines + 1),
+            });
+        }
+    }
+    summary.error_context = found;
+    Ok(())
+}
+
+
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// Per-minute counts folded into `n` periods (hours of day, weekdays); empty without entries
+fn cyclic_counts(an: &Analyzer, n: usize, period: fn(&NaiveDateTime) -> usize, label: fn(usize) -> String) -> Vec<PeriodCounts> {
+    if an.per_minute.is_empty() { return Vec::new(); }
+    let mut out: Vec<PeriodCounts> = (0..n).map(|i| PeriodCounts { period: label(i), ..Default::default() }).collect();
+    for (minute, (entries, errors)) in &an.per_minute {
+        let p = &mut out[period(minute)];
+        p.entries += entries;
+        p.errors += errors;
+    }
+    out
+}
+
+
+fn trends(an: &Analyzer) -> Option<summary::Trends> {
+    let (&first, _) = an.timeline.first_key_value()?;
+    let (&last, _) = an.timeline.last_key_value()?;
+    let errors_at = |b: &NaiveDateTime| an.error_buckets.get(b).map_or(0, |t| t.values().sum::<u64>());
+
+    // Regress over every bucket step between first and last, not just the non-empty ones
+    let (mut n, mut sx, mut sy, mut sxx, mut sxy) = (0f64, 0f64, 0f64, 0f64, 0f64);
+    let mut b = first;
+    while b <= last {
+        let (x, y) = (n, errors_at(&b) as f64);
+        n += 1.0;
+        sx += x; sy += y; sxx += x * x; sxy += x * y;
+        b = an.granularity.next_bucket(b);
+    }
+    let denom = n * sxx - sx * sx;
+    let error_slope = if denom == 0.0 { 0.0 } else { (n * sxy - sx * sy) / denom };
+
+    let step = (an.granularity.next_bucket(first) - first).num_milliseconds();
+    let fits = |period: i64| an.granularity != Granularity::Month && period * 1000 % step == 0;
+    let busiest = |key: fn(NaiveDateTime) -> NaiveDateTime| {
+        let mut per: BTreeMap<NaiveDateTime, u64> = BTreeMap::new();
+        for (b, v) in &an.timeline { *per.entry(key(*b)).or_default() += v; }
+        per.into_iter()
+            .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)))
+    };
+    let busiest_hour = fits(3600).then(|| busiest(|b| Granularity::Hour.bucket(b))).flatten();
+    let busiest_day = fits(86400).then(|| busiest(|b| Granularity::Day.bucket(b))).flatten().map(|(d, n)| (d.date(), n));
+
+    let mut per_week: BTreeMap<NaiveDateTime, (u64, u64)> = BTreeMap::new();
+    if fits(86400) {
+        for (b, v) in &an.timeline {
+            let w = per_week.entry(Granularity::Week.bucket(*b)).or_default();
+            w.0 += v;
+            w.1 += errors_at(b);
+        }
+    }
+    let change = |prev: u64, cur: u64| (prev > 0).then(|| (cur as f64 - prev as f64) / prev as f64 * 100.0);
+    let mut weeks = Vec::new();
+    if per_week.len() > 1 {
+        let mut prev: Option<(NaiveDateTime, (u64, u64))> = None;
+        for (w, (entries, errors)) in per_week {
+            // Compare only with the directly preceding calendar week
+            let prev_counts = prev.filter(|(p, _)| Granularity::Week.next_bucket(*p) == w).map(|(_, c)| c);
+            weeks.push(summary::WeekTrend {
+                week_start: w.date(),
+                entries,
+                errors,
+                entries_change_pct: prev_counts.and_then(|(e, _)| change(e, entries)),
+                errors_change_pct: prev_counts.and_then(|(_, e)| change(e, errors)),
+            });
+            prev = Some((w, (entries, errors)));
+        }
+    }
+
+    Some(summary::Trends { error_slope, busiest_hour, busiest_day, weeks })
 }
+
+
+/// Count, range, mean and nearest-rank percentiles; None without values
+pub fn histogram_stats(source: &str, values: &[f64]) -> Option<HistogramStats> {
+    if values.is_empty() { return None; }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let pct = |p: f64| sorted[((p * sorted.len() as f64).ceil() as usize).clamp(1, sorted.len()) - 1];
+    Some(HistogramStats {
+        source: source.to_string(),
+        count: sorted.len() as u64,
+        min: sorted[0],
+        max: sorted[sorted.len() - 1],
+        mean: sorted.iter().sum::<f64>() / sorted.len() as f64,
+        p50: pct(0.50),
+        p95: pct(0.95),
+    })
+}
+
+
+fn health(an: &Analyzer, weights: HealthWeights) -> Option<Health> {
+    let lines = an.info + an.warning + an.error + an.malformed_lines;
+    if lines == 0 { return None; }
+    let weighted = an.error as f64 * weights.error + an.warning as f64 * weights.warning + an.malformed_lines as f64 * weights.malformed;
+    let per_1k = weighted * 1000.0 / lines as f64;
+    let score = (100.0 - per_1k).max(0.0);
+    let grade = match score { s if s >= 90.0 => "A", s if s >= 80.0 => "B", s if s >= 70.0 => "C", s if s >= 60.0 => "D", _ => "F" };
+    Some(Health { score, grade: grade.to_string(), weighted_issues_per_1k: per_1k, weights })
+}
+
+
+fn quantile_stats(sketch: &QuantileSketch) -> Option<QuantileStats> {
+    let q = |p: f64| sketch.quantile(p).unwrap_or_default();
+    Some(QuantileStats {
+        count: sketch.count,
+        min: sketch.min,
+        max: sketch.max,
+        mean: sketch.mean()?,
+        p50: q(0.50),
+        p90: q(0.90),
+        p95: q(0.95),
+        p99: q(0.99),
+        p999: q(0.999),
+    })
+}
+
+
+fn jvm_summary(an: &Analyzer) -> Option<summary::JvmSummary> {
+    let j = &an.jvm;
+    if j.pauses_ms.is_empty() && j.oom_events.is_empty() { return None; }
+    let span_ms = an.first.zip(an.last).map_or(0, |(first, last)| (last - first).num_milliseconds());
+    Some(summary::JvmSummary {
+        gc_pauses: j.pauses_ms.count,
+        full_gcs: j.full_gcs,
+        pause_ms: quantile_stats(&j.pauses_ms),
+        total_pause_ms: j.pauses_ms.sum,
+        pause_time_pct: (span_ms > 0 && !j.pauses_ms.is_empty()).then(|| j.pauses_ms.sum * 100.0 / span_ms as f64),
+        heap_after_max_kb: j.heap_after_max_kb,
+        heap_total_max_kb: j.heap_total_max_kb,
+        oom_events: j.oom_events.clone(),
+    })
+}
+
+fn quantiles(an: &Analyzer) -> Option<Quantiles> {
+    let message_length = quantile_stats(&an.message_lengths)?;
+    Some(Quantiles {
+        relative_accuracy: an.message_lengths.relative_accuracy,
+        message_length: Some(message_length),
+        inter_arrival_secs: quantile_stats(&an.inter_arrival),
+        fields: an.field_quantiles.iter().filter_map(|(field, s)| Some((field.clone(), quantile_stats(s)?))).collect(),
+    })
+}
+
+
+/// Error templates listed in a summary's `error_templates`
+pub const MAX_ERROR_TEMPLATES: usize = 1000;
+
+fn error_templates(an: &Analyzer) -> Vec<(String, u64)> {
+    let mut totals: HashMap<String, u64> = HashMap::new();
+    for templates in an.error_buckets.values() {
+        for (t, n) in templates { *totals.entry(t.clone()).or_default() += n; }
+    }
+    top_messages(&totals, MAX_ERROR_TEMPLATES)
+}
+
+/// Fill `summary.baseline` with the error templates that are new, gone or whose share of all
+/// entries grew by more than `threshold_pct` percent relative to `baseline`, read from `source`.
+/// Baselines from before schema 1.14 only list their top error messages, so fewer templates
+/// count as known.
+pub fn compare_baseline(summary: &mut Summary, baseline: &JsonSummary, source: &str, threshold_pct: f64) {
+    let known: HashMap<String, u64> = if baseline.error_templates.is_empty() {
+        let mut known = HashMap::new();
+        for (msg, n) in &baseline.common_errors { *known.entry(message_template(msg)).or_default() += n; }
+        known
+    } else {
+        baseline.error_templates.iter().cloned().collect()
+    };
+    let current: HashMap<&str, u64> = summary.error_templates.iter().map(|(t, n)| (t.as_str(), *n)).collect();
+    // Compare shares of all entries so a busier or quieter run doesn't look like a regression
+    let share = |n: u64, total: u64| n as f64 / total.max(1) as f64;
+    let mut regressed: Vec<Regression> = summary.error_templates.iter().filter_map(|(t, n)| {
+        let (n, before) = (*n, *known.get(t)?);
+        let change_pct = (share(n, summary.total_entries) / share(before, baseline.total_entries) - 1.0) * 100.0;
+        (n > before && change_pct > threshold_pct)
+            .then(|| Regression { template: t.clone(), baseline: before, current: n, change_pct })
+    }).collect();
+    regressed.sort_by(|a, b| b.change_pct.total_cmp(&a.change_pct).then_with(|| a.template.cmp(&b.template)));
+    let mut gone: Vec<(String, u64)> = known.iter().filter(|(t, _)| !current.contains_key(t.as_str())).map(|(t, n)| (t.clone(), *n)).collect();
+    gone.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    summary.baseline = Some(BaselineDiff {
+        source: source.to_string(),
+        new: summary.error_templates.iter().filter(|(t, _)| !known.contains_key(t)).cloned().collect(),
+        gone,
+        regressed,
+        threshold_pct,
+    });
+}
+
+/// Sort keys by freq desc, then message asc, take top `n`
+pub fn top_messages(messages: &HashMap<String, u64>, n: usize) -> Vec<(String, u64)> {
+    let mut out: Vec<(String, u64)> = messages.iter().map(|(k,v)| (k.clone(), *v)).collect();
+    out.sort_by(|a,b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    out.truncate(n);
+    out
+}
+
+
+fn group_summaries(values: &BTreeMap<String, GroupStats>) -> Vec<GroupSummary> {
+    let mut out: Vec<GroupSummary> = values.iter().map(|(value, st)| {
+        let total = st.info + st.warning + st.error;
+        GroupSummary {
+            value: value.clone(),
+            total_entries: total,
+            counts: Counts { info: st.info, warning: st.warning, error: st.error },
+            error_rate: if total == 0 { 0.0 } else { st.error as f64 / total as f64 },
+            top_errors: st.error_messages.top(3),
+        }
+    }).collect();
+    out.sort_by(|a, b| b.total_entries.cmp(&a.total_entries).then_with(|| a.value.cmp(&b.value)));
+    out
+}
+
+
+/// Hosts whose error rate deviates most from the mean, at most this many
+const HOST_OUTLIERS: usize = 5;
+
+fn hosts_summary(an: &Analyzer) -> Option<HostsSummary> {
+    let hosts = group_summaries(&an.hosts);
+    let noisiest = hosts.first()?.value.clone();
+    let n = hosts.len() as f64;
+    let mean = hosts.iter().map(|h| h.error_rate).sum::<f64>() / n;
+    let sd = (hosts.iter().map(|h| (h.error_rate - mean).powi(2)).sum::<f64>() / n).sqrt();
+    let mut outliers: Vec<HostOutlier> = hosts.iter()
+        .filter(|h| sd > 0.0 && h.error_rate != mean)
+        .map(|h| HostOutlier {
+            host: h.value.clone(),
+            error_rate: h.error_rate,
+            deviation: h.error_rate - mean,
+            z_score: (h.error_rate - mean) / sd,
+        })
+        .collect();
+    outliers.sort_by(|a, b| b.deviation.abs().total_cmp(&a.deviation.abs()).then_with(|| a.host.cmp(&b.host)));
+    outliers.truncate(HOST_OUTLIERS);
+    Some(HostsSummary { hosts, noisiest, mean_error_rate: mean, outliers })
+}
+
+
+/// The `n` most frequent templates of `an.error_history`, ties by template
+fn error_history(an: &Analyzer, n: usize) -> Vec<summary::ErrorHistory> {
+    let mut top: Vec<_> = an.error_history.iter().collect();
+    top.sort_by(|a, b| b.1.count.cmp(&a.1.count).then_with(|| a.0.cmp(b.0)));
+    let ranged = an.filters.has_time_range();
+    top.into_iter().take(n).map(|(template, h)| summary::ErrorHistory {
+        template: template.clone(),
+        count: h.count,
+        first_seen: h.first_seen,
+        last_seen: h.last_seen,
+        per_period: h.per_period.iter().map(|(p, c)| (*p, *c)).collect(),
+        new_in_range: ranged.then(|| !an.errors_outside_range.contains(template)),
+    }).collect()
+}
+
+
+/// Crash indicators, plus the inputs whose last entry is an error
+fn probable_crashes(an: &Analyzer) -> Vec<summary::ProbableCrash> {
+    let mut crashes: Vec<summary::ProbableCrash> = an.crash_signs.iter().map(|c| summary::ProbableCrash {
+        ts: c.ts,
+        source: c.source.clone(),
+        line_no: c.line_no,
+        indicator: c.indicator.clone(),
+        message: c.message.clone(),
+        silence_after_secs: c.silence_secs,
+    }).collect();
+    for (source, tail) in &an.input_tails {
+        if tail.level != Level::Error || crashes.iter().any(|c| c.source == *source && c.line_no == tail.line_no) { continue; }
+        crashes.push(summary::ProbableCrash {
+            ts: tail.ts,
+            source: source.clone(),
+            line_no: tail.line_no,
+            indicator: "last entry is an error".to_string(),
+            message: tail.message.clone(),
+            silence_after_secs: None,
+        });
+    }
+    crashes.sort_by(|a, b| (a.ts, &a.source, a.line_no).cmp(&(b.ts, &b.source, b.line_no)));
+    crashes
+}
+
+fn detect_incidents(an: &Analyzer, opts: &ReportOptions) -> Vec<summary::Incident> {
+    let errors_in = |templates: &HashMap<String, u64>| templates.values().sum::<u64>();
+    let threshold = opts.incident_threshold.unwrap_or_else(|| {
+        let total: u64 = an.error_buckets.values().map(errors_in).sum();
+        (total / an.timeline.len().max(1) as u64).max(1)
+    });
+
+    // Collect runs of heavy buckets, where each bucket directly follows the previous one
+    let mut runs: Vec<Vec<(NaiveDateTime, &HashMap<String, u64>)>> = Vec::new();
+    for (b, templates) in &an.error_buckets {
+        if errors_in(templates) < threshold { continue; }
+        match runs.last_mut() {
+            Some(run) if an.granularity.next_bucket(run.last().unwrap().0) == *b => run.push((*b, templates)),
+            _ => runs.push(vec![(*b, templates)]),
+        }
+    }
+
+    runs.into_iter().map(|run| {
+        let mut templates: HashMap<&str, u64> = HashMap::new();
+        for (_, t) in &run { for (k, v) in *t { *templates.entry(k.as_str()).or_default() += v; } }
+        let top_template = templates.iter()
+            .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+            .map(|(k, _)| k.to_string())
+            .unwrap_or_default();
+        let (first, last) = (run[0].0, run[run.len() - 1].0);
+        summary::Incident {
+            start: first,
+            end: an.granularity.next_bucket(last),
+            total_errors: templates.values().sum(),
+            top_template,
+            peak_rate: run.iter().map(|(_, t)| errors_in(t)).max().unwrap_or(0),
+        }
+    }).collect()
 }
\ No newline at end of file
@@ -0,0 +1,84 @@
+//! Markdown rendering of a [`Summary`], short enough to paste into a ticket or chat.
+
+use super::term::{human_duration, percent, thousands};
+use super::{level_key, Summary};
+use crate::model::Level;
+use std::fmt::Write;
+
+/// Top errors, incidents and the like list at most this many rows
+const MAX_ROWS: usize = 10;
+
+/// Pipes and line breaks would end the table cell
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace(['\n', '\r', crate::parse::RECORD_LINE_SEP], " ")
+}
+
+pub fn render(summary: &Summary) -> String {
+    let timestamp = |ts| summary.ts_precision.format(ts);
+    let mut out = String::from("## Log summary\n\n");
+    let _ = writeln!(out, "| Level | Count | Share |\n|---|---:|---:|");
+    for level in [Level::Info, Level::Warning, Level::Error] {
+        let n = summary.count(level);
+        let _ = writeln!(out, "| {} | {} | {} |", level_key(level).to_uppercase(), thousands(n), percent(n, summary.total_entries));
+    }
+    let _ = writeln!(out, "| **Total** | **{}** | |\n", thousands(summary.total_entries));
+
+    if summary.malformed_lines > 0 { let _ = writeln!(out, "- Malformed lines: {}", thousands(summary.malformed_lines)); }
+    if summary.repeats_restored > 0 { let _ = writeln!(out, "- Restored from suppression notices: {}", thousands(summary.repeats_restored)); }
+    if let Some(h) = &summary.health { let _ = writeln!(out, "- Health: {} ({:.1}/100)", h.grade, h.score); }
+    if let (Some(first), Some(last)) = (summary.first_log, summary.last_log) {
+        let _ = writeln!(out, "- Time range: {} .. {}", timestamp(first), timestamp(last));
+    }
+    if let Some(d) = summary.time_to_first_error { let _ = writeln!(out, "- Time to first error: {}", human_duration(d.num_seconds())); }
+    if let Some(w) = &summary.worst_5m_window {
+        let _ = writeln!(out, "- Worst 5 minutes: {} errors of {} entries from {}", w.errors, w.entries, timestamp(w.start));
+    }
+
+    if !summary.common_errors.is_empty() {
+        let approx = if summary.common_errors_approximate { " (approximate)" } else { "" };
+        let _ = writeln!(out, "\n### Top errors{approx}\n\n| Count | Message |\n|---:|---|");
+        for (msg, n) in summary.common_errors.iter().take(MAX_ROWS) {
+            let _ = writeln!(out, "| {} | {} |", thousands(*n), cell(msg));
+        }
+    }
+    if !summary.top_exceptions.is_empty() {
+        let _ = writeln!(out, "\n### Top exception types\n\n| Count | Exception |\n|---:|---|");
+        for (name, n) in summary.top_exceptions.iter().take(MAX_ROWS) {
+            let _ = writeln!(out, "| {} | {} |", thousands(*n), cell(name));
+        }
+    }
+    if !summary.incidents.is_empty() {
+        let _ = writeln!(out, "\n### Incidents\n\n| Start | End | Errors | Peak | Top error |\n|---|---|---:|---:|---|");
+        for i in summary.incidents.iter().take(MAX_ROWS) {
+            let _ = writeln!(out, "| {} | {} | {} | {} | {} |", timestamp(i.start), timestamp(i.end), i.total_errors, i.peak_rate, cell(&i.top_template));
+        }
+    }
+    if !summary.tracked_keywords.is_empty() {
+        let _ = writeln!(out, "\n### Tracked keywords\n\n| Keyword | Entries | First seen |\n|---|---:|---|");
+        for k in &summary.tracked_keywords {
+            let first = k.first_seen.map_or("-".to_string(), timestamp);
+            let _ = writeln!(out, "| {} | {} | {} |", cell(&k.keyword), thousands(k.total), first);
+        }
+    }
+    if let Some(b) = &summary.baseline {
+        let _ = writeln!(
+            out, "\n### Compared with baseline\n\n{} new, {} regressed (more than {}%), {} gone error templates relative to `{}`.",
+            b.new.len(), b.regressed.len(), b.threshold_pct, b.gone.len(), b.source
+        );
+        if !b.new.is_empty() || !b.regressed.is_empty() {
+            let _ = writeln!(out, "\n| Change | Count | Baseline | Template |\n|---|---:|---:|---|");
+            for (t, n) in b.new.iter().take(MAX_ROWS) { let _ = writeln!(out, "| new | {} | 0 | {} |", thousands(*n), cell(t)); }
+            for r in b.regressed.iter().take(MAX_ROWS) {
+                let _ = writeln!(out, "| {:+.0}% | {} | {} | {} |", r.change_pct, thousands(r.current), thousands(r.baseline), cell(&r.template));
+            }
+        }
+    }
+    if !summary.probable_crashes.is_empty() {
+        let _ = writeln!(out, "\n### Probable crashes\n\n| Time | Source | Indicator | Silence after | Message |\n|---|---|---|---:|---|");
+        for c in summary.probable_crashes.iter().take(MAX_ROWS) {
+            let silence = c.silence_after_secs.map_or("-".to_string(), human_duration);
+            let _ = writeln!(out, "| {} | {}:{} | {} | {} | {} |", timestamp(c.ts), cell(&c.source), c.line_no, c.indicator, silence, cell(&c.message));
+        }
+    }
+    out
+}
@@ -0,0 +1,188 @@
+//! Typed summary of an analysis: timestamps as chrono values and per-level maps keyed by
+//! [`Level`]. [`JsonSummary`](super::JsonSummary) is its stable serialization view; the serde
+//! derives here are for saving a summary as-is (e.g. GUI sessions) and carry no schema guarantee.
+
+use crate::analyze::{Granularity, MalformedLine, MessageExample, SkippedInput, TimePrecision};
+use crate::model::Level;
+use super::{BaselineDiff, Counts, ErrorContext, GroupSummary, Health, HistogramStats, HostsSummary, NoisyMessage, PatternStats, PeriodCounts, QuantileStats, Quantiles, SamplingInfo};
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Everything reported about an analysis; built by [`summarize`](super::summarize).
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Summary {
+    /// Present when only a sample of lines was analyzed; all other counts are raw sample counts
+    pub sampling: Option<SamplingInfo>,
+    pub total_entries: u64,
+    /// Entries per level, every level present
+    pub counts: BTreeMap<Level, u64>,
+    pub malformed_lines: u64,
+    /// None without lines
+    pub health: Option<Health>,
+    /// Lines skipped by the pre-filter without parsing
+    pub prefiltered_lines: u64,
+    pub malformed_samples: Vec<MalformedLine>,
+    /// Lines cut to the maximum line length
+    pub truncated_lines: u64,
+    /// Copies of previous entries counted for suppression notices
+    pub repeats_restored: u64,
+    /// Inputs left out, e.g. binary files
+    pub skipped_inputs: Vec<SkippedInput>,
+    /// Lines per pattern of a parser with several, and lines matching none
+    pub pattern_stats: Option<PatternStats>,
+    pub first_log: Option<NaiveDateTime>,
+    pub last_log: Option<NaiveDateTime>,
+    /// First and last occurrence per level, for levels that occur
+    pub level_spans: BTreeMap<Level, (NaiveDateTime, NaiveDateTime)>,
+    pub time_to_first_error: Option<Duration>,
+    pub common_errors: Vec<(String, u64)>,
+    /// More distinct error messages occurred than were tracked; counts are upper bounds
+    pub common_errors_approximate: bool,
+    pub error_examples: BTreeMap<String, Vec<MessageExample>>,
+    /// Filled in by [`attach_error_context`](super::attach_error_context)
+    pub error_context: BTreeMap<String, ErrorContext>,
+    /// Previous level -> level -> consecutive entry pairs
+    pub transitions: BTreeMap<Level, BTreeMap<Level, u64>>,
+    /// Non-error messages directly preceding an error
+    pub error_precursors: Vec<(String, u64)>,
+    /// Most frequent error templates over time, with `--top-errors-by`
+    pub error_history: Vec<ErrorHistory>,
+    /// Period of `error_history`'s buckets
+    pub error_history_period: Option<Granularity>,
+    /// Every error template (up to [`MAX_ERROR_TEMPLATES`](super::MAX_ERROR_TEMPLATES)), most frequent first
+    pub error_templates: Vec<(String, u64)>,
+    /// Filled in by [`compare_baseline`](super::compare_baseline)
+    pub baseline: Option<BaselineDiff>,
+    /// Most frequent message templates of any level
+    pub noisiest_messages: Vec<NoisyMessage>,
+    pub top_loggers: Vec<(String, u64)>,
+    pub top_exceptions: Vec<(String, u64)>,
+    /// Bucket size of `timeline`
+    pub granularity: Granularity,
+    /// How the reports write timestamps
+    pub ts_precision: TimePrecision,
+    /// Bucket start -> entries, in time order
+    pub timeline: Vec<(NaiveDateTime, u64)>,
+    /// Entries per level of each `timeline` bucket, in the same order
+    pub timeline_levels: Vec<Counts>,
+    /// Each `--track` keyword over the timeline, in the order given
+    pub tracked_keywords: Vec<TrackedKeyword>,
+    pub incidents: Vec<Incident>,
+    /// Entries suggesting the process died, in time order
+    pub probable_crashes: Vec<ProbableCrash>,
+    pub worst_5m_window: Option<ErrorWindow>,
+    pub worst_1h_window: Option<ErrorWindow>,
+    pub groups: BTreeMap<String, Vec<GroupSummary>>,
+    pub hosts: Option<HostsSummary>,
+    pub histogram: Option<HistogramStats>,
+    pub quantiles: Option<Quantiles>,
+    /// GC pauses and OutOfMemoryErrors; None when there are neither
+    pub jvm: Option<JvmSummary>,
+    pub top_values: BTreeMap<String, Vec<(String, u64)>>,
+    pub trends: Option<Trends>,
+    pub by_hour_of_day: Vec<PeriodCounts>,
+    pub by_weekday: Vec<PeriodCounts>,
+}
+
+impl Summary {
+    pub fn count(&self, level: Level) -> u64 { self.counts.get(&level).copied().unwrap_or(0) }
+}
+
+/// When an error template occurred.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ErrorHistory {
+    pub template: String,
+    pub count: u64,
+    pub first_seen: NaiveDateTime,
+    pub last_seen: NaiveDateTime,
+    /// Period start -> errors, in time order
+    pub per_period: Vec<(NaiveDateTime, u64)>,
+    /// Never seen outside the time range; None without one
+    pub new_in_range: Option<bool>,
+}
+
+/// Entries mentioning a tracked keyword (`--track`).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TrackedKeyword {
+    pub keyword: String,
+    pub total: u64,
+    /// First bucket mentioning it; None without mentions
+    pub first_seen: Option<NaiveDateTime>,
+    /// Mentions per `timeline` bucket, in the same order
+    pub counts: Vec<u64>,
+}
+
+/// A run of consecutive error-heavy buckets.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Incident {
+    pub start: NaiveDateTime,
+    /// End of the last bucket in the run (exclusive)
+    pub end: NaiveDateTime,
+    pub total_errors: u64,
+    pub top_template: String,
+    /// Highest error count seen in a single bucket
+    pub peak_rate: u64,
+}
+
+/// An entry suggesting the process died: a crash indicator, or an error ending its input.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ProbableCrash {
+    pub ts: NaiveDateTime,
+    pub source: String,
+    pub line_no: u64,
+    /// The crash indicator found, or "last entry is an error"
+    pub indicator: String,
+    pub message: String,
+    /// Seconds until the next entry of the same input; None when nothing followed
+    pub silence_after_secs: Option<i64>,
+}
+
+/// GC pauses and OutOfMemoryErrors of JVM logs.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct JvmSummary {
+    pub gc_pauses: u64,
+    pub full_gcs: u64,
+    /// Pause percentiles in milliseconds
+    pub pause_ms: Option<QuantileStats>,
+    pub total_pause_ms: f64,
+    /// Share of the time from the first to the last entry spent in GC pauses
+    pub pause_time_pct: Option<f64>,
+    pub heap_after_max_kb: Option<u64>,
+    pub heap_total_max_kb: Option<u64>,
+    /// When `java.lang.OutOfMemoryError` was logged
+    pub oom_events: Vec<NaiveDateTime>,
+}
+
+/// The rolling window of a given length with the most errors.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ErrorWindow {
+    pub start: NaiveDateTime,
+    /// Exclusive
+    pub end: NaiveDateTime,
+    pub errors: u64,
+    pub entries: u64,
+    pub error_rate: f64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Trends {
+    /// Least-squares slope of errors per bucket, in errors per bucket step
+    pub error_slope: f64,
+    /// Start of the hour with the most entries; None when buckets are coarser than an hour
+    pub busiest_hour: Option<(NaiveDateTime, u64)>,
+    /// None when buckets are coarser than a day
+    pub busiest_day: Option<(NaiveDate, u64)>,
+    /// Per calendar week, only when the range spans more than one week
+    pub weeks: Vec<WeekTrend>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WeekTrend {
+    /// The Monday starting the week
+    pub week_start: NaiveDate,
+    pub entries: u64,
+    pub errors: u64,
+    pub entries_change_pct: Option<f64>,
+    pub errors_change_pct: Option<f64>,
+}
@@ -0,0 +1,77 @@
+use crate::model::Level;
+use std::io::IsTerminal;
+
+/// When to color the text report.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Color when stdout is a terminal and `NO_COLOR` is unset
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+/// ANSI styling for the text report; every method returns the plain text when color is off.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Term {
+    pub color: bool,
+}
+
+impl Term {
+    pub fn new(choice: ColorChoice) -> Self {
+        let color = match choice {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty()) && std::io::stdout().is_terminal(),
+        };
+        Self { color }
+    }
+
+    fn paint(&self, code: &str, text: &str) -> String {
+        if self.color { format!("\x1b[{code}m{text}\x1b[0m") } else { text.to_string() }
+    }
+
+    pub fn bold(&self, text: &str) -> String { self.paint("1", text) }
+
+    pub fn dim(&self, text: &str) -> String { self.paint("2", text) }
+
+    /// Bold red, for matched text
+    pub fn highlight(&self, text: &str) -> String { self.paint("1;31", text) }
+
+    /// Level name padded to `width` before coloring, so columns stay aligned
+    pub fn level(&self, level: Level, width: usize) -> String {
+        let (name, code) = match level {
+            Level::Info => ("INFO", "32"),
+            Level::Warning => ("WARNING", "33"),
+            Level::Error => ("ERROR", "1;31"),
+        };
+        self.paint(code, &format!("{name:<width$}"))
+    }
+}
+
+/// `1234567` -> `1,234,567`
+pub fn thousands(n: u64) -> String {
+    let digits = n.to_string();
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) { out.push(','); }
+        out.push(c);
+    }
+    out
+}
+
+/// `3725` -> `1h 2m 5s`
+pub fn human_duration(secs: i64) -> String {
+    let (d, h, m, s) = (secs / 86400, secs % 86400 / 3600, secs % 3600 / 60, secs % 60);
+    let parts: Vec<String> = [(d, "d"), (h, "h"), (m, "m")].into_iter()
+        .skip_while(|(n, _)| *n == 0)
+        .map(|(n, unit)| format!("{n}{unit}"))
+        .chain(std::iter::once(format!("{s}s")))
+        .collect();
+    parts.join(" ")
+}
+
+/// Share of `total` as `12.3%` (`0.0%` when total is zero)
+pub fn percent(part: u64, total: u64) -> String {
+    format!("{:.1}%", if total == 0 { 0.0 } else { part as f64 * 100.0 / total as f64 })
+}
@@ -0,0 +1,120 @@
+//! Streaming API for feeding entries from any source (sockets, channels, tests).
+//!
+//! Sinks compose as tuples: `(LevelCounter::default(), TopErrors::new(5))` is itself a sink whose
+//! output is the pair of outputs. [`Analyzer`] is a sink producing the full [`Summary`].
+
+use crate::analyze::{lossy_lines, Analyzer, Granularity, TopK, DEFAULT_MAX_LINE_LEN};
+use crate::model::{Level, LogEntry};
+use crate::parse::LogParser;
+use crate::report::{summarize, Counts, ReportOptions, Summary};
+use anyhow::Result;
+use chrono::NaiveDateTime;
+use std::collections::BTreeMap;
+use std::io::BufRead;
+use std::sync::mpsc;
+use std::thread;
+
+/// Consumes entries one at a time and produces a result at the end.
+pub trait AnalyzerSink: Send {
+    type Output;
+    fn push(&mut self, entry: LogEntry);
+    fn finish(self) -> Self::Output;
+}
+
+/// Counts entries per level.
+#[derive(Clone, Debug, Default)]
+pub struct LevelCounter { info: u64, warning: u64, error: u64 }
+
+impl AnalyzerSink for LevelCounter {
+    type Output = Counts;
+    fn push(&mut self, entry: LogEntry) {
+        match entry.level { Level::Info => self.info += 1, Level::Warning => self.warning += 1, Level::Error => self.error += 1 }
+    }
+    fn finish(self) -> Counts { Counts { info: self.info, warning: self.warning, error: self.error } }
+}
+
+/// Entries per timeline bucket.
+#[derive(Clone, Debug, Default)]
+pub struct Timeline { granularity: Granularity, buckets: BTreeMap<NaiveDateTime, u64> }
+
+impl Timeline {
+    pub fn new(granularity: Granularity) -> Self { Self { granularity, buckets: BTreeMap::new() } }
+}
+
+impl AnalyzerSink for Timeline {
+    type Output = BTreeMap<NaiveDateTime, u64>;
+    fn push(&mut self, entry: LogEntry) { *self.buckets.entry(self.granularity.bucket(entry.ts)).or_default() += 1; }
+    fn finish(self) -> Self::Output { self.buckets }
+}
+
+/// The `k` most common error messages.
+#[derive(Clone, Debug)]
+pub struct TopErrors { k: usize, messages: TopK }
+
+impl TopErrors {
+    pub fn new(k: usize) -> Self { Self { k, messages: TopK::default() } }
+}
+
+impl AnalyzerSink for TopErrors {
+    type Output = Vec<(String, u64)>;
+    fn push(&mut self, entry: LogEntry) {
+        if entry.level == Level::Error { self.messages.add(&entry.message); }
+    }
+    fn finish(self) -> Self::Output { self.messages.top(self.k) }
+}
+
+impl AnalyzerSink for Analyzer {
+    type Output = Summary;
+    fn push(&mut self, entry: LogEntry) { self.consume_entry(entry); }
+    fn finish(self) -> Summary { summarize(&self, &ReportOptions::default()) }
+}
+
+impl<A: AnalyzerSink, B: AnalyzerSink> AnalyzerSink for (A, B) {
+    type Output = (A::Output, B::Output);
+    fn push(&mut self, entry: LogEntry) {
+        self.0.push(entry.clone());
+        self.1.push(entry);
+    }
+    fn finish(self) -> Self::Output { (self.0.finish(), self.1.finish()) }
+}
+
+impl<A: AnalyzerSink, B: AnalyzerSink, C: AnalyzerSink> AnalyzerSink for (A, B, C) {
+    type Output = (A::Output, B::Output, C::Output);
+    fn push(&mut self, entry: LogEntry) {
+        self.0.push(entry.clone());
+        self.1.push(entry.clone());
+        self.2.push(entry);
+    }
+    fn finish(self) -> Self::Output { (self.0.finish(), self.1.finish(), self.2.finish()) }
+}
+
+/// Parse `r` line by line into `sink`, numbering entries by line; invalid UTF-8 is replaced
+/// rather than ending the input. Returns the number of lines that did not parse.
+pub fn feed<S: AnalyzerSink + ?Sized, P: LogParser + ?Sized, R: BufRead>(sink: &mut S, parser: &mut P, r: R) -> Result<u64> {
+    parser.reset();
+    let mut malformed = 0;
+    for (idx, line) in lossy_lines(parser.decode(Box::new(r)), DEFAULT_MAX_LINE_LEN).enumerate() {
+        let line = line?;
+        if parser.absorb(&line) { continue; }
+        match parser.parse_line(&line) {
+            Ok(Some(entry)) => sink.push(LogEntry { line_no: idx as u64 + 1, ..entry }),
+            Ok(None) | Err(_) => malformed += 1,
+        }
+    }
+    Ok(malformed)
+}
+
+/// Run `sink` on its own thread. Clone the sender to push from several threads;
+/// once every sender is dropped the handle yields the sink's output.
+pub fn spawn<S>(mut sink: S) -> (mpsc::Sender<LogEntry>, thread::JoinHandle<S::Output>)
+where
+    S: AnalyzerSink + 'static,
+    S::Output: Send + 'static,
+{
+    let (tx, rx) = mpsc::channel();
+    let handle = thread::spawn(move || {
+        for entry in rx { sink.push(entry); }
+        sink.finish()
+    });
+    (tx, handle)
+}
@@ -0,0 +1,159 @@
+//! Error-message counts spilled to disk (`--max-memory`): instead of evicting messages, the counter
+//! grows until it is over its memory budget, its contents are written out as a run sorted by
+//! message, and the runs are merged back into exact counts at the end of the analysis.
+
+use crate::analyze::{MessageExample, TopK};
+use anyhow::{Context, Result};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+const ZSTD_LEVEL: i32 = 1;
+/// Runs read at once when merging; more are merged in several passes
+pub const MAX_FAN_IN: usize = 64;
+
+type Record = (String, u64, Vec<MessageExample>);
+
+/// Temporary runs of one analysis, shared by its partials (see [`crate::analyze::Analyzer::spill`]).
+/// The directory is removed when this is dropped.
+pub struct Spill {
+    /// Bytes a message counter may hold before it spills
+    pub budget: usize,
+    dir: PathBuf,
+    /// (path, records) per run
+    runs: Vec<(PathBuf, u64)>,
+    /// Number of the next run file
+    next_run: usize,
+    pub bytes_written: u64,
+    /// First write error; spilling stops there and [`merge_into`](Self::merge_into) reports it
+    error: Option<anyhow::Error>,
+}
+
+impl Spill {
+    pub fn new(budget: usize) -> Result<Arc<Mutex<Self>>> {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let n = NEXT.fetch_add(1, Ordering::Relaxed);
+        let dir = std::env::temp_dir().join(format!("log_analyzer_spill_{}_{n}", std::process::id()));
+        std::fs::create_dir_all(&dir).with_context(|| format!("Creating {}", dir.display()))?;
+        Ok(Arc::new(Mutex::new(Self { budget, dir, runs: Vec::new(), next_run: 0, bytes_written: 0, error: None })))
+    }
+
+    pub fn runs(&self) -> usize { self.runs.len() }
+
+    /// Move every message of `counter` into a new run
+    pub(crate) fn spill(&mut self, counter: &mut TopK) {
+        let records = counter.take_sorted();
+        if self.error.is_some() || records.is_empty() { return; }
+        let path = self.dir.join(format!("run{}.zst", self.next_run));
+        self.next_run += 1;
+        let written = (|| -> Result<u64> {
+            let mut out = zstd::Encoder::new(BufWriter::new(File::create(&path)?), ZSTD_LEVEL)?;
+            for r in &records { bincode::serialize_into(&mut out, r)?; }
+            let mut file = out.finish()?;
+            file.flush()?;
+            Ok(file.get_ref().metadata()?.len())
+        })();
+        match written {
+            Ok(bytes) => {
+                self.bytes_written += bytes;
+                self.runs.push((path, records.len() as u64));
+            }
+            Err(e) => self.error = Some(e.context(format!("Spilling error messages to {}", self.dir.display()))),
+        }
+    }
+
+    /// Merge the runs and what `counter` still holds into exact counts, keeping the
+    /// `counter.limits.capacity` most frequent messages in `counter`. At most [`MAX_FAN_IN`] runs
+    /// are open at once; beyond that, groups of runs are first merged into longer ones.
+    pub fn merge_into(&mut self, counter: &mut TopK) -> Result<()> {
+        if let Some(e) = self.error.take() { return Err(e); }
+        counter.unbounded = false;
+        if self.runs.is_empty() && counter.len() <= counter.limits.capacity.max(1) { return Ok(()); }
+        self.spill(counter);
+        if let Some(e) = self.error.take() { return Err(e); }
+        let (keep, max_examples) = (counter.limits.capacity.max(1), counter.limits.examples);
+        while self.runs.len() > MAX_FAN_IN {
+            let group: Vec<_> = self.runs.drain(..MAX_FAN_IN).collect();
+            let path = self.dir.join(format!("run{}.zst", self.next_run));
+            self.next_run += 1;
+            let mut out = zstd::Encoder::new(BufWriter::new(File::create(&path)?), ZSTD_LEVEL)?;
+            let mut records = 0;
+            merge_runs(&group, max_examples, |r| {
+                records += 1;
+                Ok(bincode::serialize_into(&mut out, &r)?)
+            })?;
+            let mut file = out.finish()?;
+            file.flush()?;
+            self.bytes_written += file.get_ref().metadata()?.len();
+            for (path, _) in group { let _ = std::fs::remove_file(path); }
+            self.runs.push((path, records));
+        }
+        // The `keep` largest counts so far, least frequent (then last by message) on top
+        let mut top: BinaryHeap<Reverse<(u64, Reverse<String>)>> = BinaryHeap::new();
+        let mut kept_examples: HashMap<String, Vec<MessageExample>> = HashMap::new();
+        merge_runs(&self.runs, max_examples, |(msg, count, examples)| {
+            top.push(Reverse((count, Reverse(msg.clone()))));
+            kept_examples.insert(msg, examples);
+            if top.len() > keep {
+                if let Some(Reverse((_, Reverse(dropped)))) = top.pop() { kept_examples.remove(&dropped); }
+            }
+            Ok(())
+        })?;
+        for Reverse((count, Reverse(msg))) in top {
+            let examples = kept_examples.remove(&msg).unwrap_or_default();
+            counter.add_n(&msg, count, examples);
+        }
+        for (path, _) in self.runs.drain(..) { let _ = std::fs::remove_file(path); }
+        Ok(())
+    }
+}
+
+/// Stream the records of `runs` in message order to `emit`, one per message with the counts
+/// summed and up to `max_examples` examples
+fn merge_runs(runs: &[(PathBuf, u64)], max_examples: usize, mut emit: impl FnMut(Record) -> Result<()>) -> Result<()> {
+    let mut readers = Vec::new();
+    for (path, records) in runs {
+        let file = File::open(path).with_context(|| format!("Opening {}", path.display()))?;
+        readers.push((BufReader::new(zstd::Decoder::new(file)?), *records));
+    }
+    let mut next = |i: usize| -> Result<Option<Record>> {
+        let (r, left) = &mut readers[i];
+        if *left == 0 { return Ok(None); }
+        *left -= 1;
+        Ok(Some(bincode::deserialize_from(r).context("Reading spilled error messages")?))
+    };
+    // The smallest message of each run on top, so equal messages come out together; the
+    // count and examples of each run's head wait in `pending`
+    let (mut heads, mut pending) = (BinaryHeap::new(), Vec::new());
+    for i in 0..runs.len() {
+        let head = next(i)?;
+        if let Some((msg, _, _)) = &head { heads.push(Reverse((msg.clone(), i))); }
+        pending.push(head);
+    }
+    let mut current: Option<Record> = None;
+    while let Some(Reverse((_, i))) = heads.pop() {
+        let (msg, count, examples) = pending[i].take().expect("every head has a pending record");
+        if let Some(r) = next(i)? {
+            heads.push(Reverse((r.0.clone(), i)));
+            pending[i] = Some(r);
+        }
+        match current.as_mut() {
+            Some(cur) if cur.0 == msg => {
+                cur.1 += count;
+                let room = max_examples.saturating_sub(cur.2.len());
+                cur.2.extend(examples.into_iter().take(room));
+            }
+            _ => if let Some(done) = current.replace((msg, count, examples)) { emit(done)?; },
+        }
+    }
+    if let Some(done) = current { emit(done)?; }
+    Ok(())
+}
+
+impl Drop for Spill {
+    fn drop(&mut self) { let _ = std::fs::remove_dir_all(&self.dir); }
+}
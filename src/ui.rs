@@ -1,85 +1,458 @@
 #![cfg(feature = "gui")]
 
-use crate::analyze::{Analyzer, Filters, Granularity};
-use crate::parse::DefaultLogParser;
-use crate::report::{build_summary, JsonSummary};
+use crate::analyze::{Analyzer, Filters, Granularity, MalformedLine};
+use crate::discover::{gather_log_files, DiscoveryOptions};
+use crate::model::{Level, LogEntry};
+use crate::parse::{suggest_format, DefaultLogParser, FormatHints, LogParser, RegexParser};
+use crate::report::{markdown, summarize, Counts, JsonSummary, ReportOptions, Summary, SummaryFormat};
+use crate::spill::Spill;
+use crate::viz::PressureWeights;
 use eframe::{egui, App};
 use egui::{RichText, ComboBox};
 use egui_extras::{Column, TableBuilder};
 use rfd::FileDialog;
-use std::path::PathBuf;
-use egui_plot::{Plot, Line, PlotPoints};
+use std::path::{Path, PathBuf};
+use egui_plot::{Bar, BarChart, Legend, Plot, PlotPoint, PlotUi, Line, PlotPoints, Polygon, Text};
+use chrono::NaiveDateTime;
+use std::collections::HashMap;
+use serde::{Serialize, Deserialize};
 
-pub fn launch() -> anyhow::Result<()> {
+mod i18n;
+use i18n::Lang;
+
+/// Analyses kept in the Recent list
+const MAX_RECENT: usize = 10;
+/// Bumped when the `.logana` session layout changes
+const SESSION_VERSION: u32 = 2;
+/// Entries kept in memory for timeline drill-down
+const MAX_RETAINED_ENTRIES: usize = 1_000_000;
+/// Unparsed lines kept for the Malformed tab
+const MAX_RETAINED_MALFORMED: usize = 10_000;
+/// Error messages summarized, the most the Top Errors list can show
+const MAX_TOP_ERRORS: usize = 100;
+/// Lines of the selected file the Parser window tries the pattern on
+const PREVIEW_LINES: usize = 50;
+/// Share of malformed lines above which an analysis opens the Unrecognized format window
+const MALFORMED_HELP_SHARE: f64 = 0.9;
+/// Malformed lines the format hints are drawn from, and how many of them the window shows
+const HELP_SAMPLE_LINES: usize = 200;
+const HELP_SHOWN_LINES: usize = 8;
+
+/// Open the GUI; given `inputs`, they are selected and analyzed right away.
+/// `max_memory` is `--max-memory`: the budget the analyses' error messages and retained entries share
+pub fn launch(inputs: Vec<PathBuf>, max_memory: Option<usize>) -> anyhow::Result<()> {
+    // window size/position is persisted by eframe alongside our own state
     let native_options = eframe::NativeOptions::default();
-    // eframe::Error не Send/Sync → оборачиваем в anyhow через строку
+    // eframe::Error isn't Send/Sync, so it goes into anyhow as a string
     eframe::run_native(
         "Log Analyzer (GUI)",
         native_options,
-        Box::new(|_cc| Ok(Box::<GuiApp>::default())),
+        Box::new(move |cc| Ok(Box::new(GuiApp::new(cc, inputs, max_memory)))),
     )
     .map_err(|e| anyhow::anyhow!(e.to_string()))?;
     Ok(())
 }
 
-#[derive(Default)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+enum ThemeChoice { #[default] System, Light, Dark }
+
+/// Single analysis, or two side by side
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+enum View { #[default] Analyze, Compare }
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+enum ResultTab { #[default] Timeline, Charts, Malformed }
+
+/// What the donut on the Charts tab splits up
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+enum ShareOf { #[default] Levels, TopErrors }
+
+const LEVEL_COLORS: [(&str, egui::Color32); 3] = [
+    ("INFO", egui::Color32::from_rgb(70, 130, 220)),
+    ("WARNING", egui::Color32::from_rgb(230, 160, 30)),
+    ("ERROR", egui::Color32::from_rgb(210, 60, 60)),
+];
+
+/// Slice colors for the top-errors donut; the last one is "other"
+const SHARE_COLORS: [egui::Color32; 6] = [
+    egui::Color32::from_rgb(210, 60, 60),
+    egui::Color32::from_rgb(230, 120, 40),
+    egui::Color32::from_rgb(200, 170, 40),
+    egui::Color32::from_rgb(150, 90, 180),
+    egui::Color32::from_rgb(60, 150, 150),
+    egui::Color32::GRAY,
+];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+enum LevelChoice { #[default] Any, Only(Level), AtLeast(Level) }
+
+impl LevelChoice {
+    const ALL: [LevelChoice; 6] = [
+        LevelChoice::Any,
+        LevelChoice::Only(Level::Info), LevelChoice::Only(Level::Warning), LevelChoice::Only(Level::Error),
+        LevelChoice::AtLeast(Level::Warning), LevelChoice::AtLeast(Level::Error),
+    ];
+
+    fn label(self, lang: Lang) -> &'static str {
+        lang.tr(match self {
+            LevelChoice::Any => "All levels",
+            LevelChoice::Only(Level::Info) => "INFO only",
+            LevelChoice::Only(Level::Warning) => "WARNING only",
+            LevelChoice::Only(Level::Error) => "ERROR only",
+            LevelChoice::AtLeast(Level::Info) => "INFO and above",
+            LevelChoice::AtLeast(Level::Warning) => "WARNING and above",
+            LevelChoice::AtLeast(Level::Error) => "ERROR and above",
+        })
+    }
+}
+
+/// Levels shown in the entries table (a view filter; the analysis is not re-run)
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+struct LevelToggles { info: bool, warning: bool, error: bool }
+
+impl Default for LevelToggles {
+    fn default() -> Self { Self { info: true, warning: true, error: true } }
+}
+
+impl LevelToggles {
+    fn shows(self, level: Level) -> bool {
+        match level { Level::Info => self.info, Level::Warning => self.warning, Level::Error => self.error }
+    }
+}
+
+/// Background of keyword matches in results
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+struct HighlightColor([u8; 3]);
+
+impl Default for HighlightColor {
+    fn default() -> Self { Self([255, 215, 64]) }
+}
+
+/// Column the Top Errors list is sorted by
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+enum ErrorSort { #[default] Count, Message }
+
+/// How the Top Errors list is shown (a view setting; the analysis is not re-run)
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+struct TopErrorsView {
+    /// Rows shown, at most [`MAX_TOP_ERRORS`]
+    count: usize,
+    sort: ErrorSort,
+    descending: bool,
+    /// Case-insensitive substring messages must contain
+    filter: String,
+}
+
+impl Default for TopErrorsView {
+    fn default() -> Self { Self { count: 10, sort: ErrorSort::Count, descending: true, filter: String::new() } }
+}
+
+impl TopErrorsView {
+    /// Click on a column header: reverse the order of the current column, or sort by the other
+    /// one (counts largest first, messages A-Z)
+    fn click(&mut self, sort: ErrorSort) {
+        if self.sort == sort {
+            self.descending = !self.descending;
+        } else {
+            self.sort = sort;
+            self.descending = sort == ErrorSort::Count;
+        }
+    }
+
+    fn header(&self, sort: ErrorSort, label: &str) -> String {
+        match (self.sort == sort, self.descending) {
+            (false, _) => label.to_string(),
+            (true, true) => format!("{label} ⏷"),
+            (true, false) => format!("{label} ⏶"),
+        }
+    }
+
+    /// The rows to show, filtered, sorted and cut to `count`
+    fn rows<'a>(&self, errors: &'a [(String, u64)]) -> Vec<&'a (String, u64)> {
+        let needle = self.filter.to_lowercase();
+        let mut rows: Vec<_> = errors.iter().filter(|(m, _)| needle.is_empty() || m.to_lowercase().contains(&needle)).collect();
+        match self.sort {
+            ErrorSort::Count => rows.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0))),
+            ErrorSort::Message => rows.sort_by_cached_key(|(m, _)| m.to_lowercase()),
+        }
+        if self.descending { rows.reverse(); }
+        rows.truncate(self.count);
+        rows
+    }
+}
+
+/// A finished analysis in the Recent list: the file or directory read and the filters used.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+struct RecentAnalysis {
+    path: PathBuf,
+    keyword: String,
+    from: String,
+    to: String,
+    gran: Granularity,
+    custom_bucket: String,
+    level: LevelChoice,
+}
+
+impl RecentAnalysis {
+    /// `app.log  (keyword "timeout", from 2025-09-05, ERROR only)`
+    fn label(&self, lang: Lang) -> String {
+        let name = self.path.file_name().map_or_else(|| self.path.display().to_string(), |n| n.to_string_lossy().into_owned());
+        let mut filters = Vec::new();
+        if !self.keyword.is_empty() { filters.push(lang.trf("keyword \"{}\"", &[&self.keyword])); }
+        if !self.from.is_empty() { filters.push(lang.trf("from {}", &[&self.from])); }
+        if !self.to.is_empty() { filters.push(lang.trf("to {}", &[&self.to])); }
+        if self.level != LevelChoice::Any { filters.push(self.level.label(lang).to_string()); }
+        if filters.is_empty() { name } else { format!("{name}  ({})", filters.join(", ")) }
+    }
+}
+
+/// GUI state; everything except results is restored between launches.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
 struct GuiApp {
     file: Option<PathBuf>,
     keyword: String,
     from: String,
     to: String,
     gran: Granularity,
-    summary: Option<JsonSummary>,
+    /// Interval typed for the Custom granularity, e.g. `15m`
+    custom_bucket: String,
+    level: LevelChoice,
+    show_levels: LevelToggles,
+    highlight: HighlightColor,
+    top_errors: TopErrorsView,
+    /// Shade and label detected incidents on the timeline plot
+    mark_incidents: bool,
+    /// Plot severity-weighted entries per bucket instead of raw volume
+    pressure: bool,
+    theme: ThemeChoice,
+    lang: Lang,
+    tab: ResultTab,
+    share_of: ShareOf,
+    /// Most recent first
+    recent: Vec<RecentAnalysis>,
+    view: View,
+    /// Panels A and B of the Compare view
+    compare: [CompareSide; 2],
+    /// Regex being tried out on the Malformed tab, and the line it's tested against
+    test_pattern: String,
+    test_line: String,
+    /// Regex and timestamp format from the Parser window, used instead of the built-in patterns
+    /// when `use_custom_parser` is set
+    parser_pattern: String,
+    parser_ts_format: String,
+    use_custom_parser: bool,
+    #[serde(skip)]
+    show_parser: bool,
+    /// First lines of the selected file, for the Parser window's preview
+    #[serde(skip)]
+    preview_lines: Option<Vec<String>>,
+    /// Further inputs analyzed together with `file`, when several were given on the command line
+    #[serde(skip)]
+    extra_files: Vec<PathBuf>,
+    /// `--max-memory`, shared by each analysis's error messages and retained entries
+    #[serde(skip)]
+    max_memory: Option<usize>,
+    #[serde(skip)]
+    summary: Option<Summary>,
+    #[serde(skip)]
     info_text: String,
+    /// Retained entries and [start, end) of each timeline bucket from the last analysis
+    #[serde(skip)]
+    entries: Vec<LogEntry>,
+    #[serde(skip)]
+    bucket_ranges: Vec<(NaiveDateTime, NaiveDateTime)>,
+    #[serde(skip)]
+    malformed: Vec<MalformedLine>,
+    /// Plot x where a drag-selection started
+    #[serde(skip)]
+    drag_start: Option<f64>,
+    #[serde(skip)]
+    drill: Option<Drill>,
+    #[serde(skip)]
+    format_help: Option<FormatHelp>,
+}
+
+/// A saved `.logana` session: inputs, filters and the computed results, so an analysis
+/// can be revisited without re-reading the logs.
+#[derive(Serialize, Deserialize)]
+struct Session {
+    version: u32,
+    file: Option<PathBuf>,
+    keyword: String,
+    from: String,
+    to: String,
+    gran: Granularity,
+    custom_bucket: String,
+    level: LevelChoice,
+    summary: Option<Summary>,
+    bucket_ranges: Vec<(NaiveDateTime, NaiveDateTime)>,
+    entries: Vec<LogEntry>,
+    #[serde(default)]
+    malformed: Vec<MalformedLine>,
+}
+
+/// One panel of the Compare view; keyword, level and granularity come from the filter row.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct CompareSide {
+    /// None = the file selected above
+    file: Option<PathBuf>,
+    /// Time range; empty = the filter row's from/to
+    from: String,
+    to: String,
+    #[serde(skip)]
+    summary: Option<Summary>,
+    /// Every tracked error message, so "new" means absent rather than just outside the top list
+    #[serde(skip)]
+    errors: HashMap<String, u64>,
+}
+
+const SIDE_NAMES: [&str; 2] = ["A", "B"];
+
+/// An analysis where most lines didn't parse: some of them and what they look like
+struct FormatHelp {
+    malformed_pct: f64,
+    samples: Vec<String>,
+    hints: FormatHints,
+}
+
+/// Entries of a selected timeline slice
+struct Drill {
+    /// Selected bucket indices (inclusive)
+    first: usize,
+    last: usize,
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+    /// Indices into `GuiApp::entries`
+    rows: Vec<usize>,
+    top_errors: Vec<(String, u64)>,
 }
 
 impl App for GuiApp {
-    fn update(&mut self, ctx: &egui::Context, _: &mut eframe::Frame) {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, eframe::APP_KEY, self);
+    }
+
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        let lang = self.lang;
+        ctx.set_visuals(match self.theme {
+            ThemeChoice::Light => egui::Visuals::light(),
+            ThemeChoice::Dark => egui::Visuals::dark(),
+            ThemeChoice::System => frame.info().system_theme.unwrap_or(eframe::Theme::Dark).egui_visuals(),
+        });
+
         egui::TopBottomPanel::top("top").show(ctx, |ui| {
-            ui.heading(RichText::new("Log File Analyzer").size(24.0));
+            ui.horizontal(|ui| {
+                ui.heading(RichText::new(lang.tr("Log File Analyzer")).size(24.0));
+                ui.add_space(16.0);
+                ui.selectable_value(&mut self.view, View::Analyze, RichText::new(lang.tr("Analyze")).strong());
+                ui.selectable_value(&mut self.view, View::Compare, RichText::new(lang.tr("Compare")).strong());
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    const THEMES: [(ThemeChoice, &str); 3] = [(ThemeChoice::System, "System"), (ThemeChoice::Light, "Light"), (ThemeChoice::Dark, "Dark")];
+                    ComboBox::new("theme", lang.tr("Theme"))
+                        .selected_text(THEMES.iter().find(|(t, _)| *t == self.theme).map_or("", |(_, n)| lang.tr(n)))
+                        .show_ui(ui, |ui| {
+                            for (t, name) in THEMES { ui.selectable_value(&mut self.theme, t, lang.tr(name)); }
+                        });
+                    ComboBox::new("lang", lang.tr("Language"))
+                        .selected_text(self.lang.name())
+                        .show_ui(ui, |ui| {
+                            for l in Lang::ALL { ui.selectable_value(&mut self.lang, l, l.name()); }
+                        });
+                });
+            });
         });
 
+        if self.show_parser { self.show_parser_window(ctx); }
+        if self.format_help.is_some() { self.show_format_help(ctx); }
+
         egui::CentralPanel::default().show(ctx, |ui| {
-            // выбор файла
+            // Input selection
             ui.horizontal(|ui| {
-                if ui.button("Open .log...").clicked() {
+                if ui.button(lang.tr("Open .log...")).clicked() {
                     if let Some(p) = FileDialog::new().add_filter("Log", &["log"]).pick_file() {
-                        self.file = Some(p);
-                        self.info_text.clear();
-                        self.summary = None;
+                        self.select_file(p);
                     }
                 }
+                if ui.button(lang.tr("Open folder...")).on_hover_text(lang.tr("Analyze every .log file under a directory")).clicked() {
+                    if let Some(p) = FileDialog::new().pick_folder() { self.select_file(p); }
+                }
+                ui.add_enabled_ui(!self.recent.is_empty(), |ui| {
+                    ui.menu_button(lang.tr("Recent"), |ui| {
+                        if let Some(i) = recent_list(&self.recent, ui, lang) {
+                            ui.close_menu();
+                            self.reopen(i);
+                        }
+                        ui.separator();
+                        if ui.button(lang.tr("Clear list")).clicked() {
+                            self.recent.clear();
+                            ui.close_menu();
+                        }
+                    });
+                });
                 if let Some(f) = &self.file {
-                    ui.label(f.display().to_string());
+                    let more = if self.extra_files.is_empty() { String::new() } else { lang.trf(" (+{} more)", &[&self.extra_files.len()]) };
+                    ui.label(format!("{}{more}", f.display()));
                 }
+                let parser_label = if self.use_custom_parser { "Parser: custom" } else { "Parser: built-in" };
+                if ui.button(lang.tr(parser_label)).clicked() { self.show_parser = !self.show_parser; }
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button(lang.tr("Save session...")).clicked() {
+                        if let Some(p) = FileDialog::new().add_filter("Session", &["logana"]).set_file_name("analysis.logana").save_file() {
+                            self.info_text = match self.save_session(&p) {
+                                Ok(()) => lang.trf("Session saved to {}", &[&p.display()]),
+                                Err(e) => lang.trf("Session save error: {}", &[&e]),
+                            };
+                        }
+                    }
+                    if ui.button(lang.tr("Open session...")).clicked() {
+                        if let Some(p) = FileDialog::new().add_filter("Session", &["logana"]).pick_file() {
+                            if let Err(e) = self.load_session(&p) { self.info_text = lang.trf("Session open error: {}", &[&e]); }
+                        }
+                    }
+                });
             });
 
             ui.separator();
 
-            // фильтры
+            // Filters
             ui.horizontal(|ui| {
-                ui.add(egui::TextEdit::singleline(&mut self.keyword).hint_text("keyword"));
+                ui.add(egui::TextEdit::singleline(&mut self.keyword).hint_text(lang.tr("keyword")));
                 ui.add(
                     egui::TextEdit::singleline(&mut self.from)
-                        .hint_text("from: YYYY-MM-DD HH:MM:SS"),
+                        .hint_text(lang.tr("from: YYYY-MM-DD HH:MM:SS")),
                 );
                 ui.add(
-                    egui::TextEdit::singleline(&mut self.to).hint_text("to:   YYYY-MM-DD HH:MM:SS"),
+                    egui::TextEdit::singleline(&mut self.to).hint_text(lang.tr("to:   YYYY-MM-DD HH:MM:SS")),
                 );
 
-                ComboBox::from_label("Granularity")
-                    .selected_text(match self.gran {
-                        Granularity::Minute => "Minute",
-                        Granularity::Hour => "Hour",
-                        Granularity::Day => "Day",
-                    })
+                ComboBox::new("level", lang.tr("Level"))
+                    .selected_text(self.level.label(lang))
                     .show_ui(ui, |ui| {
-                        ui.selectable_value(&mut self.gran, Granularity::Minute, "Minute");
-                        ui.selectable_value(&mut self.gran, Granularity::Hour, "Hour");
-                        ui.selectable_value(&mut self.gran, Granularity::Day, "Day");
+                        for c in LevelChoice::ALL { ui.selectable_value(&mut self.level, c, c.label(lang)); }
                     });
 
-                if ui.button("Analyze").clicked() {
+                const NAMED: [(Granularity, &str); 7] = [
+                    (Granularity::Auto, "Auto"), (Granularity::Second, "Second"), (Granularity::Minute, "Minute"),
+                    (Granularity::Hour, "Hour"), (Granularity::Day, "Day"), (Granularity::Week, "Week"), (Granularity::Month, "Month"),
+                ];
+                let custom = matches!(self.gran, Granularity::Custom(_) | Granularity::Millis(_));
+                ComboBox::new("granularity", lang.tr("Granularity"))
+                    .selected_text(lang.tr(NAMED.iter().find(|(g, _)| *g == self.gran).map_or("Custom", |(_, n)| *n)))
+                    .show_ui(ui, |ui| {
+                        for (g, name) in NAMED { ui.selectable_value(&mut self.gran, g, lang.tr(name)); }
+                        if ui.selectable_label(custom, lang.tr("Custom")).clicked() && !custom {
+                            self.gran = Granularity::custom(&self.custom_bucket).unwrap_or(Granularity::Custom(900));
+                        }
+                    });
+                if custom {
+                    ui.add(egui::TextEdit::singleline(&mut self.custom_bucket).hint_text(lang.tr("e.g. 15m")).desired_width(60.0));
+                }
+
+                if self.view == View::Analyze && ui.button(lang.tr("Analyze")).clicked() {
                     self.run_analysis();
                 }
             });
@@ -88,104 +461,883 @@ impl App for GuiApp {
                 ui.label(self.info_text.clone());
             }
 
-            // результаты
+            if self.view == View::Compare {
+                self.show_compare(ui);
+                return;
+            }
+
+            if self.summary.is_none() && !self.recent.is_empty() {
+                ui.separator();
+                ui.label(RichText::new(lang.tr("Recent analyses")).strong());
+                if let Some(i) = recent_list(&self.recent, ui, lang) { self.reopen(i); }
+            }
+
+            // Results
+            let mut select = None;
             if let Some(sum) = &self.summary {
                 ui.separator();
-                ui.label(RichText::new("Summary").strong());
-                ui.label(format!(
-                    "Total: {} | Malformed: {}",
-                    sum.total_entries, sum.malformed_lines
-                ));
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new(lang.tr("Summary")).strong());
+                    // For pasting into a ticket or chat without exporting files
+                    if ui.small_button(lang.tr("Copy as Markdown")).clicked() {
+                        ui.ctx().copy_text(markdown::render(sum));
+                        self.info_text = lang.tr("Summary copied to the clipboard").to_string();
+                    }
+                    if ui.small_button(lang.tr("Copy as JSON")).clicked() {
+                        self.info_text = match SummaryFormat::Json.render(&JsonSummary::from(sum)) {
+                            Ok(json) => { ui.ctx().copy_text(json); lang.tr("Summary copied to the clipboard").to_string() }
+                            Err(e) => lang.trf("Copy error: {}", &[&e]),
+                        };
+                    }
+                });
+                ui.label(lang.trf("Total: {} | Malformed: {}", &[&sum.total_entries, &sum.malformed_lines]));
                 ui.label(format!(
                     "INFO: {}  WARNING: {}  ERROR: {}",
-                    sum.counts.info, sum.counts.warning, sum.counts.error
+                    sum.count(Level::Info), sum.count(Level::Warning), sum.count(Level::Error)
                 ));
                 if let Some(f) = &sum.first_log {
-                    ui.label(format!("First: {}", f));
+                    ui.label(lang.trf("First: {}", &[&sum.ts_precision.format(*f)]));
                 }
                 if let Some(l) = &sum.last_log {
-                    ui.label(format!("Last:  {}", l));
+                    ui.label(lang.trf("Last:  {}", &[&sum.ts_precision.format(*l)]));
                 }
 
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    ui.label(lang.tr("Show:"));
+                    ui.checkbox(&mut self.show_levels.info, "INFO");
+                    ui.checkbox(&mut self.show_levels.warning, "WARNING");
+                    ui.checkbox(&mut self.show_levels.error, "ERROR");
+                    ui.add_space(12.0);
+                    ui.label(lang.tr("Keyword highlight:"));
+                    ui.color_edit_button_srgb(&mut self.highlight.0);
+                });
+
                 ui.add_space(8.0);
-                ui.label(RichText::new("Top Errors").strong());
-                TableBuilder::new(ui)
-                    .striped(true)
-                    .column(Column::auto())
-                    .column(Column::remainder())
-                    .body(|mut body| {
-                        for (msg, n) in &sum.common_errors {
-                            body.row(20.0, |mut row| {
-                                row.col(|ui| {
-                                    ui.label(format!("{n}"));
-                                });
-                                row.col(|ui| {
-                                    ui.label(msg);
-                                });
+                let view = &mut self.top_errors;
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new(lang.tr("Top Errors")).strong());
+                    ui.add_space(12.0);
+                    ui.label(lang.tr("Show:"));
+                    ui.add(egui::DragValue::new(&mut view.count).range(1..=MAX_TOP_ERRORS));
+                    ui.add_space(12.0);
+                    ui.add(egui::TextEdit::singleline(&mut view.filter).hint_text(lang.tr("Filter messages")).desired_width(200.0));
+                });
+                ui.horizontal(|ui| {
+                    if ui.selectable_label(view.sort == ErrorSort::Count, view.header(ErrorSort::Count, lang.tr("Count"))).clicked() {
+                        view.click(ErrorSort::Count);
+                    }
+                    if ui.selectable_label(view.sort == ErrorSort::Message, view.header(ErrorSort::Message, lang.tr("Message"))).clicked() {
+                        view.click(ErrorSort::Message);
+                    }
+                });
+                let rows = view.rows(&sum.common_errors);
+                if rows.is_empty() && !sum.common_errors.is_empty() { ui.label(RichText::new(lang.tr("No error matches the filter")).weak()); }
+                let mark = |prefix: &str, msg: &str, ui: &egui::Ui| highlight_keyword(prefix, msg, &self.keyword, self.highlight, ui);
+                // Messages with examples expand to show the raw lines behind them
+                for (msg, n) in rows {
+                    let text = mark(&format!("{n}  "), msg, ui);
+                    match sum.error_examples.get(msg) {
+                        Some(examples) => {
+                            ui.collapsing(text, |ui| {
+                                for ex in examples {
+                                    ui.label(RichText::new(format!("{}  {}:{}", ex.ts, ex.source, ex.line_no)).weak());
+                                    ui.monospace(&ex.raw);
+                                }
                             });
                         }
-                    });
+                        None => { ui.label(text); }
+                    }
+                }
 
                 ui.add_space(12.0);
-                ui.label(RichText::new("Timeline").strong());
-
-                let points: Vec<[f64; 2]> = sum
-                    .timeline
-                    .iter()
-                    .enumerate()
-                    .map(|(i, (_, v))| [i as f64, *v as f64])
-                    .collect();
-
-                let plot = Plot::new("timeline").view_aspect(3.0);
-                plot.show(ui, |pui| {
-                    let line = Line::new(PlotPoints::from(points));
-                    pui.line(line);
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut self.tab, ResultTab::Timeline, RichText::new(lang.tr("Timeline")).strong());
+                    ui.selectable_value(&mut self.tab, ResultTab::Charts, RichText::new(lang.tr("Charts")).strong());
+                    ui.selectable_value(&mut self.tab, ResultTab::Malformed, RichText::new(lang.trf("Malformed ({})", &[&sum.malformed_lines])).strong());
+                    if self.tab == ResultTab::Timeline {
+                        ui.add_space(16.0);
+                        ui.checkbox(&mut self.mark_incidents, lang.trf("Mark incidents ({})", &[&sum.incidents.len()]));
+                        ui.checkbox(&mut self.pressure, lang.tr("Error pressure"))
+                            .on_hover_text(lang.tr("Errors weigh 5, warnings 2 and info entries nothing"));
+                    }
                 });
+                if self.tab == ResultTab::Charts {
+                    show_charts(ui, sum, &mut self.share_of, lang);
+                } else if self.tab == ResultTab::Malformed {
+                    show_malformed(ui, &self.malformed, sum.malformed_lines, &mut self.test_pattern, &mut self.test_line, lang);
+                } else {
+                    let points: Vec<[f64; 2]> = if self.pressure {
+                        PressureWeights::default().series(sum).iter().enumerate().map(|(i, (_, v))| [i as f64, *v]).collect()
+                    } else {
+                        sum.timeline.iter().enumerate().map(|(i, (_, v))| [i as f64, *v as f64]).collect()
+                    };
+
+                    let max_y = points.iter().map(|p| p[1]).fold(0.0, f64::max);
+                    let selected = self.drill.as_ref().map(|d| (d.first, d.last));
+                    // Incidents as bucket index ranges
+                    let incidents: Vec<_> = if self.mark_incidents {
+                        sum.incidents.iter().filter_map(|inc| {
+                            let a = sum.timeline.partition_point(|(b, _)| *b < inc.start);
+                            let b = sum.timeline.partition_point(|(b, _)| *b < inc.end).checked_sub(1)?;
+                            (a <= b).then_some((a, b, inc))
+                        }).collect()
+                    } else {
+                        Vec::new()
+                    };
+
+                    // Bucket start, total and per-level counts behind the axis labels and hover tooltip
+                    let fmt = sum.granularity.label_format();
+                    let buckets: Vec<(String, u64, Counts)> = sum.timeline.iter().zip(&sum.timeline_levels)
+                        .map(|((b, n), c)| (b.format(fmt).to_string(), *n, c.clone())).collect();
+                    let labels: Vec<String> = buckets.iter().map(|(b, _, _)| b.clone()).collect();
+                    let pressure = self.pressure;
+                    let weights = PressureWeights::default();
+
+                    // Click a point to select its bucket, or drag across a range; dragging doesn't pan
+                    let plot = Plot::new("timeline").view_aspect(3.0).allow_drag(false)
+                        // Label only integer marks, which are bucket indices
+                        .x_axis_formatter(move |mark, _| {
+                            let i = mark.value.round();
+                            if (mark.value - i).abs() > 1e-6 || i < 0.0 { return String::new(); }
+                            labels.get(i as usize).cloned().unwrap_or_default()
+                        })
+                        .label_formatter(move |_, p| {
+                            let i = p.x.round();
+                            let Some((b, n, c)) = (i >= 0.0).then(|| buckets.get(i as usize)).flatten() else { return String::new() };
+                            let mut text = format!("{b}\n{}\nINFO {}  WARNING {}  ERROR {}", lang.trf("{} entries", &[n]), c.info, c.warning, c.error);
+                            if pressure { text.push_str(&format!("\n{}", lang.trf("Pressure {}", &[&weights.pressure(c)]))); }
+                            text
+                        });
+                    let res = plot.show(ui, |pui| {
+                        if let Some((a, b)) = selected {
+                            let (x0, x1) = (a as f64 - 0.5, b as f64 + 0.5);
+                            let rect = vec![[x0, 0.0], [x1, 0.0], [x1, max_y], [x0, max_y]];
+                            pui.polygon(Polygon::new(PlotPoints::from(rect)).name("selection"));
+                        }
+                        let red = egui::Color32::from_rgb(220, 40, 40);
+                        for (a, b, inc) in &incidents {
+                            let (x0, x1) = (*a as f64 - 0.5, *b as f64 + 0.5);
+                            let rect = vec![[x0, 0.0], [x1, 0.0], [x1, max_y], [x0, max_y]];
+                            pui.polygon(Polygon::new(PlotPoints::from(rect)).name("incident").fill_color(red.gamma_multiply(0.15)).stroke(egui::Stroke::NONE));
+                            let label = RichText::new(lang.trf("{} errors", &[&inc.total_errors])).color(red).small();
+                            pui.text(Text::new(PlotPoint::new(x0, max_y), label).anchor(egui::Align2::LEFT_BOTTOM));
+                        }
+                        pui.line(Line::new(PlotPoints::from(points)));
+                        pui.pointer_coordinate().map(|p| p.x)
+                    });
+                    let x = res.inner;
+                    if res.response.drag_started() {
+                        self.drag_start = x;
+                    } else if res.response.drag_stopped() {
+                        if let (Some(a), Some(b)) = (self.drag_start.take(), x) { select = Some((a.min(b), a.max(b))); }
+                    } else if res.response.clicked() {
+                        select = x.map(|x| (x, x));
+                    }
+                }
             }
+
+            if let Some((a, b)) = select { self.drill_into(a, b); }
+            self.show_drill(ui);
         });
     }
 }
 
 impl GuiApp {
+    fn new(cc: &eframe::CreationContext<'_>, inputs: Vec<PathBuf>, max_memory: Option<usize>) -> Self {
+        let mut app: Self = cc.storage
+            .and_then(|s| eframe::get_value(s, eframe::APP_KEY))
+            .unwrap_or_default();
+        app.max_memory = max_memory;
+        let mut inputs = inputs.into_iter();
+        if let Some(first) = inputs.next() {
+            app.select_file(first);
+            app.extra_files = inputs.collect();
+            app.run_analysis();
+        }
+        app
+    }
+
+    fn select_file(&mut self, p: PathBuf) {
+        self.file = Some(p);
+        self.extra_files.clear();
+        self.preview_lines = None;
+        self.info_text.clear();
+        self.summary = None;
+        self.entries.clear();
+        self.malformed.clear();
+        self.drill = None;
+    }
+
+    fn save_session(&self, path: &std::path::Path) -> anyhow::Result<()> {
+        let session = Session {
+            version: SESSION_VERSION,
+            file: self.file.clone(),
+            keyword: self.keyword.clone(),
+            from: self.from.clone(),
+            to: self.to.clone(),
+            gran: self.gran,
+            custom_bucket: self.custom_bucket.clone(),
+            level: self.level,
+            summary: self.summary.clone(),
+            bucket_ranges: self.bucket_ranges.clone(),
+            entries: self.entries.clone(),
+            malformed: self.malformed.clone(),
+        };
+        let out = std::io::BufWriter::new(std::fs::File::create(path)?);
+        serde_json::to_writer(out, &session)?;
+        Ok(())
+    }
+
+    fn load_session(&mut self, path: &std::path::Path) -> anyhow::Result<()> {
+        let reader = std::io::BufReader::new(std::fs::File::open(path)?);
+        let s: Session = serde_json::from_reader(reader)?;
+        if s.version != SESSION_VERSION {
+            anyhow::bail!("unsupported session version {} (expected {SESSION_VERSION})", s.version);
+        }
+        if let Some(f) = &s.file { self.select_file(f.clone()); }
+        self.file = s.file;
+        self.keyword = s.keyword;
+        self.from = s.from;
+        self.to = s.to;
+        self.gran = s.gran;
+        self.custom_bucket = s.custom_bucket;
+        self.level = s.level;
+        self.summary = s.summary;
+        self.bucket_ranges = s.bucket_ranges;
+        self.entries = s.entries;
+        self.malformed = s.malformed;
+        self.info_text = self.lang.trf("Session loaded from {}", &[&path.display()]);
+        Ok(())
+    }
+
+    /// Put the analysis just run at the top of the Recent list
+    fn remember(&mut self) {
+        let Some(path) = self.file.clone() else { return };
+        let entry = RecentAnalysis {
+            path, keyword: self.keyword.clone(), from: self.from.clone(), to: self.to.clone(),
+            gran: self.gran, custom_bucket: self.custom_bucket.clone(), level: self.level,
+        };
+        self.recent.retain(|r| r.path != entry.path);
+        self.recent.insert(0, entry);
+        self.recent.truncate(MAX_RECENT);
+    }
+
+    /// Re-run the `i`-th recent analysis with its filters
+    fn reopen(&mut self, i: usize) {
+        let r = self.recent[i].clone();
+        self.select_file(r.path);
+        self.keyword = r.keyword;
+        self.from = r.from;
+        self.to = r.to;
+        self.gran = r.gran;
+        self.custom_bucket = r.custom_bucket;
+        self.level = r.level;
+        self.view = View::Analyze;
+        self.run_analysis();
+    }
+
+    /// Select the buckets under plot x range [a, b] and collect their entries
+    fn drill_into(&mut self, a: f64, b: f64) {
+        let Some(max_idx) = self.bucket_ranges.len().checked_sub(1) else { return };
+        let idx = |x: f64| (x.round().max(0.0) as usize).min(max_idx);
+        let (first, last) = (idx(a), idx(b));
+        let (start, end) = (self.bucket_ranges[first].0, self.bucket_ranges[last].1);
+        let rows: Vec<usize> = self.entries.iter().enumerate()
+            .filter(|(_, e)| e.ts >= start && e.ts < end)
+            .map(|(i, _)| i)
+            .collect();
+        let mut errors: HashMap<&str, u64> = HashMap::new();
+        for &i in &rows {
+            let e = &self.entries[i];
+            if e.level == Level::Error { *errors.entry(e.message.as_str()).or_default() += 1; }
+        }
+        let mut top_errors: Vec<(String, u64)> = errors.into_iter().map(|(m, n)| (m.to_string(), n)).collect();
+        top_errors.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        top_errors.truncate(10);
+        self.drill = Some(Drill { first, last, start, end, rows, top_errors });
+    }
+
+    fn show_drill(&mut self, ui: &mut egui::Ui) {
+        let Some(d) = &self.drill else { return };
+        let lang = self.lang;
+        ui.separator();
+        let mut clear = false;
+        ui.horizontal(|ui| {
+            ui.label(RichText::new(lang.trf(
+                "Selection: {} .. {}  ({} entries)",
+                &[&d.start.format("%Y-%m-%d %H:%M:%S"), &d.end.format("%Y-%m-%d %H:%M:%S"), &d.rows.len()],
+            )).strong());
+            clear = ui.button(lang.tr("Clear")).clicked();
+        });
+        if !d.top_errors.is_empty() {
+            ui.label(lang.tr("Top errors in selection:"));
+            for (msg, n) in &d.top_errors { ui.label(highlight_keyword(&format!("{n:>6}  "), msg, &self.keyword, self.highlight, ui)); }
+        }
+        let rows: Vec<usize> = d.rows.iter().copied().filter(|&i| self.show_levels.shows(self.entries[i].level)).collect();
+        ui.push_id("drill_entries", |ui| {
+            TableBuilder::new(ui)
+                .striped(true)
+                .max_scroll_height(300.0)
+                .column(Column::auto())
+                .column(Column::auto())
+                .column(Column::auto())
+                .column(Column::remainder())
+                .header(20.0, |mut h| {
+                    h.col(|ui| { ui.strong(lang.tr("Time")); });
+                    h.col(|ui| { ui.strong(lang.tr("Level")); });
+                    h.col(|ui| { ui.strong(lang.tr("Source")); });
+                    h.col(|ui| { ui.strong(lang.tr("Message")); });
+                })
+                .body(|body| {
+                    body.rows(18.0, rows.len(), |mut row| {
+                        let e = &self.entries[rows[row.index()]];
+                        row.col(|ui| { ui.label(e.ts.format("%Y-%m-%d %H:%M:%S").to_string()); });
+                        row.col(|ui| { ui.label(format!("{:?}", e.level)); });
+                        // File name and line; the full path on hover
+                        row.col(|ui| {
+                            if let Some(path) = &e.source {
+                                let name = path.file_name().map_or_else(|| path.to_string_lossy(), |n| n.to_string_lossy());
+                                ui.label(format!("{name}:{}", e.line_no)).on_hover_text(path.display().to_string());
+                            }
+                        });
+                        row.col(|ui| { ui.label(highlight_keyword("", &e.message, &self.keyword, self.highlight, ui)); });
+                    });
+                });
+        });
+        if clear { self.drill = None; }
+    }
+
+    /// Keyword and level filters from the filter row, over the time range `from`..`to`
+    fn filters(&self, from: &str, to: &str) -> Result<Filters, String> {
+        let f = Filters::from_cli(
+            Some(self.keyword.as_str()).filter(|s| !s.is_empty()),
+            Some(from).filter(|s| !s.is_empty()),
+            Some(to).filter(|s| !s.is_empty()),
+            match &self.level { LevelChoice::Only(l) => std::slice::from_ref(l), _ => &[] },
+        ).map_err(|e| self.lang.trf("Filter error: {}", &[&e]))?;
+        Ok(Filters { min_level: match self.level { LevelChoice::AtLeast(l) => Some(l), _ => None }, ..f })
+    }
+
+    /// The Parser window's pattern when enabled, else the built-in patterns
+    fn parser(&self) -> Result<Box<dyn LogParser>, String> {
+        if !self.use_custom_parser { return Ok(Box::new(DefaultLogParser::new())); }
+        let p = RegexParser::new(&self.parser_pattern, Some(&self.parser_ts_format)).map_err(|e| self.lang.trf("Parser error: {}", &[&e]))?;
+        Ok(Box::new(p))
+    }
+
+    /// Parser window: a custom regex and timestamp format, tried live on the first lines of the
+    /// selected file with the named groups highlighted.
+    fn show_parser_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_parser;
+        let lang = self.lang;
+        egui::Window::new(lang.tr("Parser")).id(egui::Id::new("parser_window")).open(&mut open).default_width(900.0).show(ctx, |ui| {
+            ui.label(lang.tr("Regex with named groups: ts (required), level, msg; any other group becomes a field."));
+            ui.add(egui::TextEdit::singleline(&mut self.parser_pattern)
+                .hint_text(r"e.g. ^(?P<ts>\S+ \S+) \[(?P<level>\w+)\] (?P<msg>.*)$")
+                .desired_width(f32::INFINITY).font(egui::TextStyle::Monospace));
+            ui.horizontal(|ui| {
+                ui.label(lang.tr("Timestamp format:"));
+                ui.add(egui::TextEdit::singleline(&mut self.parser_ts_format)
+                    .hint_text(lang.tr("e.g. %d/%m/%Y %H:%M:%S (empty: auto)")).font(egui::TextStyle::Monospace));
+                ui.checkbox(&mut self.use_custom_parser, lang.tr("Use for analysis"));
+            });
+            if self.parser_pattern.is_empty() { return; }
+            let mut parser = match RegexParser::new(&self.parser_pattern, Some(&self.parser_ts_format)) {
+                Ok(p) => p,
+                Err(e) => {
+                    ui.colored_label(egui::Color32::RED, e.to_string());
+                    return;
+                }
+            };
+            let Some(path) = &self.file else {
+                ui.label(lang.tr("Select a file to preview the pattern on its first lines"));
+                return;
+            };
+            let lines = self.preview_lines.get_or_insert_with(|| {
+                crate::input::open(path)
+                    .and_then(|r| Ok(std::io::BufRead::lines(r).take(PREVIEW_LINES).collect::<Result<Vec<_>, _>>()?))
+                    .unwrap_or_default()
+            });
+            ui.horizontal(|ui| {
+                ui.label(lang.tr("Groups:"));
+                for (i, name) in parser.regex().capture_names().flatten().enumerate() {
+                    ui.label(RichText::new(name).monospace().background_color(group_color(i)));
+                }
+            });
+            let parsed = lines.iter().filter(|l| matches!(parser.parse_line(l), Ok(Some(_)))).count();
+            ui.label(lang.trf("{} of the first {} lines parse", &[&parsed, &lines.len()]));
+            egui::ScrollArea::vertical().max_height(500.0).show(ui, |ui| {
+                egui::Grid::new("parser_preview").striped(true).show(ui, |ui| {
+                    for line in lines.iter() {
+                        match parser.parse_line(line) {
+                            Ok(Some(e)) => ui.colored_label(GOOD_COLOR, format!("{} {:?}", e.ts.format("%Y-%m-%d %H:%M:%S"), e.level)),
+                            Ok(None) => ui.weak(lang.tr("no match")),
+                            Err(reason) => ui.colored_label(egui::Color32::RED, reason),
+                        };
+                        ui.label(highlight_groups(parser.regex(), line, ui));
+                        ui.end_row();
+                    }
+                });
+            });
+        });
+        self.show_parser = open;
+    }
+
+    /// Unrecognized format window: sample lines that didn't parse, the delimiters and timestamps
+    /// found in them and a pattern to start the Parser window from
+    fn show_format_help(&mut self, ctx: &egui::Context) {
+        let Some(help) = &self.format_help else { return };
+        let lang = self.lang;
+        let mut open = true;
+        // Pattern and timestamp format to open the Parser window with
+        let mut to_parser: Option<(Option<String>, &'static str)> = None;
+        egui::Window::new(lang.tr("Unrecognized format")).id(egui::Id::new("format_help")).open(&mut open).default_width(800.0).show(ctx, |ui| {
+            ui.label(lang.trf("{}% of the lines didn't parse. A custom pattern in the Parser window can read them.", &[&format!("{:.0}", help.malformed_pct)]));
+            ui.add_space(6.0);
+            ui.strong(lang.tr("Sample lines"));
+            for line in help.samples.iter().take(HELP_SHOWN_LINES) { ui.monospace(line); }
+            ui.add_space(6.0);
+            ui.strong(lang.tr("Delimiters"));
+            if help.hints.delimiters.is_empty() { ui.weak(lang.tr("None found; fields seem separated by spaces")); }
+            for d in &help.hints.delimiters {
+                let sep = if d.sep == '\t' { lang.tr("Tab").to_string() } else { format!("'{}'", d.sep) };
+                ui.label(lang.trf("{} splits the lines into {} fields", &[&sep, &d.fields]));
+            }
+            ui.add_space(6.0);
+            ui.strong(lang.tr("Timestamp candidates"));
+            if help.hints.timestamps.is_empty() { ui.colored_label(egui::Color32::RED, lang.tr("No timestamp found; every entry needs one")); }
+            egui::Grid::new("timestamp_candidates").striped(true).show(ui, |ui| {
+                for t in &help.hints.timestamps {
+                    ui.monospace(&t.example);
+                    ui.monospace(if t.format.is_empty() { lang.tr("(auto)") } else { t.format });
+                    ui.label(lang.trf("{} of {} lines", &[&t.lines, &help.hints.lines]));
+                    ui.end_row();
+                }
+            });
+            ui.add_space(6.0);
+            match &help.hints.pattern {
+                Some((pattern, ts_format)) => {
+                    ui.strong(lang.tr("Suggested pattern"));
+                    ui.add(egui::Label::new(RichText::new(pattern).monospace()).wrap());
+                    ui.label(lang.trf("Parses {} of {} sample lines", &[&help.hints.parsed, &help.hints.lines]));
+                    if ui.button(lang.tr("Edit in the Parser window")).clicked() { to_parser = Some((Some(pattern.clone()), ts_format)); }
+                }
+                None => if ui.button(lang.tr("Open the Parser window")).clicked() {
+                    to_parser = Some((None, help.hints.timestamps.first().map_or("", |t| t.format)));
+                },
+            }
+        });
+        if let Some((pattern, ts_format)) = to_parser {
+            if let Some(p) = pattern {
+                self.parser_pattern = p;
+                self.use_custom_parser = true;
+            }
+            self.parser_ts_format = ts_format.to_string();
+            self.show_parser = true;
+            open = false;
+        }
+        if !open { self.format_help = None; }
+    }
+
+    /// Apply the interval typed for the Custom granularity
+    fn resolve_granularity(&mut self) -> Result<(), String> {
+        if matches!(self.gran, Granularity::Custom(_) | Granularity::Millis(_)) {
+            self.gran = Granularity::custom(&self.custom_bucket).map_err(|e| self.lang.trf("Granularity error: {}", &[&e]))?;
+        }
+        Ok(())
+    }
+
+    /// Analyze both Compare panels with the current filters
+    fn run_comparison(&mut self) {
+        if let Err(e) = self.resolve_granularity() {
+            self.info_text = e;
+            return;
+        }
+        for (i, name) in SIDE_NAMES.iter().enumerate() {
+            let side = &self.compare[i];
+            let Some(file) = side.file.clone().or_else(|| self.file.clone()) else {
+                self.info_text = self.lang.trf("Select a file for {}", &[name]);
+                return;
+            };
+            let from = if side.from.is_empty() { &self.from } else { &side.from };
+            let to = if side.to.is_empty() { &self.to } else { &side.to };
+            let (mut analyzer, mut parser) = match self.filters(from, to).and_then(|f| Ok((f, self.parser()?))) {
+                Ok((f, p)) => (Analyzer::new(self.gran, f), p),
+                Err(e) => {
+                    self.info_text = format!("{name}: {e}");
+                    return;
+                }
+            };
+            if let Err(e) = consume_path(&mut analyzer, &mut *parser, &file) {
+                self.info_text = self.lang.trf("{}: read error: {}", &[name, &e]);
+                return;
+            }
+            analyzer.settle_granularity();
+            let side = &mut self.compare[i];
+            side.summary = Some(summarize(&analyzer, &ReportOptions::default()));
+            side.errors = analyzer.error_messages.top(usize::MAX).into_iter().collect();
+        }
+        self.info_text.clear();
+    }
+
+    fn show_compare(&mut self, ui: &mut egui::Ui) {
+        let lang = self.lang;
+        ui.separator();
+        ui.columns(2, |cols| {
+            for (i, ui) in cols.iter_mut().enumerate() {
+                let side = &mut self.compare[i];
+                ui.label(RichText::new(SIDE_NAMES[i]).strong());
+                ui.horizontal(|ui| {
+                    if ui.button(lang.tr("File...")).clicked() {
+                        if let Some(p) = FileDialog::new().add_filter("Log", &["log"]).pick_file() { side.file = Some(p); }
+                    }
+                    match &side.file {
+                        Some(f) => {
+                            ui.label(f.display().to_string());
+                            if ui.small_button("x").on_hover_text(lang.tr("Use the file selected above")).clicked() { side.file = None; }
+                        }
+                        None => { ui.weak(lang.tr("(file selected above)")); }
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.add(egui::TextEdit::singleline(&mut side.from).hint_text(lang.tr("from (default: filter row)")));
+                    ui.add(egui::TextEdit::singleline(&mut side.to).hint_text(lang.tr("to")));
+                });
+            }
+        });
+        if ui.button(lang.tr("Compare")).clicked() { self.run_comparison(); }
+
+        let [a, b] = &self.compare;
+        let (Some(sa), Some(sb)) = (&a.summary, &b.summary) else { return };
+        ui.separator();
+        let rate = |s: &Summary| if s.total_entries == 0 { 0.0 } else { s.count(Level::Error) as f64 * 100.0 / s.total_entries as f64 };
+        // (metric, A, B, whether an increase is bad)
+        let rows = [
+            ("Total entries", sa.total_entries, sb.total_entries, false),
+            ("INFO", sa.count(Level::Info), sb.count(Level::Info), false),
+            ("WARNING", sa.count(Level::Warning), sb.count(Level::Warning), true),
+            ("ERROR", sa.count(Level::Error), sb.count(Level::Error), true),
+            ("Malformed", sa.malformed_lines, sb.malformed_lines, true),
+        ];
+        egui::Grid::new("compare_counts").striped(true).num_columns(4).show(ui, |ui| {
+            for h in ["", "A", "B", "Change"] { ui.strong(lang.tr(h)); }
+            ui.end_row();
+            for (name, x, y, bad_up) in rows {
+                ui.label(lang.tr(name));
+                ui.label(x.to_string());
+                ui.label(y.to_string());
+                ui.label(delta_text(x, y, bad_up, lang));
+                ui.end_row();
+            }
+            let (ra, rb) = (rate(sa), rate(sb));
+            ui.label(lang.tr("Error rate"));
+            ui.label(format!("{ra:.2}%"));
+            ui.label(format!("{rb:.2}%"));
+            let text = format!("{:+.2} pp", rb - ra);
+            ui.label(if rb > ra { RichText::new(text).color(LEVEL_COLORS[2].1) } else if rb < ra { RichText::new(text).color(GOOD_COLOR) } else { RichText::new(text) });
+            ui.end_row();
+            let stamp = |t: Option<NaiveDateTime>| t.map_or("-".to_string(), |t| t.format("%Y-%m-%d %H:%M:%S").to_string());
+            for (name, x, y) in [("First", sa.first_log, sb.first_log), ("Last", sa.last_log, sb.last_log)] {
+                ui.label(lang.tr(name));
+                ui.label(stamp(x));
+                ui.label(stamp(y));
+                ui.label("");
+                ui.end_row();
+            }
+        });
+
+        // Union of both top lists, biggest change first
+        let mut messages: Vec<&str> = sa.common_errors.iter().chain(&sb.common_errors).map(|(m, _)| m.as_str()).collect();
+        messages.sort_unstable();
+        messages.dedup();
+        let count = |errors: &HashMap<String, u64>, m: &str| errors.get(m).copied().unwrap_or(0);
+        messages.sort_by_key(|m| std::cmp::Reverse(count(&b.errors, m).abs_diff(count(&a.errors, m))));
+        ui.add_space(8.0);
+        ui.label(RichText::new(lang.tr("Top errors")).strong());
+        ui.push_id("compare_errors", |ui| {
+            TableBuilder::new(ui)
+                .striped(true)
+                .column(Column::auto())
+                .column(Column::auto())
+                .column(Column::auto())
+                .column(Column::remainder())
+                .header(20.0, |mut h| {
+                    for t in ["A", "B", "Change", "Message"] { h.col(|ui| { ui.strong(lang.tr(t)); }); }
+                })
+                .body(|mut body| {
+                    for m in messages {
+                        let (x, y) = (count(&a.errors, m), count(&b.errors, m));
+                        body.row(20.0, |mut row| {
+                            row.col(|ui| { ui.label(x.to_string()); });
+                            row.col(|ui| { ui.label(y.to_string()); });
+                            row.col(|ui| { ui.label(delta_text(x, y, true, lang)); });
+                            row.col(|ui| { ui.label(m); });
+                        });
+                    }
+                });
+        });
+    }
+
     fn run_analysis(&mut self) {
         if self.file.is_none() {
-            self.info_text = "Select a .log file first".into();
+            self.info_text = self.lang.tr("Select a .log file or folder first").into();
             return;
         }
-        let filters = match Filters::from_cli(
-            Some(self.keyword.as_str()).filter(|s| !s.is_empty()),
-            if self.from.is_empty() {
-                None
-            } else {
-                Some(self.from.as_str())
-            },
-            if self.to.is_empty() {
-                None
-            } else {
-                Some(self.to.as_str())
-            },
-            None,
-        ) {
+        let filters = match self.filters(&self.from, &self.to).and_then(|f| self.resolve_granularity().map(|_| f)) {
             Ok(f) => f,
             Err(e) => {
-                self.info_text = format!("Filter error: {e}");
+                self.info_text = e;
+                return;
+            }
+        };
+        let mut parser = match self.parser() {
+            Ok(p) => p,
+            Err(e) => {
+                self.info_text = e;
                 return;
             }
         };
-        let mut parser = DefaultLogParser::new();
-        let mut analyzer = Analyzer::new(self.gran);
-        if let Err(e) = analyzer.consume_file(&mut parser, self.file.as_ref().unwrap()) {
-            self.info_text = format!("Read error: {e}");
+        let mut analyzer = Analyzer::new(self.gran, filters);
+        analyzer.retain_entries = Some(MAX_RETAINED_ENTRIES);
+        analyzer.retain_malformed = MAX_RETAINED_MALFORMED;
+        let mut paths = self.file.iter().chain(&self.extra_files);
+        let consumed = self.max_memory.map(Spill::new).transpose()
+            .and_then(|spill| {
+                analyzer.spill = spill;
+                paths.try_for_each(|p| consume_path(&mut analyzer, &mut *parser, p))
+            })
+            .and_then(|()| analyzer.finish_spill());
+        if let Err(e) = consumed {
+            self.info_text = self.lang.trf("Read error: {}", &[&e]);
             return;
         }
-        self.summary = Some(build_summary(&analyzer, &filters));
+        self.remember();
+        analyzer.settle_granularity();
+        self.summary = Some(summarize(&analyzer, &ReportOptions { top_errors: Some(MAX_TOP_ERRORS), ..Default::default() }));
+        self.bucket_ranges = analyzer.timeline.keys().map(|&b| (b, analyzer.granularity.next_bucket(b))).collect();
+        self.entries = analyzer.entries;
+        self.malformed = analyzer.malformed;
+        self.drill = None;
+        let parsed_lines = analyzer.lines_sampled.saturating_sub(analyzer.lines_prefiltered);
+        self.format_help = (parsed_lines > 0 && analyzer.malformed_lines as f64 > parsed_lines as f64 * MALFORMED_HELP_SHARE).then(|| {
+            let samples: Vec<String> = self.malformed.iter().take(HELP_SAMPLE_LINES).map(|m| m.text.clone()).collect();
+            let hints = suggest_format(&samples.iter().map(String::as_str).collect::<Vec<_>>());
+            FormatHelp { malformed_pct: analyzer.malformed_lines as f64 * 100.0 / parsed_lines as f64, samples, hints }
+        });
+    }
+}
+
+/// Feed a file, or every `.log` file under a directory, into `analyzer`
+fn consume_path(analyzer: &mut Analyzer, parser: &mut dyn LogParser, path: &Path) -> anyhow::Result<()> {
+    let files = gather_log_files(&[path.to_path_buf()], &DiscoveryOptions::default())?;
+    if files.is_empty() { anyhow::bail!("no .log files in {}", path.display()); }
+    for f in &files { analyzer.consume_file(parser, f)?; }
+    Ok(())
+}
+
+/// One button per recent analysis; returns the index of the one clicked
+fn recent_list(recent: &[RecentAnalysis], ui: &mut egui::Ui, lang: Lang) -> Option<usize> {
+    let mut clicked = None;
+    for (i, r) in recent.iter().enumerate() {
+        let hover = lang.trf("{}\nClick to analyze again with these filters", &[&r.path.display()]);
+        if ui.button(r.label(lang)).on_hover_text(hover).clicked() { clicked = Some(i); }
+    }
+    clicked
+}
+
+/// Background of the `i`-th named group in the Parser window
+fn group_color(i: usize) -> egui::Color32 {
+    SHARE_COLORS[i % (SHARE_COLORS.len() - 1)].gamma_multiply(0.45)
+}
+
+/// `line` in monospace with the spans of `re`'s named groups on their [`group_color`]
+fn highlight_groups(re: &regex::Regex, line: &str, ui: &egui::Ui) -> egui::text::LayoutJob {
+    let font = egui::TextStyle::Monospace.resolve(ui.style());
+    let plain = egui::TextFormat { font_id: font, color: ui.visuals().text_color(), ..Default::default() };
+    let mut job = egui::text::LayoutJob::default();
+    let mut spans: Vec<(usize, usize, usize)> = match re.captures(line) {
+        Some(caps) => re.capture_names().flatten().enumerate()
+            .filter_map(|(i, name)| caps.name(name).map(|m| (m.start(), m.end(), i)))
+            .collect(),
+        None => Vec::new(),
+    };
+    spans.sort();
+    let mut pos = 0;
+    // Nested groups: the outer one wins
+    for (start, end, i) in spans.into_iter().filter(|(start, end, _)| end > start) {
+        if start < pos { continue; }
+        job.append(&line[pos..start], 0.0, plain.clone());
+        job.append(&line[start..end], 0.0, egui::TextFormat { background: group_color(i), ..plain.clone() });
+        pos = end;
+    }
+    job.append(&line[pos..], 0.0, plain);
+    job
+}
+
+/// `prefix` then `text` with its case-insensitive (ASCII) matches of `keyword` on `color`
+fn highlight_keyword(prefix: &str, text: &str, keyword: &str, color: HighlightColor, ui: &egui::Ui) -> egui::text::LayoutJob {
+    let font = egui::TextStyle::Body.resolve(ui.style());
+    let plain = egui::TextFormat { font_id: font, color: ui.visuals().text_color(), ..Default::default() };
+    let marked = egui::TextFormat {
+        background: egui::Color32::from_rgb(color.0[0], color.0[1], color.0[2]),
+        color: egui::Color32::BLACK,
+        ..plain.clone()
+    };
+    let mut job = egui::text::LayoutJob::default();
+    job.append(prefix, 0.0, plain.clone());
+    let keyword = keyword.trim().to_ascii_lowercase();
+    let mut pos = 0;
+    if !keyword.is_empty() {
+        // ASCII lowercasing keeps byte offsets, so matches index `text` directly
+        for (start, m) in text.to_ascii_lowercase().match_indices(&keyword) {
+            job.append(&text[pos..start], 0.0, plain.clone());
+            job.append(&text[start..start + m.len()], 0.0, marked.clone());
+            pos = start + m.len();
+        }
+    }
+    job.append(&text[pos..], 0.0, plain);
+    job
+}
+
+/// Decreases of "bad" counts
+const GOOD_COLOR: egui::Color32 = egui::Color32::from_rgb(60, 160, 80);
+
+/// `+12 (+50%)`, `new` or `gone`, colored when `bad_up` (red for more, green for fewer)
+fn delta_text(a: u64, b: u64, bad_up: bool, lang: Lang) -> RichText {
+    let text = match (a, b) {
+        _ if a == b => return RichText::new("="),
+        (0, _) => lang.tr("new").to_string(),
+        (_, 0) => lang.tr("gone").to_string(),
+        _ => format!("{:+} ({:+.0}%)", b as i64 - a as i64, (b as f64 - a as f64) * 100.0 / a as f64),
+    };
+    match (bad_up, b > a) {
+        (false, _) => RichText::new(text),
+        (true, true) => RichText::new(text).color(LEVEL_COLORS[2].1).strong(),
+        (true, false) => RichText::new(text).color(GOOD_COLOR),
+    }
+}
+
+/// Malformed tab: unparsed lines with their failure reason, and a box for trying out a regex
+/// (named groups `ts`, `level`, `msg` like the built-in patterns) against one of them.
+fn show_malformed(ui: &mut egui::Ui, lines: &[MalformedLine], total: u64, pattern: &mut String, test_line: &mut String, lang: Lang) {
+    ui.group(|ui| {
+        ui.label(RichText::new(lang.tr("Test custom pattern")).strong());
+        ui.add(egui::TextEdit::singleline(pattern).hint_text(r"e.g. ^(?P<ts>\S+ \S+) (?P<level>\w+) (?P<msg>.*)$").desired_width(f32::INFINITY).font(egui::TextStyle::Monospace));
+        ui.add(egui::TextEdit::singleline(test_line).hint_text(lang.tr("line to test (use \"Test\" on a row below)")).desired_width(f32::INFINITY).font(egui::TextStyle::Monospace));
+        if !pattern.is_empty() {
+            match regex::Regex::new(pattern) {
+                Err(e) => { ui.colored_label(egui::Color32::RED, lang.trf("Invalid regex: {}", &[&e])); }
+                Ok(re) => match re.captures(test_line) {
+                    None => { ui.colored_label(egui::Color32::from_rgb(230, 160, 30), lang.tr("No match")); }
+                    Some(caps) => {
+                        ui.colored_label(egui::Color32::from_rgb(60, 170, 60), lang.tr("Match"));
+                        for name in re.capture_names().flatten() {
+                            let value = caps.name(name).map_or(lang.tr("(not captured)"), |m| m.as_str());
+                            ui.monospace(format!("  {name} = {value}"));
+                        }
+                    }
+                },
+            }
+        }
+    });
+    if lines.len() as u64 != total {
+        ui.label(lang.trf("Showing the first {} of {} malformed lines", &[&lines.len(), &total]));
     }
+    let mut picked = None;
+    ui.push_id("malformed_lines", |ui| {
+        TableBuilder::new(ui)
+            .striped(true)
+            .max_scroll_height(400.0)
+            .column(Column::auto())
+            .column(Column::auto())
+            .column(Column::auto())
+            .column(Column::auto())
+            .column(Column::remainder())
+            .header(20.0, |mut h| {
+                for title in ["", "File", "Line", "Reason", "Text"] { h.col(|ui| { ui.strong(lang.tr(title)); }); }
+            })
+            .body(|body| {
+                body.rows(20.0, lines.len(), |mut row| {
+                    let m = &lines[row.index()];
+                    row.col(|ui| { if ui.small_button(lang.tr("Test")).clicked() { picked = Some(m.text.clone()); } });
+                    row.col(|ui| { ui.label(&m.source); });
+                    row.col(|ui| { ui.label(m.line_no.to_string()); });
+                    row.col(|ui| { ui.label(&m.reason); });
+                    row.col(|ui| { ui.monospace(&m.text); });
+                });
+            });
+    });
+    if let Some(text) = picked { *test_line = text; }
 }
 
-// Assuming Granularity is defined in another module, implement PartialEq for it.
-impl PartialEq for Granularity {
-    fn eq(&self, other: &Self) -> bool {
-        // Add logic to compare variants of Granularity
-        std::mem::discriminant(self) == std::mem::discriminant(other)
+/// Charts tab: per-level bar chart next to a donut of level (or top error) share.
+fn show_charts(ui: &mut egui::Ui, sum: &Summary, share_of: &mut ShareOf, lang: Lang) {
+    let counts = [Level::Info, Level::Warning, Level::Error].map(|l| sum.count(l));
+    ui.horizontal(|ui| {
+        ui.label(lang.tr("Share of:"));
+        ui.selectable_value(share_of, ShareOf::Levels, lang.tr("Levels"));
+        ui.selectable_value(share_of, ShareOf::TopErrors, lang.tr("Top errors"));
+    });
+    ui.columns(2, |cols| {
+        Plot::new("level_bars")
+            .view_aspect(1.5)
+            .legend(Legend::default())
+            .allow_drag(false)
+            .allow_zoom(false)
+            .allow_scroll(false)
+            // Label only the integer marks under the bars
+            .x_axis_formatter(|mark, _| {
+                let i = mark.value.round();
+                if (mark.value - i).abs() < 1e-6 && (0.0..3.0).contains(&i) { LEVEL_COLORS[i as usize].0.to_string() } else { String::new() }
+            })
+            .show(&mut cols[0], |pui| {
+                for (i, ((name, color), n)) in LEVEL_COLORS.into_iter().zip(counts).enumerate() {
+                    let bar = Bar::new(i as f64, n as f64).width(0.6).fill(color);
+                    pui.bar_chart(BarChart::new(vec![bar]).color(color).name(name));
+                }
+            });
+
+        let slices: Vec<(String, u64, egui::Color32)> = match share_of {
+            ShareOf::Levels => LEVEL_COLORS.into_iter().zip(counts).map(|((name, c), n)| (name.to_string(), n, c)).collect(),
+            ShareOf::TopErrors => {
+                let top = &sum.common_errors[..sum.common_errors.len().min(SHARE_COLORS.len() - 1)];
+                let mut v: Vec<_> = top.iter().zip(SHARE_COLORS).map(|((m, n), c)| (m.clone(), *n, c)).collect();
+                let other = sum.count(Level::Error).saturating_sub(top.iter().map(|(_, n)| n).sum());
+                if other > 0 { v.push((lang.tr("other errors").into(), other, SHARE_COLORS[SHARE_COLORS.len() - 1])); }
+                v
+            }
+        };
+        Plot::new("share_donut")
+            .view_aspect(1.5)
+            .data_aspect(1.0)
+            .legend(Legend::default())
+            .show_axes(false)
+            .show_grid(false)
+            .allow_drag(false)
+            .allow_zoom(false)
+            .allow_scroll(false)
+            .show(&mut cols[1], |pui| donut(pui, &slices));
+    });
+}
+
+/// Draw slices clockwise from 12 o'clock; each wedge is split into thin convex quads
+/// because plot polygons are filled as convex shapes.
+fn donut(pui: &mut PlotUi, slices: &[(String, u64, egui::Color32)]) {
+    let total: u64 = slices.iter().map(|(_, n, _)| n).sum();
+    if total == 0 { return; }
+    let pt = |r: f64, a: f64| [r * a.cos(), r * a.sin()];
+    let mut start = std::f64::consts::FRAC_PI_2;
+    for (name, n, color) in slices.iter().filter(|(_, n, _)| *n > 0) {
+        let sweep = *n as f64 / total as f64 * std::f64::consts::TAU;
+        let steps = (sweep / 0.05).ceil().max(1.0) as usize;
+        let label = format!("{name} ({:.1}%)", *n as f64 * 100.0 / total as f64);
+        for i in 0..steps {
+            let a0 = start - sweep * i as f64 / steps as f64;
+            let a1 = start - sweep * (i + 1) as f64 / steps as f64;
+            let quad = vec![pt(1.0, a0), pt(1.0, a1), pt(0.55, a1), pt(0.55, a0)];
+            pui.polygon(Polygon::new(PlotPoints::from(quad)).name(&label).fill_color(*color).stroke(egui::Stroke::new(1.0, *color)));
+        }
+        start -= sweep;
     }
 }
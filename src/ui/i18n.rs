@@ -0,0 +1,196 @@
+//! GUI strings in English, Russian and German. The English text is the key, so a string missing
+//! from the table still shows up, in English.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::LazyLock;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Lang { #[default] En, Ru, De }
+
+impl Lang {
+    pub const ALL: [Lang; 3] = [Lang::En, Lang::Ru, Lang::De];
+
+    /// The language's own name, for the selector
+    pub fn name(self) -> &'static str {
+        match self { Lang::En => "English", Lang::Ru => "Русский", Lang::De => "Deutsch" }
+    }
+
+    /// `en` in this language
+    pub fn tr(self, en: &'static str) -> &'static str {
+        match (self, TABLE.get(en)) {
+            (Lang::Ru, Some((ru, _))) => ru,
+            (Lang::De, Some((_, de))) => de,
+            _ => en,
+        }
+    }
+
+    /// [`tr`](Self::tr) with each `{}` replaced by the next of `args`
+    pub fn trf(self, en: &'static str, args: &[&dyn Display]) -> String {
+        let mut parts = self.tr(en).split("{}");
+        let mut out = parts.next().unwrap_or_default().to_string();
+        for (part, arg) in parts.zip(args.iter().map(|a| a.to_string()).chain(std::iter::repeat(String::new()))) {
+            out.push_str(&arg);
+            out.push_str(part);
+        }
+        out
+    }
+}
+
+static TABLE: LazyLock<HashMap<&'static str, (&'static str, &'static str)>> =
+    LazyLock::new(|| STRINGS.iter().map(|&(en, ru, de)| (en, (ru, de))).collect());
+
+/// (English, Russian, German); `{}` marks an argument of [`Lang::trf`], in the same order in every language
+const STRINGS: &[(&str, &str, &str)] = &[
+    // Top bar
+    ("Log File Analyzer", "Анализатор логов", "Log-Analyse"),
+    ("Analyze", "Анализ", "Analysieren"),
+    ("Compare", "Сравнение", "Vergleich"),
+    ("Theme", "Тема", "Design"),
+    ("System", "Системная", "System"),
+    ("Light", "Светлая", "Hell"),
+    ("Dark", "Тёмная", "Dunkel"),
+    ("Language", "Язык", "Sprache"),
+    // Inputs and sessions
+    ("Open .log...", "Открыть .log...", ".log öffnen..."),
+    ("Open folder...", "Открыть папку...", "Ordner öffnen..."),
+    ("Analyze every .log file under a directory", "Проанализировать все .log-файлы в папке", "Alle .log-Dateien eines Verzeichnisses analysieren"),
+    ("Recent", "Недавние", "Zuletzt"),
+    ("Clear list", "Очистить список", "Liste leeren"),
+    (" (+{} more)", " (и ещё {})", " (+{} weitere)"),
+    ("Parser: custom", "Парсер: свой", "Parser: eigener"),
+    ("Parser: built-in", "Парсер: встроенный", "Parser: integriert"),
+    ("Save session...", "Сохранить сессию...", "Sitzung speichern..."),
+    ("Open session...", "Открыть сессию...", "Sitzung öffnen..."),
+    ("Session saved to {}", "Сессия сохранена в {}", "Sitzung gespeichert unter {}"),
+    ("Session save error: {}", "Ошибка сохранения сессии: {}", "Fehler beim Speichern der Sitzung: {}"),
+    ("Session open error: {}", "Ошибка открытия сессии: {}", "Fehler beim Öffnen der Sitzung: {}"),
+    ("Session loaded from {}", "Сессия загружена из {}", "Sitzung geladen aus {}"),
+    ("Recent analyses", "Недавние анализы", "Letzte Analysen"),
+    ("{}\nClick to analyze again with these filters", "{}\nНажмите, чтобы повторить анализ с этими фильтрами", "{}\nKlicken, um mit diesen Filtern erneut zu analysieren"),
+    ("keyword \"{}\"", "ключевое слово \"{}\"", "Stichwort \"{}\""),
+    ("from {}", "с {}", "ab {}"),
+    ("to {}", "по {}", "bis {}"),
+    ("Select a .log file or folder first", "Сначала выберите .log-файл или папку", "Zuerst eine .log-Datei oder einen Ordner wählen"),
+    ("Read error: {}", "Ошибка чтения: {}", "Lesefehler: {}"),
+    // Filter row
+    ("keyword", "ключевое слово", "Stichwort"),
+    ("from: YYYY-MM-DD HH:MM:SS", "с: ГГГГ-ММ-ДД ЧЧ:ММ:СС", "ab: JJJJ-MM-TT HH:MM:SS"),
+    ("to:   YYYY-MM-DD HH:MM:SS", "по: ГГГГ-ММ-ДД ЧЧ:ММ:СС", "bis: JJJJ-MM-TT HH:MM:SS"),
+    ("Level", "Уровень", "Level"),
+    ("All levels", "Все уровни", "Alle Level"),
+    ("INFO only", "Только INFO", "Nur INFO"),
+    ("WARNING only", "Только WARNING", "Nur WARNING"),
+    ("ERROR only", "Только ERROR", "Nur ERROR"),
+    ("INFO and above", "INFO и выше", "INFO und höher"),
+    ("WARNING and above", "WARNING и выше", "WARNING und höher"),
+    ("ERROR and above", "ERROR и выше", "ERROR und höher"),
+    ("Granularity", "Интервал", "Intervall"),
+    ("Auto", "Авто", "Auto"),
+    ("Second", "Секунда", "Sekunde"),
+    ("Minute", "Минута", "Minute"),
+    ("Hour", "Час", "Stunde"),
+    ("Day", "День", "Tag"),
+    ("Week", "Неделя", "Woche"),
+    ("Month", "Месяц", "Monat"),
+    ("Custom", "Свой", "Eigenes"),
+    ("e.g. 15m", "напр. 15m", "z. B. 15m"),
+    ("Filter error: {}", "Ошибка фильтра: {}", "Filterfehler: {}"),
+    ("Granularity error: {}", "Ошибка интервала: {}", "Intervallfehler: {}"),
+    // Results
+    ("Summary", "Сводка", "Zusammenfassung"),
+    ("Copy as Markdown", "Копировать как Markdown", "Als Markdown kopieren"),
+    ("Copy as JSON", "Копировать как JSON", "Als JSON kopieren"),
+    ("Summary copied to the clipboard", "Сводка скопирована в буфер обмена", "Zusammenfassung in die Zwischenablage kopiert"),
+    ("Copy error: {}", "Ошибка копирования: {}", "Fehler beim Kopieren: {}"),
+    ("Total: {} | Malformed: {}", "Всего: {} | Нераспознанных: {}", "Gesamt: {} | Fehlerhaft: {}"),
+    ("First: {}", "Первая: {}", "Erste: {}"),
+    ("Last:  {}", "Последняя: {}", "Letzte: {}"),
+    ("Show:", "Показать:", "Anzeigen:"),
+    ("Keyword highlight:", "Подсветка ключевого слова:", "Stichwort-Markierung:"),
+    ("Top Errors", "Частые ошибки", "Häufigste Fehler"),
+    ("Filter messages", "Фильтр сообщений", "Meldungen filtern"),
+    ("Count", "Количество", "Anzahl"),
+    ("No error matches the filter", "Нет ошибок, подходящих под фильтр", "Kein Fehler passt zum Filter"),
+    ("Timeline", "Хронология", "Zeitverlauf"),
+    ("Charts", "Графики", "Diagramme"),
+    ("Malformed ({})", "Нераспознанные ({})", "Fehlerhaft ({})"),
+    ("Mark incidents ({})", "Отметить инциденты ({})", "Vorfälle markieren ({})"),
+    ("{} errors", "ошибок: {}", "{} Fehler"),
+    ("Error pressure", "Нагрузка ошибок", "Fehlerdruck"),
+    ("{} entries", "записей: {}", "{} Einträge"),
+    ("Pressure {}", "Нагрузка: {}", "Druck {}"),
+    ("Errors weigh 5, warnings 2 and info entries nothing", "Ошибка весит 5, предупреждение 2, информационная запись 0", "Fehler zählen 5, Warnungen 2 und Info-Einträge nichts"),
+    ("Selection: {} .. {}  ({} entries)", "Выделено: {} .. {}  (записей: {})", "Auswahl: {} .. {}  ({} Einträge)"),
+    ("Clear", "Сбросить", "Zurücksetzen"),
+    ("Top errors in selection:", "Частые ошибки в выделении:", "Häufigste Fehler in der Auswahl:"),
+    ("Time", "Время", "Zeit"),
+    ("Message", "Сообщение", "Meldung"),
+    ("Source", "Источник", "Quelle"),
+    // Parser window
+    ("Parser", "Парсер", "Parser"),
+    ("Parser error: {}", "Ошибка парсера: {}", "Parserfehler: {}"),
+    ("Regex with named groups: ts (required), level, msg; any other group becomes a field.",
+        "Регулярное выражение с именованными группами: ts (обязательно), level, msg; остальные группы становятся полями.",
+        "Regex mit benannten Gruppen: ts (erforderlich), level, msg; jede weitere Gruppe wird ein Feld."),
+    ("Timestamp format:", "Формат времени:", "Zeitstempelformat:"),
+    ("e.g. %d/%m/%Y %H:%M:%S (empty: auto)", "напр. %d/%m/%Y %H:%M:%S (пусто: авто)", "z. B. %d/%m/%Y %H:%M:%S (leer: automatisch)"),
+    ("Use for analysis", "Использовать для анализа", "Für die Analyse verwenden"),
+    ("Select a file to preview the pattern on its first lines", "Выберите файл, чтобы проверить шаблон на его первых строках", "Datei wählen, um das Muster an ihren ersten Zeilen zu testen"),
+    ("Groups:", "Группы:", "Gruppen:"),
+    ("{} of the first {} lines parse", "Распознано {} из первых {} строк", "{} der ersten {} Zeilen erkannt"),
+    ("no match", "нет совпадения", "kein Treffer"),
+    // Unrecognized format window
+    ("Unrecognized format", "Нераспознанный формат", "Unbekanntes Format"),
+    ("{}% of the lines didn't parse. A custom pattern in the Parser window can read them.",
+        "{}% строк не распознано. Их можно прочитать собственным шаблоном в окне парсера.",
+        "{}% der Zeilen wurden nicht erkannt. Ein eigenes Muster im Parser-Fenster kann sie lesen."),
+    ("Sample lines", "Примеры строк", "Beispielzeilen"),
+    ("Delimiters", "Разделители", "Trennzeichen"),
+    ("None found; fields seem separated by spaces", "Не найдены; поля, похоже, разделены пробелами", "Keine gefunden; Felder scheinen durch Leerzeichen getrennt"),
+    ("Tab", "Табуляция", "Tabulator"),
+    ("{} splits the lines into {} fields", "{} делит строки на {} полей", "{} teilt die Zeilen in {} Felder"),
+    ("Timestamp candidates", "Возможные метки времени", "Mögliche Zeitstempel"),
+    ("No timestamp found; every entry needs one", "Метка времени не найдена; она нужна каждой записи", "Kein Zeitstempel gefunden; jeder Eintrag braucht einen"),
+    ("(auto)", "(авто)", "(automatisch)"),
+    ("{} of {} lines", "{} из {} строк", "{} von {} Zeilen"),
+    ("Suggested pattern", "Предлагаемый шаблон", "Vorgeschlagenes Muster"),
+    ("Parses {} of {} sample lines", "Распознаёт {} из {} примеров", "Erkennt {} von {} Beispielzeilen"),
+    ("Edit in the Parser window", "Изменить в окне парсера", "Im Parser-Fenster bearbeiten"),
+    ("Open the Parser window", "Открыть окно парсера", "Parser-Fenster öffnen"),
+    // Compare view
+    ("Select a file for {}", "Выберите файл для {}", "Datei für {} wählen"),
+    ("{}: read error: {}", "{}: ошибка чтения: {}", "{}: Lesefehler: {}"),
+    ("File...", "Файл...", "Datei..."),
+    ("Use the file selected above", "Использовать файл, выбранный выше", "Oben gewählte Datei verwenden"),
+    ("(file selected above)", "(файл, выбранный выше)", "(oben gewählte Datei)"),
+    ("from (default: filter row)", "с (по умолчанию: из фильтров)", "ab (Standard: Filterzeile)"),
+    ("to", "по", "bis"),
+    ("Total entries", "Всего записей", "Einträge gesamt"),
+    ("Malformed", "Нераспознанные", "Fehlerhaft"),
+    ("Change", "Изменение", "Änderung"),
+    ("Error rate", "Доля ошибок", "Fehlerquote"),
+    ("First", "Первая", "Erste"),
+    ("Last", "Последняя", "Letzte"),
+    ("Top errors", "Частые ошибки", "Häufigste Fehler"),
+    ("new", "новая", "neu"),
+    ("gone", "исчезла", "weg"),
+    // Malformed tab
+    ("Test custom pattern", "Проверка своего шаблона", "Eigenes Muster testen"),
+    ("line to test (use \"Test\" on a row below)", "строка для проверки (кнопка \"Проверить\" в строке ниже)", "zu testende Zeile (\"Testen\" in einer Zeile unten)"),
+    ("Invalid regex: {}", "Неверное регулярное выражение: {}", "Ungültiger Regex: {}"),
+    ("No match", "Нет совпадения", "Kein Treffer"),
+    ("Match", "Совпадение", "Treffer"),
+    ("(not captured)", "(не захвачено)", "(nicht erfasst)"),
+    ("Showing the first {} of {} malformed lines", "Показаны первые {} из {} нераспознанных строк", "Die ersten {} von {} fehlerhaften Zeilen"),
+    ("File", "Файл", "Datei"),
+    ("Line", "Строка", "Zeile"),
+    ("Reason", "Причина", "Grund"),
+    ("Text", "Текст", "Text"),
+    ("Test", "Проверить", "Testen"),
+    // Charts tab
+    ("Share of:", "Доля:", "Anteil nach:"),
+    ("Levels", "Уровни", "Level"),
+    ("other errors", "прочие ошибки", "andere Fehler"),
+];
@@ -1,20 +1,164 @@
+use crate::model::Level;
 use crate::analyze::Granularity;
-use crate::report::JsonSummary;
+use crate::report::{Counts, HistogramStats, Summary};
 use anyhow::Result;
+use chrono::{Datelike, Duration, NaiveDateTime, Weekday};
+use plotters::coord::types::RangedCoordf64;
 use plotters::prelude::*;
+use plotters::style::text_anchor::{HPos, Pos, VPos};
+use std::path::PathBuf;
 
-pub fn save_level_barchart(path: &std::path::Path, summary: &JsonSummary) -> Result<()> {
-    let root = BitMapBackend::new(path, (900, 500)).into_drawing_area();
+#[derive(Clone, Copy, Debug, Default)]
+pub enum Palette {
+    #[default]
+    Default,
+    /// Okabe-Ito colors, distinguishable with common color vision deficiencies
+    Colorblind,
+    Grayscale,
+}
+
+impl Palette {
+    /// Colors for INFO, WARNING, ERROR
+    fn levels(&self) -> [RGBColor; 3] {
+        match self {
+            Palette::Default => [RGBColor(50, 100, 200), RGBColor(110, 100, 200), RGBColor(170, 100, 200)],
+            Palette::Colorblind => [RGBColor(0, 114, 178), RGBColor(230, 159, 0), RGBColor(213, 94, 0)],
+            Palette::Grayscale => [RGBColor(170, 170, 170), RGBColor(110, 110, 110), RGBColor(40, 40, 40)],
+        }
+    }
+
+    fn line(&self) -> RGBColor {
+        match self {
+            Palette::Default => BLUE,
+            Palette::Colorblind => RGBColor(0, 114, 178),
+            Palette::Grayscale => BLACK,
+        }
+    }
+
+    /// Colors for several series on one chart, reused in turn past the last
+    fn series(&self) -> [RGBColor; 6] {
+        match self {
+            Palette::Default => [BLUE, RED, RGBColor(0, 150, 0), RGBColor(230, 130, 0), RGBColor(140, 60, 180), RGBColor(0, 160, 170)],
+            Palette::Colorblind => [
+                RGBColor(0, 114, 178), RGBColor(213, 94, 0), RGBColor(0, 158, 115),
+                RGBColor(230, 159, 0), RGBColor(204, 121, 167), RGBColor(86, 180, 233),
+            ],
+            Palette::Grayscale => [BLACK, RGBColor(90, 90, 90), RGBColor(140, 140, 140), RGBColor(40, 40, 40), RGBColor(115, 115, 115), RGBColor(170, 170, 170)],
+        }
+    }
+}
+
+/// Background bands behind the timeline.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Shading {
+    /// Saturdays and Sundays
+    Weekends,
+    /// [`NIGHT_START`] to [`NIGHT_END`] o'clock
+    Nights,
+}
+
+pub const NIGHT_START: u32 = 22;
+pub const NIGHT_END: u32 = 6;
+/// Incidents labelled on the timeline (by total errors); the rest are only shaded
+pub const INCIDENT_LABELS: usize = 5;
+
+/// Appearance overrides shared by all chart outputs.
+#[derive(Clone, Debug, Default)]
+pub struct ChartStyle {
+    /// Width x height in pixels; each chart has its own default
+    pub size: Option<(u32, u32)>,
+    /// Replaces the chart's default caption
+    pub title: Option<String>,
+    pub palette: Palette,
+    /// Bands shaded behind the timeline
+    pub shading: Vec<Shading>,
+    /// Shade the summary's incidents on the timeline, labelling the [`INCIDENT_LABELS`] largest
+    pub mark_incidents: bool,
+}
+
+impl ChartStyle {
+    fn caption<'a>(&'a self, default: &'a str) -> &'a str { self.title.as_deref().unwrap_or(default) }
+}
+
+/// `--hist-out field=latency,bins=40,path=hist.png`; a bare value is the path.
+#[derive(Clone, Debug)]
+pub struct HistogramSpec {
+    /// Extracted field to plot (None = message length)
+    pub field: Option<String>,
+    pub path: PathBuf,
+    pub bins: usize,
+}
+
+impl std::str::FromStr for HistogramSpec {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (mut field, mut path, mut bins) = (None, None, 30);
+        for part in s.split(',') {
+            match part.split_once('=') {
+                Some(("field", v)) => field = Some(v.to_string()),
+                Some(("path", v)) => path = Some(PathBuf::from(v)),
+                Some(("bins", v)) => bins = v.parse().ok().filter(|&b| b > 0).ok_or(format!("invalid bins: {v}"))?,
+                Some((k, _)) => return Err(format!("unknown histogram option: {k} (expected field, path or bins)")),
+                None => path = Some(PathBuf::from(part)),
+            }
+        }
+        Ok(HistogramSpec { field, path: path.ok_or("missing path=... for the histogram")?, bins })
+    }
+}
+
+/// Per-level weights of the error pressure chart, `--pressure-weights error=5,warning=2,info=0`;
+/// levels left out keep their default.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PressureWeights {
+    pub error: f64,
+    pub warning: f64,
+    pub info: f64,
+}
+
+impl Default for PressureWeights {
+    fn default() -> Self { Self { error: 5.0, warning: 2.0, info: 0.0 } }
+}
+
+impl PressureWeights {
+    /// Weighted sum of one bucket's entries
+    pub fn pressure(&self, c: &Counts) -> f64 {
+        self.error * c.error as f64 + self.warning * c.warning as f64 + self.info * c.info as f64
+    }
+
+    /// Bucket start -> pressure, in time order
+    pub fn series(&self, summary: &Summary) -> Vec<(NaiveDateTime, f64)> {
+        summary.timeline.iter().zip(&summary.timeline_levels).map(|((b, _), c)| (*b, self.pressure(c))).collect()
+    }
+}
+
+impl std::str::FromStr for PressureWeights {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut w = PressureWeights::default();
+        for part in s.split(',') {
+            let (level, v) = part.split_once('=').ok_or(format!("expected level=weight, got: {part}"))?;
+            let v: f64 = v.parse().ok().filter(|v: &f64| v.is_finite()).ok_or(format!("invalid weight: {v}"))?;
+            match level.to_ascii_lowercase().as_str() {
+                "error" => w.error = v,
+                "warning" | "warn" => w.warning = v,
+                "info" => w.info = v,
+                _ => return Err(format!("unknown level: {level} (expected error, warning or info)")),
+            }
+        }
+        Ok(w)
+    }
+}
+
+pub fn save_level_barchart(path: &std::path::Path, summary: &Summary, style: &ChartStyle) -> Result<()> {
+    let root = BitMapBackend::new(path, style.size.unwrap_or((900, 500))).into_drawing_area();
     root.fill(&WHITE)?;
 
-    let max_y = [summary.counts.info, summary.counts.warning, summary.counts.error]
-        .into_iter()
-        .max()
-        .unwrap_or(1);
+    let bars = [Level::Info, Level::Warning, Level::Error].map(|l| summary.count(l));
+    let max_y = bars.into_iter().max().unwrap_or(1);
 
     let mut chart = ChartBuilder::on(&root)
         .margin(20)
-        .caption("Logs per Level", ("sans-serif", 28))
+        .caption(style.caption("Logs per Level"), ("sans-serif", 28))
         .x_label_area_size(40)
         .y_label_area_size(50)
         .build_cartesian_2d(0i32..3i32, 0u64..(max_y + max_y / 5 + 1))?;
@@ -31,13 +175,13 @@ pub fn save_level_barchart(path: &std::path::Path, summary: &JsonSummary) -> Res
         .y_desc("Count")
         .draw()?;
 
-    let bars = vec![summary.counts.info, summary.counts.warning, summary.counts.error];
+    let colors = style.palette.levels();
     for (i, val) in bars.iter().enumerate() {
         let x0 = i as i32;
         let x1 = (i + 1) as i32;
         chart.draw_series(std::iter::once(Rectangle::new(
             [(x0, 0u64), (x1, *val)],
-            RGBColor(50 + 60 * i as u8, 100, 200).filled(),
+            colors[i].filled(),
         )))?;
     }
 
@@ -45,43 +189,298 @@ pub fn save_level_barchart(path: &std::path::Path, summary: &JsonSummary) -> Res
     Ok(())
 }
 
-pub fn save_timeline_chart(path: &std::path::Path, summary: &JsonSummary, _gran: Granularity) -> Result<()> {
-    let root = BitMapBackend::new(path, (1200, 500)).into_drawing_area();
+pub fn save_timeline_chart(path: &std::path::Path, summary: &Summary, style: &ChartStyle) -> Result<()> {
+    let points: Vec<_> = summary.timeline.iter().map(|&(ts, n)| (ts, n as f64)).collect();
+    let max_y = summary.timeline.iter().map(|p| p.1).max().unwrap_or(1);
+    let chart = BucketChart {
+        caption: style.caption("Log Frequency Over Time"),
+        y_desc: "Entries".into(),
+        top: (max_y + max_y / 5 + 1) as f64,
+        series: vec![BucketSeries { label: "Entries".into(), points, color: style.palette.line(), width: 1 }],
+        legend: false,
+    };
+    save_bucket_chart(path, summary.granularity, chart, style, |chart, first, end, top| {
+        if !style.mark_incidents { return Ok(false); }
+        let spans: Vec<_> = summary.incidents.iter().map(|inc| (inc.start.max(first), inc.end.min(end), inc)).collect();
+        let color = RGBColor(220, 40, 40);
+        if !spans.is_empty() {
+            chart
+                .draw_series(spans.iter().map(|(from, to, _)| Rectangle::new([(*from, 0.0), (*to, top)], color.mix(0.18).filled())))?
+                .label("Incident")
+                .legend(move |(x, y)| Rectangle::new([(x, y - 5), (x + 10, y + 5)], color.mix(0.18).filled()));
+        }
+        let mut largest: Vec<_> = spans.iter().collect();
+        largest.sort_by_key(|inc| std::cmp::Reverse(inc.2.total_errors));
+        let font = ("sans-serif", 13).into_font().color(&color);
+        // Staggered so labels of neighbouring incidents don't overlap
+        let middle = first + (end - first) / 2;
+        for (i, (from, to, inc)) in largest.into_iter().take(INCIDENT_LABELS).enumerate() {
+            let mut template: String = inc.top_template.chars().take(40).collect();
+            if inc.top_template.chars().count() > 40 { template.push('…'); }
+            let y = top - top * (i % 3) as f64 / 12.0;
+            // Right of the band in the left half of the chart, left of it in the right half
+            let (x, anchor) = if *from < middle { (*from, HPos::Left) } else { (*to, HPos::Right) };
+            let style = font.clone().pos(Pos::new(anchor, VPos::Top));
+            chart.draw_series(std::iter::once(Text::new(format!("{} errors: {template}", inc.total_errors), (x, y), style)))?;
+        }
+        Ok(!spans.is_empty())
+    })
+}
+
+/// Severity-weighted entries per bucket ("error pressure"): flat volume with more errors in it
+/// still shows as a rise
+pub fn save_pressure_chart(path: &std::path::Path, summary: &Summary, weights: &PressureWeights, style: &ChartStyle) -> Result<()> {
+    let points = weights.series(summary);
+    let max_y = points.iter().map(|p| p.1).fold(0.0, f64::max).max(1.0);
+    let chart = BucketChart {
+        caption: style.caption("Error Pressure Over Time"),
+        y_desc: format!("Pressure (error={}, warning={}, info={})", weights.error, weights.warning, weights.info),
+        top: max_y * 1.2,
+        series: vec![BucketSeries { label: "Pressure".into(), points, color: style.palette.levels()[2], width: 2 }],
+        legend: false,
+    };
+    save_bucket_chart(path, summary.granularity, chart, style, |_, _, _, _| Ok(false))
+}
+
+/// A line of a [`BucketChart`]
+struct BucketSeries {
+    label: String,
+    /// Bucket start -> value, in time order
+    points: Vec<(NaiveDateTime, f64)>,
+    color: RGBColor,
+    width: u32,
+}
+
+/// Per-bucket values over time, as drawn by [`save_bucket_chart`]
+struct BucketChart<'a> {
+    caption: &'a str,
+    y_desc: String,
+    /// Upper end of the value axis
+    top: f64,
+    series: Vec<BucketSeries>,
+    /// Show the legend even when only the series would be in it
+    legend: bool,
+}
+
+type TimeChart<'a, 'b> = ChartContext<'a, BitMapBackend<'b>, Cartesian2d<RangedDateTime<NaiveDateTime>, RangedCoordf64>>;
+
+/// Draw `chart` on a real time axis, so gaps take the room they span, with `style`'s shading
+/// behind it. `decorate` gets the chart, its time range and `top` to draw more before the series,
+/// and returns whether it added anything to the legend.
+fn save_bucket_chart(
+    path: &std::path::Path,
+    gran: Granularity,
+    chart: BucketChart,
+    style: &ChartStyle,
+    decorate: impl FnOnce(&mut TimeChart, NaiveDateTime, NaiveDateTime, f64) -> Result<bool>,
+) -> Result<()> {
+    let root = BitMapBackend::new(path, style.size.unwrap_or((1200, 500))).into_drawing_area();
     root.fill(&WHITE)?;
 
-    // X as index (uniform spacing), label with first/last
-    let n = summary.timeline.len().max(1);
-    let max_y = summary.timeline.iter().map(|(_, v)| *v).max().unwrap_or(1);
+    let first = chart.series.iter().filter_map(|s| s.points.first()).map(|p| p.0).min()
+        .unwrap_or_else(|| chrono::Local::now().naive_local());
+    let end = chart.series.iter().filter_map(|s| s.points.last()).map(|p| gran.next_bucket(p.0)).max()
+        .unwrap_or(first).max(first + Duration::seconds(1));
+    let top = chart.top;
+    // Dates alone once the range spans several days
+    let tick_format = if end - first > Duration::days(3) { "%Y-%m-%d" } else { gran.label_format() };
 
-    let mut chart = ChartBuilder::on(&root)
+    let mut ctx = ChartBuilder::on(&root)
         .margin(20)
-        .caption("Log Frequency Over Time", ("sans-serif", 28))
+        .margin_right(40)
+        .caption(chart.caption, ("sans-serif", 28))
         .x_label_area_size(50)
         .y_label_area_size(60)
-        .build_cartesian_2d(0i32..(n as i32 - 1).max(0), 0u64..(max_y + max_y / 5 + 1))?;
+        .build_cartesian_2d(RangedDateTime::from(first..end), 0f64..top)?;
 
-    chart
+    ctx
         .configure_mesh()
-        .y_desc("Entries")
-        .x_desc("Bucket")
+        .y_desc(chart.y_desc)
+        .y_label_formatter(&|v| if v.fract() == 0.0 { format!("{v:.0}") } else { format!("{v:.1}") })
+        .x_desc("Time")
         .x_labels(10)
-        .x_label_formatter(&|i| {
-            if summary.timeline.is_empty() {
-                return "".into();
-            }
-            let idx = (*i as usize).min(summary.timeline.len() - 1);
-            summary.timeline[idx].0.clone()
-        })
+        .x_label_formatter(&|ts| ts.format(tick_format).to_string())
         .draw()?;
 
-    let series: Vec<(i32, u64)> = summary
-        .timeline
-        .iter()
-        .enumerate()
-        .map(|(i, (_, v))| (i as i32, *v))
-        .collect();
-    chart.draw_series(LineSeries::new(series.into_iter(), &BLUE))?;
+    let mut legend = chart.legend;
+    for shading in &style.shading {
+        let (color, label) = match shading {
+            Shading::Weekends => (RGBColor(120, 120, 120).mix(0.15), "Weekend"),
+            Shading::Nights => (RGBColor(40, 60, 160).mix(0.10), "Night"),
+        };
+        let bands = shading_bands(*shading, first, end);
+        if bands.is_empty() { continue; }
+        ctx
+            .draw_series(bands.into_iter().map(|(from, to)| Rectangle::new([(from, 0.0), (to, top)], color.filled())))?
+            .label(label)
+            .legend(move |(x, y)| Rectangle::new([(x, y - 5), (x + 10, y + 5)], color.filled()));
+        legend = true;
+    }
+    legend |= decorate(&mut ctx, first, end, top)?;
+
+    for s in chart.series {
+        // Empty buckets count zero: drop to the axis after a bucket whose successor is missing
+        let mut line = Vec::with_capacity(s.points.len());
+        for (i, &(ts, v)) in s.points.iter().enumerate() {
+            line.push((ts, v));
+            let next = gran.next_bucket(ts);
+            if let Some(&(after, _)) = s.points.get(i + 1).filter(|p| p.0 > next) {
+                line.extend([(next, 0.0), (after, 0.0)]);
+            }
+        }
+        let stroke = s.color.stroke_width(s.width);
+        ctx
+            .draw_series(LineSeries::new(line, stroke))?
+            .label(s.label)
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 15, y)], stroke));
+    }
+    if legend {
+        ctx.configure_series_labels().background_style(WHITE.mix(0.8)).border_style(BLACK).draw()?;
+    }
+
+    root.present()?;
+    Ok(())
+}
+
+/// One line per `--track` keyword: entries mentioning it per timeline bucket
+pub fn save_keyword_chart(path: &std::path::Path, summary: &Summary, style: &ChartStyle) -> Result<()> {
+    let colors = style.palette.series();
+    let series: Vec<_> = summary.tracked_keywords.iter().enumerate().map(|(i, keyword)| BucketSeries {
+        label: format!("{} ({})", keyword.keyword, keyword.total),
+        points: summary.timeline.iter().zip(&keyword.counts).map(|(&(ts, _), &n)| (ts, n as f64)).collect(),
+        color: colors[i % colors.len()],
+        width: 2,
+    }).collect();
+    let max_y = summary.tracked_keywords.iter().flat_map(|k| k.counts.iter().copied()).max().unwrap_or(1);
+    let chart = BucketChart {
+        caption: style.caption("Tracked Keywords Over Time"),
+        y_desc: "Entries mentioning the keyword".into(),
+        top: (max_y + max_y / 5 + 1) as f64,
+        legend: !series.is_empty(),
+        series,
+    };
+    save_bucket_chart(path, summary.granularity, chart, style, |_, _, _, _| Ok(false))
+}
+
+/// Spans of `shading` between `first` and `end`, clipped to that range
+fn shading_bands(shading: Shading, first: NaiveDateTime, end: NaiveDateTime) -> Vec<(NaiveDateTime, NaiveDateTime)> {
+    let mut bands = Vec::new();
+    // From the day before, for the night that started then
+    let mut day = first.date().pred_opt().unwrap_or(first.date()).and_hms_opt(0, 0, 0).unwrap();
+    while day < end {
+        let band = match shading {
+            Shading::Weekends => matches!(day.weekday(), Weekday::Sat | Weekday::Sun).then(|| (day, day + Duration::days(1))),
+            Shading::Nights => Some((day + Duration::hours(NIGHT_START as i64), day + Duration::days(1) + Duration::hours(NIGHT_END as i64))),
+        };
+        if let Some((from, to)) = band.filter(|(from, to)| *to > first && *from < end) {
+            // Consecutive weekend days make one band
+            match bands.last_mut() {
+                Some((_, last_to)) if *last_to >= from => *last_to = to.min(end),
+                _ => bands.push((from.max(first), to.min(end))),
+            }
+        }
+        day += Duration::days(1);
+    }
+    bands
+}
+
+/// Range covered by the histogram and the count of each of its `bins` equal-width bins
+fn histogram_bins(values: &[f64], stats: &HistogramStats, bins: usize) -> (f64, f64, Vec<u64>) {
+    let (lo, hi) = if stats.max > stats.min { (stats.min, stats.max) } else { (stats.min - 0.5, stats.min + 0.5) };
+    let width = (hi - lo) / bins as f64;
+    let mut counts = vec![0u64; bins];
+    for v in values { counts[(((v - lo) / width) as usize).min(bins - 1)] += 1; }
+    (lo, hi, counts)
+}
+
+pub fn save_histogram(path: &std::path::Path, values: &[f64], stats: &HistogramStats, bins: usize, style: &ChartStyle) -> Result<()> {
+    let root = BitMapBackend::new(path, style.size.unwrap_or((1000, 500))).into_drawing_area();
+    root.fill(&WHITE)?;
+
+    let (lo, hi, counts) = histogram_bins(values, stats, bins);
+    let width = (hi - lo) / bins as f64;
+    let max_y = counts.iter().copied().max().unwrap_or(1);
+
+    let default_caption = format!("Distribution of {}", stats.source);
+    let mut chart = ChartBuilder::on(&root)
+        .margin(20)
+        .caption(style.caption(&default_caption), ("sans-serif", 28))
+        .x_label_area_size(40)
+        .y_label_area_size(60)
+        .build_cartesian_2d(lo..hi, 0u64..(max_y + max_y / 5 + 1))?;
+
+    chart.configure_mesh().x_desc(stats.source.as_str()).y_desc("Count").draw()?;
+
+    let [bar, p50, p95] = style.palette.levels();
+    chart
+        .draw_series(counts.iter().enumerate().map(|(i, &n)| {
+            let x0 = lo + i as f64 * width;
+            let mut r = Rectangle::new([(x0, 0), (x0 + width, n)], bar.filled());
+            r.set_margin(0, 0, 1, 1);
+            r
+        }))?
+        .label(format!("n={}  mean={:.2}", stats.count, stats.mean))
+        .legend(move |(x, y)| Rectangle::new([(x, y - 5), (x + 10, y + 5)], bar.filled()));
+    for (name, v, color) in [("p50", stats.p50, p50), ("p95", stats.p95, p95)] {
+        chart
+            .draw_series(std::iter::once(PathElement::new(vec![(v, 0), (v, max_y + max_y / 5)], color.stroke_width(2))))?
+            .label(format!("{name}={v:.2}"))
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 15, y)], color.stroke_width(2)));
+    }
+    chart.configure_series_labels().background_style(WHITE.mix(0.8)).border_style(BLACK).draw()?;
 
     root.present()?;
     Ok(())
-}
\ No newline at end of file
+}
+
+/// `level,count` rows behind [`save_level_barchart`]
+pub fn write_level_csv(path: &std::path::Path, summary: &Summary) -> Result<()> {
+    let c = |level| summary.count(level);
+    std::fs::write(path, format!("level,count\nINFO,{}\nWARNING,{}\nERROR,{}\n", c(Level::Info), c(Level::Warning), c(Level::Error)))?;
+    Ok(())
+}
+
+/// `bucket,count` rows behind [`save_timeline_chart`], buckets written to the summary's `ts_precision`
+pub fn write_timeline_csv(path: &std::path::Path, summary: &Summary) -> Result<()> {
+    let mut out = String::from("bucket,count\n");
+    for (bucket, n) in &summary.timeline { out.push_str(&format!("{},{n}\n", summary.ts_precision.format(*bucket))); }
+    std::fs::write(path, out)?;
+    Ok(())
+}
+
+/// `bucket,<keyword>,...` rows behind [`save_keyword_chart`], one column per tracked keyword
+pub fn write_keyword_csv(path: &std::path::Path, summary: &Summary) -> Result<()> {
+    let quote = |s: &str| if s.contains([',', '"', '\n']) { format!("\"{}\"", s.replace('"', "\"\"")) } else { s.to_string() };
+    let mut out = String::from("bucket");
+    for k in &summary.tracked_keywords { out.push_str(&format!(",{}", quote(&k.keyword))); }
+    out.push('\n');
+    for (i, (bucket, _)) in summary.timeline.iter().enumerate() {
+        out.push_str(&summary.ts_precision.format(*bucket));
+        for k in &summary.tracked_keywords { out.push_str(&format!(",{}", k.counts.get(i).copied().unwrap_or(0))); }
+        out.push('\n');
+    }
+    std::fs::write(path, out)?;
+    Ok(())
+}
+
+/// `bucket,info,warning,error,pressure` rows behind [`save_pressure_chart`]
+pub fn write_pressure_csv(path: &std::path::Path, summary: &Summary, weights: &PressureWeights) -> Result<()> {
+    let mut out = String::from("bucket,info,warning,error,pressure\n");
+    for ((bucket, _), c) in summary.timeline.iter().zip(&summary.timeline_levels) {
+        out.push_str(&format!("{},{},{},{},{}\n", summary.ts_precision.format(*bucket), c.info, c.warning, c.error, weights.pressure(c)));
+    }
+    std::fs::write(path, out)?;
+    Ok(())
+}
+
+/// `bin_start,bin_end,count` rows behind [`save_histogram`]
+pub fn write_histogram_csv(path: &std::path::Path, values: &[f64], stats: &HistogramStats, bins: usize) -> Result<()> {
+    let (lo, hi, counts) = histogram_bins(values, stats, bins);
+    let width = (hi - lo) / bins as f64;
+    let mut out = String::from("bin_start,bin_end,count\n");
+    for (i, n) in counts.iter().enumerate() {
+        out.push_str(&format!("{},{},{n}\n", lo + i as f64 * width, lo + (i + 1) as f64 * width));
+    }
+    std::fs::write(path, out)?;
+    Ok(())
+}
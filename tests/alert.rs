@@ -0,0 +1,12 @@
+use log_analyzer::alert::{AlertRule, Metric};
+use log_analyzer::analyze::parse_duration;
+use std::time::Duration;
+
+#[test]
+fn alert_rules_parse_and_reject_overflowing_windows() {
+    let rule: AlertRule = "errors >= 100 per 5m".parse().unwrap();
+    assert_eq!((rule.metric, rule.threshold, rule.window), (Metric::Errors, 99, Duration::from_secs(300)));
+    assert_eq!(parse_duration("250ms").unwrap(), Duration::from_millis(250));
+    assert!(parse_duration("99999999999999999w").is_err());
+    assert!("errors > 1 per 30500000000000000d".parse::<AlertRule>().is_err());
+}
@@ -0,0 +1,59 @@
+#![cfg(all(feature = "api", unix))]
+
+use log_analyzer::api::{router, ApiOptions};
+
+fn json(resp: ureq::Response) -> serde_json::Value { serde_json::from_str(&resp.into_string().unwrap()).unwrap() }
+
+/// Serve the API over `root` on a free local port, returning its base URL
+fn start(root: &std::path::Path, max_memory: Option<usize>) -> String {
+    let opts = ApiOptions { listen: ":0".into(), root: Some(root.to_path_buf()), max_upload: 1 << 20, max_memory };
+    let app = router(&opts).unwrap();
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let listener = runtime.block_on(tokio::net::TcpListener::bind("127.0.0.1:0")).unwrap();
+    let addr = listener.local_addr().unwrap();
+    std::thread::spawn(move || runtime.block_on(async { axum::serve(listener, app).await }));
+    format!("http://{addr}")
+}
+
+#[test]
+fn symlinks_out_of_the_root_are_not_read() {
+    let base = std::env::temp_dir().join(format!("log_analyzer_api_{}", std::process::id()));
+    let (root, outside) = (base.join("root"), base.join("outside"));
+    std::fs::create_dir_all(root.join("logs")).unwrap();
+    std::fs::create_dir_all(&outside).unwrap();
+    std::fs::write(root.join("logs/app.log"), "2025-09-05 09:00:00,001 INFO Starting service\n").unwrap();
+    std::fs::write(outside.join("secret.log"), "2025-09-05 09:00:02,999 ERROR top secret\n").unwrap();
+    std::os::unix::fs::symlink(outside.join("secret.log"), root.join("logs/secret.log")).unwrap();
+    std::os::unix::fs::symlink(&outside, root.join("logs/elsewhere")).unwrap();
+    let url = start(&root, None);
+
+    let summary = json(ureq::post(&format!("{url}/analyze?path=logs")).call().unwrap());
+    assert_eq!((summary["counts"]["info"].as_u64(), summary["counts"]["error"].as_u64()), (Some(1), Some(0)));
+    let entries = json(ureq::get(&format!("{url}/entries")).call().unwrap());
+    assert!(!entries.to_string().contains("top secret"));
+
+    for path in ["logs/secret.log", "logs/elsewhere", "../outside/secret.log"] {
+        match ureq::post(&format!("{url}/analyze?path={path}")).call() {
+            Err(ureq::Error::Status(code, _)) => assert_eq!(code, 403, "{path}"),
+            other => panic!("{path}: {other:?}"),
+        }
+    }
+    std::fs::remove_dir_all(&base).unwrap();
+}
+
+#[test]
+fn max_memory_caps_the_entries_kept() {
+    let root = std::env::temp_dir().join(format!("log_analyzer_api_memory_{}", std::process::id()));
+    std::fs::create_dir_all(&root).unwrap();
+    let log: String = (0..2000).map(|i| format!("2025-09-05 09:{:02}:{:02},000 ERROR Request {i} failed\n", i / 60 % 60, i % 60)).collect();
+    let entries = |max_memory| {
+        let url = start(&root, max_memory);
+        let summary = json(ureq::post(&format!("{url}/analyze")).send_string(&log).unwrap());
+        assert_eq!(summary["counts"]["error"], 2000);
+        json(ureq::get(&format!("{url}/entries?limit=5000")).call().unwrap()).as_array().unwrap().len()
+    };
+    assert_eq!(entries(None), 2000);
+    let kept = entries(Some(64 << 10));
+    assert!(kept > 0 && kept < 2000, "{kept} entries kept");
+    std::fs::remove_dir_all(&root).unwrap();
+}
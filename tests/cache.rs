@@ -0,0 +1,89 @@
+mod common;
+
+use common::SAMPLE;
+use log_analyzer::analyze::{Analyzer, Filters, Granularity};
+use log_analyzer::cache::AnalysisCache;
+use log_analyzer::checkpoint::Checkpointer;
+use log_analyzer::parse::DefaultLogParser;
+use log_analyzer::report::{build_summary, ReportOptions};
+use std::path::PathBuf;
+use std::time::Duration;
+
+fn scratch(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("log_analyzer_{name}_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn analyzer() -> Analyzer { Analyzer::new(Granularity::Hour, Filters::default()) }
+
+#[test]
+fn cache_hits_until_the_file_or_settings_change() {
+    let dir = scratch("cache");
+    let (log, cache_path) = (dir.join("app.log"), dir.join("cache.bin"));
+    std::fs::write(&log, SAMPLE).unwrap();
+
+    let mut cache = AnalysisCache::load(&cache_path, "hour".into());
+    let meta = std::fs::metadata(&log).unwrap();
+    assert!(cache.lookup(&log, &meta).is_none());
+    let mut partial = analyzer();
+    partial.consume_file(&mut DefaultLogParser::new(), &log).unwrap();
+    cache.store(&log, &meta, &partial);
+    cache.save(&cache_path).unwrap();
+
+    let cache = AnalysisCache::load(&cache_path, "hour".into());
+    let hit = cache.lookup(&log, &meta).expect("unchanged file is cached");
+    assert_eq!((hit.lines_read, hit.malformed.len()), (6, 1));
+    // Other settings invalidate every file
+    assert!(AnalysisCache::load(&cache_path, "day".into()).lookup(&log, &meta).is_none());
+
+    std::fs::write(&log, format!("{SAMPLE}2025-09-12 11:00:01,000 INFO Recovered\n")).unwrap();
+    assert!(cache.lookup(&log, &std::fs::metadata(&log).unwrap()).is_none());
+
+    // An unreadable cache starts over empty
+    std::fs::write(&cache_path, b"not bincode").unwrap();
+    assert!(AnalysisCache::load(&cache_path, "hour".into()).lookup(&log, &meta).is_none());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn checkpoints_resume_where_the_run_stopped() {
+    let dir = scratch("checkpoint");
+    let (log, cp_path) = (dir.join("app.log"), dir.join("run.ckpt"));
+    let text: String = (0..50).map(|i| format!("2025-09-05 09:{i:02}:00,000 {} Request {}\n", ["INFO", "ERROR"][i % 2], i % 7)).collect();
+    std::fs::write(&log, &text).unwrap();
+    let inputs = [log.clone()];
+
+    let mut whole = analyzer();
+    whole.consume_reader(&mut DefaultLogParser::new(), text.as_bytes(), "app.log").unwrap();
+
+    // Interrupted after the second chunk of 8 lines
+    let mut cp = Checkpointer::new(&cp_path, "hour".into(), &inputs, Duration::ZERO);
+    let mut partial = analyzer();
+    let err = partial.consume_resumable(&mut DefaultLogParser::new(), text.as_bytes(), "app.log", 0, 8, |p, lines| {
+        cp.save(0, &analyzer(), Some((lines, p)), true)?;
+        if lines >= 16 { anyhow::bail!("interrupted") }
+        Ok(())
+    });
+    assert_eq!(err.unwrap_err().to_string(), "interrupted");
+
+    let cp = Checkpointer::new(&cp_path, "hour".into(), &inputs, Duration::ZERO);
+    let saved = cp.resume().unwrap().expect("checkpoint written");
+    assert_eq!(saved.files_done, 0);
+    let (skip, p) = saved.current.expect("file in progress");
+    assert_eq!(skip, 16);
+    let mut resumed = analyzer();
+    resumed.merge(p);
+    resumed.consume_resumable(&mut DefaultLogParser::new(), text.as_bytes(), "app.log", skip, 8, |_, _| Ok(())).unwrap();
+
+    let (a, b) = (build_summary(&whole, &ReportOptions::default()), build_summary(&resumed, &ReportOptions::default()));
+    assert_eq!((a.counts.info, a.counts.error, a.total_entries), (b.counts.info, b.counts.error, b.total_entries));
+    assert_eq!((a.common_errors, a.timeline), (b.common_errors, b.timeline));
+
+    let err = |settings: &str, inputs: &[PathBuf]| Checkpointer::new(&cp_path, settings.into(), inputs, Duration::ZERO).resume().err().unwrap().to_string();
+    assert!(err("day", &inputs).contains("different options"));
+    assert!(err("hour", &[dir.join("other.log")]).contains("different inputs"));
+    cp.finish().unwrap();
+    assert!(cp.resume().unwrap().is_none());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,23 @@
+//! Fixtures shared by the integration tests; each test crate uses only some of them
+#![allow(dead_code)]
+
+use log_analyzer::analyze::{Analyzer, Filters, Granularity};
+use log_analyzer::parse::DefaultLogParser;
+use log_analyzer::report::{build_summary, JsonSummary, ReportOptions};
+
+pub const SAMPLE: &str = "\
+2025-09-05 09:00:00,001 INFO Starting service
+2025-09-05 09:00:01,250 WARNING Disk space at 85%
+2025-09-05 09:00:02,999 ERROR Failed to connect to DB
+2025-09-05 10:15:34,777 ERROR Failed to connect to DB
+2025-09-12 11:00:00,000 ERROR Timeout talking to \"cache\"
+not a log line
+";
+
+/// Summary of [`SAMPLE`] by hour, grouped by `logger`
+pub fn sample_summary() -> JsonSummary {
+    let mut an = Analyzer::new(Granularity::Hour, Filters::default());
+    an.group_by = vec!["logger".into()];
+    an.consume_reader(&mut DefaultLogParser::new(), SAMPLE.as_bytes(), "sample.log").unwrap();
+    build_summary(&an, &ReportOptions::default())
+}
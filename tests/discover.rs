@@ -0,0 +1,54 @@
+use log_analyzer::discover::{gather_log_files, DiscoveryOptions};
+use std::path::{Path, PathBuf};
+
+/// `files` under a fresh directory named after `name`
+fn tree(name: &str, files: &[&str]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("log_analyzer_discover_{name}_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    for f in files {
+        let path = dir.join(f);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, "2025-09-05 09:00:00,001 INFO Starting service\n").unwrap();
+    }
+    dir
+}
+
+fn found(dir: &Path, opts: &DiscoveryOptions) -> Vec<String> {
+    let mut files: Vec<_> = gather_log_files(&[dir.to_path_buf()], opts).unwrap().iter()
+        .map(|p| p.strip_prefix(dir).unwrap().to_string_lossy().replace('\\', "/"))
+        .collect();
+    files.sort();
+    files
+}
+
+#[test]
+fn directories_are_filtered_by_include_and_exclude_globs() {
+    let dir = tree("globs", &["app.log", "notes.txt", "api/access.log", "api/debug.log", "worker/jobs.log", "worker/old/jobs.log"]);
+    let opts = |include: &[&str], exclude: &[&str]| DiscoveryOptions {
+        include: include.iter().map(|g| g.to_string()).collect(),
+        exclude: exclude.iter().map(|g| g.to_string()).collect(),
+        ..Default::default()
+    };
+    assert_eq!(found(&dir, &opts(&[], &[])), ["api/access.log", "api/debug.log", "app.log", "worker/jobs.log", "worker/old/jobs.log"]);
+    assert_eq!(found(&dir, &opts(&["api/*"], &[])), ["api/access.log", "api/debug.log"]);
+    assert_eq!(found(&dir, &opts(&[], &["**/debug.log", "worker/old/**"])), ["api/access.log", "app.log", "worker/jobs.log"]);
+    // Exclude wins over include
+    assert_eq!(found(&dir, &opts(&["api/*", "worker/**"], &["*/debug.log"])), ["api/access.log", "worker/jobs.log", "worker/old/jobs.log"]);
+    // Explicit files are kept whatever the globs say
+    let explicit = gather_log_files(&[dir.join("notes.txt")], &opts(&["api/*"], &["*.txt"])).unwrap();
+    assert_eq!(explicit, [dir.join("notes.txt")]);
+    assert!(gather_log_files(std::slice::from_ref(&dir), &opts(&["[z-a]"], &[])).is_err());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn extensions_patterns_depth_and_rotated_siblings() {
+    let dir = tree("select", &["app.log", "app.log.1", "app.log.2.gz", "trace.txt", "syslog", "nested/deep.log"]);
+    let by_ext = DiscoveryOptions { extensions: vec![".txt".into()], ..Default::default() };
+    assert_eq!(found(&dir, &by_ext), ["trace.txt"]);
+    let by_name = DiscoveryOptions { file_pattern: Some("^syslog$".into()), max_depth: Some(1), ..Default::default() };
+    assert_eq!(found(&dir, &by_name), ["app.log", "syslog"]);
+    let rotated = gather_log_files(&[dir.join("app.log")], &DiscoveryOptions { include_rotated: true, ..Default::default() }).unwrap();
+    assert_eq!(rotated, [dir.join("app.log.2.gz"), dir.join("app.log.1"), dir.join("app.log")]);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
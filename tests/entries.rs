@@ -0,0 +1,31 @@
+mod common;
+
+use common::SAMPLE;
+use log_analyzer::analyze::MalformedLine;
+use log_analyzer::entries;
+use log_analyzer::model::Level;
+
+#[test]
+fn entries_iterate_lazily_over_files_and_directories() {
+    let dir = std::env::temp_dir().join(format!("log_analyzer_entries_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("nested")).unwrap();
+    std::fs::write(dir.join("a.log"), SAMPLE).unwrap();
+    std::fs::write(dir.join("nested/b.log"), "2025-09-06 08:00:00,000 ERROR Disk full\n").unwrap();
+
+    let entries: Vec<_> = entries::parse_file_iter(dir.join("a.log")).unwrap().collect::<anyhow::Result<_>>().unwrap();
+    assert_eq!(entries.len(), 5);
+    assert_eq!((entries[2].level, entries[2].line_no), (Level::Error, 3));
+    assert_eq!(entries[2].source.as_deref(), Some(dir.join("a.log").as_path()));
+
+    let malformed: Vec<_> = entries::parse_file_iter(dir.join("a.log")).unwrap().with_malformed(true)
+        .filter_map(|e| e.err()?.downcast::<MalformedLine>().ok())
+        .collect();
+    assert_eq!((malformed.len(), malformed[0].line_no, malformed[0].text.as_str()), (1, 6, "not a log line"));
+
+    let mut all: Vec<_> = entries::parse_dir_iter(&dir).unwrap().map(|e| e.unwrap().message).collect();
+    all.sort();
+    assert_eq!(all.len(), 6);
+    assert!(all.contains(&"Disk full".to_string()));
+    assert!(entries::parse_file_iter(dir.join("missing.log")).is_err());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,151 @@
+mod common;
+
+use common::{SAMPLE, sample_summary};
+use log_analyzer::analyze::{Analyzer, Filters, Granularity};
+use log_analyzer::parse::DefaultLogParser;
+use log_analyzer::report::{attach_error_context, build_summary, compare_baseline, summarize, ReportOptions};
+
+#[test]
+fn error_precursors_and_transitions() {
+    let summary = sample_summary();
+    assert_eq!(summary.error_precursors, vec![("Disk space at 85%".to_string(), 1)]);
+    assert_eq!(summary.transitions["warning"]["error"], 1);
+    assert_eq!(summary.transitions["error"]["error"], 2);
+}
+
+#[test]
+fn error_history_marks_templates_new_in_range() {
+    let filters = Filters::from_cli(None, Some("2025-09-10"), None, &[]).unwrap();
+    let mut an = Analyzer::new(Granularity::Hour, filters);
+    an.error_history_period = Some(Granularity::Day);
+    an.consume_reader(&mut DefaultLogParser::new(), SAMPLE.as_bytes(), "sample.log").unwrap();
+    let summary = build_summary(&an, &ReportOptions::default());
+    let [h] = &summary.error_history[..] else { panic!("one error template in range") };
+    assert_eq!(h.template, "Timeout talking to \"cache\"");
+    assert_eq!((h.first_seen.as_str(), h.last_seen.as_str()), ("2025-09-12 11:00:00", "2025-09-12 11:00:00"));
+    assert_eq!(h.per_period, vec![("2025-09-12 00:00:00".to_string(), 1)]);
+    assert_eq!(h.new_in_range, Some(true));
+}
+
+#[test]
+fn noisiest_messages_rank_templates_of_all_levels() {
+    let mut an = Analyzer::new(Granularity::Hour, Filters::default());
+    an.track_noise = true;
+    an.consume_reader(&mut DefaultLogParser::new(), SAMPLE.as_bytes(), "sample.log").unwrap();
+    let summary = build_summary(&an, &ReportOptions { noisiest: 2, ..Default::default() });
+    assert_eq!(summary.noisiest_messages.len(), 2);
+    assert_eq!(summary.noisiest_messages[0].template, "Failed to connect to DB");
+    assert_eq!(summary.noisiest_messages[0].entries, 2);
+    assert_eq!(summary.noisiest_messages[0].share_pct, 40.0);
+}
+
+#[test]
+fn probable_crashes_flag_indicators_and_trailing_errors() {
+    let api = "\
+2025-09-05 09:00:00,000 INFO Serving requests
+2025-09-05 09:00:05,000 ERROR thread 'main' panicked at src/main.rs:10:5
+2025-09-05 09:01:35,000 INFO Starting service
+2025-09-05 09:02:00,000 INFO Serving requests
+";
+    let worker = "\
+2025-09-05 09:00:00,000 INFO Job started
+2025-09-05 09:00:30,000 WARNING java.lang.OutOfMemoryError: Java heap space
+";
+    let mut an = Analyzer::new(Granularity::Hour, Filters::default());
+    for (source, text) in [("api.log", api), ("worker.log", worker), ("sample.log", SAMPLE)] {
+        let mut partial = an.new_partial();
+        partial.consume_reader(&mut DefaultLogParser::new(), text.as_bytes(), source).unwrap();
+        an.merge(partial);
+    }
+    let summary = summarize(&an, &ReportOptions::default());
+    let crashes: Vec<_> = summary.probable_crashes.iter()
+        .map(|c| (c.source.as_str(), c.line_no, c.indicator.as_str(), c.silence_after_secs))
+        .collect();
+    assert_eq!(crashes, [
+        ("api.log", 2, "panicked at", Some(90)),
+        ("worker.log", 2, "OutOfMemoryError", None),
+        ("sample.log", 5, "last entry is an error", None),
+    ]);
+}
+
+#[test]
+fn baseline_lists_new_gone_and_regressed_templates() {
+    let baseline = sample_summary();
+    assert_eq!(baseline.error_templates, [("Failed to connect to DB".to_string(), 2), ("Timeout talking to \"cache\"".to_string(), 1)]);
+    let current = "\
+2025-09-06 09:00:00,000 INFO Starting service
+2025-09-06 09:00:01,000 ERROR Timeout talking to \"cache\"
+2025-09-06 09:00:02,000 ERROR Timeout talking to \"cache\"
+2025-09-06 09:00:03,000 ERROR Disk /dev/sda1 full at 99%
+";
+    let mut an = Analyzer::new(Granularity::Hour, Filters::default());
+    an.consume_reader(&mut DefaultLogParser::new(), current.as_bytes(), "current.log").unwrap();
+    let mut summary = summarize(&an, &ReportOptions::default());
+    compare_baseline(&mut summary, &baseline, "baseline.json", 50.0);
+    let diff = summary.baseline.unwrap();
+    assert_eq!(diff.new, [("Disk /dev/sda<*> full at <*>%".to_string(), 1)]);
+    assert_eq!(diff.gone, [("Failed to connect to DB".to_string(), 2)]);
+    // 1 of 5 entries before, 2 of 4 now
+    let r = &diff.regressed[0];
+    assert_eq!((r.template.as_str(), r.baseline, r.current, r.change_pct.round()), ("Timeout talking to \"cache\"", 1, 2, 150.0));
+}
+
+#[test]
+fn error_context_rereads_neighbouring_records() {
+    let path = std::env::temp_dir().join(format!("log_analyzer_context_{}.log", std::process::id()));
+    let mut data = SAMPLE.as_bytes().to_vec();
+    data.splice(0..0, b"2025-09-05 08:59:59,000 INFO bad byte \xff here\n".iter().copied());
+    std::fs::write(&path, data).unwrap();
+    let source = path.display().to_string();
+    let mut an = Analyzer::new(Granularity::Hour, Filters::default());
+    an.consume_reader(&mut DefaultLogParser::new(), std::fs::read(&path).unwrap().as_slice(), &source).unwrap();
+    let mut summary = summarize(&an, &ReportOptions::default());
+    // Far more than the file holds, and more than is ever kept
+    attach_error_context(&mut summary, &DefaultLogParser::new(), usize::MAX).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let ctx = &summary.error_context["Failed to connect to DB"];
+    assert_eq!((ctx.source.as_str(), ctx.line_no), (source.as_str(), 4));
+    assert_eq!(ctx.before.len(), 3);
+    assert_eq!(ctx.before[0], "2025-09-05 08:59:59,000 INFO bad byte \u{fffd} here");
+    assert_eq!(ctx.after.last().map(String::as_str), Some("not a log line"));
+    assert_eq!(ctx.after.len(), 3);
+}
+
+#[test]
+fn incidents_group_runs_of_error_heavy_buckets() {
+    let (db, timeout, info) = ("ERROR Failed to connect to DB on port 5432", "ERROR Timeout after 30s", "INFO ok");
+    // Errors per minute: 10:00 1, 10:01 none, 10:02 5, 10:03 6, 10:04 none, 10:05 3, 10:06 1
+    let minutes: [&[(&str, usize)]; 7] = [
+        &[(db, 1)], &[(info, 2)], &[(db, 3), (timeout, 2)], &[(db, 4), (timeout, 2), (info, 1)],
+        &[(info, 3)], &[(timeout, 3)], &[(db, 1)],
+    ];
+    let mut log = String::new();
+    for (m, lines) in minutes.iter().enumerate() {
+        let messages = lines.iter().flat_map(|(msg, n)| std::iter::repeat_n(*msg, *n));
+        for (s, msg) in messages.enumerate() { log += &format!("2025-09-05 10:{m:02}:{s:02},000 {msg}\n"); }
+    }
+    let incidents = |threshold: Option<u64>| {
+        let mut an = Analyzer::new(Granularity::Minute, Filters::default());
+        an.consume_reader(&mut DefaultLogParser::new(), log.as_bytes(), "app.log").unwrap();
+        let opts = ReportOptions { incident_threshold: threshold, ..Default::default() };
+        build_summary(&an, &opts).incidents.into_iter()
+            .map(|i| (i.start, i.end, i.total_errors, i.top_template, i.peak_rate))
+            .collect::<Vec<_>>()
+    };
+    let (db, timeout) = ("Failed to connect to DB on port <*>".to_string(), "Timeout after <*>s".to_string());
+    let at = |m: u32| format!("2025-09-05 10:{m:02}:00");
+
+    // 16 errors over 7 buckets: the default threshold is 2 errors a bucket
+    assert_eq!(incidents(None), [
+        (at(2), at(4), 11, db.clone(), 6),
+        (at(5), at(6), 3, timeout.clone(), 3),
+    ]);
+    // A lower threshold takes in the single errors, extending the second run
+    assert_eq!(incidents(Some(1)), [
+        (at(0), at(1), 1, db.clone(), 1),
+        (at(2), at(4), 11, db.clone(), 6),
+        (at(5), at(7), 4, timeout.clone(), 3),
+    ]);
+    assert_eq!(incidents(Some(6)), [(at(3), at(4), 6, db.clone(), 6)]);
+    assert!(incidents(Some(7)).is_empty());
+}
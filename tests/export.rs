@@ -0,0 +1,24 @@
+#![cfg(feature = "parquet")]
+
+use log_analyzer::analyze::Filters;
+use log_analyzer::export::write_parquet;
+use log_analyzer::model::Level;
+use log_analyzer::parse::{DefaultLogParser, LogParser};
+
+#[test]
+fn parquet_export_skips_binary_inputs_and_bad_bytes() {
+    let dir = std::env::temp_dir().join(format!("log_analyzer_parquet_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let mut text = b"2025-09-05 09:00:00,000 INFO Starting\n2025-09-05 09:00:01,000 ERROR bad \xff byte\n".to_vec();
+    text.extend_from_slice(b"2025-09-05 09:00:02,000 ERROR Failed to connect to DB\n");
+    std::fs::write(dir.join("app.log"), text).unwrap();
+    std::fs::write(dir.join("core.log"), [0u8; 512]).unwrap();
+
+    let files = [dir.join("core.log"), dir.join("app.log")];
+    let filters = Filters { min_level: Some(Level::Error), ..Filters::default() };
+    let new_parser = || Ok(Box::new(DefaultLogParser::new()) as Box<dyn LogParser>);
+    let rows = write_parquet(&dir.join("out.parquet"), &files, new_parser, &filters).unwrap();
+    assert_eq!(rows, 2);
+    assert!(std::fs::metadata(dir.join("out.parquet")).unwrap().len() > 0);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,119 @@
+mod common;
+
+use common::SAMPLE;
+use log_analyzer::analyze::{parse_datetime, parse_duration, Analyzer, Filters, Granularity, Sampling, TimeBound};
+use log_analyzer::generate::{generate, GenerateOptions, Style};
+use log_analyzer::model::{Level, LevelSet};
+use log_analyzer::parse::DefaultLogParser;
+use log_analyzer::report::{build_summary, summarize, ReportOptions};
+
+#[test]
+fn level_sets_keep_several_levels() {
+    let set: LevelSet = "error, warning".parse().unwrap();
+    assert!(set.contains(Level::Error) && set.contains(Level::Warning) && !set.contains(Level::Info));
+    assert!("all".parse::<LevelSet>().unwrap().is_all());
+    assert!("fatal".parse::<LevelSet>().is_err());
+
+    let filters = Filters::from_cli(None, None, None, &[Level::Info, Level::Error]).unwrap();
+    let mut an = Analyzer::new(Granularity::Hour, filters);
+    an.consume_reader(&mut DefaultLogParser::new(), SAMPLE.as_bytes(), "sample.log").unwrap();
+    let summary = summarize(&an, &ReportOptions::default());
+    assert_eq!((summary.count(Level::Info), summary.count(Level::Warning), summary.count(Level::Error)), (1, 0, 3));
+}
+
+#[test]
+fn filters_round_trip_through_json() {
+    let f = Filters::from_json(r#"{"levels":["error","warning"],"keywords":["TimeOut"],"from":"2025-09-05T09:00:01"}"#).unwrap();
+    assert_eq!(f.keywords, ["timeout"]);
+    assert!(f.levels.contains(Level::Warning) && !f.levels.contains(Level::Info));
+    assert!(Filters::from_json(r#"{"level":"error"}"#).is_err());
+    let back = Filters::from_json(&serde_json::to_string(&f).unwrap()).unwrap();
+    assert_eq!((back.levels, back.from, back.keywords), (f.levels, f.from, f.keywords.clone()));
+
+    let mut an = Analyzer::new(Granularity::Hour, f);
+    an.consume_reader(&mut DefaultLogParser::new(), SAMPLE.as_bytes(), "sample.log").unwrap();
+    let summary = build_summary(&an, &ReportOptions::default());
+    assert_eq!((summary.counts.warning, summary.counts.error), (0, 1));
+}
+
+#[test]
+fn fractional_sampling_differs_between_inputs() {
+    let log: String = (0..200).map(|i| format!("2025-09-05 {:02}:{:02}:00,000 ERROR Failed to connect to DB\n", i / 60, i % 60)).collect();
+    let kept = |source: &str| {
+        let mut an = Analyzer::new(Granularity::Minute, Filters::default());
+        an.sampling = Some(Sampling::Fraction(0.5));
+        an.consume_reader(&mut DefaultLogParser::new(), log.as_bytes(), source).unwrap();
+        build_summary(&an, &ReportOptions::default()).timeline.into_iter().map(|(t, _)| t).collect::<Vec<_>>()
+    };
+    let (a, b) = (kept("a.log"), kept("b.log"));
+    assert_eq!(a, kept("a.log"));
+    assert_ne!(a, b);
+    assert!((60..140).contains(&a.len()) && (60..140).contains(&b.len()));
+}
+
+#[test]
+fn level_prefilter_leaves_the_summary_unchanged() {
+    let opts = GenerateOptions { lines: 3000, bursts: 2, malformed: 0.02, styles: vec![Style::Iso, Style::IsoBracketed, Style::Syslog, Style::Log4j, Style::Python], ..Default::default() };
+    let mut log = Vec::new();
+    generate(&opts, &mut log).unwrap();
+    let run = |levels: &[Level], prefilter: bool| {
+        let mut an = Analyzer::new(Granularity::Minute, Filters::from_cli(None, None, None, levels).unwrap());
+        an.prefilter = prefilter;
+        an.consume_reader(&mut DefaultLogParser::new(), &log[..], "gen.log").unwrap();
+        let mut summary = serde_json::to_value(build_summary(&an, &ReportOptions::default())).unwrap();
+        let prefiltered = summary["prefiltered_lines"].as_u64().unwrap();
+        // Lines the prefilter drops are never parsed, so they can't count as malformed (nor
+        // weigh on the health score)
+        for key in ["prefiltered_lines", "malformed_lines", "malformed_samples", "pattern_stats", "health"] {
+            summary.as_object_mut().unwrap().remove(key);
+        }
+        (summary, prefiltered)
+    };
+    for levels in [&[Level::Error][..], &[Level::Warning], &[Level::Error, Level::Warning], &[Level::Info], &[]] {
+        let ((with, dropped), (without, _)) = (run(levels, true), run(levels, false));
+        assert_eq!(with, without, "--level {levels:?}");
+        assert_eq!(dropped > 0, !levels.is_empty() && levels != [Level::Info], "--level {levels:?}");
+    }
+}
+
+#[test]
+fn time_bounds_take_dates_times_of_day_offsets_and_durations() {
+    let at = |s: &str| parse_datetime(s).unwrap().to_string();
+    assert_eq!(at("2025-09-05T14:00:00+02:00"), "2025-09-05 12:00:00");
+    assert_eq!(at("2025-09-05T14:00:00.250Z"), "2025-09-05 14:00:00.250");
+    assert_eq!(at("2025-09-05 14:00"), "2025-09-05 14:00:00");
+    assert_eq!(at("2025-09-05"), "2025-09-05 00:00:00");
+    assert_eq!(at("1757080800"), "2025-09-05 14:00:00");
+    for bad in ["", "yesterday", "2025-13-01", "2025-09-05 25:00"] {
+        assert!(parse_datetime(bad).unwrap_err().to_string().contains("accepted forms"), "{bad}");
+    }
+
+    assert!(matches!("2025-09-05".parse(), Ok(TimeBound::Day(d)) if d.to_string() == "2025-09-05"));
+    assert!(matches!("22:30".parse(), Ok(TimeBound::TimeOfDay(t)) if t.to_string() == "22:30:00"));
+    assert!(matches!("2025-09-05T14:00:00Z".parse(), Ok(TimeBound::At(_))));
+    let err = "25:00".parse::<TimeBound>().unwrap_err().to_string();
+    assert!(err.contains("'25:00'") && err.contains("time of day"), "{err}");
+
+    // A date as `--to` runs to the end of that day
+    let f = Filters::from_cli(None, Some("2025-09-04"), Some("2025-09-05"), &[]).unwrap();
+    assert_eq!((f.from.unwrap().to_string(), f.to.unwrap().to_string()), ("2025-09-04 00:00:00".into(), "2025-09-06 00:00:00".into()));
+    assert!(Filters::from_cli(None, Some("soon"), None, &[]).is_err());
+
+    // Times of day apply on every day, and a window ending before it starts wraps past midnight
+    let log = "\
+2025-09-04 21:59:59,000 ERROR before the window
+2025-09-04 23:00:00,000 ERROR late
+2025-09-05 05:59:59,000 ERROR early
+2025-09-05 06:00:00,000 ERROR after the window
+";
+    let f = Filters::from_cli(None, Some("22:00"), Some("06:00"), &[]).unwrap();
+    assert_eq!((f.from, f.to, f.from_time.unwrap().to_string()), (None, None, "22:00:00".into()));
+    let mut an = Analyzer::new(Granularity::Hour, f);
+    an.consume_reader(&mut DefaultLogParser::new(), log.as_bytes(), "night.log").unwrap();
+    assert_eq!(build_summary(&an, &ReportOptions::default()).counts.error, 2);
+
+    // Durations (bucket sizes, alert windows) take a unit from ms to weeks
+    let secs = |s: &str| parse_duration(s).unwrap().as_secs_f64();
+    assert_eq!([secs("250ms"), secs("90"), secs("15m"), secs("2h"), secs("1d"), secs("1w")], [0.25, 90.0, 900.0, 7200.0, 86400.0, 604800.0]);
+    assert!(parse_duration("5y").is_err() && parse_duration("h").is_err() && parse_duration("-5m").is_err());
+}
@@ -0,0 +1,25 @@
+use log_analyzer::analyze::{Analyzer, Filters, Granularity};
+use log_analyzer::generate::{generate, GenerateOptions, Style};
+use log_analyzer::parse::DefaultLogParser;
+
+#[test]
+fn generated_logs_read_back_with_their_mix() {
+    let opts = GenerateOptions {
+        lines: 2000,
+        styles: vec![Style::Iso, Style::Log4j, Style::Python],
+        bursts: 2,
+        malformed: 0.05,
+        ..Default::default()
+    };
+    let mut out = Vec::new();
+    let [info, warning, error] = generate(&opts, &mut out).unwrap();
+    let mut again = Vec::new();
+    generate(&opts, &mut again).unwrap();
+    assert_eq!(out, again, "same seed, same output");
+
+    let mut an = Analyzer::new(Granularity::Hour, Filters::default());
+    an.consume_reader(&mut DefaultLogParser::new(), out.as_slice(), "generated.log").unwrap();
+    assert_eq!((an.info, an.warning, an.error), (info, warning, error));
+    assert_eq!(an.malformed_lines, 2000 - info - warning - error);
+    assert!(an.malformed_lines > 0 && error > 2000 * 3 / 100);
+}
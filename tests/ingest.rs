@@ -0,0 +1,48 @@
+#![cfg(feature = "tokio")]
+
+use log_analyzer::analyze::{Analyzer, Filters, Granularity};
+use log_analyzer::ingest::{read_lines, Pipeline, RawLine};
+use log_analyzer::model::Level;
+use log_analyzer::parse::{DefaultLogParser, LogParser, W3cParser};
+
+#[test]
+fn pipeline_reads_lines_like_consume_lines() {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let _guard = runtime.enter();
+    let mut an = Analyzer::new(Granularity::Hour, Filters::from_cli(None, None, None, &[Level::Error]).unwrap());
+    an.max_line_len = 40;
+    let pipeline = Pipeline::start(an, 4);
+    let tx = pipeline.lines();
+    let mut input = format!("2025-09-05 09:00:00,000 INFO Starting\n2025-09-05 09:00:01,000 ERROR {}\n", "x".repeat(100)).into_bytes();
+    input.extend_from_slice(b"2025-09-05 09:00:02,000 ERROR bad \xff\n");
+    runtime.spawn(async move { read_lines("app.log", input.as_slice(), &tx).await.unwrap() });
+    let shared = pipeline.analyzer();
+    pipeline.run_parser(|| Ok(Box::new(DefaultLogParser::new()) as Box<dyn LogParser>)).unwrap();
+
+    let an = shared.lock().unwrap();
+    assert_eq!((an.lines_read, an.lines_prefiltered, an.lines_truncated, an.error), (3, 1, 1, 2));
+    assert_eq!(an.error_messages.top(2)[0].0, "bad \u{fffd}");
+}
+
+#[test]
+fn pipeline_keeps_a_parser_per_source() {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let _guard = runtime.enter();
+    let pipeline = Pipeline::start(Analyzer::new(Granularity::Hour, Filters::default()), 16);
+    let tx = pipeline.lines();
+    // Two W3C logs with different columns, interleaved line by line
+    let a = ["#Fields: date time cs-method cs-uri-stem sc-status", "2025-09-05 09:00:00 GET /a 200", "2025-09-05 09:00:01 GET /a 500"];
+    let b = ["#Fields: date time sc-status cs-uri-stem", "2025-09-05 09:00:00 404 /b", "2025-09-05 09:00:01 200 /b"];
+    runtime.spawn(async move {
+        for (i, (a, b)) in a.into_iter().zip(b).enumerate() {
+            for (source, text) in [("a.log", a), ("b.log", b)] {
+                tx.send(RawLine { source: source.into(), line_no: i as u64 + 1, text: text.into() }).await.unwrap();
+            }
+        }
+    });
+    let shared = pipeline.analyzer();
+    pipeline.run_parser(|| Ok(Box::new(W3cParser::default()) as Box<dyn LogParser>)).unwrap();
+
+    let an = shared.lock().unwrap();
+    assert_eq!((an.info, an.warning, an.error, an.malformed_lines), (2, 1, 1, 0));
+}
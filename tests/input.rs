@@ -0,0 +1,153 @@
+mod common;
+
+use common::{SAMPLE, sample_summary};
+use log_analyzer::analyze::{Analyzer, Filters, Granularity};
+use log_analyzer::grep::{self, GrepOptions};
+use log_analyzer::merge::{self, MergeOptions};
+use log_analyzer::model::Level;
+use log_analyzer::parse::{DefaultLogParser, LogParser};
+use log_analyzer::report::term::Term;
+use log_analyzer::report::{build_summary, ReportOptions};
+use log_analyzer::sink::{self, AnalyzerSink, LevelCounter, TopErrors};
+use regex::Regex;
+
+#[test]
+fn binary_inputs_are_skipped_and_long_lines_cut() {
+    let mut an = Analyzer::new(Granularity::Hour, Filters::default());
+    an.max_line_len = 40;
+    an.consume_reader(&mut DefaultLogParser::new(), &[0u8; 512][..], "core.log").unwrap();
+    // An overlong line, then one with invalid UTF-8
+    let mut input = format!("2025-09-05 09:00:00,001 ERROR {}\n2025-09-05 09:00:01,000 INFO ok ", "x".repeat(100)).into_bytes();
+    input.extend_from_slice(b"\xff\n");
+    an.consume_reader(&mut DefaultLogParser::new(), &input[..], "app.log").unwrap();
+    let summary = build_summary(&an, &ReportOptions::default());
+    assert_eq!(summary.skipped_inputs.len(), 1);
+    assert_eq!(summary.skipped_inputs[0].source, "core.log");
+    assert_eq!(summary.truncated_lines, 1);
+    assert_eq!(summary.total_entries, 2);
+    assert_eq!(summary.common_errors[0].0.len(), 40 - "2025-09-05 09:00:00,001 ERROR ".len());
+}
+
+#[test]
+fn entries_remember_their_source_line() {
+    let mut an = Analyzer::new(Granularity::Hour, Filters::from_cli(None, None, None, &[Level::Error]).unwrap());
+    an.retain_entries = Some(10);
+    an.consume_reader(&mut DefaultLogParser::new(), SAMPLE.as_bytes(), "sample.log").unwrap();
+    let at: Vec<_> = an.entries.iter().map(|e| (e.source.as_deref().unwrap().to_str().unwrap(), e.line_no)).collect();
+    assert_eq!(at, [("sample.log", 3), ("sample.log", 4), ("sample.log", 5)]);
+    assert!(std::sync::Arc::ptr_eq(an.entries[0].source.as_ref().unwrap(), an.entries[2].source.as_ref().unwrap()));
+}
+
+#[test]
+fn dominant_format_is_detected_per_input() {
+    let log4j = "\
+2025-09-05 09:00:00,001 [main] INFO com.acme.App - Starting
+2025-09-05 09:00:01,001 [main] ERROR com.acme.Db - Failed
+2025-09-05 09:00:02,001 INFO plain line
+";
+    let mut an = Analyzer::new(Granularity::Hour, Filters::default());
+    an.consume_reader(&mut DefaultLogParser::new(), log4j.as_bytes(), "app.log").unwrap();
+    assert_eq!(an.format_detections["app.log"], "log4j (2 of 3 sample lines)");
+    // Lines of another format still parse, through the fallback
+    assert_eq!((an.info, an.error, an.malformed_lines), (2, 1, 0));
+}
+
+#[test]
+fn pattern_stats_count_lines_per_builtin_pattern() {
+    let stats = sample_summary().pattern_stats.unwrap();
+    let matches: Vec<_> = stats.matches.iter().map(|(k, v)| (k.as_str(), *v)).collect();
+    assert_eq!(matches, [("iso", 5), ("iso-bracketed", 0), ("log4j", 0), ("python", 0), ("syslog", 0)]);
+    assert_eq!(stats.unmatched, 1);
+}
+
+#[test]
+fn suppression_notices_repeat_the_previous_entry() {
+    let log = "\
+2025-09-05 09:00:00,000 ERROR Failed to connect to DB
+2025-09-05 09:00:30,000 INFO message repeated 4 times: [ ERROR Failed to connect to DB ]
+2025-09-05 09:01:00,000 WARNING Disk space at 85%
+2025-09-05 09:01:05,000 INFO 12 similar messages suppressed
+not a log line
+2025-09-05 09:02:00,000 INFO last message repeated 3 times
+";
+    let mut an = Analyzer::new(Granularity::Minute, Filters::default());
+    an.consume_reader(&mut DefaultLogParser::new(), log.as_bytes(), "app.log").unwrap();
+    let summary = build_summary(&an, &ReportOptions::default());
+    assert_eq!(summary.repeats_restored, 16);
+    // The notice after an unparsed line has nothing to repeat and counts as itself
+    assert_eq!((summary.counts.error, summary.counts.warning, summary.counts.info), (5, 13, 1));
+    assert_eq!(summary.common_errors, [("Failed to connect to DB".to_string(), 5)]);
+    assert_eq!(summary.timeline.iter().map(|(_, n)| *n).collect::<Vec<_>>(), [5, 13, 1]);
+}
+
+#[test]
+fn suppression_notices_survive_the_level_prefilter() {
+    let log = "\
+2025-09-05 09:00:00,000 ERROR Failed to connect to DB
+2025-09-05 09:00:30,000 INFO message repeated 4 times
+2025-09-05 09:01:00,000 INFO Retrying
+2025-09-05 09:01:05,000 INFO last message repeated 2 times
+";
+    let run = |prefilter: bool| {
+        let mut an = Analyzer::new(Granularity::Minute, Filters::from_cli(None, None, None, &[Level::Error]).unwrap());
+        an.prefilter = prefilter;
+        an.consume_reader(&mut DefaultLogParser::new(), log.as_bytes(), "app.log").unwrap();
+        let summary = build_summary(&an, &ReportOptions::default());
+        (summary.counts.error, summary.counts.info, summary.repeats_restored, summary.common_errors)
+    };
+    assert_eq!(run(true), run(false));
+    assert_eq!(run(true), (5, 0, 6, vec![("Failed to connect to DB".to_string(), 5)]));
+}
+
+#[test]
+fn grep_reads_past_invalid_utf8() {
+    let path = std::env::temp_dir().join(format!("log_analyzer_grep_{}.log", std::process::id()));
+    std::fs::write(&path, b"2025-09-05 09:00:00,001 ERROR bad byte \xff in DB\n2025-09-05 09:00:01,250 ERROR Failed to connect to DB\n").unwrap();
+    let opts = GrepOptions {
+        pattern: Regex::new("DB").unwrap(), before: 0, after: 0,
+        filters: Filters::from_cli(None, None, None, &[Level::Error]).unwrap(),
+        line_numbers: false, count: true, term: Term::default(),
+    };
+    let n = grep::run(&opts, &mut DefaultLogParser::new(), std::slice::from_ref(&path)).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(n, 2);
+}
+
+#[test]
+fn merge_reads_past_invalid_utf8() {
+    let dir = std::env::temp_dir().join(format!("log_analyzer_merge_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("a.log"), b"2025-09-05 09:00:00,001 ERROR bad byte \xff here\n2025-09-05 09:00:02,000 INFO Done\n").unwrap();
+    std::fs::write(dir.join("b.log"), "2025-09-05 09:00:01,000 WARNING Disk space at 85%\n").unwrap();
+    let opts = MergeOptions { filters: Filters::default(), term: Term::default() };
+    let mut out = Vec::new();
+    let parser = || Ok(Box::new(DefaultLogParser::new()) as Box<dyn LogParser>);
+    let n = merge::run(&[dir.join("a.log"), dir.join("b.log")], parser, &opts, &mut out).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(n, 3);
+    assert_eq!(String::from_utf8(out).unwrap(), "\
+a.log | 2025-09-05 09:00:00,001 ERROR bad byte \u{fffd} here
+b.log | 2025-09-05 09:00:01,000 WARNING Disk space at 85%
+a.log | 2025-09-05 09:00:02,000 INFO Done
+");
+}
+
+#[test]
+fn sinks_read_past_invalid_utf8() {
+    let log = b"2025-09-05 09:00:00,001 ERROR bad byte \xff here\nnot a log line\n2025-09-05 09:00:01,250 WARNING Disk space at 85%\n";
+    let mut sink = (LevelCounter::default(), TopErrors::new(1));
+    let malformed = sink::feed(&mut sink, &mut DefaultLogParser::new(), &log[..]).unwrap();
+    let (counts, top) = sink.finish();
+    assert_eq!((malformed, counts.error, counts.warning), (1, 1, 1));
+    assert_eq!(top, [("bad byte \u{fffd} here".to_string(), 1)]);
+}
+
+#[cfg(not(feature = "remote"))]
+#[test]
+fn object_store_urls_need_the_remote_feature() {
+    let url = std::path::Path::new("s3://bucket/logs/app.log");
+    assert!(log_analyzer::remote::is_url(url));
+    for err in [log_analyzer::remote::open(url).err().unwrap(), log_analyzer::remote::list(url).unwrap_err()] {
+        assert!(err.to_string().contains("--features remote"));
+    }
+}
@@ -0,0 +1,171 @@
+mod common;
+
+use common::SAMPLE;
+use log_analyzer::analyze::Filters;
+use log_analyzer::model::Level;
+use log_analyzer::otlp;
+use log_analyzer::parse::{DefaultLogParser, LogParser};
+
+#[test]
+fn otlp_export_sends_log_records_and_retries_on_backpressure() {
+    use std::io::{BufRead, BufReader, Read, Write};
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    // No scheme: http is assumed
+    let endpoint = listener.local_addr().unwrap().to_string();
+    // A collector that turns the first request away, then keeps the second one's body
+    let collector = std::thread::spawn(move || {
+        let mut bodies = Vec::new();
+        for status in ["503 Service Unavailable", "200 OK"] {
+            let (stream, _) = listener.accept().unwrap();
+            let mut r = BufReader::new(stream.try_clone().unwrap());
+            let (mut request_line, mut len, mut line) = (String::new(), 0, String::new());
+            r.read_line(&mut request_line).unwrap();
+            while r.read_line(&mut line).unwrap() > 2 {
+                if let Some(v) = line.to_ascii_lowercase().strip_prefix("content-length:") { len = v.trim().parse().unwrap(); }
+                line.clear();
+            }
+            let mut body = vec![0; len];
+            r.read_exact(&mut body).unwrap();
+            write!(&stream, "HTTP/1.1 {status}\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{{}}").unwrap();
+            bodies.push((request_line, String::from_utf8(body).unwrap()));
+        }
+        bodies
+    });
+
+    let dir = std::env::temp_dir().join(format!("log_analyzer_otlp_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("app.log");
+    let mut text = SAMPLE.as_bytes().to_vec();
+    text.extend_from_slice(b"2025-09-12 12:00:00,000 ERROR bad \xff byte\n");
+    std::fs::write(&file, text).unwrap();
+    let filters = Filters { min_level: Some(Level::Warning), ..Filters::default() };
+    let exporter = otlp::OtlpExporter::new(&endpoint, "billing");
+    let new_parser = || Ok(Box::new(DefaultLogParser::new()) as Box<dyn LogParser>);
+    let sent = otlp::export_otlp(exporter, std::slice::from_ref(&file), new_parser, &filters).unwrap();
+    assert_eq!(sent, 5);
+
+    let bodies = collector.join().unwrap();
+    assert_eq!(bodies[0], bodies[1]);
+    assert!(bodies[1].0.starts_with("POST /v1/logs "));
+    let body: serde_json::Value = serde_json::from_str(&bodies[1].1).unwrap();
+    let resource = &body["resourceLogs"][0];
+    assert_eq!(resource["resource"]["attributes"][0]["value"]["stringValue"], "billing");
+    let records = resource["scopeLogs"][0]["logRecords"].as_array().unwrap();
+    assert_eq!(records.len(), 5);
+    assert_eq!(records[4]["body"]["stringValue"], "bad \u{fffd} byte");
+    assert_eq!(records[0]["severityText"], "WARN");
+    assert_eq!(records[1]["severityNumber"], 17);
+    assert_eq!(records[1]["body"]["stringValue"], "Failed to connect to DB");
+    assert_eq!(records[1]["timeUnixNano"], "1757062802999000000");
+    let attrs = records[1]["attributes"].as_array().unwrap();
+    assert!(attrs.contains(&serde_json::json!({ "key": "log.file.line", "value": { "intValue": "3" } })));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+/// (field number, varint or fixed64 value, length-delimited payload) of each field of a protobuf message
+#[cfg(feature = "grpc")]
+fn fields(mut b: &[u8]) -> Vec<(u64, u64, &[u8])> {
+    fn varint(b: &mut &[u8]) -> u64 {
+        let (mut v, mut shift) = (0, 0);
+        loop {
+            let byte = b[0];
+            *b = &b[1..];
+            v |= u64::from(byte & 0x7f) << shift;
+            if byte < 0x80 { return v; }
+            shift += 7;
+        }
+    }
+    let mut out = Vec::new();
+    while !b.is_empty() {
+        let key = varint(&mut b);
+        match key & 7 {
+            0 => out.push((key >> 3, varint(&mut b), &[][..])),
+            1 => {
+                out.push((key >> 3, u64::from_le_bytes(b[..8].try_into().unwrap()), &[][..]));
+                b = &b[8..];
+            }
+            2 => {
+                let len = varint(&mut b) as usize;
+                out.push((key >> 3, 0, &b[..len]));
+                b = &b[len..];
+            }
+            wire_type => panic!("wire type {wire_type}"),
+        }
+    }
+    out
+}
+
+#[cfg(feature = "grpc")]
+#[test]
+fn otlp_grpc_export_sends_protobuf_records_and_retries_on_unavailable() {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let listener = runtime.block_on(tokio::net::TcpListener::bind("127.0.0.1:0")).unwrap();
+    let endpoint = format!("http://{}", listener.local_addr().unwrap());
+    // A collector that answers the first export UNAVAILABLE, then keeps the second one's message
+    let collector = std::thread::spawn(move || runtime.block_on(async move {
+        let (socket, _) = listener.accept().await.unwrap();
+        let mut connection = h2::server::handshake(socket).await.unwrap();
+        let mut requests = Vec::new();
+        while let Some(request) = connection.accept().await {
+            let (request, mut respond) = request.unwrap();
+            let (head, mut body) = request.into_parts();
+            let mut message = Vec::new();
+            while let Some(chunk) = body.data().await {
+                let chunk = chunk.unwrap();
+                body.flow_control().release_capacity(chunk.len()).unwrap();
+                message.extend_from_slice(&chunk);
+            }
+            let reply = http::Response::builder().header("content-type", "application/grpc");
+            if requests.is_empty() {
+                respond.send_response(reply.header("grpc-status", "14").body(()).unwrap(), true).unwrap();
+            } else {
+                let mut stream = respond.send_response(reply.body(()).unwrap(), false).unwrap();
+                stream.send_data(bytes::Bytes::from_static(&[0, 0, 0, 0, 0]), false).unwrap();
+                let mut trailers = http::HeaderMap::new();
+                trailers.insert("grpc-status", "0".parse().unwrap());
+                stream.send_trailers(trailers).unwrap();
+            }
+            requests.push((head.uri.path().to_string(), head.headers["content-type"].clone(), message));
+        }
+        requests
+    }));
+
+    let dir = std::env::temp_dir().join(format!("log_analyzer_otlp_grpc_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("app.log");
+    std::fs::write(&file, SAMPLE).unwrap();
+    let filters = Filters { min_level: Some(Level::Warning), ..Filters::default() };
+    let exporter = otlp::OtlpExporter::grpc(&endpoint, "billing").unwrap();
+    let new_parser = || Ok(Box::new(DefaultLogParser::new()) as Box<dyn LogParser>);
+    let sent = otlp::export_otlp(exporter, std::slice::from_ref(&file), new_parser, &filters).unwrap();
+    assert_eq!(sent, 4);
+
+    let requests = collector.join().unwrap();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].2, requests[1].2);
+    let (path, content_type, message) = &requests[1];
+    assert_eq!(path, "/opentelemetry.proto.collector.logs.v1.LogsService/Export");
+    assert_eq!(content_type, "application/grpc");
+    // Uncompressed, length-prefixed
+    assert_eq!(message[0], 0);
+    assert_eq!(u32::from_be_bytes(message[1..5].try_into().unwrap()) as usize, message.len() - 5);
+
+    let payload = |b: &[u8], n: u64| -> Vec<Vec<u8>> { fields(b).into_iter().filter(|f| f.0 == n).map(|f| f.2.to_vec()).collect() };
+    let value = |b: &[u8], n: u64| fields(b).into_iter().find(|f| f.0 == n).unwrap().1;
+    let string = |b: &[u8], n: u64| String::from_utf8(payload(b, n)[0].clone()).unwrap();
+    let resource_logs = &payload(&message[5..], 1)[0];
+    let service = &payload(&payload(resource_logs, 1)[0], 1)[0];
+    assert_eq!((string(service, 1), string(&payload(service, 2)[0], 1)), ("service.name".to_string(), "billing".to_string()));
+    let scope_logs = &payload(resource_logs, 2)[0];
+    assert_eq!(string(&payload(scope_logs, 1)[0], 1), "log_analyzer");
+    let records = payload(scope_logs, 2);
+    assert_eq!(records.len(), 4);
+    assert_eq!(string(&records[0], 3), "WARN");
+    let record = &records[1];
+    assert_eq!((value(record, 1), value(record, 2)), (1757062802999000000, 17));
+    assert_eq!(string(&payload(record, 5)[0], 1), "Failed to connect to DB");
+    let attributes: Vec<_> = payload(record, 6).iter().map(|kv| (string(kv, 1), payload(kv, 2)[0].clone())).collect();
+    assert!(attributes.contains(&("log.file.line".to_string(), vec![3 << 3, 3])));
+    assert!(attributes.iter().any(|(key, value)| key == "log.file.path" && String::from_utf8_lossy(value).ends_with("app.log")));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
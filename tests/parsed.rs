@@ -0,0 +1,26 @@
+mod common;
+
+use common::SAMPLE;
+use log_analyzer::analyze::{Analyzer, Filters, Granularity};
+use log_analyzer::model::Level;
+use log_analyzer::parse::DefaultLogParser;
+use log_analyzer::parsed::{self, Recorder};
+use log_analyzer::report::{build_summary, ReportOptions};
+
+#[test]
+fn saved_parsed_lines_replay_into_the_same_summary() {
+    let path = std::env::temp_dir().join(format!("log_analyzer_parsed_{}.lgz", std::process::id()));
+    let mut an = Analyzer::new(Granularity::Hour, Filters::default());
+    an.recorder = Some(Recorder::create(&path).unwrap());
+    an.consume_reader(&mut DefaultLogParser::new(), SAMPLE.as_bytes(), "sample.log").unwrap();
+    assert_eq!(an.recorder.take().unwrap().lock().unwrap().finish().unwrap(), 6);
+
+    // Different filters on reload
+    let mut errors = Analyzer::new(Granularity::Day, Filters::from_cli(None, None, None, &[Level::Error]).unwrap());
+    assert_eq!(parsed::load(&path, &mut errors).unwrap(), 6);
+    std::fs::remove_file(&path).unwrap();
+    let summary = build_summary(&errors, &ReportOptions::default());
+    assert_eq!((summary.counts.info, summary.counts.error, summary.malformed_lines), (0, 3, 1));
+    assert_eq!(summary.error_examples["Failed to connect to DB"][0].raw, "2025-09-05 09:00:02,999 ERROR Failed to connect to DB");
+    assert_eq!(summary.error_examples["Failed to connect to DB"][0].line_no, 3);
+}
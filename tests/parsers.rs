@@ -0,0 +1,457 @@
+use log_analyzer::analyze::{Analyzer, Filters, Granularity};
+use log_analyzer::model::Level;
+use log_analyzer::parse::{suggest_format, CefParser, CloudFrontParser, DefaultLogParser, Delimiter, ElbParser, ExportToJson, GelfParser, HaproxyParser, JournaldParser, JsonLinesParser, JvmParser, LeefParser, LogParser, MonthLocale, MysqlParser, PostgresParser, PythonParser, RegexParser, SyslogParser, TraefikParser, W3cParser, WinEventParser};
+use log_analyzer::report::{build_summary, ReportOptions};
+
+#[test]
+fn jvm_gc_pauses_and_out_of_memory_errors() {
+    let log = "\
+[2025-09-05T14:32:11.000+0000][1.100s][info][gc] GC(0) Pause Young (Normal) (G1 Evacuation Pause) 24M->4M(256M) 3.456ms
+[2025-09-05T14:32:12.000+0000][2.100s][info][gc] GC(1) Pause Full (System.gc()) 10M->3M(256M) 12.5ms
+2025-09-05T14:32:14.000+0000: 4.100: [GC (Allocation Failure) [PSYoungGen: 33280K->5104K(38400K)] 33280K->5112K(125952K), 0.0061230 secs]
+2025-09-05 14:32:21,000 ERROR java.lang.OutOfMemoryError: Java heap space
+";
+    let mut parser = JvmParser::default();
+    let full = parser.parse_line(log.lines().nth(1).unwrap()).unwrap().unwrap();
+    assert_eq!(full.level, Level::Warning);
+    assert_eq!((full.fields["gc"].as_str(), full.fields["gc_cause"].as_str()), ("Pause Full", "System.gc()"));
+    assert_eq!((full.fields["heap_after_kb"].as_str(), full.fields["heap_total_kb"].as_str()), ("3072", "262144"));
+    let legacy = parser.parse_line(log.lines().nth(2).unwrap()).unwrap().unwrap();
+    assert_eq!((legacy.fields["gc_pause_ms"].as_str(), legacy.fields["heap_after_kb"].as_str()), ("6.123", "5112"));
+
+    let mut an = Analyzer::new(Granularity::Hour, Filters::default());
+    an.consume_reader(&mut parser, log.as_bytes(), "gc.log").unwrap();
+    let jvm = build_summary(&an, &ReportOptions::default()).jvm.unwrap();
+    assert_eq!((jvm.gc_pauses, jvm.full_gcs, jvm.heap_after_max_kb), (3, 1, Some(5112)));
+    assert!((jvm.total_pause_ms - 22.079).abs() < 1e-6);
+    // 22 ms of pauses in the 10 s from the first entry to the last
+    assert!((jvm.pause_time_pct.unwrap() - 0.22079).abs() < 1e-6);
+    assert_eq!(jvm.pause_ms.unwrap().max, 12.5);
+    assert_eq!(jvm.oom_events, ["2025-09-05 14:32:21"]);
+}
+
+#[test]
+fn cef_and_leef_events_keep_severity_and_extensions() {
+    let mut cef = CefParser::new(Some(2025));
+    let line = r"Sep 19 08:26:10 fw-1 CEF:0|Security|threatmanager|1.0|100|Worm a\|b stopped|10|src=10.0.0.1 dst=2.1.2.2 msg=Detected a threat. No action\=needed spt=1232";
+    let e = cef.parse_line(line).unwrap().unwrap();
+    assert_eq!((e.level, e.message.as_str()), (Level::Error, "Worm a|b stopped"));
+    assert_eq!(e.ts.to_string(), "2025-09-19 08:26:10");
+    assert_eq!(e.fields["msg"], "Detected a threat. No action=needed");
+    assert_eq!((e.fields["src"].as_str(), e.fields["spt"].as_str(), e.fields["host"].as_str()), ("10.0.0.1", "1232", "fw-1"));
+    assert_eq!((e.fields["vendor"].as_str(), e.fields["signature_id"].as_str(), e.fields["severity"].as_str()), ("Security", "100", "10"));
+    let e = cef.parse_line("CEF:0|Acme|IDS|2|7|Port scan|Medium|rt=Sep 20 2025 10:00:00.500 UTC act=logged").unwrap().unwrap();
+    assert_eq!((e.level, e.ts.to_string()), (Level::Warning, "2025-09-20 10:00:00.500".to_string()));
+    assert!(cef.parse_line("CEF:0|Acme|IDS|2|7|No time|3|act=logged").is_err());
+    assert!(cef.parse_line("plain text").unwrap().is_none());
+
+    let mut leef = LeefParser::new(Some(2025));
+    let e = leef.parse_line("LEEF:1.0|Microsoft|MSExchange|2016|15345|src=10.50.1.1\tdst=2.10.20.20\tsev=5\tdevTime=1758270370000").unwrap().unwrap();
+    assert_eq!((e.level, e.message.as_str(), e.fields["dst"].as_str()), (Level::Warning, "15345", "2.10.20.20"));
+    assert_eq!(e.ts.to_string(), "2025-09-19 08:26:10");
+    let e = leef.parse_line("<13>Sep 19 08:26:10 qradar LEEF:2.0|Lancope|StealthWatch|1.0|41|^|src=10.0.1.8^sev=9^usrName=bob").unwrap().unwrap();
+    assert_eq!((e.level, e.fields["usrName"].as_str(), e.fields["host"].as_str()), (Level::Error, "bob", "qradar"));
+}
+
+#[test]
+fn unknown_formats_get_delimiter_timestamp_and_pattern_hints() {
+    let lines = [
+        "[25/09/2025 14:03:11] | worker-1 | ERROR | Job 17 failed",
+        "[25/09/2025 14:03:12] | worker-2 | INFO | Job 18 started",
+        "[25/09/2025 14:03:15] | worker-1 | WARN | Job 18 slow",
+        "[26/09/2025 08:00:00] | main | INFO | Shutting down",
+    ];
+    let hints = suggest_format(&lines);
+    assert_eq!(hints.delimiters, [Delimiter { sep: '|', fields: 4 }]);
+    // 25/09 can't be month-first
+    assert_eq!(hints.timestamps.iter().map(|t| (t.format, t.lines, t.at_start)).collect::<Vec<_>>(), [("%d/%m/%Y %H:%M:%S", 4, true)]);
+    assert_eq!(hints.parsed, 4);
+    let (pattern, ts_format) = hints.pattern.unwrap();
+    let e = RegexParser::new(&pattern, Some(ts_format)).unwrap().parse_line(lines[0]).unwrap().unwrap();
+    assert_eq!((e.level, e.message.as_str()), (Level::Error, "Job 17 failed"));
+    assert_eq!(e.ts.to_string(), "2025-09-25 14:03:11");
+
+    let lines = ["1758808991.250 host-a INFO login ok", "1758808992.000 host-b WARN disk 91%", "garbage"];
+    let hints = suggest_format(&lines);
+    assert!(hints.delimiters.is_empty());
+    assert_eq!((hints.timestamps[0].format, hints.timestamps[0].lines), ("", 2));
+    let (pattern, ts_format) = hints.pattern.unwrap();
+    let e = RegexParser::new(&pattern, Some(ts_format)).unwrap().parse_line(lines[1]).unwrap().unwrap();
+    assert_eq!((e.level, e.message.as_str()), (Level::Warning, "disk 91%"));
+}
+
+#[test]
+fn journal_export_binary_fields_are_bounded() {
+    use std::io::Read;
+    let entry = |len: u64, data: &[u8]| {
+        let mut export = b"__REALTIME_TIMESTAMP=1757062800000000\nMESSAGE\n".to_vec();
+        export.extend_from_slice(&len.to_le_bytes());
+        export.extend_from_slice(data);
+        export.extend_from_slice(b"\n\n");
+        let mut out = String::new();
+        ExportToJson::new(export.as_slice()).read_to_string(&mut out).map(|_| out)
+    };
+    assert!(entry(5, b"hello").unwrap().contains(r#""MESSAGE":"hello""#));
+    assert_eq!(entry(u64::MAX, b"hello").unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+    assert_eq!(entry(64, b"hello").unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn syslog_dates_roll_over_into_the_next_year() {
+    let log = "\
+Dec 31 23:59:58 web-1 app[42]: ERROR Disk full
+Jan  1 00:00:03 web-1 app[42]: INFO Rotated logs
+Jan  1 00:00:01 web-1 app[42]: WARNING Late write
+Feb  3 08:00:00 web-1 app[42]: INFO Recovered
+";
+    let dates = |parser: &mut dyn LogParser| -> Vec<String> {
+        parser.reset();
+        log.lines().map(|l| parser.parse_line(l).unwrap().unwrap().ts.format("%Y-%m-%d").to_string()).collect()
+    };
+    // A date months behind the previous one starts a new year; a few seconds back is just out of order
+    let expected = ["2024-12-31", "2025-01-01", "2025-01-01", "2025-02-03"];
+    assert_eq!(dates(&mut DefaultLogParser::new().with_assume_year(Some(2024))), expected);
+    let mut syslog = SyslogParser::new(Some(2024));
+    assert_eq!(dates(&mut syslog), expected);
+    // Each input starts again from the assumed year
+    assert_eq!(dates(&mut syslog), expected);
+}
+
+#[test]
+fn gelf_severity_message_and_additional_fields() {
+    let mut gelf = GelfParser;
+    let e = gelf.parse_line(r#"{"version":"1.1","host":"web-1","short_message":"Upstream timed out","full_message":"Upstream timed out\nafter 30s","timestamp":1757062802.5,"level":3,"_request_id":"abc-1","_status":504}"#).unwrap().unwrap();
+    assert_eq!((e.level, e.message.as_str()), (Level::Error, "Upstream timed out"));
+    assert_eq!(e.ts.to_string(), "2025-09-05 09:00:02.500");
+    assert_eq!((e.fields["host"].as_str(), e.fields["full_message"].as_str()), ("web-1", "Upstream timed out\nafter 30s"));
+    // `_`-prefixed fields lose the underscore; numbers are kept as text
+    assert_eq!((e.fields["request_id"].as_str(), e.fields["status"].as_str()), ("abc-1", "504"));
+    assert!(!e.fields.contains_key("version") && !e.fields.contains_key("short_message"));
+    // Syslog severities 0-3 are errors, 4 a warning, 5-7 info; a missing level is ALERT
+    let mut level = |n: &str| gelf.parse_line(&format!(r#"{{"short_message":"m","timestamp":1757062802{n}}}"#)).unwrap().unwrap().level;
+    let levels: Vec<_> = ["", ",\"level\":0", ",\"level\":4", ",\"level\":5", ",\"level\":7"].iter().map(|n| level(n)).collect();
+    assert_eq!(levels, [Level::Error, Level::Error, Level::Warning, Level::Info, Level::Info]);
+    assert!(gelf.parse_line(r#"{"timestamp":1757062802}"#).unwrap_err().contains("short_message"));
+    assert!(gelf.parse_line("plain text").unwrap().is_none());
+}
+
+#[test]
+fn python_tracebacks_fold_into_their_record() {
+    let log = "\
+2025-09-05 14:32:10,123 - app.api - INFO - Request started
+2025-09-05 14:32:10,456 - app.db - ERROR - Query failed
+Traceback (most recent call last):
+  File \"/srv/app/db.py\", line 42, in run
+    rows = parse(raw)
+  File \"/srv/app/db.py\", line 17, in parse
+    return int(raw)
+ValueError: invalid literal for int() with base 10: 'x'
+2025-09-05 14:32:11,000 - app.db - ERROR - Retry failed
+Traceback (most recent call last):
+  File \"/srv/app/db.py\", line 17, in parse
+ValueError: invalid literal for int() with base 10: 'y'
+2025-09-05 14:32:12,000 - app.api - WARNING - Slow request
+";
+    let mut an = Analyzer::new(Granularity::Hour, Filters::default());
+    an.retain_entries = Some(10);
+    an.consume_reader(&mut PythonParser, log.as_bytes(), "app.log").unwrap();
+    // Four records, not fourteen lines, and no traceback line left over as malformed
+    assert_eq!((an.entries.len(), an.malformed_lines), (4, 0));
+    let failed = &an.entries[1];
+    assert_eq!((failed.level, failed.message.as_str(), failed.line_no), (Level::Error, "Query failed", 2));
+    assert_eq!((failed.fields["logger"].as_str(), failed.fields["exception"].as_str()), ("app.db", "ValueError"));
+    let traceback = &failed.fields["traceback"];
+    assert!(traceback.starts_with("Traceback (most recent call last):\n") && traceback.ends_with("with base 10: 'x'"), "{traceback}");
+    assert_eq!(traceback.lines().count(), 6);
+
+    let summary = build_summary(&an, &ReportOptions::default());
+    assert_eq!(summary.top_exceptions, [("ValueError".to_string(), 2)]);
+    assert_eq!((summary.counts.error, summary.counts.warning), (2, 1));
+}
+
+/// Errors per `backend` group of `log` read with `parser`
+fn backend_errors(parser: &mut dyn LogParser, log: &str) -> Vec<(String, u64)> {
+    let mut an = Analyzer::new(Granularity::Hour, Filters::default());
+    an.group_by = vec!["backend".into()];
+    an.consume_reader(parser, log.as_bytes(), "access.log").unwrap();
+    build_summary(&an, &ReportOptions::default()).groups["backend"].iter().map(|g| (g.value.clone(), g.counts.error)).collect()
+}
+
+#[test]
+fn haproxy_and_traefik_status_backend_and_timings() {
+    let haproxy = "\
+Feb  6 12:14:14 lb1 haproxy[14389]: 10.0.1.2:33317 [06/Feb/2025:12:14:14.655] http-in static/srv1 10/0/30/69/109 200 2750 - - ---- 1/1/1/1/0 0/0 \"GET /index.html HTTP/1.1\"
+10.0.1.3:33318 [06/Feb/2025:12:14:15.001] http-in api/srv2 5/0/1/-1/3002 503 212 - - sH-- 2/2/0/0/0 0/0 {Mozilla} \"POST /orders HTTP/1.1\"
+10.0.1.4:33319 [06/Feb/2025:12:14:16.000] http-in api/srv1 4/0/1/20/25 404 90 - - ---- 1/1/0/0/0 0/0 \"GET /missing HTTP/1.1\"
+";
+    let mut parser = HaproxyParser;
+    let e = parser.parse_line(haproxy.lines().nth(1).unwrap()).unwrap().unwrap();
+    assert_eq!((e.level, e.message.as_str()), (Level::Error, "POST /orders 503"));
+    assert_eq!((e.fields["backend"].as_str(), e.fields["server"].as_str(), e.fields["frontend"].as_str()), ("api", "srv2", "http-in"));
+    assert_eq!((e.fields["connect_ms"].as_str(), e.fields["duration_ms"].as_str()), ("1", "3002"));
+    // -1 timers are left out
+    assert!(!e.fields.contains_key("response_ms"));
+    let ok = parser.parse_line(haproxy.lines().next().unwrap()).unwrap().unwrap();
+    assert_eq!((ok.level, ok.fields["response_ms"].as_str(), ok.fields["client_ip"].as_str()), (Level::Info, "69", "10.0.1.2"));
+    assert_eq!(parser.parse_line(haproxy.lines().nth(2).unwrap()).unwrap().unwrap().level, Level::Warning);
+    assert_eq!(backend_errors(&mut parser, haproxy), [("api".to_string(), 1), ("static".to_string(), 0)]);
+
+    let traefik = concat!(
+        r#"10.0.0.1 - - [05/Sep/2025:09:00:00 +0000] "GET /api/users HTTP/1.1" 502 18 "-" "curl/8.0" 7 "api@docker" "http://10.0.0.5:80" 31ms"#, "\n",
+        r#"{"ClientHost":"10.0.0.2","DownstreamStatus":200,"Duration":12500000,"RequestMethod":"GET","RequestPath":"/","RouterName":"web@docker","ServiceName":"web@docker","ServiceURL":"http://10.0.0.6:80","StartUTC":"2025-09-05T09:00:01Z","level":"info","msg":""}"#, "\n",
+        r#"{"ClientHost":"10.0.0.3","DownstreamStatus":500,"Duration":1000000,"RequestMethod":"PUT","RequestPath":"/x","RouterName":"web@docker","ServiceName":"web@docker","StartUTC":"2025-09-05T09:00:02Z"}"#, "\n",
+    );
+    let mut parser = TraefikParser;
+    let clf = parser.parse_line(traefik.lines().next().unwrap()).unwrap().unwrap();
+    assert_eq!((clf.level, clf.message.as_str()), (Level::Error, "GET /api/users 502"));
+    assert_eq!((clf.fields["router"].as_str(), clf.fields["backend"].as_str(), clf.fields["duration_ms"].as_str()), ("api@docker", "http://10.0.0.5:80", "31"));
+    let json = parser.parse_line(traefik.lines().nth(1).unwrap()).unwrap().unwrap();
+    assert_eq!((json.level, json.message.as_str(), json.ts.to_string()), (Level::Info, "GET / 200", "2025-09-05 09:00:01".to_string()));
+    assert_eq!((json.fields["backend"].as_str(), json.fields["server"].as_str(), json.fields["duration_ms"].as_str()), ("web@docker", "http://10.0.0.6:80", "12.5"));
+    assert!(!json.fields.contains_key("level") && !json.fields.contains_key("msg"));
+    // Busiest backend first
+    assert_eq!(backend_errors(&mut parser, traefik), [("web@docker".to_string(), 1), ("http://10.0.0.5:80".to_string(), 1)]);
+}
+
+#[test]
+fn elb_and_cloudfront_columns_statuses_and_short_lines() {
+    let mut elb = ElbParser;
+    let alb = concat!(
+        r#"https 2025-09-05T09:00:01.186641Z app/shop-lb/50dc6c495c0c9188 192.168.131.39:2817 10.0.0.1:80 0.001 0.048 0.000 200 200 34 366 "#,
+        r#""GET https://shop.example.com:443/cart?id=7 HTTP/1.1" "curl/8.0" ECDHE-RSA-AES128-GCM-SHA256 TLSv1.2 "#,
+        r#"arn:aws:elasticloadbalancing:us-east-2:123456789012:targetgroup/web/73e2d6bc24d8a067 "Root=1-58337281-1d84f3d73c47ec4e58577259" "#,
+        r#""shop.example.com" "-" 0 2025-09-05T09:00:01.100000Z "forward" "-" "-" "10.0.0.1:80" "200" "-" "-""#,
+    );
+    let e = elb.parse_line(alb).unwrap().unwrap();
+    assert_eq!((e.level, e.message.as_str(), e.ts.to_string()), (Level::Info, "GET /cart?id=7 200", "2025-09-05 09:00:01.186641".to_string()));
+    assert_eq!((e.fields["client_ip"].as_str(), e.fields["target"].as_str(), e.fields["target_status"].as_str()), ("192.168.131.39", "10.0.0.1:80", "200"));
+    assert_eq!((e.fields["request_ms"].as_str(), e.fields["target_ms"].as_str(), e.fields["response_ms"].as_str(), e.fields["duration_ms"].as_str()), ("1", "48", "0", "49"));
+    assert_eq!((e.fields["type"].as_str(), e.fields["domain"].as_str(), e.fields["url"].as_str()), ("https", "shop.example.com", "https://shop.example.com:443/cart?id=7"));
+    assert!(!e.fields.contains_key("chosen_cert_arn"));
+    // No target answered: the load balancer's 502, no target status and no timers
+    let failed = alb.replacen("10.0.0.1:80 0.001 0.048 0.000 200 200", "- -1 -1 -1 502 -", 1);
+    let e = elb.parse_line(&failed).unwrap().unwrap();
+    assert_eq!((e.level, e.fields["status"].as_str(), e.fields["elb_status"].as_str()), (Level::Error, "502", "502"));
+    assert!(["target", "target_status", "target_ms", "duration_ms"].iter().all(|k| !e.fields.contains_key(*k)));
+    // Classic ELB: the same columns without `type`, up to ssl_protocol
+    let classic = r#"2025-09-05T09:00:02.945958Z shop-lb 192.168.131.39:2817 10.0.0.1:80 0.000073 0.001048 0.000057 404 404 0 29 "GET http://shop.example.com:80/missing HTTP/1.1" "curl/7.38.0" - -"#;
+    let e = elb.parse_line(classic).unwrap().unwrap();
+    assert_eq!((e.level, e.message.as_str(), e.fields["target_ms"].as_str()), (Level::Warning, "GET /missing 404", "1.048"));
+    assert!(!e.fields.contains_key("type"));
+    // Too few columns for either kind
+    let short_alb: Vec<_> = alb.split(' ').take(17).collect();
+    assert!(elb.parse_line(&short_alb.join(" ")).unwrap().is_none());
+    assert!(elb.parse_line(classic.rsplit_once(" - -").unwrap().0).unwrap().is_none());
+
+    let mut cloudfront = CloudFrontParser::default();
+    let mut columns = vec!["-"; 33];
+    for (i, v) in [(0, "2025-09-05"), (1, "09:00:03"), (2, "FRA56-C1"), (3, "2390"), (4, "192.0.2.100"), (5, "GET"), (7, "/index.html"),
+        (8, "503"), (13, "Error"), (15, "d111111abcdef8.cloudfront.net"), (18, "0.250"), (27, "0.2004")] {
+        columns[i] = v;
+    }
+    let e = cloudfront.parse_line(&columns.join("\t")).unwrap().unwrap();
+    assert_eq!((e.level, e.message.as_str(), e.ts.to_string()), (Level::Error, "GET /index.html 503", "2025-09-05 09:00:03".to_string()));
+    assert_eq!((e.fields["client_ip"].as_str(), e.fields["domain"].as_str(), e.fields["x-edge-location"].as_str()), ("192.0.2.100", "d111111abcdef8.cloudfront.net", "FRA56-C1"));
+    assert_eq!((e.fields["duration_ms"].as_str(), e.fields["ttfb_ms"].as_str()), ("250", "200.4"));
+    columns[8] = "403";
+    assert_eq!(cloudfront.parse_line(&columns.join("\t")).unwrap().unwrap().level, Level::Warning);
+    let err = cloudfront.parse_line(&columns[..20].join("\t")).unwrap_err();
+    assert!(err.contains("Expected 33 fields"), "{err}");
+}
+
+/// Entries of `log` read with `parser`, after its multi-line records are folded
+fn read_entries(parser: &mut dyn LogParser, log: &str) -> Vec<log_analyzer::model::LogEntry> {
+    let mut an = Analyzer::new(Granularity::Hour, Filters::default());
+    an.retain_entries = Some(100);
+    an.consume_reader(parser, log.as_bytes(), "db.log").unwrap();
+    assert_eq!(an.malformed_lines, 0);
+    an.entries
+}
+
+#[test]
+fn postgres_and_mysql_slow_query_durations() {
+    let postgres = "\
+2025-09-05 14:32:10.123 UTC [4242] app@shop LOG:  duration: 1520.250 ms  statement: SELECT *
+\tFROM orders
+\tWHERE id = 7
+2025-09-05 14:32:11.000 UTC [4243] ERROR:  relation \"ordrs\" does not exist at character 15
+2025-09-05 14:32:11.000 UTC [4243] STATEMENT:  SELECT * FROM ordrs
+2025-09-05 14:32:12.000 UTC [4244] LOG:  checkpoint starting: time
+";
+    let entries = read_entries(&mut PostgresParser, postgres);
+    assert_eq!(entries.len(), 3);
+    let slow = &entries[0];
+    assert_eq!((slow.level, slow.fields["duration_ms"].as_str(), slow.fields["statement"].as_str()), (Level::Info, "1520.250", "SELECT *\nFROM orders\nWHERE id = 7"));
+    assert_eq!((slow.fields["user"].as_str(), slow.fields["db"].as_str(), slow.fields["pid"].as_str()), ("app", "shop", "4242"));
+    let failed = &entries[1];
+    assert_eq!((failed.level, failed.fields["statement"].as_str()), (Level::Error, "SELECT * FROM ordrs"));
+    assert!(!failed.fields.contains_key("duration_ms") && !entries[2].fields.contains_key("duration_ms"));
+
+    let mysql = "\
+/usr/sbin/mysqld, Version: 8.0.36 (MySQL Community Server - GPL). started with:
+Tcp port: 3306  Unix socket: /var/run/mysqld/mysqld.sock
+Time                 Id Command    Argument
+# Time: 2025-09-05T14:32:10.123456Z
+# User@Host: app[app] @ localhost [127.0.0.1]  Id:    12
+# Query_time: 2.000123  Lock_time: 0.000101 Rows_sent: 1  Rows_examined: 250000
+use shop;
+SET timestamp=1757082730;
+SELECT COUNT(*)
+FROM orders;
+# User@Host: report[report] @ db-2 [10.0.0.9]  Id:    13
+# Query_time: 0.5  Lock_time: 0 Rows_sent: 10  Rows_examined: 10
+SELECT 1;
+2025-09-05T14:32:12.000000Z 0 [ERROR] [MY-010119] [Server] Aborting
+";
+    let entries = read_entries(&mut MysqlParser::default(), mysql);
+    assert_eq!(entries.len(), 3);
+    let slow = &entries[0];
+    assert_eq!((slow.level, slow.message.as_str(), slow.ts.to_string()), (Level::Info, "SELECT COUNT(*)\nFROM orders;", "2025-09-05 14:32:10.123456".to_string()));
+    assert_eq!((slow.fields["duration_ms"].as_str(), slow.fields["lock_time_ms"].as_str(), slow.fields["rows_examined"].as_str()), ("2000.123", "0.101", "250000"));
+    assert_eq!((slow.fields["user"].as_str(), slow.fields["thread_id"].as_str(), slow.fields["db"].as_str()), ("app", "12", "shop"));
+    // A later query in the same second has no `# Time:` of its own
+    let next = &entries[1];
+    assert_eq!((next.ts, next.fields["duration_ms"].as_str(), next.fields["host"].as_str()), (slow.ts, "500.000", "db-2"));
+    assert_eq!((entries[2].level, entries[2].fields["code"].as_str()), (Level::Error, "MY-010119"));
+}
+
+#[test]
+fn windows_events_map_level_provider_and_event_data() {
+    let event = |level: u8, data: &str| format!(
+        "<Event xmlns='http://schemas.microsoft.com/win/2004/08/events/event'><System><Provider Name='Service Control Manager'/>\
+<EventID>7034</EventID><Level>{level}</Level><TimeCreated SystemTime='2025-09-05T09:00:00.5000000Z'/><EventRecordID>4711</EventRecordID>\
+<Channel>System</Channel><Computer>srv-01.corp</Computer></System>{data}</Event>"
+    );
+    let mut parser = WinEventParser::default();
+    let e = parser.parse_line(&event(2, "<EventData><Data Name='param1'>Spooler</Data><Data Name='param2'>1</Data></EventData>")).unwrap().unwrap();
+    assert_eq!((e.level, e.message.as_str(), e.ts.to_string()), (Level::Error, "param1=Spooler; param2=1", "2025-09-05 09:00:00.500".to_string()));
+    assert_eq!((e.fields["provider"].as_str(), e.fields["event_id"].as_str(), e.fields["channel"].as_str()), ("Service Control Manager", "7034", "System"));
+    assert_eq!((e.fields["host"].as_str(), e.fields["record_id"].as_str()), ("srv-01.corp", "4711"));
+    // EventLevel 1-2 is ERROR, 3 WARNING, 0 (LogAlways), 4 and 5 INFO
+    let levels: Vec<_> = [1, 3, 0, 4, 5].iter().map(|&l| parser.parse_line(&event(l, "")).unwrap().unwrap().level).collect();
+    assert_eq!(levels, [Level::Error, Level::Warning, Level::Info, Level::Info, Level::Info]);
+    // Without data the message names the event; RenderedXml messages win over the data
+    assert_eq!(parser.parse_line(&event(4, "")).unwrap().unwrap().message, "Service Control Manager event 7034");
+    let rendered = event(3, "<EventData><Data>x</Data></EventData><RenderingInfo Culture='en-US'><Message>The Spooler service terminated unexpectedly.</Message></RenderingInfo>");
+    assert_eq!(parser.parse_line(&rendered).unwrap().unwrap().message, "The Spooler service terminated unexpectedly.");
+    assert!(parser.parse_line("<Event><System></System></Event>").unwrap_err().contains("TimeCreated"));
+
+    // Pretty-printed exports: one event over several lines, inside <Events>
+    let export = format!("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<Events>\n{}\n{}\n</Events>\n",
+        event(2, "").replace("><", ">\n  <"), event(4, "<EventData><Data Name='param1'>Spooler</Data></EventData>").replace("><", ">\n  <"));
+    let mut an = Analyzer::new(Granularity::Hour, Filters::default());
+    an.consume_reader(&mut parser, export.as_bytes(), "system.xml").unwrap();
+    let summary = build_summary(&an, &ReportOptions::default());
+    assert_eq!((summary.counts.error, summary.counts.info, summary.malformed_lines), (1, 1, 0));
+}
+
+#[test]
+fn w3c_fields_directive_names_columns_and_may_change() {
+    let log = "\
+#Software: Microsoft Internet Information Services 10.0
+#Version: 1.0
+#Date: 2025-09-05 09:00:00
+#Fields: date time s-ip cs-method cs-uri-stem cs-uri-query s-port c-ip cs(User-Agent) sc-status time-taken
+2025-09-05 09:00:01 10.0.0.10 GET /default.htm id=7 80 192.0.2.1 Mozilla/5.0+(Windows+NT+10.0) 200 15
+2025-09-05 09:00:02 10.0.0.10 POST /api/orders - 80 192.0.2.2 curl/8.0 500 1203
+#Software: Microsoft Internet Information Services 10.0
+#Date: 2025-09-06 00:00:00
+#Fields: time c-ip cs-method cs-uri-stem sc-status
+00:00:05 192.0.2.3 GET /missing 404
+";
+    let mut an = Analyzer::new(Granularity::Hour, Filters::default());
+    an.retain_entries = Some(10);
+    an.consume_reader(&mut W3cParser::default(), log.as_bytes(), "u_ex250905.log").unwrap();
+    assert_eq!((an.entries.len(), an.malformed_lines), (3, 0));
+    let e = &an.entries[0];
+    assert_eq!((e.level, e.message.as_str(), e.ts.to_string()), (Level::Info, "GET /default.htm?id=7 200", "2025-09-05 09:00:01".to_string()));
+    assert_eq!((e.fields["c-ip"].as_str(), e.fields["cs(User-Agent)"].as_str(), e.fields["time-taken"].as_str()), ("192.0.2.1", "Mozilla/5.0+(Windows+NT+10.0)", "15"));
+    assert!(!e.fields.contains_key("date") && !e.fields.contains_key("time"));
+    let e = &an.entries[1];
+    assert_eq!((e.level, e.message.as_str()), (Level::Error, "POST /api/orders 500"));
+    assert!(!e.fields.contains_key("cs-uri-query"));
+    // The second #Fields: has fewer columns and no date; #Date: supplies it
+    let e = &an.entries[2];
+    assert_eq!((e.level, e.message.as_str(), e.ts.to_string()), (Level::Warning, "GET /missing 404", "2025-09-06 00:00:05".to_string()));
+    assert_eq!(e.fields.keys().collect::<Vec<_>>(), ["c-ip", "cs-method", "cs-uri-stem", "sc-status"]);
+
+    // Lines that don't match the current #Fields:, or come before any
+    let mut parser = W3cParser::default();
+    assert!(parser.parse_line("2025-09-05 09:00:01 GET / 200").unwrap_err().contains("before any #Fields:"));
+    assert!(parser.absorb("#Fields: date time cs-method cs-uri-stem sc-status"));
+    assert!(parser.parse_line("2025-09-05 09:00:01 GET / 200").unwrap().is_some());
+    assert!(parser.parse_line("2025-09-05 09:00:01 GET 200").unwrap_err().contains("Expected 5 fields"));
+}
+
+#[test]
+fn journald_json_priority_message_and_hostname() {
+    let record = |priority: &str| format!(
+        r#"{{"__REALTIME_TIMESTAMP":"1757062800250000","_HOSTNAME":"web-1","SYSLOG_IDENTIFIER":"nginx","_PID":"812","_SYSTEMD_UNIT":"nginx.service","_BOOT_ID":"b1"{priority},"MESSAGE":"upstream timed out"}}"#
+    );
+    let mut parser = JournaldParser;
+    let e = parser.parse_line(&record(r#","PRIORITY":"3""#)).unwrap().unwrap();
+    assert_eq!((e.level, e.message.as_str(), e.ts.to_string()), (Level::Error, "upstream timed out", "2025-09-05 09:00:00.250".to_string()));
+    assert_eq!((e.fields["host"].as_str(), e.fields["app"].as_str(), e.fields["pid"].as_str(), e.fields["unit"].as_str()), ("web-1", "nginx", "812", "nginx.service"));
+    // Only the mapped fields are kept
+    assert_eq!(e.fields.len(), 4);
+    // 0-3 are errors, 4 a warning, 5-7 info, and a missing PRIORITY is info
+    let levels: Vec<_> = ["0", "2", "4", "5", "7"].iter()
+        .map(|p| parser.parse_line(&record(&format!(r#","PRIORITY":"{p}""#))).unwrap().unwrap().level)
+        .collect();
+    assert_eq!(levels, [Level::Error, Level::Error, Level::Warning, Level::Info, Level::Info]);
+    assert_eq!(parser.parse_line(&record("")).unwrap().unwrap().level, Level::Info);
+    // Binary MESSAGE values come as byte arrays
+    let bytes = r#"{"__REALTIME_TIMESTAMP":"1757062800000000","PRIORITY":"4","MESSAGE":[100,105,115,107,32,57,49,37]}"#;
+    assert_eq!(parser.parse_line(bytes).unwrap().unwrap().message, "disk 91%");
+    assert!(parser.parse_line(r#"{"MESSAGE":"no time"}"#).unwrap_err().contains("__REALTIME_TIMESTAMP"));
+}
+
+#[test]
+fn json_nested_fields_filter_and_top_values() {
+    let log = r#"{"time":"2025-09-05T09:00:00Z","level":"error","msg":"upstream failed","http":{"method":"GET","status":502,"route":{"name":"orders"}},"user":null}
+{"time":"2025-09-05T09:00:01Z","level":"info","msg":"ok","http":{"method":"GET","status":200,"route":{"name":"orders"}}}
+{"time":"2025-09-05T09:00:02Z","level":"error","msg":"upstream failed","http":{"method":"POST","status":502,"route":{"name":"cart"}}}
+{"time":"2025-09-05T09:00:03Z","level":"warn","msg":"slow","http":{"method":"GET","status":200,"route":{"name":"orders"}}}
+{"time":"2025-09-05T09:00:04Z","level":"error","msg":"bad gateway","http":{"method":"GET","status":502,"route":{"name":"orders"}}}
+"#;
+    let entries = read_entries(&mut JsonLinesParser, log);
+    let first = &entries[0];
+    assert_eq!((first.level, first.message.as_str()), (Level::Error, "upstream failed"));
+    // Nested objects flatten to dotted names, numbers keep their JSON text and nulls are dropped
+    let keys: Vec<_> = first.fields.keys().map(String::as_str).collect();
+    assert_eq!(keys, ["http.method", "http.route.name", "http.status"]);
+    assert_eq!(first.fields["http.status"], "502");
+
+    let filters = Filters { fields: vec![("http.status".into(), "502".into())], ..Filters::default() };
+    let mut an = Analyzer::new(Granularity::Hour, filters);
+    an.top_value_fields = vec!["http.route.name".into(), "http.method".into(), "missing".into()];
+    an.consume_reader(&mut JsonLinesParser, log.as_bytes(), "app.json").unwrap();
+    let summary = build_summary(&an, &ReportOptions::default());
+    assert_eq!((summary.counts.info, summary.counts.warning, summary.counts.error), (0, 0, 3));
+    let top = |field: &str| summary.top_values[field].iter().map(|(v, n)| (v.as_str(), *n)).collect::<Vec<_>>();
+    assert_eq!(top("http.route.name"), [("orders", 2), ("cart", 1)]);
+    assert_eq!(top("http.method"), [("GET", 2), ("POST", 1)]);
+    // A field no entry has is left out rather than reported empty
+    assert!(!summary.top_values.contains_key("missing"));
+}
+
+#[test]
+fn month_locales_read_non_english_syslog_months() {
+    let parse = |locales: &[MonthLocale], line: &str| {
+        let mut parser = DefaultLogParser::new().with_assume_year(Some(2025)).with_month_locales(locales);
+        parser.parse_line(line).ok().flatten().map(|e| (e.ts.to_string(), e.level, e.message))
+    };
+    let french = "janv.  5 14:32:10 fw-1 kernel: ERROR link down";
+    assert_eq!(parse(&[MonthLocale::French], french), Some(("2025-01-05 14:32:10".into(), Level::Error, "link down".into())));
+    // Full names win over the abbreviation they start with, and case doesn't matter
+    assert_eq!(parse(&[MonthLocale::French], "Mars 3 08:00:00 fw-1 kernel: WARN fan slow").unwrap().0, "2025-03-03 08:00:00");
+    assert_eq!(parse(&[MonthLocale::German], "Mär 17 08:00:00 fw-1 kernel: INFO up").unwrap().0, "2025-03-17 08:00:00");
+    assert_eq!(parse(&[MonthLocale::Russian], "мая 9 10:00:00 db-1 pg: ERROR failed").unwrap().0, "2025-05-09 10:00:00");
+    // English names keep working alongside a locale, which is off by default
+    assert_eq!(parse(&[MonthLocale::German], "Dec 24 18:00:00 fw-1 kernel: INFO closed").unwrap().0, "2025-12-24 18:00:00");
+    assert_eq!(parse(&[], french), None);
+    // Several locales can be combined
+    let both = [MonthLocale::Dutch, MonthLocale::French];
+    assert_eq!(parse(&both, "mei 2 09:00:00 fw-1 kernel: INFO up").unwrap().0, "2025-05-02 09:00:00");
+    assert_eq!(parse(&both, "août 2 09:00:00 fw-1 kernel: INFO up").unwrap().0, "2025-08-02 09:00:00");
+}
@@ -0,0 +1,30 @@
+#![cfg(feature = "wasm")]
+
+use log_analyzer::model::Level;
+use log_analyzer::parse::{LogParser, WasmParser};
+
+/// Parses every line into the same error, except lines starting with `x`, on which it spins forever
+const PLUGIN: &str = r#"(module
+  (memory (export "memory") 1)
+  (data (i32.const 0) "{\"time\":\"2025-09-05T09:00:00\",\"level\":\"error\",\"msg\":\"Failed to connect to DB\"}")
+  (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+  (func (export "parse_line") (param $ptr i32) (param $len i32) (result i64)
+    (if (i32.eq (i32.load8_u (local.get $ptr)) (i32.const 120))
+      (then (loop $spin (br $spin))))
+    (i64.const 78)))
+"#;
+
+#[test]
+fn plugins_that_run_out_of_fuel_fail_the_line() {
+    let path = std::env::temp_dir().join(format!("log_analyzer_plugin_{}.wat", std::process::id()));
+    std::fs::write(&path, PLUGIN).unwrap();
+    let mut parser = WasmParser::load(std::slice::from_ref(&path)).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let entry = parser.parse_line("anything").unwrap().unwrap();
+    assert_eq!((entry.level, entry.message.as_str()), (Level::Error, "Failed to connect to DB"));
+    let err = parser.parse_line("xyz").unwrap_err();
+    assert!(err.contains("ran out of fuel"), "{err}");
+    // The plugin gets fresh fuel for the next line
+    assert!(parser.parse_line("anything").unwrap().is_some());
+}
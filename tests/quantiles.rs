@@ -0,0 +1,51 @@
+use log_analyzer::analyze::{Analyzer, Filters, Granularity, HistogramSource, QuantileSketch, DEFAULT_ACCURACY};
+use log_analyzer::parse::DefaultLogParser;
+use log_analyzer::report::{build_summary, ReportOptions};
+
+/// The `q` quantile of sorted `values`, ranked the way the sketch ranks them
+fn exact(values: &[f64], q: f64) -> f64 { values[(q * (values.len() - 1) as f64) as usize] }
+
+fn assert_within(estimate: f64, exact: f64, accuracy: f64) {
+    assert!((estimate - exact).abs() <= accuracy * exact.abs(), "{estimate} vs {exact} (±{accuracy})");
+}
+
+#[test]
+fn sketch_percentiles_stay_within_relative_accuracy() {
+    // Latencies with a long tail: mostly 1-100 ms, then 1% up to ten seconds
+    let mut values: Vec<f64> = (0..9900).map(|i| 1.0 + 99.0 * (i as f64 / 9899.0).powi(2)).collect();
+    values.extend((0..100).map(|i| 100.0 * 1.047f64.powi(i)));
+    for accuracy in [DEFAULT_ACCURACY, 0.05] {
+        let mut sketch = QuantileSketch::new(accuracy);
+        // Out of order, like a real stream
+        for v in values.iter().rev() { sketch.add(*v); }
+        let mut sorted = values.clone();
+        sorted.sort_by(f64::total_cmp);
+        for q in [0.5, 0.9, 0.99, 0.999] { assert_within(sketch.quantile(q).unwrap(), exact(&sorted, q), accuracy); }
+        assert_eq!((sketch.count, sketch.min, sketch.max), (10_000, 1.0, sorted[9999]));
+    }
+
+    // Halves merged match a single pass exactly
+    let (mut one, mut half, mut other) = (QuantileSketch::default(), QuantileSketch::default(), QuantileSketch::default());
+    for (i, v) in values.iter().enumerate() {
+        one.add(*v);
+        if i % 2 == 0 { half.add(*v) } else { other.add(*v) }
+    }
+    half.merge(other);
+    assert_eq!([0.5, 0.99].map(|q| half.quantile(q)), [0.5, 0.99].map(|q| one.quantile(q)));
+    assert_eq!(QuantileSketch::default().quantile(0.5), None);
+}
+
+#[test]
+fn quantiles_field_reports_p50_and_p99() {
+    // 1..=1000 ms, taken from `latency_ms=` in the message
+    let log: String = (1..=1000).map(|i| format!("2025-09-05 10:{:02}:{:02},000 INFO GET /api done latency_ms={i}\n", i / 60 % 60, i % 60)).collect();
+    let mut an = Analyzer::new(Granularity::Hour, Filters::default());
+    an.quantile_fields = vec![HistogramSource::field("latency_ms")];
+    an.consume_reader(&mut DefaultLogParser::new(), log.as_bytes(), "api.log").unwrap();
+    let quantiles = build_summary(&an, &ReportOptions::default()).quantiles.unwrap();
+    let latency = &quantiles.fields["latency_ms"];
+    assert_eq!((latency.count, latency.min, latency.max, latency.mean), (1000, 1.0, 1000.0, 500.5));
+    assert_within(latency.p50, 500.0, quantiles.relative_accuracy);
+    assert_within(latency.p99, 990.0, quantiles.relative_accuracy);
+    assert_within(latency.p999, 999.0, quantiles.relative_accuracy);
+}
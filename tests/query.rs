@@ -0,0 +1,57 @@
+use log_analyzer::parse::{DefaultLogParser, LogParser, LogfmtParser};
+use log_analyzer::query::{self, Expr};
+
+const LOGFMT: &str = "\
+time=2025-09-05T09:00:00 level=info msg=\"Starting service\" host=web-1 latency=12
+time=2025-09-05T09:00:01 level=warning msg=\"Disk space at 85%\" host=db-1 latency=250
+time=2025-09-05T09:00:02 level=error msg=\"Failed to connect to DB\" host=db-1 latency=1500
+time=2025-09-12T11:00:00 level=error msg=\"Timeout talking to cache\" host=web-2
+";
+
+fn matching(query: &str) -> Vec<String> {
+    let expr = Expr::parse(query).unwrap();
+    let mut parser = LogfmtParser;
+    LOGFMT.lines()
+        .filter_map(|l| parser.parse_line(l).unwrap())
+        .filter(|e| expr.matches(e))
+        .map(|e| e.message)
+        .collect()
+}
+
+#[test]
+fn queries_compare_levels_timestamps_and_fields() {
+    assert_eq!(matching(r#"level == "error""#), ["Failed to connect to DB", "Timeout talking to cache"]);
+    assert_eq!(matching("level != error && level != info"), ["Disk space at 85%"]);
+    assert_eq!(matching("ts >= 2025-09-05T09:00:01 && ts < 2025-09-12"), ["Disk space at 85%", "Failed to connect to DB"]);
+    assert_eq!(matching(r#"message ~ "(?i)timeout|db""#), ["Failed to connect to DB", "Timeout talking to cache"]);
+    assert_eq!(matching(r#"host == 'db-1' && !(msg ~ "Disk")"#), ["Failed to connect to DB"]);
+    // Numbers compare numerically, so 1500 > 300 even though "1500" < "300"
+    assert_eq!(matching("latency > 300"), ["Failed to connect to DB"]);
+    // A missing field satisfies only negative tests
+    assert_eq!(matching("latency != 12 || host ~ web-2").len(), 3);
+    assert_eq!(matching("latency !~ .").len(), 1);
+}
+
+#[test]
+fn query_parse_errors_explain_the_problem() {
+    let err = |q: &str| Expr::parse(q).unwrap_err().to_string();
+    assert_eq!(err("level > error"), "level supports only == and !=");
+    assert_eq!(err("ts ~ 2025"), "ts does not support regex matching");
+    assert_eq!(err("(level == error"), "Expected ')' in query");
+    assert_eq!(err("host = db"), "Expected '==' in query");
+    assert_eq!(err(r#"msg == "open"#), "Unterminated string in query");
+    assert!(err("msg ~ '('").starts_with("Invalid regex"));
+    assert!(err("level == error host").starts_with("Unexpected"));
+}
+
+#[test]
+fn query_run_reads_past_invalid_utf8() {
+    let path = std::env::temp_dir().join(format!("log_analyzer_query_{}.log", std::process::id()));
+    let mut data = b"2025-09-05 09:00:00,001 ERROR bad byte \xff here\n".to_vec();
+    data.extend_from_slice(b"2025-09-05 09:00:01,250 ERROR Failed to connect to DB\n");
+    std::fs::write(&path, data).unwrap();
+    let expr = Expr::parse("level == error").unwrap();
+    let n = query::run(&expr, &mut DefaultLogParser::new(), std::slice::from_ref(&path), true).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(n, 2);
+}
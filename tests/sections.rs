@@ -0,0 +1,169 @@
+//! Computed summary sections, checked against values worked out by hand
+use log_analyzer::analyze::{Analyzer, Filters, Granularity, HistogramSource};
+use log_analyzer::parse::DefaultLogParser;
+use log_analyzer::report::{build_summary, histogram_stats, ErrorWindow, JsonSummary, PeriodCounts, ReportOptions};
+
+fn summary_of(mut an: Analyzer, log: &str) -> JsonSummary {
+    an.consume_reader(&mut DefaultLogParser::new(), log.as_bytes(), "app.log").unwrap();
+    build_summary(&an, &ReportOptions::default())
+}
+
+#[test]
+fn histogram_stats_use_nearest_rank_percentiles() {
+    let h = histogram_stats("latency", &[40.0, 10.0, 30.0, 20.0, 1000.0]).unwrap();
+    assert_eq!((h.source.as_str(), h.count, h.min, h.max, h.mean), ("latency", 5, 10.0, 1000.0, 220.0));
+    // p50 is the 3rd of 5 values and p95 the 5th
+    assert_eq!((h.p50, h.p95), (30.0, 1000.0));
+    let h = histogram_stats("x", &(1..=20).map(f64::from).collect::<Vec<_>>()).unwrap();
+    assert_eq!((h.p50, h.p95, h.mean), (10.0, 19.0, 10.5));
+    assert!(histogram_stats("x", &[]).is_none());
+
+    // A field value, or `name=` inside the message; entries without one are left out
+    let log = "\
+2025-09-05 10:00:00,000 INFO GET /a latency=12ms
+2025-09-05 10:00:01,000 INFO GET /b latency: 48
+2025-09-05 10:00:02,000 ERROR GET /c latency=200.5
+2025-09-05 10:00:03,000 INFO healthy
+";
+    let mut an = Analyzer::new(Granularity::Hour, Filters::default());
+    an.histogram = Some(HistogramSource::field("latency"));
+    let h = summary_of(an, log).histogram.unwrap();
+    assert_eq!((h.source.as_str(), h.count, h.min, h.max, h.p50), ("latency", 3, 12.0, 200.5, 48.0));
+    assert!((h.mean - 86.833_333).abs() < 1e-6);
+
+    let mut an = Analyzer::new(Granularity::Hour, Filters::default());
+    an.histogram = Some(HistogramSource::MessageLength);
+    let h = summary_of(an, log).histogram.unwrap();
+    assert_eq!((h.source.as_str(), h.count, h.min, h.max), ("message length", 4, 7.0, 20.0));
+    assert!(summary_of(Analyzer::new(Granularity::Hour, Filters::default()), log).histogram.is_none());
+}
+
+#[test]
+fn worst_windows_slide_over_minutes() {
+    let log = "\
+2025-09-05 10:00:10,000 ERROR a
+2025-09-05 10:00:20,000 INFO b
+2025-09-05 10:03:00,000 ERROR c
+2025-09-05 10:03:59,000 ERROR d
+2025-09-05 10:04:00,000 INFO e
+2025-09-05 10:06:00,000 ERROR f
+2025-09-05 10:07:59,000 ERROR g
+2025-09-05 10:09:00,000 ERROR h
+2025-09-05 10:40:00,000 ERROR i
+2025-09-05 10:40:01,000 ERROR j
+2025-09-05 10:40:02,000 ERROR k
+2025-09-05 10:40:03,000 ERROR l
+2025-09-05 11:30:00,000 ERROR m
+";
+    let s = summary_of(Analyzer::new(Granularity::Hour, Filters::default()), log);
+    let window = |w: &Option<ErrorWindow>| {
+        let w = w.as_ref().unwrap();
+        (w.start.clone(), w.end.clone(), w.errors, w.entries, w.error_rate)
+    };
+    // 10:03-10:08 and 10:40-10:45 both hold 4 errors; the earlier one wins although 10:40 has the higher rate
+    assert_eq!(window(&s.worst_5m_window), ("2025-09-05 10:03:00".into(), "2025-09-05 10:08:00".into(), 4, 5, 0.8));
+    // Windows start on a minute and end exclusively, so 11:00 onwards isn't in the first hour
+    assert_eq!(window(&s.worst_1h_window), ("2025-09-05 10:00:00".into(), "2025-09-05 11:00:00".into(), 10, 12, 10.0 / 12.0));
+
+    let quiet = summary_of(Analyzer::new(Granularity::Hour, Filters::default()), "2025-09-05 10:00:00,000 WARNING slow\n");
+    assert!(quiet.worst_5m_window.is_none() && quiet.worst_1h_window.is_none());
+}
+
+#[test]
+fn host_outliers_rank_by_deviation_from_the_mean_rate() {
+    let log = "\
+Sep  5 10:00:00 web-1 nginx[10]: INFO a
+Sep  5 10:00:01 web-1 nginx[10]: INFO b
+Sep  5 10:00:02 web-1 nginx[10]: WARN c
+Sep  5 10:00:03 web-1 nginx[10]: INFO d
+Sep  5 10:00:04 web-2 nginx[11]: INFO e
+Sep  5 10:00:05 web-2 nginx[11]: ERROR f
+Sep  5 10:00:06 db-1 postgres[12]: ERROR g
+Sep  5 10:00:07 web-3 nginx[13]: INFO h
+Sep  5 10:00:08 web-3 nginx[13]: INFO i
+";
+    let read = |filters: Filters| {
+        let mut an = Analyzer::new(Granularity::Hour, filters);
+        let mut parser = DefaultLogParser::new().with_assume_year(Some(2025));
+        an.consume_reader(&mut parser, log.as_bytes(), "syslog").unwrap();
+        build_summary(&an, &ReportOptions::default())
+    };
+    let hosts = read(Filters::default()).hosts.unwrap();
+    let entries: Vec<_> = hosts.hosts.iter().map(|h| (h.value.as_str(), h.total_entries, h.counts.error)).collect();
+    assert_eq!(entries, [("web-1", 4, 0), ("web-2", 2, 1), ("web-3", 2, 0), ("db-1", 1, 1)]);
+    assert_eq!(hosts.noisiest, "web-1");
+    // Rates 0, 0.5, 0 and 1: mean 0.375, population standard deviation sqrt(0.171875)
+    assert_eq!(hosts.mean_error_rate, 0.375);
+    let sd = 0.171875f64.sqrt();
+    let outliers: Vec<_> = hosts.outliers.iter().map(|o| (o.host.as_str(), o.error_rate, o.deviation)).collect();
+    assert_eq!(outliers, [("db-1", 1.0, 0.625), ("web-1", 0.0, -0.375), ("web-3", 0.0, -0.375), ("web-2", 0.5, 0.125)]);
+    assert!((hosts.outliers[0].z_score - 0.625 / sd).abs() < 1e-12);
+    assert!((hosts.outliers[1].z_score + 0.375 / sd).abs() < 1e-12);
+
+    // `--host` is case-insensitive; a single host has no outliers
+    let one = read(Filters { hosts: vec!["WEB-2".into()], ..Filters::default() });
+    assert_eq!((one.counts.info, one.counts.error), (1, 1));
+    let hosts = one.hosts.unwrap();
+    assert_eq!((hosts.hosts.len(), hosts.mean_error_rate, hosts.outliers.len()), (1, 0.5, 0));
+}
+
+#[test]
+fn hour_of_day_and_weekday_tables_fold_every_day() {
+    let log = "\
+2025-09-05 03:10:00,000 ERROR nightly job failed
+2025-09-05 03:59:59,000 ERROR nightly job failed
+2025-09-06 03:00:00,000 INFO nightly job done
+2025-09-08 14:00:00,000 INFO deploy
+2025-09-08 23:59:00,000 WARNING disk 80%
+";
+    let s = summary_of(Analyzer::new(Granularity::Day, Filters::default()), log);
+    let table = |rows: &[PeriodCounts]| {
+        rows.iter().filter(|p| p.entries > 0).map(|p| (p.period.clone(), p.entries, p.errors)).collect::<Vec<_>>()
+    };
+    assert_eq!(s.by_hour_of_day.len(), 24);
+    assert_eq!((s.by_hour_of_day[0].period.as_str(), s.by_hour_of_day[23].period.as_str()), ("00", "23"));
+    assert_eq!(table(&s.by_hour_of_day), [("03".into(), 3, 2), ("14".into(), 1, 0), ("23".into(), 1, 0)]);
+    // Monday first; 2025-09-05 was a Friday
+    assert_eq!(s.by_weekday.iter().map(|p| p.period.as_str()).collect::<Vec<_>>(), ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"]);
+    assert_eq!(table(&s.by_weekday), [("Mon".into(), 2, 0), ("Fri".into(), 2, 2), ("Sat".into(), 1, 0)]);
+
+    let empty = summary_of(Analyzer::new(Granularity::Day, Filters::default()), "");
+    assert!(empty.by_hour_of_day.is_empty() && empty.by_weekday.is_empty());
+}
+
+#[test]
+fn trends_fit_a_slope_and_compare_consecutive_weeks() {
+    let log = "\
+2025-09-01 09:00:00,000 ERROR a
+2025-09-01 10:00:00,000 INFO b
+2025-09-03 09:00:00,000 INFO c
+2025-09-03 11:00:00,000 INFO d
+2025-09-08 08:00:00,000 ERROR e
+2025-09-08 12:00:00,000 ERROR f
+2025-09-08 16:00:00,000 INFO g
+2025-09-09 14:05:00,000 ERROR h
+2025-09-09 14:10:00,000 INFO i
+2025-09-09 14:15:00,000 INFO j
+2025-09-22 09:00:00,000 ERROR k
+";
+    let t = summary_of(Analyzer::new(Granularity::Day, Filters::default()), log).trends.unwrap();
+    // Errors 1, 2, 1 and 1 on days 0, 7, 8 and 21 of 22, empty days counting as 0:
+    // (22 * 43 - 231 * 5) / (22 * 3311 - 231^2)
+    assert!((t.error_slope - -209.0 / 19481.0).abs() < 1e-12, "{}", t.error_slope);
+    // Days tie on 3 entries; the earlier wins. Day buckets are too coarse for an hour
+    assert_eq!((t.busiest_day, t.busiest_hour), (Some(("2025-09-08".into(), 3)), None));
+    let weeks: Vec<_> = t.weeks.iter().map(|w| (w.week_start.as_str(), w.entries, w.errors, w.entries_change_pct, w.errors_change_pct)).collect();
+    assert_eq!(weeks, [
+        ("2025-09-01", 4, 1, None, None),
+        ("2025-09-08", 6, 3, Some(50.0), Some(200.0)),
+        // The week of the 15th had nothing, so there is no week to compare with
+        ("2025-09-22", 1, 1, None, None),
+    ]);
+
+    let t = summary_of(Analyzer::new(Granularity::Hour, Filters::default()), log).trends.unwrap();
+    assert_eq!(t.busiest_hour, Some(("2025-09-09 14:00".into(), 3)));
+    assert_eq!(t.weeks.len(), 3);
+    // A single week has no week-over-week table
+    let t = summary_of(Analyzer::new(Granularity::Day, Filters::default()), &log[..log.find("2025-09-08").unwrap()]).trends.unwrap();
+    assert!(t.weeks.is_empty());
+}
@@ -0,0 +1,87 @@
+use log_analyzer::analyze::{Analyzer, Filters, Granularity, MessageLimits, TopK};
+use log_analyzer::parse::DefaultLogParser;
+use log_analyzer::spill::Spill;
+
+#[test]
+fn spilled_error_messages_merge_into_exact_counts() {
+    // Far more distinct messages than the counter holds, one frequent among them
+    let log: String = (0..60).map(|i| {
+        let msg = if i % 3 == 0 { "Disk full".to_string() } else { format!("Request {i} failed") };
+        format!("2025-09-05 09:00:{:02},000 ERROR {msg}\n", i % 60)
+    }).collect();
+    let run = |spill: bool| {
+        let mut an = Analyzer::new(Granularity::Hour, Filters::default());
+        an.error_messages = TopK::new(MessageLimits { capacity: 4, ..an.error_messages.limits });
+        if spill { an.spill = Some(Spill::new(1 << 20).unwrap()); }
+        for half in log.lines().collect::<Vec<_>>().chunks(30) {
+            let mut partial = an.new_partial();
+            partial.consume_reader(&mut DefaultLogParser::new(), half.join("\n").as_bytes(), "app.log").unwrap();
+            an.merge(partial);
+        }
+        an.finish_spill().unwrap();
+        an
+    };
+    let evicting = run(false);
+    assert!(evicting.error_messages.is_approximate());
+    // Evicted counts are inherited by whatever message comes next
+    assert!(evicting.error_messages.top(2)[1].1 > 1);
+
+    let spilled = run(true);
+    assert!(!spilled.error_messages.is_approximate());
+    assert_eq!(spilled.spill.as_ref().unwrap().lock().unwrap().runs(), 0);
+    let top = spilled.error_messages.top(2);
+    assert_eq!(top[0], ("Disk full".to_string(), 20));
+    assert_eq!(top[1], ("Request 1 failed".to_string(), 1));
+    assert_eq!(spilled.error_messages.len(), 4);
+}
+
+#[test]
+fn many_spilled_runs_merge_in_passes() {
+    let log: String = (0..300).map(|i| format!("2025-09-05 09:{:02}:{:02},000 ERROR Request {} failed\n", i / 60, i % 60, i % 100)).collect();
+    let mut an = Analyzer::new(Granularity::Hour, Filters::default());
+    an.error_messages = TopK::new(MessageLimits { capacity: 2, ..an.error_messages.limits });
+    // A budget of one byte spills after every error
+    an.spill = Some(Spill::new(1).unwrap());
+    an.consume_reader(&mut DefaultLogParser::new(), log.as_bytes(), "app.log").unwrap();
+    let runs = an.spill.as_ref().unwrap().lock().unwrap().runs();
+    assert!(runs > log_analyzer::spill::MAX_FAN_IN, "{runs} runs");
+    an.finish_spill().unwrap();
+    assert!(!an.error_messages.is_approximate());
+    assert_eq!(an.error_messages.top(2), [("Request 0 failed".to_string(), 3), ("Request 1 failed".to_string(), 3)]);
+}
+
+#[test]
+fn retained_entries_share_the_memory_budget() {
+    let log: String = (0..300).map(|i| format!("2025-09-05 09:{:02}:{:02},000 ERROR Request {i} failed\n", i / 60, i % 60)).collect();
+    let lines: Vec<_> = log.lines().collect();
+    let budget = 8 << 10;
+    let run = |spill: bool, partials: bool| {
+        let mut an = Analyzer::new(Granularity::Hour, Filters::default());
+        an.retain_entries = Some(1000);
+        if spill { an.spill = Some(Spill::new(budget).unwrap()); }
+        if partials {
+            for chunk in lines.chunks(100) {
+                let mut partial = an.new_partial();
+                partial.consume_reader(&mut DefaultLogParser::new(), chunk.join("\n").as_bytes(), "app.log").unwrap();
+                an.merge(partial);
+            }
+        } else {
+            an.consume_reader(&mut DefaultLogParser::new(), log.as_bytes(), "app.log").unwrap();
+        }
+        let runs = an.spill.as_ref().map(|s| s.lock().unwrap().runs());
+        an.finish_spill().unwrap();
+        (an, runs)
+    };
+    assert_eq!(run(false, false).0.entries.len(), 300);
+    for partials in [false, true] {
+        let (an, runs) = run(true, partials);
+        // The first entries up to half the budget, each at least a LogEntry
+        let kept = an.entries.len();
+        assert!(kept > 0 && kept <= budget / 2 / std::mem::size_of::<log_analyzer::model::LogEntry>(), "{kept} entries kept");
+        assert_eq!(an.entries[kept - 1].message, format!("Request {} failed", kept - 1));
+        // Error messages still spill, and still come out exact
+        assert!(runs.unwrap() > 0);
+        assert!(!an.error_messages.is_approximate());
+        assert_eq!((an.error_messages.len(), an.error_messages.top(1)[0].1), (300, 1));
+    }
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use common::{SAMPLE, sample_summary};
+use log_analyzer::analyze::{Analyzer, Filters, Granularity, TimePrecision};
+use log_analyzer::model::Level;
+use log_analyzer::parse::DefaultLogParser;
+use log_analyzer::report::{build_summary, markdown, summarize, JsonSummary, ReportOptions, SummaryFormat};
+
+fn round_trip(format: SummaryFormat) {
+    let summary = sample_summary();
+    let text = format.render(&summary).unwrap();
+    let back = format.parse(&text).unwrap();
+    assert_eq!(serde_json::to_value(&back).unwrap(), serde_json::to_value(&summary).unwrap(), "{format:?} output:\n{text}");
+}
+
+#[test]
+fn json_round_trip() { round_trip(SummaryFormat::Json); }
+
+#[test]
+fn yaml_round_trip() { round_trip(SummaryFormat::Yaml); }
+
+#[test]
+fn toml_round_trip() { round_trip(SummaryFormat::Toml); }
+
+#[test]
+fn formats_agree() {
+    let summary = sample_summary();
+    let values: Vec<serde_json::Value> = [SummaryFormat::Json, SummaryFormat::Yaml, SummaryFormat::Toml]
+        .into_iter()
+        .map(|f| serde_json::to_value(f.parse(&f.render(&summary).unwrap()).unwrap()).unwrap())
+        .collect();
+    assert_eq!(values[0], values[1]);
+    assert_eq!(values[0], values[2]);
+    assert_eq!(values[0]["counts"]["error"], 3);
+    assert_eq!(values[0]["malformed_lines"], 1);
+}
+
+#[test]
+fn typed_summary_backs_json_view() {
+    let mut an = Analyzer::new(Granularity::Hour, Filters::default());
+    an.consume_reader(&mut DefaultLogParser::new(), SAMPLE.as_bytes(), "sample.log").unwrap();
+    let summary = summarize(&an, &ReportOptions::default());
+    assert_eq!(summary.count(Level::Error), 3);
+    assert_eq!(summary.first_log.unwrap().to_string(), "2025-09-05 09:00:00.001");
+    assert_eq!(summary.timeline[0].0.to_string(), "2025-09-05 09:00:00");
+
+    let json = JsonSummary::from(&summary);
+    assert_eq!(json.first_log.as_deref(), Some("2025-09-05 09:00:00"));
+    assert_eq!(json.timeline[0], ("2025-09-05 09:00:00".to_string(), 3));
+    assert_eq!(json.time_to_first_error_secs, Some(2));
+}
+
+#[test]
+fn markdown_summary_is_a_pasteable_table() {
+    let mut an = Analyzer::new(Granularity::Hour, Filters::default());
+    an.consume_reader(&mut DefaultLogParser::new(), SAMPLE.as_bytes(), "sample.log").unwrap();
+    let md = markdown::render(&summarize(&an, &ReportOptions::default()));
+    assert!(md.starts_with("## Log summary\n"));
+    assert!(md.contains("| ERROR | 3 | 60.0% |"));
+    assert!(md.contains("| 2 | Failed to connect to DB |"));
+}
+
+#[test]
+fn sub_second_buckets_and_timestamp_precision() {
+    let mut an = Analyzer::new(Granularity::custom("500ms").unwrap(), Filters::default());
+    an.consume_reader(&mut DefaultLogParser::new(), SAMPLE.as_bytes(), "app.log").unwrap();
+    let summary = build_summary(&an, &ReportOptions::default());
+    // Sub-second buckets are written with milliseconds even at the default precision
+    assert_eq!(summary.first_log.as_deref(), Some("2025-09-05 09:00:00.001"));
+    assert_eq!(summary.timeline.iter().take(3).map(|(b, _)| b.as_str()).collect::<Vec<_>>(),
+        ["2025-09-05 09:00:00.000", "2025-09-05 09:00:01.000", "2025-09-05 09:00:02.500"]);
+
+    let mut an = Analyzer::new(Granularity::Hour, Filters::default());
+    an.ts_precision = TimePrecision::Micros;
+    an.consume_reader(&mut DefaultLogParser::new(), SAMPLE.as_bytes(), "app.log").unwrap();
+    let summary = build_summary(&an, &ReportOptions::default());
+    assert_eq!(summary.last_log.as_deref(), Some("2025-09-12 11:00:00.000000"));
+    assert_eq!(summary.error_examples["Failed to connect to DB"][0].ts, "2025-09-05 09:00:02.999000");
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use common::sample_summary;
+use log_analyzer::report::{json_schema, SummaryFormat, SCHEMA_VERSION};
+use serde_json::{json, Value};
+use std::collections::BTreeSet;
+
+fn keys(v: &Value) -> BTreeSet<String> {
+    v.as_object().unwrap().keys().cloned().collect()
+}
+
+#[test]
+fn summary_carries_schema_version() {
+    let value = serde_json::to_value(sample_summary()).unwrap();
+    assert_eq!(value["schema_version"], SCHEMA_VERSION);
+}
+
+/// The layout from before versioning (as in the README example) still loads
+#[test]
+fn pre_versioning_summary_loads() {
+    let old = r#"{
+        "total_entries": 7,
+        "malformed_lines": 0,
+        "counts": {"info": 2, "warning": 2, "error": 3},
+        "first_log": "2025-09-05 09:00:00",
+        "last_log": "2025-09-05 11:00:00",
+        "common_errors": [["Failed to connect to DB", 3]],
+        "timeline": [["2025-09-05 09:00:00", 3], ["2025-09-05 10:15:00", 2]]
+    }"#;
+    let summary = SummaryFormat::Json.parse(old).unwrap();
+    assert_eq!(summary.total_entries, 7);
+    assert_eq!(summary.counts.error, 3);
+    assert_eq!(summary.common_errors, vec![("Failed to connect to DB".to_string(), 3)]);
+    assert!(summary.incidents.is_empty());
+    assert!(summary.trends.is_none());
+}
+
+/// A later minor version may add fields anywhere; readers ignore them
+#[test]
+fn newer_minor_version_loads() {
+    let mut value = serde_json::to_value(sample_summary()).unwrap();
+    let next_minor = format!("{}.99", SCHEMA_VERSION.split('.').next().unwrap());
+    value["schema_version"] = json!(next_minor);
+    value["added_later"] = json!({"anything": [1, 2, 3]});
+    value["counts"]["debug"] = json!(5);
+    let summary = SummaryFormat::Json.parse(&value.to_string()).unwrap();
+    assert_eq!(summary.counts.error, 3);
+    assert_eq!(summary.schema_version, next_minor);
+}
+
+#[test]
+fn error_examples_point_at_raw_lines() {
+    let summary = sample_summary();
+    let examples = &summary.error_examples["Failed to connect to DB"];
+    assert_eq!(examples.len(), 2);
+    assert_eq!((examples[0].source.as_str(), examples[0].line_no), ("sample.log", 3));
+    assert_eq!(examples[1].ts, "2025-09-05 10:15:34");
+    assert_eq!(examples[1].raw, "2025-09-05 10:15:34,777 ERROR Failed to connect to DB");
+}
+
+#[test]
+fn other_major_version_is_rejected() {
+    let mut value = serde_json::to_value(sample_summary()).unwrap();
+    value["schema_version"] = json!("2.0");
+    let Err(err) = SummaryFormat::Json.parse(&value.to_string()) else { panic!("2.0 summary accepted") };
+    assert!(err.to_string().contains("2.0"), "{err}");
+}
+
+#[test]
+fn schema_covers_every_field() {
+    let schema = json_schema();
+    let value = serde_json::to_value(sample_summary()).unwrap();
+    assert_eq!(keys(&schema["properties"]), keys(&value));
+    assert_eq!(keys(&schema["$defs"]["Counts"]["properties"]), keys(&value["counts"]));
+    assert_eq!(keys(&schema["$defs"]["MalformedLine"]["properties"]), keys(&value["malformed_samples"][0]));
+    assert_eq!(keys(&schema["$defs"]["MessageExample"]["properties"]), keys(&value["error_examples"]["Failed to connect to DB"][0]));
+    assert_eq!(schema["x-schema-version"], SCHEMA_VERSION);
+}
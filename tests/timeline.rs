@@ -0,0 +1,110 @@
+mod common;
+
+use common::SAMPLE;
+use log_analyzer::analyze::{Analyzer, Filters, Granularity};
+use log_analyzer::parse::DefaultLogParser;
+use log_analyzer::report::{markdown, summarize, JsonSummary, ReportOptions};
+use log_analyzer::viz::PressureWeights;
+
+#[test]
+fn pressure_weighs_levels_per_bucket() {
+    let mut an = Analyzer::new(Granularity::Hour, Filters::default());
+    an.consume_reader(&mut DefaultLogParser::new(), SAMPLE.as_bytes(), "sample.log").unwrap();
+    let summary = summarize(&an, &ReportOptions::default());
+    let series = PressureWeights::default().series(&summary);
+    assert_eq!(series.iter().map(|p| p.1).collect::<Vec<_>>(), [7.0, 5.0, 5.0]);
+    let custom: PressureWeights = "warning=1,info=0.5".parse().unwrap();
+    assert_eq!(custom, PressureWeights { error: 5.0, warning: 1.0, info: 0.5 });
+    assert_eq!(custom.series(&summary)[0].1, 6.5);
+    assert!("fatal=9".parse::<PressureWeights>().is_err());
+}
+
+#[test]
+fn tracked_keywords_follow_the_timeline() {
+    let mut an = Analyzer::new(Granularity::Hour, Filters::default());
+    an.tracked_keywords = vec!["connect".into(), "timeout".into(), "deadlock".into()];
+    // Split across partials, like a parallel run
+    let mut second = an.new_partial();
+    let (head, tail) = SAMPLE.split_at(SAMPLE.find("2025-09-05 10:15").unwrap());
+    an.consume_reader(&mut DefaultLogParser::new(), head.as_bytes(), "a.log").unwrap();
+    second.consume_reader(&mut DefaultLogParser::new(), tail.as_bytes(), "b.log").unwrap();
+    an.merge(second);
+
+    let summary = summarize(&an, &ReportOptions::default());
+    let tracked: Vec<_> = summary.tracked_keywords.iter().map(|k| (k.keyword.as_str(), k.total, k.counts.clone())).collect();
+    assert_eq!(tracked, [("connect", 2, vec![1, 1, 0]), ("timeout", 1, vec![0, 0, 1]), ("deadlock", 0, vec![0, 0, 0])]);
+    assert_eq!(summary.tracked_keywords[1].first_seen.unwrap().to_string(), "2025-09-12 11:00:00");
+    assert_eq!(summary.tracked_keywords[2].first_seen, None);
+
+    let json = JsonSummary::from(&summary);
+    assert_eq!(json.tracked_keywords[0].timeline[1], ("2025-09-05 10:00:00".to_string(), 1));
+    assert!(markdown::render(&summary).contains("| timeout | 1 | 2025-09-12 11:00:00 |"));
+}
+
+/// Timeline keys and counts after reading `log` into buckets of `granularity`
+fn buckets(granularity: Granularity, log: &str) -> Vec<(String, u64)> {
+    let mut an = Analyzer::new(granularity, Filters::default());
+    an.consume_reader(&mut DefaultLogParser::new(), log.as_bytes(), "app.log").unwrap();
+    an.settle_granularity();
+    an.timeline.iter().map(|(b, n)| (b.to_string(), *n)).collect()
+}
+
+#[test]
+fn week_month_and_custom_buckets_start_where_expected() {
+    let log = "\
+2025-12-28 23:59:59,000 INFO Sunday before New Year
+2025-12-29 00:00:00,000 INFO Monday
+2025-12-31 18:07:30,000 ERROR New Year's Eve
+2026-01-01 00:00:00,000 INFO New Year
+2026-01-31 12:00:00,000 WARNING end of January
+2026-02-01 00:00:01,000 INFO February
+";
+    let owned = |v: &[(&str, u64)]| v.iter().map(|(b, n)| (b.to_string(), *n)).collect::<Vec<_>>();
+    // Weeks start on Monday, across the year boundary
+    assert_eq!(buckets(Granularity::Week, log), owned(&[
+        ("2025-12-22 00:00:00", 1), ("2025-12-29 00:00:00", 3), ("2026-01-26 00:00:00", 2),
+    ]));
+    assert_eq!(buckets(Granularity::Month, log), owned(&[
+        ("2025-12-01 00:00:00", 3), ("2026-01-01 00:00:00", 2), ("2026-02-01 00:00:00", 1),
+    ]));
+    let month = Granularity::Month;
+    let dec = month.bucket("2025-12-31T18:07:30".parse().unwrap());
+    assert_eq!((month.next_bucket(dec).to_string(), month.next_bucket(month.next_bucket(dec)).to_string()),
+        ("2026-01-01 00:00:00".into(), "2026-02-01 00:00:00".into()));
+
+    // Custom steps are aligned to the Unix epoch, not to the hour
+    let step = Granularity::custom("7m").unwrap();
+    assert_eq!(step, Granularity::Custom(420));
+    assert_eq!(step.bucket("2025-12-31T18:07:30".parse().unwrap()).to_string(), "2025-12-31 18:03:00");
+    assert_eq!(step.next_bucket(step.bucket("2025-12-31T18:07:30".parse().unwrap())).to_string(), "2025-12-31 18:10:00");
+    let quarter = "2025-09-05 10:14:59,000 INFO a\n2025-09-05 10:15:00,000 INFO b\n2025-09-05 10:29:59,999 ERROR c\n";
+    assert_eq!(buckets("15m".parse().unwrap(), quarter), owned(&[("2025-09-05 10:00:00", 1), ("2025-09-05 10:15:00", 2)]));
+    assert_eq!(Granularity::custom("250ms").unwrap().bucket("2025-09-05T10:15:00.999".parse().unwrap()).to_string(), "2025-09-05 10:15:00.750");
+    assert!(Granularity::custom("0s").is_err() && "fortnight".parse::<Granularity>().is_err());
+}
+
+#[test]
+fn auto_granularity_targets_a_few_hundred_buckets() {
+    let auto = |from: &str, to: &str| Granularity::auto(from.parse().unwrap(), to.parse().unwrap());
+    assert_eq!(auto("2025-09-05T10:00:00", "2025-09-05T10:01:00"), Granularity::Second);
+    assert_eq!(auto("2025-09-05T10:00:00", "2025-09-05T11:00:00"), Granularity::Custom(15));
+    assert_eq!(auto("2025-09-05T00:00:00", "2025-09-06T00:00:00"), Granularity::Custom(300));
+    assert_eq!(auto("2025-09-01T00:00:00", "2025-10-01T00:00:00"), Granularity::Custom(10800));
+    assert_eq!(auto("2025-01-01T00:00:00", "2026-01-01T00:00:00"), Granularity::Custom(172800));
+    assert_eq!(auto("2015-01-01T00:00:00", "2025-01-01T00:00:00"), Granularity::Month);
+
+    // One entry a second for 12,000 s: re-bucketed while reading, then settled on minutes
+    let log: String = (0..12_000).map(|i| format!("2025-09-05 {:02}:{:02}:{:02},000 INFO tick {i}\n", 10 + i / 3600, i / 60 % 60, i % 60)).collect();
+    let mut an = Analyzer::new(Granularity::Auto, Filters::default());
+    an.consume_reader(&mut DefaultLogParser::new(), log.as_bytes(), "ticks.log").unwrap();
+    an.settle_granularity();
+    assert_eq!((an.granularity, an.timeline.len()), (Granularity::Minute, 200));
+    assert_eq!(an.timeline.first_key_value().map(|(b, n)| (b.to_string(), *n)), Some(("2025-09-05 10:00:00".into(), 60)));
+    assert_eq!(an.timeline.values().sum::<u64>(), 12_000);
+
+    // An hour of data lands in 15 s buckets; fixed granularities are left alone
+    let hour = "2025-09-05 10:00:07,000 INFO a\n2025-09-05 10:30:00,000 INFO b\n2025-09-05 11:00:00,000 ERROR c\n";
+    let keys: Vec<_> = buckets(Granularity::Auto, hour).into_iter().map(|(b, _)| b).collect();
+    assert_eq!(keys, ["2025-09-05 10:00:00", "2025-09-05 10:30:00", "2025-09-05 11:00:00"]);
+    assert_eq!(buckets(Granularity::Second, hour)[0].0, "2025-09-05 10:00:07");
+}